@@ -0,0 +1,37 @@
+//! Mints the entries used by the crate before it is compiled, so that the macros only
+//! read the registry, and generates a tag constant for each message of `messages.idl`.
+use std::{env, fs, path::Path};
+
+use unique_uuid::build::RegistryBuilder;
+
+fn main() {
+    let registry = concat!(env!("CARGO_MANIFEST_DIR"), "/../types.toml");
+    unique_uuid::build::ensure_tags(registry, &["build.greeting"]).unwrap();
+    unique_uuid::build::ensure_type_tags(registry, &["Greeter"]).unwrap();
+
+    println!("cargo:rerun-if-changed=messages.idl");
+    let idl = fs::read_to_string("messages.idl").unwrap();
+    let mut builder = RegistryBuilder::open(registry).unwrap();
+    let mut generated = String::new();
+    for name in idl
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let key = format!("build.messages.{}", name.to_lowercase());
+        let tag = builder.get_or_insert_tag(&key).unwrap();
+        generated.push_str(&format!(
+            "/// The tag of `{}`, `{}` in the registry.\n\
+             pub const {}: unique_uuid::UniqueTag =\n    \
+             unique_uuid::UniqueTag(unique_uuid::uuid::Uuid::from_u128({:#034x}));\n",
+            name,
+            key,
+            name.to_uppercase(),
+            tag.0.as_u128()
+        ));
+    }
+    builder.commit().unwrap();
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("messages.rs"), generated).unwrap();
+}
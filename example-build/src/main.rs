@@ -0,0 +1,18 @@
+use unique_uuid::{unique_tag, UniqueTypeTag};
+
+/// Minted by the build script, like `build.greeting`.
+#[derive(UniqueTypeTag)]
+pub struct Greeter;
+
+/// The tags of the messages of `messages.idl`, generated by the build script.
+mod messages {
+    include!(concat!(env!("OUT_DIR"), "/messages.rs"));
+}
+
+fn main() {
+    let greeting = unique_tag!("build.greeting");
+    println!("Tag for \"build.greeting\": {:?}", greeting);
+    println!("Tag for type struct Greeter: {:?}", Greeter::TYPE_TAG);
+    println!("Tag for message Hello: {:?}", messages::HELLO);
+    assert_eq!(messages::GOODBYE, unique_tag!("build.messages.goodbye"));
+}
@@ -0,0 +1,15 @@
+//! Types of the example, shared by its binary and its tests.
+
+// The fingerprint of the registry, for peers to check they were built against it
+unique_uuid::registry_fingerprint!();
+
+/// The types of an example shop, registered in the running binary by the derive.
+pub mod shop {
+    use unique_uuid::UniqueTypeTag;
+
+    #[derive(UniqueTypeTag)]
+    pub struct Cart;
+
+    #[derive(UniqueTypeTag)]
+    pub struct Checkout;
+}
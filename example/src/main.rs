@@ -1,4 +1,4 @@
-use unique_uuid::{unique_tag, UniqueTypeTag};
+use unique_uuid::{registry::Registry, unique_tag, UniqueTypeTag};
 
 #[derive(UniqueTypeTag)]
 pub struct Test;
@@ -16,4 +16,16 @@ fn main() {
 
     // For types
     println!("Tag for type struct Test: {:?}", Test::TYPE_TAG);
+
+    // Reading the registry back at runtime
+    let registry = Registry::load("types.toml").unwrap();
+    println!(
+        "Registry key for {:?}: {:?}",
+        Test::TYPE_TAG,
+        registry.reverse(Test::TYPE_TAG)
+    );
+
+    // Showing registered names in debug output
+    registry.register_names();
+    println!("Tag for type struct Test: {:?}", Test::TYPE_TAG);
 }
@@ -0,0 +1,65 @@
+//! The events of the example shop, dispatched by matching on their tags.
+use unique_uuid::{unique_tag, UniqueTag};
+
+const ORDER_PLACED: UniqueTag = unique_tag!(test, "shop.order_placed");
+const ORDER_PAID: UniqueTag = unique_tag!(test, "shop.order_paid");
+const ORDER_SHIPPED: UniqueTag = unique_tag!(test, "shop.order_shipped");
+const ORDER_CANCELLED: UniqueTag = unique_tag!(test, "shop.order_cancelled");
+
+/// Returns the queue handling the events tagged `tag`, if the shop knows them.
+fn queue(tag: UniqueTag) -> Option<&'static str> {
+    match tag {
+        ORDER_PLACED | ORDER_PAID => Some("billing"),
+        ORDER_SHIPPED => Some("shipping"),
+        ORDER_CANCELLED => Some("refunds"),
+        _ => None,
+    }
+}
+
+/// Returns the queue handling the events tagged `raw`, a tag received as a `u128`.
+fn queue_of_raw(raw: u128) -> Option<&'static str> {
+    const PLACED: u128 = ORDER_PLACED.as_u128();
+    const PAID: u128 = ORDER_PAID.as_u128();
+    const SHIPPED: u128 = ORDER_SHIPPED.as_u128();
+    const CANCELLED: u128 = ORDER_CANCELLED.as_u128();
+
+    match raw {
+        PLACED | PAID => Some("billing"),
+        SHIPPED => Some("shipping"),
+        CANCELLED => Some("refunds"),
+        _ => None,
+    }
+}
+
+#[test]
+fn known_tags_reach_their_queue() {
+    assert_eq!(queue(ORDER_PLACED), Some("billing"));
+    assert_eq!(queue(ORDER_PAID), Some("billing"));
+    assert_eq!(queue(ORDER_SHIPPED), Some("shipping"));
+    assert_eq!(queue(ORDER_CANCELLED), Some("refunds"));
+    // The same tag, resolved by another invocation of the macro
+    assert_eq!(
+        queue(unique_tag!(test, "shop.order_shipped")),
+        Some("shipping")
+    );
+}
+
+#[test]
+fn other_tags_fall_through() {
+    assert_eq!(queue(unique_tag!("test1")), None);
+    assert_eq!(queue(UniqueTag::from_u128(0)), None);
+}
+
+#[test]
+fn raw_tags_match_like_tags() {
+    for tag in [
+        ORDER_PLACED,
+        ORDER_PAID,
+        ORDER_SHIPPED,
+        ORDER_CANCELLED,
+        unique_tag!("test1"),
+    ] {
+        assert_eq!(queue_of_raw(tag.as_u128()), queue(tag));
+        assert_eq!(UniqueTag::from_u128(tag.as_u128()), tag);
+    }
+}
@@ -0,0 +1,48 @@
+//! The fingerprint of the registry, declared in the example library and in this test.
+use unique_uuid::{
+    check_fingerprint, registry::Registry, registry_fingerprint, FingerprintMismatch,
+};
+
+registry_fingerprint!();
+
+const PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../types.toml");
+
+#[test]
+fn crates_built_against_the_same_file_agree() {
+    assert_eq!(REGISTRY_FINGERPRINT, example::REGISTRY_FINGERPRINT);
+    assert_eq!(
+        check_fingerprint(REGISTRY_FINGERPRINT, example::REGISTRY_FINGERPRINT),
+        Ok(())
+    );
+
+    let registry = Registry::load(PATH).unwrap();
+    assert_eq!(registry.fingerprint(None), example::REGISTRY_FINGERPRINT);
+}
+
+#[test]
+fn a_forked_file_disagrees() {
+    let contents = std::fs::read_to_string(PATH).unwrap();
+    let registry = Registry::parse(&contents).unwrap();
+    let uuid = registry.get("::Cart").unwrap().tag.0.to_string();
+    let forked =
+        Registry::parse(&contents.replace(&uuid, "9d0c1f53-52a4-4f4e-8d5a-14b1b2a1f0de")).unwrap();
+
+    let theirs = forked.fingerprint(None);
+    assert_ne!(theirs, example::REGISTRY_FINGERPRINT);
+    assert_eq!(
+        check_fingerprint(example::REGISTRY_FINGERPRINT, theirs),
+        Err(FingerprintMismatch {
+            ours: example::REGISTRY_FINGERPRINT,
+            theirs,
+        })
+    );
+
+    // Fields other than the UUIDs do not count
+    let annotated = contents.replace(
+        &format!("uuid = \"{}\"", uuid),
+        &format!("description = \"The cart\", uuid = \"{}\"", uuid),
+    );
+    assert_ne!(annotated, contents);
+    let annotated = Registry::parse(&annotated).unwrap();
+    assert_eq!(annotated.fingerprint(None), example::REGISTRY_FINGERPRINT);
+}
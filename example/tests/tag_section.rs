@@ -0,0 +1,58 @@
+//! The type tags embedded in the example binary, read back without running it.
+use unique_uuid::{
+    registry::{Registry, Section},
+    tag_section::read_binary,
+};
+use unique_uuid_admin::Cli;
+
+const BINARY: &str = env!("CARGO_BIN_EXE_example");
+
+#[test]
+fn the_binary_lists_its_type_tags() {
+    let tags = read_binary(&std::fs::read(BINARY).unwrap()).unwrap();
+    assert!(tags.iter().any(|tag| tag.key == "::Test"), "{:?}", tags);
+
+    // Every record agrees with the registry the binary was built against
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../types.toml");
+    let registry = Registry::load(path).unwrap();
+    for tag in &tags {
+        let entry = registry.entry(Section::UniqueTypeTags, &tag.key).unwrap();
+        assert_eq!(entry.tag(), tag.tag, "{}", tag.key);
+    }
+}
+
+#[test]
+fn the_admin_inspects_the_binary() {
+    let tags = read_binary(&std::fs::read(BINARY).unwrap()).unwrap();
+    let test = tags.iter().find(|tag| tag.key == "::Test").unwrap();
+
+    let cli = Cli::parse(["inspect", BINARY].map(String::from)).unwrap();
+    let mut out = Vec::new();
+    assert!(cli.run(&mut out).unwrap());
+    let out = String::from_utf8(out).unwrap();
+    assert!(
+        out.lines()
+            .any(|line| line == format!("::Test\t{}", test.tag.0)),
+        "{}",
+        out
+    );
+
+    let cli = Cli::parse(["inspect", BINARY, "--json"].map(String::from)).unwrap();
+    let mut out = Vec::new();
+    assert!(cli.run(&mut out).unwrap());
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert!(json
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|tag| tag["key"] == "::Test" && tag["uuid"] == test.tag.0.to_string()));
+}
+
+#[test]
+fn files_that_are_not_binaries_are_reported() {
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    let cli = Cli::parse(["inspect", manifest].map(String::from)).unwrap();
+    let err = cli.run(&mut Vec::new()).unwrap_err();
+    let message = format!("cannot inspect `{}`: cannot parse the binary", manifest);
+    assert!(err.to_string().starts_with(&message), "{}", err);
+}
@@ -0,0 +1,52 @@
+//! The type tags registered by the derive, in the example library and in this test.
+use example::shop::{Cart, Checkout};
+use unique_uuid::{
+    registry::{find_by_tag, iter_type_tags, Registry},
+    unique_tag, UniqueTypeTag,
+};
+
+#[derive(UniqueTypeTag)]
+struct Receipt;
+
+#[derive(UniqueTypeTag)]
+struct Generic<T>(T);
+
+#[test]
+fn registrations_of_every_crate_are_visible() {
+    let mut names: Vec<_> = iter_type_tags().map(|entry| entry.type_name).collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        [
+            "example::shop::Cart",
+            "example::shop::Checkout",
+            "type_tags::Receipt"
+        ]
+    );
+}
+
+#[test]
+fn lookups_resolve_by_tag() {
+    for (tag, key) in [
+        (Cart::TYPE_TAG, "::Cart"),
+        (Checkout::TYPE_TAG, "::Checkout"),
+        (Receipt::TYPE_TAG, "::Receipt"),
+    ] {
+        let info = find_by_tag(tag).unwrap();
+        assert_eq!(info.tag, tag);
+        assert_eq!(info.key, key);
+    }
+    assert!(find_by_tag(Generic::<u8>::TYPE_TAG).is_none());
+    assert!(find_by_tag(unique_tag!("test1")).is_none());
+}
+
+#[test]
+fn the_registry_file_and_the_binary_agree() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../types.toml");
+    let registry = Registry::load(path).unwrap();
+    for tag in [Cart::TYPE_TAG, Checkout::TYPE_TAG] {
+        let info = find_by_tag(tag).unwrap();
+        assert_eq!(registry.get(info.key), Some(info));
+        assert_eq!(info.source_crate, Some("example"));
+    }
+}
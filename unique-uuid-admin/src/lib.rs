@@ -0,0 +1,1019 @@
+//! Command-line maintenance of the `types.toml` registry written by the `unique-uuid`
+//! macros.
+//!
+//! The `unique-uuid-admin` binary is a thin wrapper around [`Cli`], which is exposed
+//! so that the commands can be driven from other tools and tests.
+//!
+//! # Commands
+//! - `list [--namespace X] [--json]`: lists the entries with the crate that minted them
+//!   and when, optionally restricted to a namespace (see
+//!   [`unique_uuid::registry::in_namespace`])
+//! - `verify`: reports validation issues, and fails if there are any, then lists the
+//!   reserved entries (see [`unique_uuid::registry::Registry::reservations`]), which are
+//!   not issues
+//! - `rename <old> <new>`: moves an entry to a new key, keeping its UUID
+//! - `reserve <key> [--owner <owner>]`: pre-allocates the UUID of `key` with a
+//!   `reserved = true` entry, which the macros refuse to claim, in `[unique_type_tags]`
+//!   for a key starting with `::` and in `[unique_tags]` otherwise (see
+//!   [`unique_uuid::registry::Registry::reserve`])
+//! - `unreserve <key>`: lifts the reservation of `key`, so that the macros may claim it
+//! - `prune --keep-file <usage.log>`: removes the entries that are not listed in a
+//!   usage log recorded with `UNIQUE_UUID_TRACK_USAGE`
+//! - `compact`: moves the entries of the journal and the pending fragments into the
+//!   registry file (see [`unique_uuid::registry::Registry::compact`])
+//! - `compact-ids [--yes]`: renumbers the ids of the entries densely, keeping their
+//!   UUIDs, and prints the previous and new id of each entry as
+//!   `<section>\t<key>\t<old>\t<new>` lines (see
+//!   [`unique_uuid::registry::Registry::compact_ids`]). Renumbering breaks the
+//!   consumers of the previous ids, so without `--yes` the registry is left untouched
+//!   and the command fails after printing the new ids
+//! - `export [--format json|csv]`: prints the registry in the format documented in
+//!   [`unique_uuid::registry::Registry::export_json`] and
+//!   [`unique_uuid::registry::Registry::export_csv`]
+//! - `diff <old> [<new>]`: reports the entries added, removed, renamed or whose UUID
+//!   changed between two registries (see [`unique_uuid::registry::Registry::diff`]),
+//!   and fails if a UUID changed. Each side is either a path, or a git revision at
+//!   which the registry is read; `<new>` defaults to the registry
+//! - `merge <base> <ours> <theirs> [--policy error|ours|theirs] [--output <path>]`:
+//!   merges two registries derived from `base` (see
+//!   [`unique_uuid::registry::Registry::merge`]), and writes the result to `<ours>`, or
+//!   to `--output`. Keys whose UUID changed differently on both sides fail the merge,
+//!   unless `--policy` prefers a side. The arguments follow the `%O %A %B` placeholders
+//!   of a git merge driver, so that `types.toml` merges can be delegated to the command
+//! - `markdown [--namespace X] [--group-by namespace|crate] [--no-metadata]`: prints an
+//!   inventory of the registry as a Markdown page (see
+//!   [`unique_uuid::registry::Registry::write_markdown`]), without the description,
+//!   crate, creation and groups columns with `--no-metadata`
+//! - `summary [--json]`: prints entry counts per section, namespace and minting crate
+//!   (see [`unique_uuid::registry::Registry::summary`])
+//! - `stats [--json]`: prints health indicators of the registry: legacy keys, entries
+//!   without metadata, UUID versions and namespaces (see
+//!   [`unique_uuid::registry::Registry::stats`])
+//! - `codegen [--output <path>]`: regenerates the Rust module of
+//!   [`unique_uuid::registry::Registry::to_rust_module`], `types_generated.rs` next to
+//!   the registry by default
+//! - `c-header [--namespace X] [--prefix P] [--output <path>]`: regenerates the C header
+//!   of [`unique_uuid::registry::Registry::to_c_header`], `types.h` next to the
+//!   registry by default
+//! - `typescript [--namespace X] [--output <path>]`: regenerates the TypeScript module
+//!   of [`unique_uuid::registry::Registry::to_typescript`], `types.ts` next to the
+//!   registry by default
+//! - `flatten [--output <path>]`: merges a registry directory, e.g. `types.d`, into a
+//!   single registry file, `types.toml` next to it by default (see
+//!   [`unique_uuid::registry::Registry::load`])
+//! - `inspect <binary> [--json]`: lists the type tags embedded in a compiled binary
+//!   built with the `tag-section` feature, as `<key>\t<uuid>` lines sorted by key,
+//!   without running it (see [`unique_uuid::tag_section`]). The registry is not read
+//!
+//! Every command accepts `--registry <path>` (`types.toml` by default); a path ending
+//! with `.json` is read and written as JSON (see [`unique_uuid::registry::Format`]).
+//! Commands that modify the registry write it back with the same deterministic format
+//! as the macros, so the file does not churn, after compacting its journal.
+//!
+//! # Example
+//!
+//! ```rust
+//! use unique_uuid_admin::Cli;
+//!
+//! let registry = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/shop.toml");
+//! let cli = Cli::parse(["list", "--namespace", "orders", "--registry", registry].map(String::from))
+//!     .unwrap();
+//!
+//! let mut out = Vec::new();
+//! assert!(cli.run(&mut out).unwrap());
+//! assert_eq!(
+//!     String::from_utf8(out).unwrap(),
+//!     "unique_tags\torders.cancelled\td7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\t-\t-\t-\n\
+//!      unique_tags\torders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa\tbilling\t2024-06-01T12:00:00Z\t-\n"
+//! );
+//! ```
+use std::{
+    fmt,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use unique_uuid::{
+    registry::{
+        self, CHeaderOptions, CodegenError, CompactIdsPolicy, Format, MarkdownGrouping,
+        MarkdownOptions, MergeError, MergePolicy, Registry, RegistryStats, RegistrySummary,
+        RenameError, ReservationError, RustModuleOptions, Section, TypeScriptOptions, UsageLog,
+    },
+    tag_section,
+};
+
+/// The usage message of the binary.
+pub const USAGE: &str = "\
+Usage: unique-uuid-admin [--registry <path>] <command>
+
+Commands:
+  list [--namespace <namespace>] [--json]  List the registry entries
+  verify                                   Check the registry for issues
+  rename <old> <new>                       Rename an entry, keeping its UUID
+  reserve <key> [--owner <owner>]          Reserve a key, which the macros cannot claim
+  unreserve <key>                          Lift the reservation of a key
+  prune --keep-file <usage.log>            Remove the entries missing from a usage log
+  compact                                  Move the journal and fragments into the registry
+  compact-ids [--yes]                      Renumber the ids densely, keeping the UUIDs
+  export [--format json|csv]               Print the registry for other tooling
+  diff <old> [<new>]                       Compare registries, given as paths or git revisions
+  merge <base> <ours> <theirs> [--policy error|ours|theirs] [--output <path>]
+                                           Merge two registries, into <ours> by default
+  markdown [--namespace <namespace>] [--group-by namespace|crate] [--no-metadata]
+                                           Print a Markdown inventory of the registry
+  summary [--json]                         Print entry counts per namespace and crate
+  stats [--json]                           Print health indicators of the registry
+  codegen [--output <path>]                Regenerate the Rust constants module
+  c-header [--namespace <namespace>] [--prefix <prefix>] [--output <path>]
+                                           Regenerate the C header of tag constants
+  typescript [--namespace <namespace>] [--output <path>]
+                                           Regenerate the TypeScript module of tag constants
+  flatten [--output <path>]                Merge a registry directory into a single file
+  inspect <binary> [--json]                List the type tags embedded in a compiled binary
+
+Options:
+  --registry <path>  The registry file to operate on [default: types.toml]
+  -h, --help         Print this message";
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cli {
+    /// The registry file to operate on.
+    pub registry: PathBuf,
+
+    /// The command to run.
+    pub command: Command,
+}
+
+/// A command of the binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Print the usage message.
+    Help,
+
+    /// List the entries, optionally restricted to a namespace.
+    ///
+    /// Each entry is printed as a `<section>\t<key>\t<uuid>\t<crate>\t<created>\t<source>`
+    /// line, with `-` for the metadata missing from legacy entries.
+    List {
+        /// Only list the entries of this namespace.
+        namespace: Option<String>,
+        /// Print the entries as a JSON array instead of tab-separated lines.
+        json: bool,
+    },
+
+    /// Report validation issues.
+    Verify,
+
+    /// Move an entry to a new key.
+    Rename {
+        /// The current key of the entry.
+        old: String,
+        /// The new key of the entry.
+        new: String,
+    },
+
+    /// Reserve a key for future use.
+    Reserve {
+        /// The key to reserve.
+        key: String,
+        /// The person or team to contact to lift the reservation.
+        owner: Option<String>,
+    },
+
+    /// Lift the reservation of a key.
+    Unreserve {
+        /// The reserved key.
+        key: String,
+    },
+
+    /// Remove the entries missing from a usage log.
+    Prune {
+        /// The usage log listing the entries to keep.
+        keep_file: PathBuf,
+    },
+
+    /// Move the entries of the journal into the registry file.
+    Compact,
+
+    /// Renumber the ids of the entries densely.
+    CompactIds {
+        /// [`CompactIdsPolicy::Renumber`] with `--yes`, and otherwise
+        /// [`CompactIdsPolicy::DryRun`].
+        policy: CompactIdsPolicy,
+    },
+
+    /// Print the registry for other tooling.
+    Export {
+        /// The output format.
+        format: ExportFormat,
+    },
+
+    /// Compare two registries.
+    Diff {
+        /// The old registry, as a path or a git revision.
+        old: String,
+        /// The new registry, as a path or a git revision, the registry itself if `None`.
+        new: Option<String>,
+    },
+
+    /// Merge two registries derived from a common one.
+    Merge {
+        /// The registry both sides derive from.
+        base: PathBuf,
+        /// Our side of the merge.
+        ours: PathBuf,
+        /// Their side of the merge.
+        theirs: PathBuf,
+        /// How keys whose UUID changed differently on both sides are resolved.
+        policy: MergePolicy,
+        /// The merged registry, `ours` if `None`.
+        output: Option<PathBuf>,
+    },
+
+    /// Print a Markdown inventory of the registry.
+    Markdown {
+        /// The namespace, grouping and columns of the inventory.
+        options: MarkdownOptions,
+    },
+
+    /// Print entry counts per section, namespace and minting crate.
+    Summary {
+        /// Print the summary as a JSON object instead of tables.
+        json: bool,
+    },
+
+    /// Print health indicators of the registry.
+    Stats {
+        /// Print the indicators as a JSON object instead of a report.
+        json: bool,
+    },
+
+    /// Regenerate the Rust constants module.
+    Codegen {
+        /// The generated module, `types_generated.rs` next to the registry if `None`.
+        output: Option<PathBuf>,
+    },
+
+    /// Regenerate the C header of tag constants.
+    CHeader {
+        /// Only declare the entries of this namespace.
+        namespace: Option<String>,
+        /// The prefix of the identifiers, `UU_` if `None`.
+        prefix: Option<String>,
+        /// The generated header, `types.h` next to the registry if `None`.
+        output: Option<PathBuf>,
+    },
+
+    /// Regenerate the TypeScript module of tag constants.
+    TypeScript {
+        /// Only declare the entries of this namespace.
+        namespace: Option<String>,
+        /// The generated module, `types.ts` next to the registry if `None`.
+        output: Option<PathBuf>,
+    },
+
+    /// Merge a registry directory into a single registry file.
+    Flatten {
+        /// The registry file, the directory with a `.toml` extension if `None`.
+        output: Option<PathBuf>,
+    },
+
+    /// List the type tags embedded in a compiled binary.
+    Inspect {
+        /// The ELF, Mach-O or PE file to read.
+        binary: PathBuf,
+        /// Print the tags as a JSON array instead of tab-separated lines.
+        json: bool,
+    },
+}
+
+/// The output format of the `export` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// A JSON array of entries.
+    #[default]
+    Json,
+
+    /// CSV with a header line.
+    Csv,
+}
+
+/// An error that occurred while parsing or running a command.
+#[derive(Debug)]
+pub enum Error {
+    /// The command line is invalid.
+    Usage(String),
+
+    /// The registry or the usage log could not be loaded or saved.
+    Registry(registry::Error),
+
+    /// The entry could not be renamed.
+    Rename(RenameError),
+
+    /// The key could not be reserved, or its reservation lifted.
+    Reservation(ReservationError),
+
+    /// The Rust module could not be generated.
+    Codegen(CodegenError),
+
+    /// The registries could not be merged.
+    Merge(MergeError),
+
+    /// The registry could not be read from a git revision.
+    Git(String),
+
+    /// The type tags of a binary could not be read.
+    Inspect(String),
+
+    /// The output could not be written.
+    Output(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(message) => write!(f, "{}", message),
+            Error::Registry(err) => write!(f, "{}", err),
+            Error::Rename(err) => write!(f, "{}", err),
+            Error::Reservation(err) => write!(f, "{}", err),
+            Error::Codegen(err) => write!(f, "{}", err),
+            Error::Merge(err) => write!(f, "cannot merge the registries: {}", err),
+            Error::Git(message) | Error::Inspect(message) => write!(f, "{}", message),
+            Error::Output(err) => write!(f, "failed to write output: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Usage(_) | Error::Git(_) | Error::Inspect(_) => None,
+            Error::Registry(err) => Some(err),
+            Error::Rename(err) => Some(err),
+            Error::Reservation(err) => Some(err),
+            Error::Codegen(err) => Some(err),
+            Error::Merge(err) => Some(err),
+            Error::Output(err) => Some(err),
+        }
+    }
+}
+
+impl From<registry::Error> for Error {
+    fn from(err: registry::Error) -> Self {
+        Error::Registry(err)
+    }
+}
+
+impl From<RenameError> for Error {
+    fn from(err: RenameError) -> Self {
+        Error::Rename(err)
+    }
+}
+
+impl From<ReservationError> for Error {
+    fn from(err: ReservationError) -> Self {
+        Error::Reservation(err)
+    }
+}
+
+impl From<CodegenError> for Error {
+    fn from(err: CodegenError) -> Self {
+        Error::Codegen(err)
+    }
+}
+
+impl From<MergeError> for Error {
+    fn from(err: MergeError) -> Self {
+        Error::Merge(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Output(err)
+    }
+}
+
+impl Cli {
+    /// Parses the command line arguments, without the program name.
+    ///
+    /// # Errors
+    /// Returns [`Error::Usage`] if the arguments are invalid.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid_admin::{Cli, Command};
+    ///
+    /// let cli = Cli::parse(["rename", "::OrderRecord", "::Order"].map(String::from)).unwrap();
+    /// assert_eq!(cli.registry.to_str(), Some("types.toml"));
+    /// assert_eq!(
+    ///     cli.command,
+    ///     Command::Rename { old: "::OrderRecord".into(), new: "::Order".into() }
+    /// );
+    ///
+    /// assert!(Cli::parse(["rename", "::OrderRecord"].map(String::from)).is_err());
+    /// assert!(Cli::parse(["list", "--frobnicate"].map(String::from)).is_err());
+    /// ```
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut registry = PathBuf::from("types.toml");
+        let mut namespace = None;
+        let mut json = false;
+        let mut keep_file = None;
+        let mut format = ExportFormat::default();
+        let mut output = None;
+        let mut prefix = None;
+        let mut owner = None;
+        let mut grouping = MarkdownGrouping::default();
+        let mut metadata = true;
+        let mut yes = false;
+        let mut policy = MergePolicy::default();
+        let mut positional = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| Error::Usage(format!("`{}` expects a value", name)))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    return Ok(Self {
+                        registry,
+                        command: Command::Help,
+                    })
+                }
+                "--registry" => registry = PathBuf::from(value("--registry")?),
+                "--namespace" => namespace = Some(value("--namespace")?),
+                "--prefix" => prefix = Some(value("--prefix")?),
+                "--owner" => owner = Some(value("--owner")?),
+                "--output" => output = Some(PathBuf::from(value("--output")?)),
+                "--keep-file" => keep_file = Some(PathBuf::from(value("--keep-file")?)),
+                "--format" => {
+                    format = match value("--format")?.as_str() {
+                        "json" => ExportFormat::Json,
+                        "csv" => ExportFormat::Csv,
+                        other => {
+                            return Err(Error::Usage(format!("unknown export format `{}`", other)))
+                        }
+                    }
+                }
+                "--group-by" => {
+                    grouping = match value("--group-by")?.as_str() {
+                        "namespace" => MarkdownGrouping::Namespace,
+                        "crate" => MarkdownGrouping::Crate,
+                        other => return Err(Error::Usage(format!("unknown grouping `{}`", other))),
+                    }
+                }
+                "--policy" => {
+                    policy = match value("--policy")?.as_str() {
+                        "error" => MergePolicy::Error,
+                        "ours" => MergePolicy::PreferOurs,
+                        "theirs" => MergePolicy::PreferTheirs,
+                        other => return Err(Error::Usage(format!("unknown policy `{}`", other))),
+                    }
+                }
+                "--no-metadata" => metadata = false,
+                "--json" => json = true,
+                "--yes" => yes = true,
+                _ if arg.starts_with('-') => {
+                    return Err(Error::Usage(format!("unknown option `{}`", arg)))
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let command = match positional.next().as_deref() {
+            Some("list") => Command::List { namespace, json },
+            Some("verify") => Command::Verify,
+            Some("rename") => match (positional.next(), positional.next()) {
+                (Some(old), Some(new)) => Command::Rename { old, new },
+                _ => return Err(Error::Usage("`rename` expects <old> and <new>".into())),
+            },
+            Some("reserve") => match positional.next() {
+                Some(key) => Command::Reserve { key, owner },
+                None => return Err(Error::Usage("`reserve` expects <key>".into())),
+            },
+            Some("unreserve") => match positional.next() {
+                Some(key) => Command::Unreserve { key },
+                None => return Err(Error::Usage("`unreserve` expects <key>".into())),
+            },
+            Some("prune") => Command::Prune {
+                keep_file: keep_file
+                    .ok_or_else(|| Error::Usage("`prune` expects `--keep-file`".into()))?,
+            },
+            Some("compact") => Command::Compact,
+            Some("compact-ids") => Command::CompactIds {
+                policy: if yes {
+                    CompactIdsPolicy::Renumber
+                } else {
+                    CompactIdsPolicy::DryRun
+                },
+            },
+            Some("export") => Command::Export { format },
+            Some("diff") => match positional.next() {
+                Some(old) => Command::Diff {
+                    old,
+                    new: positional.next(),
+                },
+                None => return Err(Error::Usage("`diff` expects <old>".into())),
+            },
+            Some("merge") => match (positional.next(), positional.next(), positional.next()) {
+                (Some(base), Some(ours), Some(theirs)) => Command::Merge {
+                    base: PathBuf::from(base),
+                    ours: PathBuf::from(ours),
+                    theirs: PathBuf::from(theirs),
+                    policy,
+                    output,
+                },
+                _ => {
+                    return Err(Error::Usage(
+                        "`merge` expects <base>, <ours> and <theirs>".into(),
+                    ))
+                }
+            },
+            Some("markdown") => Command::Markdown {
+                options: MarkdownOptions {
+                    namespace,
+                    grouping,
+                    description: metadata,
+                    crate_name: metadata,
+                    created: metadata,
+                    groups: metadata,
+                },
+            },
+            Some("summary") => Command::Summary { json },
+            Some("stats") => Command::Stats { json },
+            Some("codegen") => Command::Codegen { output },
+            Some("c-header") => Command::CHeader {
+                namespace,
+                prefix,
+                output,
+            },
+            Some("typescript") => Command::TypeScript { namespace, output },
+            Some("flatten") => Command::Flatten { output },
+            Some("inspect") => match positional.next() {
+                Some(binary) => Command::Inspect {
+                    binary: PathBuf::from(binary),
+                    json,
+                },
+                None => return Err(Error::Usage("`inspect` expects <binary>".into())),
+            },
+            Some(other) => return Err(Error::Usage(format!("unknown command `{}`", other))),
+            None => return Err(Error::Usage("missing command".into())),
+        };
+        if let Some(extra) = positional.next() {
+            return Err(Error::Usage(format!("unexpected argument `{}`", extra)));
+        }
+
+        Ok(Self { registry, command })
+    }
+
+    /// Runs the command, writing its report to `out`.
+    ///
+    /// Returns `Ok(false)` if the command completed but found a problem, i.e. if
+    /// `verify` reported issues, or if `compact-ids` was not confirmed with `--yes`.
+    ///
+    /// # Errors
+    /// Returns an error if a file cannot be read or written, or if the entry to rename
+    /// cannot be renamed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid_admin::Cli;
+    ///
+    /// let registry = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/duplicate_uuid.toml");
+    /// let cli = Cli::parse(["--registry", registry, "verify"].map(String::from)).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// assert!(!cli.run(&mut out).unwrap());
+    /// assert!(String::from_utf8(out).unwrap().starts_with("error: "));
+    /// ```
+    ///
+    /// ```rust
+    /// use unique_uuid_admin::Cli;
+    ///
+    /// let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    /// let registry = std::env::temp_dir().join("unique-uuid-admin-doctest-prune.toml");
+    /// std::fs::copy(format!("{}/shop.toml", fixtures), &registry).unwrap();
+    ///
+    /// let args = [
+    ///     "prune".to_string(),
+    ///     "--keep-file".to_string(),
+    ///     format!("{}/usage.log", fixtures),
+    ///     "--registry".to_string(),
+    ///     registry.display().to_string(),
+    /// ];
+    /// let mut out = Vec::new();
+    /// assert!(Cli::parse(args).unwrap().run(&mut out).unwrap());
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "pruned `orders.cancelled` from [unique_tags]\n\
+    ///      pruned `payments.refunded` from [unique_tags]\n"
+    /// );
+    ///
+    /// // The pinned entry is kept
+    /// assert_eq!(
+    ///     std::fs::read_to_string(&registry).unwrap(),
+    ///     "[unique_tags]\n\
+    ///      \"orders.created\" = { crate = \"billing\", created = \"2024-06-01T12:00:00Z\", uuid = \"2af1216e-7042-406a-a577-c39a7e7c3faa\" }\n\
+    ///      \n\
+    ///      [unique_type_tags]\n\
+    ///      \"::Invoice\" = \"8701a796-9ffe-4723-89cf-ebcaaafe9ebb\"\n\
+    ///      \"::OrderRecord\" = { pinned = true, uuid = \"6aac8026-6ca8-4c82-ac39-d74bedf1ac50\" }\n"
+    /// );
+    /// ```
+    pub fn run(&self, out: &mut dyn Write) -> Result<bool, Error> {
+        match &self.command {
+            Command::Help => writeln!(out, "{}", USAGE)?,
+            Command::List { namespace, json } => {
+                let registry = Registry::load(&self.registry)?;
+                let entries = Section::ALL.into_iter().flat_map(|section| {
+                    registry
+                        .entries(section)
+                        .map(move |(key, entry)| (section, key, entry))
+                });
+                let entries = entries.filter(|(_, key, _)| {
+                    namespace
+                        .as_ref()
+                        .is_none_or(|namespace| registry::in_namespace(key, namespace))
+                });
+
+                if *json {
+                    let entries: Vec<_> = entries
+                        .map(|(section, key, entry)| {
+                            serde_json::json!({
+                                "section": section.name(),
+                                "key": key,
+                                "uuid": entry.uuid().to_string(),
+                                "crate": entry.crate_name(),
+                                "created": entry.created(),
+                                "source": entry.source(),
+                            })
+                        })
+                        .collect();
+                    writeln!(out, "{}", serde_json::Value::Array(entries))?;
+                } else {
+                    for (section, key, entry) in entries {
+                        writeln!(
+                            out,
+                            "{}\t{}\t{}\t{}\t{}\t{}",
+                            section,
+                            key,
+                            entry.uuid(),
+                            entry.crate_name().unwrap_or("-"),
+                            entry.created().unwrap_or("-"),
+                            entry.source().unwrap_or("-")
+                        )?;
+                    }
+                }
+            }
+            Command::Verify => {
+                let registry = Registry::load(&self.registry)?;
+                let issues = registry.validate();
+                for issue in &issues {
+                    writeln!(out, "{}", issue)?;
+                }
+                for reservation in registry.reservations() {
+                    writeln!(out, "{}", reservation)?;
+                }
+                return Ok(issues.is_empty());
+            }
+            Command::Rename { old, new } => {
+                let section = Registry::update(&self.registry, |registry| {
+                    Ok::<_, Error>(registry.rename(old, new)?)
+                })?;
+                writeln!(out, "renamed `{}` to `{}` in [{}]", old, new, section)?;
+            }
+            Command::Reserve { key, owner } => {
+                let section = if key.starts_with("::") {
+                    Section::UniqueTypeTags
+                } else {
+                    Section::UniqueTags
+                };
+                let tag = Registry::update(&self.registry, |registry| {
+                    Ok::<_, Error>(registry.reserve(section, key, owner.as_deref())?)
+                })?;
+                writeln!(out, "reserved `{}` in [{}] as {}", key, section, tag.0)?;
+            }
+            Command::Unreserve { key } => {
+                let section = Registry::update(&self.registry, |registry| {
+                    Ok::<_, Error>(registry.lift_reservation(key)?)
+                })?;
+                writeln!(out, "lifted the reservation of `{}` in [{}]", key, section)?;
+            }
+            Command::Prune { keep_file } => {
+                let keep = UsageLog::load(keep_file)?.keys();
+                let pruned = Registry::update(&self.registry, |registry| {
+                    Ok::<_, Error>(registry.prune(&keep))
+                })?;
+                for entry in pruned {
+                    writeln!(out, "pruned `{}` from [{}]", entry.key, entry.section)?;
+                }
+            }
+            Command::Compact => {
+                let merged = Registry::compact(&self.registry)?;
+                writeln!(out, "compacted {} pending entries", merged)?;
+            }
+            Command::CompactIds { policy } => {
+                // A dry run writes nothing, not even the journal entries
+                let remap = if *policy == CompactIdsPolicy::DryRun {
+                    Registry::load(&self.registry)?.compact_ids(*policy)
+                } else {
+                    Registry::update(&self.registry, |registry| {
+                        Ok::<_, Error>(registry.compact_ids(*policy))
+                    })?
+                };
+                for entry in &remap.entries {
+                    writeln!(
+                        out,
+                        "{}\t{}\t{}\t{}",
+                        entry.section, entry.key, entry.old, entry.new
+                    )?;
+                }
+                if *policy == CompactIdsPolicy::DryRun {
+                    return Ok(false);
+                }
+            }
+            Command::Export { format } => {
+                let registry = Registry::load(&self.registry)?;
+                match format {
+                    ExportFormat::Json => registry.export_json(out)?,
+                    ExportFormat::Csv => registry.export_csv(out)?,
+                }
+            }
+            Command::Diff { old, new } => {
+                let old = self.load_revision(old)?;
+                let new = match new {
+                    Some(new) => self.load_revision(new)?,
+                    None => Registry::load(&self.registry)?,
+                };
+                let diff = Registry::diff(&old, &new);
+                write!(out, "{}", diff)?;
+                return Ok(diff.uuid_changed.is_empty());
+            }
+            Command::Merge {
+                base,
+                ours,
+                theirs,
+                policy,
+                output,
+            } => {
+                let merged = Registry::merge(
+                    &Registry::load(base)?,
+                    &Registry::load(ours)?,
+                    &Registry::load(theirs)?,
+                    *policy,
+                )?;
+                let output = output.as_ref().unwrap_or(ours);
+                merged.save(output)?;
+                writeln!(
+                    out,
+                    "merged {} entries into `{}`",
+                    merged.len(),
+                    output.display()
+                )?;
+            }
+            Command::Markdown { options } => {
+                Registry::load(&self.registry)?.write_markdown(out, options)?;
+            }
+            Command::Summary { json } => {
+                let summary = Registry::load(&self.registry)?.summary();
+                if *json {
+                    writeln!(out, "{}", summary_json(&summary))?;
+                } else {
+                    write_summary(out, &summary)?;
+                }
+            }
+            Command::Stats { json } => {
+                let stats = Registry::load(&self.registry)?.stats();
+                if *json {
+                    writeln!(out, "{}", stats_json(&stats))?;
+                } else {
+                    writeln!(out, "{}", stats)?;
+                }
+            }
+            Command::Codegen { output } => {
+                let output = output
+                    .clone()
+                    .unwrap_or_else(|| self.registry.with_file_name("types_generated.rs"));
+                Registry::load(&self.registry)?
+                    .write_rust_module(&output, &RustModuleOptions::default())?;
+                writeln!(out, "generated `{}`", output.display())?;
+            }
+            Command::CHeader {
+                namespace,
+                prefix,
+                output,
+            } => {
+                let output = output
+                    .clone()
+                    .unwrap_or_else(|| self.registry.with_file_name("types.h"));
+                let mut options = CHeaderOptions {
+                    namespace: namespace.clone(),
+                    ..CHeaderOptions::default()
+                };
+                if let Some(prefix) = prefix {
+                    options.prefix = prefix.clone();
+                }
+                Registry::load(&self.registry)?.write_c_header(&output, &options)?;
+                writeln!(out, "generated `{}`", output.display())?;
+            }
+            Command::TypeScript { namespace, output } => {
+                let output = output
+                    .clone()
+                    .unwrap_or_else(|| self.registry.with_file_name("types.ts"));
+                let options = TypeScriptOptions {
+                    namespace: namespace.clone(),
+                };
+                Registry::load(&self.registry)?.write_typescript(&output, &options)?;
+                writeln!(out, "generated `{}`", output.display())?;
+            }
+            Command::Flatten { output } => {
+                if self
+                    .registry
+                    .extension()
+                    .is_none_or(|extension| extension != "d")
+                {
+                    return Err(Error::Usage(format!(
+                        "`flatten` expects a registry directory, `{}` is not one",
+                        self.registry.display()
+                    )));
+                }
+                let output = output
+                    .clone()
+                    .unwrap_or_else(|| self.registry.with_extension("toml"));
+                Registry::load(&self.registry)?.save(&output)?;
+                writeln!(
+                    out,
+                    "flattened `{}` into `{}`",
+                    self.registry.display(),
+                    output.display()
+                )?;
+            }
+            Command::Inspect { binary, json } => {
+                let inspect_error = |message: String| {
+                    Error::Inspect(format!(
+                        "cannot inspect `{}`: {}",
+                        binary.display(),
+                        message
+                    ))
+                };
+                let bytes = std::fs::read(binary).map_err(|err| inspect_error(err.to_string()))?;
+                let mut tags = tag_section::read_binary(&bytes)
+                    .map_err(|err| inspect_error(err.to_string()))?;
+                tags.sort();
+                tags.dedup();
+
+                if *json {
+                    let tags: Vec<_> = tags
+                        .iter()
+                        .map(|tag| serde_json::json!({ "key": tag.key, "uuid": tag.tag.0.to_string() }))
+                        .collect();
+                    writeln!(out, "{}", serde_json::Value::Array(tags))?;
+                } else {
+                    for tag in &tags {
+                        writeln!(out, "{}\t{}", tag.key, tag.tag.0)?;
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Cli {
+    /// Loads a registry from `source`, a path if such a file exists, and otherwise a git
+    /// revision at which the registry file is read.
+    fn load_revision(&self, source: &str) -> Result<Registry, Error> {
+        if std::path::Path::new(source).is_file() {
+            return Ok(Registry::load(source)?);
+        }
+
+        // `./` makes git resolve the path relative to the current directory
+        let object = format!("{}:./{}", source, self.registry.display());
+        let output = std::process::Command::new("git")
+            .args(["show", &object])
+            .output()
+            .map_err(|err| Error::Git(format!("cannot run `git`: {}", err)))?;
+        if !output.status.success() {
+            return Err(Error::Git(format!(
+                "`{}` is neither a file nor a git revision: {}",
+                source,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let contents = String::from_utf8(output.stdout)
+            .map_err(|_| Error::Git(format!("`{}` is not valid UTF-8", object)))?;
+        let format = Format::of(&self.registry);
+        Ok(Registry::parse_format(&contents, format)?)
+    }
+}
+
+/// Writes the summary as aligned tables.
+fn write_summary(out: &mut dyn Write, summary: &RegistrySummary) -> io::Result<()> {
+    writeln!(
+        out,
+        "{} entries ({} structured, {} legacy), {} aliases, {} malformed",
+        summary.entries, summary.structured, summary.legacy, summary.aliases, summary.malformed
+    )?;
+    if let (Some(oldest), Some(newest)) = (&summary.oldest, &summary.newest) {
+        writeln!(out, "created between {} and {}", oldest, newest)?;
+    }
+
+    let tables: [(&str, Vec<(String, usize)>); 3] = [
+        (
+            "section",
+            summary
+                .per_section
+                .iter()
+                .map(|(section, count)| (section.to_string(), *count))
+                .collect(),
+        ),
+        (
+            "namespace",
+            summary
+                .per_namespace
+                .iter()
+                .map(|(namespace, count)| (label(namespace, "(none)"), *count))
+                .collect(),
+        ),
+        (
+            "crate",
+            summary
+                .per_crate
+                .iter()
+                .map(|(name, count)| (label(name, "(unknown)"), *count))
+                .collect(),
+        ),
+    ];
+    for (title, rows) in tables {
+        let width = rows
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .chain([title.len()])
+            .max()
+            .unwrap_or_default();
+        writeln!(out)?;
+        writeln!(out, "{:<width$}  entries", title, width = width)?;
+        for (name, count) in rows {
+            writeln!(out, "{:<width$}  {}", name, count, width = width)?;
+        }
+    }
+    Ok(())
+}
+
+fn label(value: &Option<String>, missing: &str) -> String {
+    value.clone().unwrap_or_else(|| missing.to_string())
+}
+
+/// Returns the summary as a JSON object. Missing namespaces and crates are keyed by
+/// an empty string.
+fn summary_json(summary: &RegistrySummary) -> serde_json::Value {
+    let counts = |map: &std::collections::BTreeMap<Option<String>, usize>| {
+        map.iter()
+            .map(|(key, count)| (key.clone().unwrap_or_default(), serde_json::json!(count)))
+            .collect::<serde_json::Map<_, _>>()
+    };
+    serde_json::json!({
+        "entries": summary.entries,
+        "legacy": summary.legacy,
+        "structured": summary.structured,
+        "aliases": summary.aliases,
+        "malformed": summary.malformed,
+        "per_section": summary
+            .per_section
+            .iter()
+            .map(|(section, count)| (section.to_string(), serde_json::json!(count)))
+            .collect::<serde_json::Map<_, _>>(),
+        "per_namespace": counts(&summary.per_namespace),
+        "per_crate": counts(&summary.per_crate),
+        "oldest": summary.oldest,
+        "newest": summary.newest,
+    })
+}
+
+/// Returns the health indicators as a JSON object, with UUID versions keyed by their
+/// number.
+fn stats_json(stats: &RegistryStats) -> serde_json::Value {
+    serde_json::json!({
+        "entries": stats.entries(),
+        "per_section": stats
+            .per_section
+            .iter()
+            .map(|(section, count)| (section.to_string(), serde_json::json!(count)))
+            .collect::<serde_json::Map<_, _>>(),
+        "legacy_type_keys": stats.legacy_type_keys,
+        "missing_metadata": stats.missing_metadata,
+        "pinned": stats.pinned,
+        "uuid_versions": stats
+            .uuid_versions
+            .iter()
+            .map(|(version, count)| (version.to_string(), serde_json::json!(count)))
+            .collect::<serde_json::Map<_, _>>(),
+        "namespaces": stats.namespaces,
+    })
+}
@@ -0,0 +1,26 @@
+use std::process::ExitCode;
+
+use unique_uuid_admin::{Cli, Error, USAGE};
+
+fn main() -> ExitCode {
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err @ Error::Usage(_)) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match cli.run(&mut std::io::stdout().lock()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
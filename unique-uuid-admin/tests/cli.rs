@@ -0,0 +1,788 @@
+//! Runs the `unique-uuid-admin` binary against the fixture registries.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// Copies a fixture to a scratch location, so that commands can modify it.
+fn scratch_copy(name: &str, test: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("unique-uuid-admin-{}-{}", test, name));
+    fs::copy(fixture(name), &path).unwrap();
+    path
+}
+
+fn admin(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_unique-uuid-admin"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+#[test]
+fn list_prints_every_entry() {
+    let registry = fixture("shop.toml");
+    let output = admin(&["list", "--registry", registry.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output).lines().count(), 5);
+    assert!(stdout(&output).contains(
+        "unique_tags\torders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa\tbilling\t2024-06-01T12:00:00Z\t-\n"
+    ));
+    assert!(stdout(&output).contains(
+        "unique_type_tags\t::OrderRecord\t6aac8026-6ca8-4c82-ac39-d74bedf1ac50\t-\t-\t-\n"
+    ));
+}
+
+#[test]
+fn list_filters_by_namespace_as_json() {
+    let registry = fixture("shop.toml");
+    let output = admin(&[
+        "list",
+        "--json",
+        "--namespace",
+        "payments",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "[{\"crate\":null,\"created\":null,\"key\":\"payments.refunded\",\"section\":\"unique_tags\",\
+         \"source\":null,\"uuid\":\"ce4baeef-bd9c-4932-88be-24eb1033f695\"}]\n"
+    );
+}
+
+#[test]
+fn list_prints_the_source_of_the_entries() {
+    let registry = fixture("sourced.toml");
+    let output = admin(&["list", "--registry", registry.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "unique_tags\torders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa\tbilling\t\
+         2024-06-01T12:00:00Z\tsrc/orders.rs:12\n"
+    );
+}
+
+#[test]
+fn verify_fails_on_issues() {
+    let valid = admin(&[
+        "verify",
+        "--registry",
+        fixture("shop.toml").to_str().unwrap(),
+    ]);
+    assert!(valid.status.success());
+    assert_eq!(stdout(&valid), "");
+
+    let invalid = admin(&[
+        "verify",
+        "--registry",
+        fixture("duplicate_uuid.toml").to_str().unwrap(),
+    ]);
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(stdout(&invalid).contains("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+}
+
+#[test]
+fn rename_keeps_the_uuid() {
+    let registry = scratch_copy("shop.toml", "rename");
+    let output = admin(&[
+        "rename",
+        "::OrderRecord",
+        "::Order",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    let contents = fs::read_to_string(&registry).unwrap();
+    assert!(contents.contains(
+        "\"::Order\" = { pinned = true, uuid = \"6aac8026-6ca8-4c82-ac39-d74bedf1ac50\" }\n"
+    ));
+    assert!(!contents.contains("::OrderRecord"));
+
+    // Renaming onto an existing key is refused and leaves the file untouched
+    let output = admin(&[
+        "rename",
+        "::Order",
+        "::Invoice",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(fs::read_to_string(&registry).unwrap(), contents);
+}
+
+#[test]
+fn json_registries_are_written_back_as_json() {
+    let registry = scratch_copy("shop.json", "json");
+    let list = admin(&["list", "--registry", registry.to_str().unwrap()]);
+    assert!(list.status.success());
+    assert_eq!(
+        stdout(&list),
+        stdout(&admin(&[
+            "list",
+            "--registry",
+            fixture("shop.toml").to_str().unwrap()
+        ]))
+    );
+
+    let output = admin(&[
+        "rename",
+        "::OrderRecord",
+        "::Order",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    let contents = fs::read_to_string(&registry).unwrap();
+    assert_eq!(
+        contents,
+        fs::read_to_string(fixture("shop.json"))
+            .unwrap()
+            .replace("\"::OrderRecord\"", "\"::Order\"")
+    );
+}
+
+#[test]
+fn prune_removes_unused_entries() {
+    let registry = scratch_copy("shop.toml", "prune");
+    let output = admin(&[
+        "prune",
+        "--keep-file",
+        fixture("usage.log").to_str().unwrap(),
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    let contents = fs::read_to_string(&registry).unwrap();
+    assert!(contents.contains("orders.created"));
+    assert!(!contents.contains("orders.cancelled"));
+    assert!(!contents.contains("payments.refunded"));
+}
+
+#[test]
+fn reserved_keys_are_listed_and_kept_until_lifted() {
+    let registry = scratch_copy("shop.toml", "reserve");
+    let path = registry.to_str().unwrap();
+    let output = admin(&[
+        "reserve",
+        "handshake.v2",
+        "--owner",
+        "protocol-team",
+        "--registry",
+        path,
+    ]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("reserved `handshake.v2` in [unique_tags] as "));
+    let output = admin(&["reserve", "::Handshake", "--registry", path]);
+    assert!(output.status.success());
+
+    // Reservations are listed, but are not issues
+    let output = admin(&["verify", "--registry", path]);
+    assert!(output.status.success());
+    let lines: Vec<_> = stdout(&output).lines().collect();
+    assert_eq!(lines.len(), 2, "{:?}", lines);
+    assert!(lines[0].starts_with("reserved: `handshake.v2` in [unique_tags] ("));
+    assert!(lines[0].ends_with("), owned by `protocol-team`"));
+    assert!(lines[1].starts_with("reserved: `::Handshake` in [unique_type_tags] ("));
+
+    // Keys in use cannot be reserved
+    let output = admin(&["reserve", "orders.created", "--registry", path]);
+    assert_eq!(output.status.code(), Some(1));
+
+    // Pruning never removes a reservation
+    let usage = fixture("usage.log");
+    let output = admin(&[
+        "prune",
+        "--keep-file",
+        usage.to_str().unwrap(),
+        "--registry",
+        path,
+    ]);
+    assert!(output.status.success());
+    let contents = fs::read_to_string(&registry).unwrap();
+    assert!(contents.contains("\"handshake.v2\" = { crate = "));
+    assert!(contents.contains("owner = \"protocol-team\", reserved = true, uuid = "));
+    assert!(contents.contains("\"::Handshake\""));
+
+    let output = admin(&["unreserve", "handshake.v2", "--registry", path]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "lifted the reservation of `handshake.v2` in [unique_tags]\n"
+    );
+    let contents = fs::read_to_string(&registry).unwrap();
+    assert!(contents.contains("\"handshake.v2\""));
+    assert!(!contents.contains("protocol-team"));
+    let output = admin(&["unreserve", "handshake.v2", "--registry", path]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn compact_ids_requires_confirmation() {
+    let registry = scratch_copy("sparse_ids.toml", "compact-ids-dry-run");
+    let output = admin(&["compact-ids", "--registry", registry.to_str().unwrap()]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "unique_tags\torders.cancelled\t9\t1\n\
+         unique_tags\torders.created\t4\t2\n\
+         unique_type_tags\t::Order\t1\t3\n"
+    );
+    assert_eq!(
+        fs::read_to_string(&registry).unwrap(),
+        fs::read_to_string(fixture("sparse_ids.toml")).unwrap()
+    );
+}
+
+#[test]
+fn compact_ids_renumbers_densely() {
+    let registry = scratch_copy("sparse_ids.toml", "compact-ids");
+    let output = admin(&[
+        "compact-ids",
+        "--yes",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "unique_tags\torders.cancelled\t9\t1\n\
+         unique_tags\torders.created\t4\t2\n\
+         unique_type_tags\t::Order\t1\t3\n"
+    );
+    assert_eq!(
+        fs::read_to_string(&registry).unwrap(),
+        "[unique_tags]\n\
+         \"orders.cancelled\" = { id = 1, uuid = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\" }\n\
+         \"orders.created\" = { id = 2, uuid = \"2af1216e-7042-406a-a577-c39a7e7c3faa\" }\n\
+         \n\
+         [unique_type_tags]\n\
+         \"::Invoice\" = \"8701a796-9ffe-4723-89cf-ebcaaafe9ebb\"\n\
+         \"::Order\" = { id = 3, uuid = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\" }\n\
+         \n\
+         [config]\n\
+         ids = true\n\
+         \n\
+         [ids]\n\
+         last = 3\n"
+    );
+}
+
+#[test]
+fn compact_moves_the_journal_into_the_registry() {
+    let directory = std::env::temp_dir().join("unique-uuid-admin-compact");
+    fs::create_dir_all(&directory).unwrap();
+    let registry = directory.join("types.toml");
+    let journal = directory.join("types.toml.journal");
+    fs::copy(fixture("journal.toml"), &registry).unwrap();
+    fs::copy(fixture("journal.toml.journal"), &journal).unwrap();
+
+    // Journal entries are listed before compaction, the registry file winning conflicts
+    let output = admin(&["list", "--registry", registry.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("orders.shipped\t8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4"));
+    assert!(stdout(&output).contains("orders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa"));
+
+    let output = admin(&["compact", "--registry", registry.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "compacted 2 pending entries\n");
+    assert_eq!(fs::read_to_string(&journal).unwrap(), "");
+    let contents = fs::read_to_string(&registry).unwrap();
+    assert!(contents.contains("\"::Invoice\" = \"1457ff91-dcf2-421e-89f1-8a244944ca74\"\n"));
+    assert!(!contents.contains("d7edd7b5"));
+    assert!(!contents.contains("orders.refu"));
+
+    // Compacting again is a no-op
+    let output = admin(&["compact", "--registry", registry.to_str().unwrap()]);
+    assert_eq!(stdout(&output), "compacted 0 pending entries\n");
+    assert_eq!(fs::read_to_string(&registry).unwrap(), contents);
+}
+
+#[test]
+fn rename_compacts_the_journal_first() {
+    let directory = std::env::temp_dir().join("unique-uuid-admin-rename-journal");
+    fs::create_dir_all(&directory).unwrap();
+    let registry = directory.join("types.toml");
+    let journal = directory.join("types.toml.journal");
+    fs::copy(fixture("journal.toml"), &registry).unwrap();
+    fs::copy(fixture("journal.toml.journal"), &journal).unwrap();
+
+    let output = admin(&[
+        "rename",
+        "::Invoice",
+        "::Bill",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&journal).unwrap(), "");
+    let contents = fs::read_to_string(&registry).unwrap();
+    assert!(contents.contains("\"::Bill\" = \"1457ff91-dcf2-421e-89f1-8a244944ca74\"\n"));
+    assert!(!contents.contains("::Invoice"));
+}
+
+#[test]
+fn untouched_registry_is_written_back_identically() {
+    let registry = scratch_copy("shop.toml", "roundtrip");
+    let output = admin(&[
+        "prune",
+        "--keep-file",
+        fixture("shop_usage.log").to_str().unwrap(),
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&registry).unwrap(),
+        fs::read_to_string(fixture("shop.toml")).unwrap()
+    );
+}
+
+#[test]
+fn export_matches_the_library_output() {
+    let registry = fixture("shop.toml");
+    let output = admin(&[
+        "export",
+        "--format",
+        "csv",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "key,uuid,kind,namespace,aliases,description,groups\r\n\
+         orders.cancelled,d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24,tag,orders,,,\r\n\
+         orders.created,2af1216e-7042-406a-a577-c39a7e7c3faa,tag,orders,,,\r\n\
+         payments.refunded,ce4baeef-bd9c-4932-88be-24eb1033f695,tag,payments,,,\r\n\
+         ::Invoice,8701a796-9ffe-4723-89cf-ebcaaafe9ebb,type,,,,\r\n\
+         ::OrderRecord,6aac8026-6ca8-4c82-ac39-d74bedf1ac50,type,,,,\r\n"
+    );
+}
+
+#[test]
+fn codegen_writes_the_module_next_to_the_registry() {
+    let directory = std::env::temp_dir().join("unique-uuid-admin-codegen");
+    fs::create_dir_all(&directory).unwrap();
+    let registry = directory.join("types.toml");
+    fs::copy(fixture("shop.toml"), &registry).unwrap();
+
+    let output = admin(&["codegen", "--registry", registry.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    let module = fs::read_to_string(directory.join("types_generated.rs")).unwrap();
+    assert!(module.contains("pub const ORDERS_CANCELLED: unique_uuid::UniqueTag"));
+    assert!(module.contains("pub const ORDER_RECORD: unique_uuid::UniqueTag"));
+}
+
+#[test]
+fn c_header_declares_the_namespace() {
+    let header = std::env::temp_dir().join("unique-uuid-admin-c-header.h");
+    let output = admin(&[
+        "c-header",
+        "--namespace",
+        "orders",
+        "--prefix",
+        "SHOP_",
+        "--registry",
+        fixture("shop.toml").to_str().unwrap(),
+        "--output",
+        header.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    let header = fs::read_to_string(header).unwrap();
+    assert!(header.contains(
+        "static const uint8_t SHOP_TAG_ORDERS_CREATED[16] = {0x2a, 0xf1, 0x21, 0x6e, 0x70, \
+         0x42, 0x40, 0x6a, 0xa5, 0x77, 0xc3, 0x9a, 0x7e, 0x7c, 0x3f, 0xaa};"
+    ));
+    assert!(header.contains("#ifndef SHOP_TAGS_H"));
+    assert!(!header.contains("PAYMENTS"));
+    assert!(!header.contains("[unique_type_tags]"));
+}
+
+#[test]
+fn typescript_exports_the_lookup_object() {
+    let module = std::env::temp_dir().join("unique-uuid-admin-typescript.ts");
+    let output = admin(&[
+        "typescript",
+        "--namespace",
+        "orders",
+        "--registry",
+        fixture("shop.toml").to_str().unwrap(),
+        "--output",
+        module.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    let module = fs::read_to_string(module).unwrap();
+    assert!(module.contains(
+        "export const ORDERS_CREATED = \"2af1216e-7042-406a-a577-c39a7e7c3faa\" as const;"
+    ));
+    assert!(module.contains("  \"orders.created\": ORDERS_CREATED,\n"));
+    assert!(!module.contains("PAYMENTS"));
+}
+
+#[test]
+fn codegen_reports_conflicting_identifiers() {
+    let module = std::env::temp_dir().join("unique-uuid-admin-codegen-conflict.rs");
+    let _ = fs::remove_file(&module);
+    let output = admin(&[
+        "codegen",
+        "--registry",
+        fixture("conflicting_identifiers.toml").to_str().unwrap(),
+        "--output",
+        module.to_str().unwrap(),
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(std::str::from_utf8(&output.stderr)
+        .unwrap()
+        .contains("`orders-created` `orders.created`"));
+    assert!(!module.exists());
+}
+
+#[test]
+fn summary_renders_tables() {
+    let registry = fixture("shop.toml");
+    let output = admin(&["summary", "--registry", registry.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "5 entries (2 structured, 3 legacy), 0 aliases, 0 malformed\n\
+         created between 2024-06-01T12:00:00Z and 2024-06-01T12:00:00Z\n\
+         \n\
+         section           entries\n\
+         unique_tags       3\n\
+         unique_type_tags  2\n\
+         \n\
+         namespace  entries\n\
+         (none)     2\n\
+         orders     2\n\
+         payments   1\n\
+         \n\
+         crate      entries\n\
+         (unknown)  4\n\
+         billing    1\n"
+    );
+}
+
+#[test]
+fn summary_as_json() {
+    let registry = fixture("shop.toml");
+    let output = admin(&[
+        "summary",
+        "--json",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("\"per_crate\":{\"\":4,\"billing\":1}"));
+    assert!(stdout(&output).contains("\"per_namespace\":{\"\":2,\"orders\":2,\"payments\":1}"));
+}
+
+#[test]
+fn stats_renders_a_report() {
+    let registry = fixture("shop.toml");
+    let output = admin(&["stats", "--registry", registry.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "entries: 5 (3 in [unique_tags], 2 in [unique_type_tags])\n\
+         legacy `::Type` keys: 2\n\
+         missing metadata: 4\n\
+         pinned: 1\n\
+         UUID versions: v4: 5\n\
+         namespaces: 2\n"
+    );
+}
+
+#[test]
+fn stats_as_json() {
+    let registry = fixture("shop.toml");
+    let output = admin(&["stats", "--json", "--registry", registry.to_str().unwrap()]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("\"legacy_type_keys\":2"));
+    assert!(stdout(&output).contains("\"uuid_versions\":{\"4\":5}"));
+}
+
+#[test]
+fn diff_detects_renames() {
+    let output = admin(&[
+        "diff",
+        fixture("shop.toml").to_str().unwrap(),
+        fixture("shop_renamed.toml").to_str().unwrap(),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "~ `::OrderRecord` in [unique_type_tags] renamed to `::Order` in [unique_type_tags] \
+         (6aac8026-6ca8-4c82-ac39-d74bedf1ac50)\n\
+         + `orders.shipped` in [unique_tags] (a3e7c969-8413-4f6b-9928-28a140227b0b)\n\
+         - `payments.refunded` in [unique_tags] (ce4baeef-bd9c-4932-88be-24eb1033f695)\n"
+    );
+}
+
+#[test]
+fn diff_fails_on_uuid_change() {
+    let output = admin(&[
+        "diff",
+        fixture("shop.toml").to_str().unwrap(),
+        fixture("shop_uuid_changed.toml").to_str().unwrap(),
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "! `orders.cancelled` in [unique_tags] changed UUID from \
+         d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24 to 8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4\n"
+    );
+}
+
+/// Writes `contents` to a scratch registry of `test`, returning its path.
+fn scratch_registry(test: &str, name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("unique-uuid-admin-{}-{}", test, name));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn merge_unions_keys_and_metadata() {
+    let base = fs::read_to_string(fixture("shop.toml")).unwrap();
+    let ours = scratch_registry(
+        "merge",
+        "ours.toml",
+        &base
+            .replace(
+                "{ crate = \"billing\",",
+                "{ crate = \"billing\", description = \"An order was placed\",",
+            )
+            .replace(
+                "\"payments.refunded\"",
+                "\"orders.shipped\" = \"a3e7c969-8413-4f6b-9928-28a140227b0b\"\n\"payments.refunded\"",
+            ),
+    );
+    let theirs = scratch_registry(
+        "merge",
+        "theirs.toml",
+        &base
+            .replace(
+                "{ crate = \"billing\",",
+                "{ crate = \"billing\", owner = \"sales\",",
+            )
+            .replace(
+                "\"payments.refunded\" = \"ce4baeef-bd9c-4932-88be-24eb1033f695\"",
+                "\"payments.captured\" = \"0f847191-bc85-4d75-a9a9-f46e9200e95d\"\n\
+                 \"payments.refunded\" = \"ce4baeef-bd9c-4932-88be-24eb1033f695\"",
+            ),
+    );
+    let output = admin(&[
+        "merge",
+        fixture("shop.toml").to_str().unwrap(),
+        ours.to_str().unwrap(),
+        theirs.to_str().unwrap(),
+    ]);
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        stdout(&output),
+        format!("merged 7 entries into `{}`\n", ours.display())
+    );
+    let merged = fs::read_to_string(&ours).unwrap();
+    assert!(
+        merged.contains(
+            "\"orders.created\" = { crate = \"billing\", created = \"2024-06-01T12:00:00Z\", \
+             description = \"An order was placed\", owner = \"sales\", \
+             uuid = \"2af1216e-7042-406a-a577-c39a7e7c3faa\" }"
+        ),
+        "{}",
+        merged
+    );
+    assert!(merged.contains("\"orders.shipped\""), "{}", merged);
+    assert!(merged.contains("\"payments.captured\""), "{}", merged);
+}
+
+#[test]
+fn merge_conflicts_follow_the_policy() {
+    let ours = scratch_copy("shop_uuid_changed.toml", "merge-conflict");
+    let theirs = scratch_registry(
+        "merge-conflict",
+        "theirs.toml",
+        &fs::read_to_string(fixture("shop.toml")).unwrap().replace(
+            "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24",
+            "a3e7c969-8413-4f6b-9928-28a140227b0b",
+        ),
+    );
+    let merge = |policy: &[&str]| {
+        let mut args = vec![
+            "merge",
+            fixture("shop.toml").to_str().unwrap(),
+            ours.to_str().unwrap(),
+            theirs.to_str().unwrap(),
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+        args.extend(policy.iter().map(|arg| arg.to_string()));
+        Command::new(env!("CARGO_BIN_EXE_unique-uuid-admin"))
+            .args(&args)
+            .output()
+            .unwrap()
+    };
+
+    let output = merge(&[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(std::str::from_utf8(&output.stderr).unwrap().contains(
+        "`orders.cancelled` in [unique_tags] is 8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4 in ours \
+         but a3e7c969-8413-4f6b-9928-28a140227b0b in theirs"
+    ));
+    assert_eq!(
+        fs::read_to_string(&ours).unwrap(),
+        fs::read_to_string(fixture("shop_uuid_changed.toml")).unwrap()
+    );
+
+    let output = merge(&["--policy", "theirs"]);
+    assert!(output.status.success());
+    assert!(fs::read_to_string(&ours)
+        .unwrap()
+        .contains("\"orders.cancelled\" = \"a3e7c969-8413-4f6b-9928-28a140227b0b\""));
+}
+
+#[test]
+fn invalid_usage_exits_with_code_two() {
+    let output = admin(&["rename", "::OrderRecord"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(std::str::from_utf8(&output.stderr)
+        .unwrap()
+        .contains("Usage: unique-uuid-admin"));
+}
+
+#[test]
+fn directory_registry_is_reported() {
+    let directory = std::env::temp_dir().join("unique-uuid-admin-directory/types.toml");
+    fs::create_dir_all(&directory).unwrap();
+
+    let output = admin(&["list", "--registry", directory.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains(&format!(
+        "`{}` is a directory; the registry must be a file",
+        directory.display()
+    )));
+}
+
+#[test]
+fn missing_registry_directory_is_reported() {
+    let registry = std::env::temp_dir().join("unique-uuid-admin-missing/types.toml");
+
+    let output = admin(&["verify", "--registry", registry.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains(&format!(
+        "the directory `{}` does not exist",
+        registry.parent().unwrap().display()
+    )));
+}
+
+#[test]
+fn flatten_merges_a_registry_directory() {
+    let root = std::env::temp_dir().join("unique-uuid-admin-flatten");
+    let directory = root.join("types.d");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(
+        directory.join("billing.toml"),
+        "[unique_tags]\n\"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n",
+    )
+    .unwrap();
+    fs::write(
+        directory.join("shipping.toml"),
+        "[unique_type_tags]\n\"::Parcel\" = \"8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4\"\n",
+    )
+    .unwrap();
+
+    let output = admin(&["flatten", "--registry", directory.to_str().unwrap()]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        fs::read_to_string(root.join("types.toml")).unwrap(),
+        "[unique_tags]\n\"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n\
+         [unique_type_tags]\n\"::Parcel\" = \"8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4\"\n"
+    );
+
+    // A key stored by two crates names both files
+    fs::write(
+        directory.join("support.toml"),
+        "[unique_type_tags]\n\"::Parcel\" = \"ce4baeef-bd9c-4932-88be-24eb1033f695\"\n",
+    )
+    .unwrap();
+    let output = admin(&["flatten", "--registry", directory.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = std::str::from_utf8(&output.stderr).unwrap();
+    assert!(
+        stderr.contains("`::Parcel` in [unique_type_tags] is stored in both"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("shipping.toml"), "{}", stderr);
+    assert!(stderr.contains("support.toml"), "{}", stderr);
+}
+
+#[test]
+fn markdown_matches_the_golden_file() {
+    let registry = fixture("shop.toml");
+    let args = ["markdown", "--group-by", "crate", "--registry"];
+    let output = admin(&[&args[..], &[registry.to_str().unwrap()]].concat());
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        fs::read_to_string(fixture("shop_by_crate.md")).unwrap()
+    );
+
+    let output = admin(&[
+        "markdown",
+        "--no-metadata",
+        "--namespace",
+        "payments",
+        "--registry",
+        registry.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "# Registry\n\n## Tags\n\n### `payments`\n\n| Key | UUID |\n|---|---|\n\
+         | `payments.refunded` | `ce4baeef-bd9c-4932-88be-24eb1033f695` |\n\n\
+         ## Type tags\n\nNo entries.\n"
+    );
+}
@@ -0,0 +1,72 @@
+//! Measures the cost of reading a synthetic registry of 10 000 entries, by parsing the
+//! TOML file and by decoding its sidecar cache.
+//!
+//! Run with `cargo bench -p unique-uuid-core --bench cache`.
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+#[path = "../src/cache.rs"]
+mod cache;
+#[path = "../src/document.rs"]
+mod document;
+
+use document::FileStructure;
+
+const ENTRIES: u128 = 10_000;
+const ITERATIONS: u32 = 50;
+
+fn entry(uuid: uuid::Uuid) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert("crate".into(), "bench".into());
+    table.insert("created".into(), "2024-06-01T12:00:00Z".into());
+    table.insert("uuid".into(), uuid.to_string().into());
+    toml::Value::Table(table)
+}
+
+fn measure(name: &str, mut f: impl FnMut() -> FileStructure) -> (FileStructure, Duration) {
+    let output = f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{:<24} {:>10.3} ms", name, elapsed.as_secs_f64() * 1e3);
+    (output, elapsed)
+}
+
+fn main() {
+    let mut registry = FileStructure::default();
+    for i in 0..ENTRIES {
+        let uuid = uuid::Uuid::from_u128(i * 0x9e37_79b9_7f4a_7c15);
+        let section = if i % 10 == 0 {
+            &mut registry.unique_type_tags
+        } else {
+            &mut registry.unique_tags
+        };
+        section.insert(format!("bench.tag_{:05}", i), entry(uuid));
+    }
+    let contents = registry.to_canonical_string();
+
+    let directory = std::env::temp_dir().join("unique-uuid-core-bench-cache");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("types.toml");
+    std::fs::write(&path, &contents).unwrap();
+    cache::store(&path, &contents, &registry);
+
+    println!("reading {} entries:", ENTRIES);
+    let (parsed, before) = measure("parse (before)", || toml::from_str(&contents).unwrap());
+    let (cached, after) = measure("cache (after)", || {
+        cache::load(&path, &contents).expect("the cache is up to date")
+    });
+
+    assert_eq!(
+        cached.to_canonical_string(),
+        parsed.to_canonical_string(),
+        "the cache must decode to the parsed registry"
+    );
+    println!(
+        "reading the registry is {:.1}x faster",
+        before.as_secs_f64() / after.as_secs_f64()
+    );
+}
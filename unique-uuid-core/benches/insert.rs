@@ -0,0 +1,72 @@
+//! Measures the cost of adding one entry to a synthetic registry of 10 000 entries, by
+//! re-serializing the whole document and by inserting the line of the entry.
+//!
+//! Run with `cargo bench -p unique-uuid-core --bench insert`.
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+#[path = "../src/document.rs"]
+mod document;
+
+use document::{insert_entry, FileStructure};
+
+const ENTRIES: u128 = 10_000;
+const ITERATIONS: u32 = 50;
+
+fn entry(uuid: uuid::Uuid) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert("crate".into(), "bench".into());
+    table.insert("created".into(), "2024-06-01T12:00:00Z".into());
+    table.insert("uuid".into(), uuid.to_string().into());
+    toml::Value::Table(table)
+}
+
+fn measure(name: &str, mut f: impl FnMut() -> String) -> (String, Duration) {
+    let output = f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{:<24} {:>10.3} ms", name, elapsed.as_secs_f64() * 1e3);
+    (output, elapsed)
+}
+
+fn main() {
+    let mut registry = FileStructure::default();
+    for i in 0..ENTRIES {
+        let uuid = uuid::Uuid::from_u128(i * 0x9e37_79b9_7f4a_7c15);
+        let section = if i % 10 == 0 {
+            &mut registry.unique_type_tags
+        } else {
+            &mut registry.unique_tags
+        };
+        section.insert(format!("bench.tag_{:05}", i), entry(uuid));
+    }
+    let contents = registry.to_canonical_string();
+    let key = "bench.tag_05000a";
+    let value = entry(uuid::Uuid::from_u128(u128::MAX));
+
+    println!("one insertion into {} entries:", ENTRIES);
+    measure("parse (both)", || {
+        let parsed: FileStructure = toml::from_str(&contents).unwrap();
+        std::hint::black_box(parsed);
+        String::new()
+    });
+    let mut updated: FileStructure = toml::from_str(&contents).unwrap();
+    updated.unique_tags.insert(key.into(), value.clone());
+    let (rewritten, before) = measure("full rewrite (before)", || updated.to_canonical_string());
+    let (inserted, after) = measure("line insertion (after)", || {
+        insert_entry(&contents, "unique_tags", key, &value).unwrap()
+    });
+
+    assert_eq!(
+        inserted, rewritten,
+        "the insertion must match the canonical writer"
+    );
+    println!(
+        "writing the entry is {:.1}x faster",
+        before.as_secs_f64() / after.as_secs_f64()
+    );
+}
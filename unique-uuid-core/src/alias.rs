@@ -0,0 +1,103 @@
+//! Alternative keys of an entry, listed in its `aliases` field:
+//!
+//! ```toml
+//! [unique_type_tags]
+//! "::Order" = { uuid = "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db", aliases = ["::OrderRecord"] }
+//! ```
+//!
+//! A key missing from its section resolves to the entry listing it as an alias, so that
+//! the old key of a type keeps its UUID while it is migrated. An alias must not be the
+//! key or an alias of another entry. Must stay in sync with
+//! `unique_uuid::registry::Registry::get`.
+use std::collections::BTreeMap;
+
+use crate::Section;
+
+/// Returns the aliases listed by the entry `value`.
+fn aliases_of(value: &toml::Value) -> impl Iterator<Item = &str> {
+    value
+        .get("aliases")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+}
+
+/// Returns the key and the entry of `entries` listing `alias` as an alias, if any.
+pub(crate) fn owner<'a>(
+    entries: &'a BTreeMap<String, toml::Value>,
+    alias: &str,
+) -> Option<(&'a String, &'a toml::Value)> {
+    entries
+        .iter()
+        .find(|(_, value)| aliases_of(value).any(|other| other == alias))
+}
+
+/// Checks that `aliases`, the aliases requested for `tag`, are neither the key nor an
+/// alias of another entry of `sections`, the entries of its section in each file of the
+/// registry. The key `rename_from`, about to be moved to `tag`, is not another entry.
+pub(crate) fn check(
+    tag: &str,
+    section: Section,
+    aliases: &[String],
+    rename_from: Option<&str>,
+    sections: &[&BTreeMap<String, toml::Value>],
+) -> Result<(), String> {
+    for alias in aliases {
+        if alias == tag {
+            return Err(format!("`{}` cannot be an alias of itself", tag));
+        }
+        for entries in sections {
+            if rename_from != Some(alias.as_str()) && entries.contains_key(alias) {
+                return Err(format!(
+                    "`{}` cannot be an alias of `{}`: it is already an entry of [{}]. Use \
+                     `rename = \"{}\"` to move that entry to `{}` first.",
+                    alias,
+                    tag,
+                    section.name(),
+                    alias,
+                    tag
+                ));
+            }
+            if let Some((other, _)) = owner(entries, alias).filter(|(other, _)| *other != tag) {
+                return Err(format!(
+                    "`{}` cannot be an alias of `{}`: it is already an alias of `{}` in [{}]",
+                    alias,
+                    tag,
+                    other,
+                    section.name()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns `value`, an entry, in structured form with `aliases` added to its `aliases`
+/// field, or `None` if it already lists all of them. Aliases are never removed.
+pub(crate) fn with_aliases(value: &toml::Value, aliases: &[String]) -> Option<toml::Value> {
+    let missing: Vec<&String> = aliases
+        .iter()
+        .filter(|alias| !aliases_of(value).any(|other| other == alias.as_str()))
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    let mut table = match value {
+        toml::Value::Table(table) => table.clone(),
+        toml::Value::String(uuid) => {
+            toml::Table::from_iter([("uuid".to_string(), toml::Value::String(uuid.clone()))])
+        }
+        _ => return None,
+    };
+    let mut listed: Vec<toml::Value> = aliases_of(value)
+        .map(|alias| toml::Value::String(alias.to_string()))
+        .collect();
+    listed.extend(
+        missing
+            .into_iter()
+            .map(|alias| toml::Value::String(alias.clone())),
+    );
+    table.insert("aliases".to_string(), toml::Value::Array(listed));
+    Some(toml::Value::Table(table))
+}
@@ -0,0 +1,226 @@
+//! The sidecar cache of the parsed registry, kept next to the registry file.
+//!
+//! Parsing a registry of thousands of entries dominates the cost of a cold expansion.
+//! When the registry sets `cache = true` in its `[config]` table, the parsed registry is
+//! also stored in `<registry>.cache`, in a length-prefixed binary form that decodes
+//! without any parsing. The cache records the size, the modification time and a hash of
+//! the registry file it was built from, and is only used while all three match.
+//!
+//! The cache is never the source of truth: it is rebuilt whenever it is stale, and a
+//! missing, truncated or corrupted cache is silently ignored in favour of the registry
+//! file. It can be deleted at any time.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use crate::document::FileStructure;
+
+/// Identifies the format of the cache, to be bumped whenever the encoding changes.
+const MAGIC: &[u8; 8] = b"UUCACHE1";
+
+/// The deepest nesting of values decoded, so that a corrupted cache cannot exhaust the
+/// stack.
+const MAX_DEPTH: usize = 64;
+
+/// Returns the path of the cache of the registry stored at `registry`.
+pub(crate) fn cache_path(registry: &Path) -> PathBuf {
+    let mut path = registry.as_os_str().to_owned();
+    path.push(".cache");
+    path.into()
+}
+
+/// Returns the registry stored at `registry`, whose content is `contents`, from its
+/// cache if the cache is up to date.
+pub(crate) fn load(registry: &Path, contents: &str) -> Option<FileStructure> {
+    let bytes = fs::read(cache_path(registry)).ok()?;
+    let mut reader = Reader { bytes: &bytes };
+    if reader.take(MAGIC.len())? != MAGIC || reader.take(KEY_LENGTH)? != key(registry, contents)? {
+        return None;
+    }
+
+    let unique_tags = reader.entries(0)?;
+    let unique_type_tags = reader.entries(0)?;
+    let other = reader.entries(0)?;
+    if !reader.bytes.is_empty() {
+        return None;
+    }
+    Some(FileStructure {
+        unique_tags: unique_tags.into_iter().collect(),
+        unique_type_tags: unique_type_tags.into_iter().collect(),
+        other: other.into_iter().collect(),
+    })
+}
+
+/// Stores `file_structure`, parsed from the content `contents` of the registry stored at
+/// `registry`, in the cache.
+///
+/// Failures are ignored, the next expansion parses the registry file instead. The cache
+/// is written to a temporary file renamed over it, so that concurrent readers never see
+/// a partial cache.
+pub(crate) fn store(registry: &Path, contents: &str, file_structure: &FileStructure) {
+    let Some(key) = key(registry, contents) else {
+        return;
+    };
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend_from_slice(&key);
+    for entries in [
+        &file_structure.unique_tags,
+        &file_structure.unique_type_tags,
+    ] {
+        write_table(&mut bytes, entries.iter());
+    }
+    write_table(&mut bytes, file_structure.other.iter());
+
+    let path = cache_path(registry);
+    let mut temporary = path.clone().into_os_string();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    if fs::write(&temporary, bytes).is_err() || fs::rename(&temporary, &path).is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+}
+
+/// The length of the key identifying the registry file a cache was built from.
+const KEY_LENGTH: usize = 8 + 8 + 4 + 8;
+
+/// Returns the key of the registry stored at `registry` whose content is `contents`: its
+/// size, its modification time and the FNV-1a hash of `contents`.
+fn key(registry: &Path, contents: &str) -> Option<[u8; KEY_LENGTH]> {
+    let metadata = fs::metadata(registry).ok()?;
+    if metadata.len() != contents.len() as u64 {
+        return None;
+    }
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    let hash = contents
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+
+    let mut key = [0; KEY_LENGTH];
+    key[..8].copy_from_slice(&metadata.len().to_le_bytes());
+    key[8..16].copy_from_slice(&modified.as_secs().to_le_bytes());
+    key[16..20].copy_from_slice(&modified.subsec_nanos().to_le_bytes());
+    key[20..].copy_from_slice(&hash.to_le_bytes());
+    Some(key)
+}
+
+fn write_length(bytes: &mut Vec<u8>, length: usize) {
+    bytes.extend_from_slice(&(length as u32).to_le_bytes());
+}
+
+fn write_str(bytes: &mut Vec<u8>, value: &str) {
+    write_length(bytes, value.len());
+    bytes.extend_from_slice(value.as_bytes());
+}
+
+fn write_table<'a>(
+    bytes: &mut Vec<u8>,
+    entries: impl ExactSizeIterator<Item = (&'a String, &'a toml::Value)>,
+) {
+    write_length(bytes, entries.len());
+    for (key, value) in entries {
+        write_str(bytes, key);
+        write_value(bytes, value);
+    }
+}
+
+fn write_value(bytes: &mut Vec<u8>, value: &toml::Value) {
+    match value {
+        toml::Value::String(value) => {
+            bytes.push(0);
+            write_str(bytes, value);
+        }
+        toml::Value::Integer(value) => {
+            bytes.push(1);
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        toml::Value::Float(value) => {
+            bytes.push(2);
+            bytes.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
+        toml::Value::Boolean(value) => bytes.extend_from_slice(&[3, u8::from(*value)]),
+        toml::Value::Datetime(value) => {
+            bytes.push(4);
+            write_str(bytes, &value.to_string());
+        }
+        toml::Value::Array(values) => {
+            bytes.push(5);
+            write_length(bytes, values.len());
+            for value in values {
+                write_value(bytes, value);
+            }
+        }
+        toml::Value::Table(table) => {
+            bytes.push(6);
+            write_table(bytes, table.iter());
+        }
+    }
+}
+
+/// Decodes the cache, every method returning `None` on malformed input.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.bytes.len() < length {
+            return None;
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn length(&mut self) -> Option<usize> {
+        Some(u32::from_le_bytes(self.array()?) as usize)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let length = self.length()?;
+        String::from_utf8(self.take(length)?.to_vec()).ok()
+    }
+
+    /// Decodes the entries of a table, in order.
+    fn entries(&mut self, depth: usize) -> Option<Vec<(String, toml::Value)>> {
+        let mut entries = Vec::new();
+        for _ in 0..self.length()? {
+            let key = self.string()?;
+            entries.push((key, self.value(depth)?));
+        }
+        Some(entries)
+    }
+
+    fn value(&mut self, depth: usize) -> Option<toml::Value> {
+        if depth == MAX_DEPTH {
+            return None;
+        }
+        let value = match self.take(1)?[0] {
+            0 => toml::Value::String(self.string()?),
+            1 => toml::Value::Integer(i64::from_le_bytes(self.array()?)),
+            2 => toml::Value::Float(f64::from_bits(u64::from_le_bytes(self.array()?))),
+            3 => match self.take(1)?[0] {
+                0 => toml::Value::Boolean(false),
+                1 => toml::Value::Boolean(true),
+                _ => return None,
+            },
+            4 => toml::Value::Datetime(self.string()?.parse().ok()?),
+            5 => {
+                let mut values = Vec::new();
+                for _ in 0..self.length()? {
+                    values.push(self.value(depth + 1)?);
+                }
+                toml::Value::Array(values)
+            }
+            6 => toml::Value::Table(self.entries(depth + 1)?.into_iter().collect()),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
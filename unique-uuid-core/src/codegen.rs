@@ -0,0 +1,259 @@
+//! Generation of a Rust module declaring a constant for every registry entry, and its
+//! opt-in refresh whenever the registry is written.
+//!
+//! When the `UNIQUE_UUID_RUST_MODULE` environment variable is set to a path, every
+//! write of the registry also regenerates the Rust module at that path, with a
+//! `pub const` for each entry, as [`rust_module`] generates it.
+use std::{collections::BTreeMap, fmt, fs, io};
+
+use crate::{document::FileStructure, registry::entry_uuid, Section};
+
+static RUST_MODULE_ENV: &str = "UNIQUE_UUID_RUST_MODULE";
+
+/// An entry to declare as a constant: its key, UUID and description.
+type Constant<'a> = (&'a str, uuid::Uuid, Option<&'a str>);
+
+/// An error that occurred while generating a Rust module.
+#[derive(Debug)]
+pub enum CodegenError {
+    /// A key has no character usable in an identifier.
+    EmptyIdentifier {
+        /// The section holding the key.
+        section: Section,
+        /// The key.
+        key: String,
+    },
+
+    /// Several keys of a section map to the same identifier.
+    ConflictingIdentifiers {
+        /// The section holding the keys.
+        section: Section,
+        /// The shared identifier.
+        identifier: String,
+        /// The keys mapping to the identifier.
+        keys: Vec<String>,
+    },
+
+    /// A key of each section maps to the same identifier, in a generated file declaring
+    /// the entries of both sections in the same scope.
+    ConflictingSections {
+        /// The shared identifier.
+        identifier: String,
+        /// The key of the `[unique_tags]` entry.
+        tag: String,
+        /// The key of the `[unique_type_tags]` entry.
+        type_tag: String,
+    },
+
+    /// The generated module could not be written.
+    Write {
+        /// The path of the generated module.
+        path: std::path::PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for CodegenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodegenError::EmptyIdentifier { section, key } => {
+                write!(
+                    f,
+                    "`{}` in [{}] cannot be turned into an identifier",
+                    key, section
+                )
+            }
+            CodegenError::ConflictingIdentifiers {
+                section,
+                identifier,
+                keys,
+            } => {
+                write!(f, "keys of [{}] all map to `{}`:", section, identifier)?;
+                for key in keys {
+                    write!(f, " `{}`", key)?;
+                }
+                Ok(())
+            }
+            CodegenError::ConflictingSections {
+                identifier,
+                tag,
+                type_tag,
+            } => {
+                write!(
+                    f,
+                    "`{}` in [{}] and `{}` in [{}] both map to `{}`",
+                    tag,
+                    Section::UniqueTags,
+                    type_tag,
+                    Section::UniqueTypeTags,
+                    identifier
+                )
+            }
+            CodegenError::Write { path, source } => {
+                write!(f, "cannot write `{}`: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodegenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CodegenError::Write { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Regenerates the Rust module from the registry, if enabled.
+///
+/// Returns a warning to surface if the module cannot be generated or written; the
+/// previous module is left untouched in that case.
+pub(crate) fn refresh_rust_module(file_structure: &FileStructure) -> Option<String> {
+    let path = std::env::var_os(RUST_MODULE_ENV)?;
+    let module = match rust_module(file_structure, "unique_uuid") {
+        Ok(module) => module,
+        Err(err) => return Some(format!("The Rust module was not regenerated: {}", err)),
+    };
+
+    let mut temporary = path.clone();
+    temporary.push(".tmp");
+    fs::write(&temporary, module)
+        .and_then(|()| fs::rename(&temporary, &path))
+        .err()
+        .map(|err| {
+            format!(
+                "The Rust module `{}` could not be written: {}",
+                path.to_string_lossy(),
+                err
+            )
+        })
+}
+
+/// Returns the source of a Rust module declaring a `pub const` `UniqueTag` for every
+/// entry of `file_structure`, referring to the `unique-uuid` crate as `crate_path`.
+///
+/// The constants are grouped in one `pub mod` per section, named after the section,
+/// and named with [`constant_name`]. Each constant is documented with its key and UUID,
+/// and with the entry's description if it has one. Malformed entries are left out.
+///
+/// # Errors
+/// Returns [`CodegenError::EmptyIdentifier`] if a key has no usable character, and
+/// [`CodegenError::ConflictingIdentifiers`] if several keys of a section map to the
+/// same identifier.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::{rust_module, Registry};
+///
+/// let mut registry = Registry::default();
+/// registry.unique_tags.insert(
+///     "orders.created".to_string(),
+///     "2af1216e-7042-406a-a577-c39a7e7c3faa".into(),
+/// );
+/// let module = rust_module(&registry, "unique_uuid").unwrap();
+/// assert!(module.contains("pub const ORDERS_CREATED: unique_uuid::UniqueTag"));
+/// ```
+pub fn rust_module(file_structure: &FileStructure, crate_path: &str) -> Result<String, CodegenError> {
+    let mut module = String::from(
+        "// @generated by unique-uuid from the registry file, do not edit.\n\
+         // Regenerate it with `unique-uuid-admin codegen`.\n",
+    );
+
+    for (section, entries) in [
+        (Section::UniqueTags, &file_structure.unique_tags),
+        (Section::UniqueTypeTags, &file_structure.unique_type_tags),
+    ] {
+        let mut constants: BTreeMap<String, Vec<Constant>> = BTreeMap::new();
+        for (key, value) in entries {
+            let Some(uuid) = entry_uuid(value) else {
+                continue;
+            };
+            let name = constant_name(key).ok_or_else(|| CodegenError::EmptyIdentifier {
+                section,
+                key: key.clone(),
+            })?;
+            let description = value.get("description").and_then(toml::Value::as_str);
+            constants
+                .entry(name)
+                .or_default()
+                .push((key, uuid, description));
+        }
+        if let Some((identifier, keys)) = constants.iter().find(|(_, keys)| keys.len() > 1) {
+            return Err(CodegenError::ConflictingIdentifiers {
+                section,
+                identifier: identifier.clone(),
+                keys: keys.iter().map(|(key, ..)| key.to_string()).collect(),
+            });
+        }
+
+        module.push_str(&format!(
+            "\n/// The entries of the `[{}]` section.\npub mod {} {{",
+            section, section
+        ));
+        for (name, keys) in &constants {
+            let (key, uuid, description) = keys[0];
+            module.push_str(&format!("\n    /// `{}` (`{}`)\n", key.replace('`', "'"), uuid));
+            if let Some(description) = description {
+                module.push_str("    ///\n");
+                for line in description.lines() {
+                    module.push_str(&format!("    /// {}\n", line).replace(" \n", "\n"));
+                }
+            }
+            module.push_str(&format!(
+                "    pub const {name}: {path}::UniqueTag =\n        \
+                 {path}::UniqueTag({path}::uuid::uuid!(\"{uuid}\"));\n",
+                name = name,
+                path = crate_path,
+                uuid = uuid,
+            ));
+        }
+        module.push_str("}\n");
+    }
+
+    Ok(module)
+}
+
+/// Turns a registry key into a `SCREAMING_SNAKE_CASE` identifier.
+///
+/// Every run of characters other than ASCII letters and digits becomes a single `_`,
+/// and an `_` is inserted where a lowercase letter or digit is followed by an
+/// uppercase letter. Returns `None` if the key has no ASCII letter or digit.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::constant_name;
+///
+/// assert_eq!(constant_name("orders.created").as_deref(), Some("ORDERS_CREATED"));
+/// assert_eq!(constant_name("::shop::OrderRecord").as_deref(), Some("SHOP_ORDER_RECORD"));
+/// assert_eq!(constant_name("2fa-enabled").as_deref(), Some("_2FA_ENABLED"));
+/// assert_eq!(constant_name("::"), None);
+/// ```
+pub fn constant_name(key: &str) -> Option<String> {
+    let mut name = String::with_capacity(key.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in key.chars() {
+        if c.is_ascii_alphanumeric() {
+            let boundary = previous.is_some_and(|p| {
+                !p.is_ascii_alphanumeric()
+                    || (c.is_ascii_uppercase() && (p.is_ascii_lowercase() || p.is_ascii_digit()))
+            });
+            if boundary && !name.is_empty() {
+                name.push('_');
+            }
+            name.push(c.to_ascii_uppercase());
+        }
+        previous = Some(c);
+    }
+
+    if name.is_empty() {
+        None
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        Some(format!("_{}", name))
+    } else {
+        Some(name)
+    }
+}
@@ -0,0 +1,530 @@
+//! Settings read from the `[config]` table of the registry.
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{document::FileStructure, pattern, style::TagStyle, Section};
+
+/// The settings of the registry, e.g.
+/// ```toml
+/// [config]
+/// tag_style = "snake_case"
+/// tag_pattern = '^[a-z0-9_]+(\.[a-z0-9_]+)*$'
+/// strict = true
+/// journal = true
+/// cache = true
+/// pin_conflict = "prefer-pinned"
+/// case_insensitive_keys = "error"
+/// frozen_sections = ["unique_tags"]
+/// frozen_namespaces = ["payments"]
+/// tags_file = "protocol/tags.toml"
+/// ids = true
+/// unique_meta = ["code"]
+///
+/// [config.prefixes]
+/// billing = "2af1"
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::Config;
+///
+/// let items = toml::from_str("[config.prefixes]\nbilling = \"2af1\"\n").unwrap();
+/// let config = Config::from_items(&items).unwrap();
+/// assert_eq!(config.prefixes(), [("billing".to_string(), vec![0x2a, 0xf1])]);
+///
+/// let unknown = toml::from_str("[config]\njournals = true\n").unwrap();
+/// assert!(Config::from_items(&unknown).is_err());
+/// ```
+pub struct Config {
+    /// `tag_style`: the naming convention new tags must follow.
+    pub(crate) tag_style: TagStyle,
+
+    /// `tag_pattern`: the regular expression new tags must match.
+    pub(crate) tag_pattern: Option<Regex>,
+
+    /// `strict`: whether lints fail the expansion instead of emitting warnings.
+    pub(crate) strict: bool,
+
+    /// `journal`: whether new entries are appended to the journal instead of being
+    /// written to the registry file.
+    pub(crate) journal: bool,
+
+    /// `journal_limit`: the number of journal lines past which the journal is compacted
+    /// into the registry file.
+    pub(crate) journal_limit: usize,
+
+    /// `fragments`: whether new entries are written to fragment files of their own
+    /// instead of waiting for the registry lock.
+    pub(crate) fragments: bool,
+
+    /// `cache`: whether the parsed registry is kept in a sidecar cache.
+    pub(crate) cache: bool,
+
+    /// `pin_conflict`: how a pinned UUID differing from the one of the registry is
+    /// resolved.
+    pub(crate) pin_conflict: PinConflict,
+
+    /// `case_insensitive_keys`: how a new key only differing by case from an existing
+    /// key is reported.
+    pub(crate) case_insensitive_keys: CaseInsensitiveKeys,
+
+    /// `frozen_sections`: the sections the macros must not add entries to.
+    pub(crate) frozen_sections: Vec<Section>,
+
+    /// `frozen_namespaces`: the namespaces the macros must not add entries to.
+    pub(crate) frozen_namespaces: Vec<String>,
+
+    /// `tags_file`: the file storing `[unique_tags]` instead of the registry file,
+    /// relative to the directory of the registry file.
+    pub(crate) tags_file: Option<String>,
+
+    /// `type_tags_file`: the file storing `[unique_type_tags]` instead of the registry
+    /// file, relative to the directory of the registry file.
+    pub(crate) type_tags_file: Option<String>,
+
+    /// `prefixes`: the leading bytes of the UUIDs minted by each crate, by crate name.
+    pub(crate) prefixes: Vec<(String, Vec<u8>)>,
+
+    /// `ids`: whether the entries get a small integer id alongside their UUID.
+    pub(crate) ids: bool,
+
+    /// `unique_meta`: the names of the `meta` values that must differ between entries.
+    pub(crate) unique_meta: Vec<String>,
+}
+
+/// How to resolve a key whose pinned UUID differs from the UUID of its entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinConflict {
+    /// `error`: fail the expansion.
+    Error,
+
+    /// `prefer-pinned`: rewrite the entry with the pinned UUID.
+    PreferPinned,
+
+    /// `prefer-registry`: keep the UUID of the entry, with a warning.
+    PreferRegistry,
+}
+
+impl PinConflict {
+    /// Parses the value of `config.pin_conflict`.
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "error" => Some(Self::Error),
+            "prefer-pinned" => Some(Self::PreferPinned),
+            "prefer-registry" => Some(Self::PreferRegistry),
+            _ => None,
+        }
+    }
+}
+
+/// How to report a new key that only differs by case from an existing key of its
+/// section.
+///
+/// Keys are compared under Unicode case folding, so that `straße` and `STRASSE` collide,
+/// and are never rewritten or merged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaseInsensitiveKeys {
+    /// `"off"`: accept the key.
+    #[default]
+    Off,
+
+    /// `"warn"`: accept the key, with a warning.
+    Warn,
+
+    /// `"error"`: fail the expansion, and the loading of a registry holding such keys.
+    Error,
+}
+
+impl CaseInsensitiveKeys {
+    /// Parses the value of `config.case_insensitive_keys`.
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of `config.case_insensitive_keys` selecting this policy.
+    fn name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tag_style: TagStyle::default(),
+            tag_pattern: None,
+            strict: false,
+            journal: false,
+            journal_limit: 256,
+            fragments: false,
+            cache: false,
+            pin_conflict: PinConflict::Error,
+            case_insensitive_keys: CaseInsensitiveKeys::Off,
+            frozen_sections: Vec::new(),
+            frozen_namespaces: Vec::new(),
+            tags_file: None,
+            type_tags_file: None,
+            prefixes: Vec::new(),
+            ids: false,
+            unique_meta: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the `[config]` table among the top-level items of the registry, if any.
+    ///
+    /// # Errors
+    /// Returns an error naming the setting if a setting is unknown or invalid, or if two
+    /// settings cannot be combined.
+    pub fn from_items(items: &toml::Table) -> Result<Self, String> {
+        let mut config = Self::default();
+        let Some(table) = items.get("config") else {
+            return Ok(config);
+        };
+        let Some(table) = table.as_table() else {
+            return Err("`config` must be a table".to_string());
+        };
+
+        for (name, value) in table {
+            match name.as_str() {
+                "tag_style" => {
+                    config.tag_style =
+                        value.as_str().and_then(TagStyle::parse).ok_or_else(|| {
+                            format!(
+                                "`config.tag_style` must be one of \"snake_case\", \
+                                 \"kebab-case\" or \"none\", found `{}`",
+                                value
+                            )
+                        })?;
+                }
+                "tag_pattern" => {
+                    let source = value.as_str().ok_or_else(|| {
+                        format!(
+                            "`config.tag_pattern` must be a regular expression, found `{}`",
+                            value
+                        )
+                    })?;
+                    config.tag_pattern = Some(pattern::compile(source)?);
+                }
+                "strict" => {
+                    config.strict = value.as_bool().ok_or_else(|| {
+                        format!("`config.strict` must be a boolean, found `{}`", value)
+                    })?;
+                }
+                "journal" => {
+                    config.journal = value.as_bool().ok_or_else(|| {
+                        format!("`config.journal` must be a boolean, found `{}`", value)
+                    })?;
+                }
+                "journal_limit" => {
+                    config.journal_limit = value
+                        .as_integer()
+                        .and_then(|limit| usize::try_from(limit).ok())
+                        .ok_or_else(|| {
+                            format!(
+                                "`config.journal_limit` must be a positive integer, found `{}`",
+                                value
+                            )
+                        })?;
+                }
+                "fragments" => {
+                    config.fragments = value.as_bool().ok_or_else(|| {
+                        format!("`config.fragments` must be a boolean, found `{}`", value)
+                    })?;
+                }
+                "cache" => {
+                    config.cache = value.as_bool().ok_or_else(|| {
+                        format!("`config.cache` must be a boolean, found `{}`", value)
+                    })?;
+                }
+                "pin_conflict" => {
+                    config.pin_conflict =
+                        value.as_str().and_then(PinConflict::parse).ok_or_else(|| {
+                            format!(
+                                "`config.pin_conflict` must be one of \"error\", \
+                                 \"prefer-pinned\" or \"prefer-registry\", found `{}`",
+                                value
+                            )
+                        })?;
+                }
+                "case_insensitive_keys" => {
+                    config.case_insensitive_keys = value
+                        .as_str()
+                        .and_then(CaseInsensitiveKeys::parse)
+                        .ok_or_else(|| {
+                            format!(
+                                "`config.case_insensitive_keys` must be one of \"warn\", \
+                                 \"error\" or \"off\", found `{}`",
+                                value
+                            )
+                        })?;
+                }
+                "frozen_sections" => {
+                    config.frozen_sections = strings(name, value)?
+                        .into_iter()
+                        .map(|section| match section {
+                            "unique_tags" => Ok(Section::UniqueTags),
+                            "unique_type_tags" => Ok(Section::UniqueTypeTags),
+                            _ => Err(format!(
+                                "`config.frozen_sections` may only list \"unique_tags\" and \
+                                 \"unique_type_tags\", found `{}`",
+                                section
+                            )),
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "unique_meta" => {
+                    config.unique_meta = strings(name, value)?
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect();
+                }
+                "frozen_namespaces" => {
+                    config.frozen_namespaces = strings(name, value)?
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect();
+                }
+                "tags_file" | "type_tags_file" => {
+                    let path = value
+                        .as_str()
+                        .filter(|path| !path.is_empty())
+                        .ok_or_else(|| {
+                            format!("`config.{}` must be a path, found `{}`", name, value)
+                        })?;
+                    if name == "tags_file" {
+                        config.tags_file = Some(path.to_string());
+                    } else {
+                        config.type_tags_file = Some(path.to_string());
+                    }
+                }
+                "prefixes" => config.prefixes = parse_prefixes(value)?,
+                "ids" => {
+                    config.ids = value.as_bool().ok_or_else(|| {
+                        format!("`config.ids` must be a boolean, found `{}`", value)
+                    })?;
+                }
+                // Only read from a manifest, by `manifest::opted_in_manifest`
+                "registry" => {
+                    if !matches!(value.as_str(), Some("file" | "cargo-metadata")) {
+                        return Err(format!(
+                            "`config.registry` must be one of \"file\" or \"cargo-metadata\", \
+                             found `{}`",
+                            value
+                        ));
+                    }
+                }
+                _ => return Err(format!("unknown setting `config.{}`", name)),
+            }
+        }
+        // The last id allocated is kept in the registry file
+        if config.ids
+            && (config.journal
+                || config.fragments
+                || config.tags_file.is_some()
+                || config.type_tags_file.is_some())
+        {
+            return Err(
+                "`config.ids` cannot be combined with `journal`, `fragments`, `tags_file` or \
+                 `type_tags_file`: ids are allocated in the registry file"
+                    .to_string(),
+            );
+        }
+        if config.journal && config.fragments {
+            return Err(
+                "`config.journal` cannot be combined with `fragments`: new entries are \
+                 written either to the journal or to fragments"
+                    .to_string(),
+            );
+        }
+        Ok(config)
+    }
+
+    /// Returns the `tag_pattern` setting, compiled, if any.
+    pub fn tag_pattern(&self) -> Option<&Regex> {
+        self.tag_pattern.as_ref()
+    }
+
+    /// Returns the `pin_conflict` setting.
+    pub fn pin_conflict(&self) -> PinConflict {
+        self.pin_conflict
+    }
+
+    /// Returns the `case_insensitive_keys` setting.
+    pub fn case_insensitive_keys(&self) -> CaseInsensitiveKeys {
+        self.case_insensitive_keys
+    }
+
+    /// Returns the prefixes reserved for the UUIDs of each crate, by crate name, in the
+    /// order of `[config.prefixes]`.
+    pub fn prefixes(&self) -> &[(String, Vec<u8>)] {
+        &self.prefixes
+    }
+
+    /// Returns the setting forbidding the macros to add `tag` to `section`, described for
+    /// an error message, if any.
+    pub(crate) fn frozen(&self, tag: &str, section: Section) -> Option<String> {
+        if self.frozen_sections.contains(&section) {
+            return Some(format!("`frozen_sections` freezes [{}]", section.name()));
+        }
+        self.frozen_namespaces
+            .iter()
+            .find(|namespace| {
+                tag.strip_prefix(namespace.as_str()).is_some_and(|rest| {
+                    rest.len() > 1 && (rest.starts_with('.') || rest.starts_with("::"))
+                })
+            })
+            .map(|namespace| format!("`frozen_namespaces` freezes the `{}` namespace", namespace))
+    }
+
+    /// Returns the existing key of `keys` that `tag` only differs from by case, described
+    /// for a diagnostic, if the `case_insensitive_keys` setting checks for them.
+    ///
+    /// The keys are compared by [`fold_key`], as `unique_uuid::registry::Registry::validate`
+    /// compares them, so that e.g. `straße` and `STRASSE` collide.
+    pub(crate) fn case_collision<'a>(
+        &self,
+        tag: &str,
+        section: Section,
+        keys: impl IntoIterator<Item = &'a String>,
+    ) -> Option<String> {
+        if self.case_insensitive_keys == CaseInsensitiveKeys::Off {
+            return None;
+        }
+        let folded = fold_key(tag);
+        let existing = keys
+            .into_iter()
+            .find(|key| key.as_str() != tag && fold_key(key) == folded)?;
+        Some(format!(
+            "`{}` only differs by case from `{}`, already in [{}]. Reuse `{}`, or choose a \
+             key that differs by more than case (`case_insensitive_keys` is \"{}\").",
+            tag,
+            existing,
+            section.name(),
+            existing,
+            self.case_insensitive_keys.name()
+        ))
+    }
+
+    /// Fails if two keys of the same section of `registry` only differ by case, naming
+    /// both, when the `case_insensitive_keys` setting is `"error"`.
+    pub fn check_case_collisions(&self, registry: &FileStructure) -> Result<(), String> {
+        if self.case_insensitive_keys != CaseInsensitiveKeys::Error {
+            return Ok(());
+        }
+        for (section, entries) in [
+            (Section::UniqueTags, &registry.unique_tags),
+            (Section::UniqueTypeTags, &registry.unique_type_tags),
+        ] {
+            let mut keys: Vec<(String, &str)> = entries
+                .keys()
+                .map(|key| (fold_key(key), key.as_str()))
+                .collect();
+            keys.sort();
+            if let Some(pair) = keys.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+                return Err(format!(
+                    "`{}` and `{}` in [{}] only differ by case, which `case_insensitive_keys` \
+                     forbids",
+                    pair[0].1, pair[1].1, section
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the prefix reserved for the UUIDs minted by `crate_name`, if any.
+    pub(crate) fn prefix(&self, crate_name: &str) -> Option<&[u8]> {
+        self.prefixes
+            .iter()
+            .find(|(name, _)| name == crate_name)
+            .map(|(_, prefix)| prefix.as_slice())
+    }
+}
+
+/// Folds `key` into the form under which keys differing only by case or Unicode
+/// normalization are equal: its NFKC normalization, upper-cased then lower-cased to
+/// approximate Unicode case folding, e.g. `Straße` into `strasse` and `ﬁle` into `file`.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::fold_key;
+///
+/// assert_eq!(fold_key("::Straße"), fold_key("::STRASSE"));
+/// assert_eq!(fold_key("\u{fb01}le"), fold_key("FILE"));
+/// assert_ne!(fold_key("orders.created"), fold_key("orders.cancelled"));
+/// ```
+pub fn fold_key(key: &str) -> String {
+    key.nfkc().collect::<String>().to_uppercase().to_lowercase()
+}
+
+/// Reads the value of the setting `name`, an array of non-empty strings.
+fn strings<'a>(name: &str, value: &'a toml::Value) -> Result<Vec<&'a str>, String> {
+    let invalid = || {
+        format!(
+            "`config.{}` must be an array of non-empty strings, found `{}`",
+            name, value
+        )
+    };
+    value
+        .as_array()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|item| {
+            item.as_str()
+                .filter(|item| !item.is_empty())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// Parses the `[config.prefixes]` table, mapping crate names to 1 to 4 bytes written in
+/// hexadecimal, and rejects prefixes of which another is a prefix: the UUIDs of both
+/// crates could collide.
+fn parse_prefixes(value: &toml::Value) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let table = value
+        .as_table()
+        .ok_or_else(|| format!("`config.prefixes` must be a table, found `{}`", value))?;
+    let mut prefixes: Vec<(String, Vec<u8>)> = Vec::new();
+    for (name, value) in table {
+        let prefix = value.as_str().and_then(parse_prefix).ok_or_else(|| {
+            format!(
+                "`config.prefixes.{}` must be 1 to 4 bytes in hexadecimal, e.g. \"2af1\", \
+                 found `{}`",
+                name, value
+            )
+        })?;
+        if let Some((other, _)) = prefixes
+            .iter()
+            .find(|(_, other)| other.starts_with(&prefix) || prefix.starts_with(other))
+        {
+            return Err(format!(
+                "the prefixes of `{}` and `{}` overlap, the UUIDs of both crates could collide",
+                other, name
+            ));
+        }
+        prefixes.push((name.clone(), prefix));
+    }
+    Ok(prefixes)
+}
+
+/// Parses 1 to 4 bytes written as an even number of hexadecimal digits.
+fn parse_prefix(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || hex.len() > 8 || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
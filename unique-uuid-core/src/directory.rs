@@ -0,0 +1,142 @@
+//! Registries stored as a directory, e.g. `UNIQUE_UUID_REGISTRY=types.d`, holding one
+//! TOML file per crate named after the package, e.g. `types.d/billing.toml`.
+//!
+//! The macros of a crate only ever write its own file, as the registry file, so that
+//! crates never contend for a lock nor conflict when merged. Lookups also see the
+//! entries of the other files, with their journals and pending fragments, and a key stored in two files fails
+//! the expansion with both file names.
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    document::{FileStructure, Layout},
+    format::Format,
+    fragment,
+    handle::RegistryHandle,
+    journal,
+};
+
+/// The name of the file of crates built without Cargo, which do not have a package name.
+const DEFAULT_CRATE_FILE_NAME: &str = "types.toml";
+
+/// Returns whether the registry configured at `path` is a directory: a path with a `.d`
+/// extension, created on the first write. Any other directory is an invalid registry.
+pub(crate) fn is_directory(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "d")
+}
+
+/// Returns the file of the compiled crate in the registry directory `directory`.
+pub(crate) fn crate_file(directory: &Path) -> PathBuf {
+    match std::env::var("CARGO_PKG_NAME") {
+        Ok(name) if !name.is_empty() => directory.join(format!("{}.toml", name)),
+        _ => directory.join(DEFAULT_CRATE_FILE_NAME),
+    }
+}
+
+/// Reads the entries of the files of the registry directory other than the registry file
+/// of the compiled crate, whose entries `file_structure` holds and no other file may
+/// hold.
+///
+/// Returns an empty registry if the registry is not a directory.
+pub(crate) fn load_others(
+    handle: &RegistryHandle,
+    file_structure: &FileStructure,
+) -> Result<FileStructure, String> {
+    let mut others = FileStructure::default();
+    let Some(directory) = handle.directory() else {
+        return Ok(others);
+    };
+    let own = handle.path();
+    let files = match files(&directory) {
+        Ok(files) => files,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(others),
+        Err(err) => return Err(format!("cannot list `{}`: {}", directory.display(), err)),
+    };
+
+    // The file holding each key, to name both files of a collision
+    let mut origins = Origins::new();
+    for (name, entries) in sections(file_structure) {
+        for key in entries.keys() {
+            origins.insert((name, key.clone()), own.to_path_buf());
+        }
+    }
+    for path in files.into_iter().filter(|path| *path != own) {
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|err| format!("cannot read `{}`: {}", path.display(), err))?;
+        let mut file = Format::Toml
+            .parse(&Layout::strip(&contents))
+            .map_err(|err| format!("cannot parse `{}`: {}", path.display(), err))?;
+        if let Ok(contents) = std::fs::read_to_string(journal::journal_path(&path)) {
+            journal::merge(&mut file, &contents);
+        }
+        let fragments = fragment::read(&path)
+            .map_err(|err| format!("cannot read the fragments of `{}`: {}", path.display(), err))?;
+        fragments.merge(&mut file);
+        add(&mut others, &mut origins, &path, &file)?;
+    }
+    Ok(others)
+}
+
+/// The file holding each key of a registry directory, by section name and key.
+pub(crate) type Origins = BTreeMap<(&'static str, String), PathBuf>;
+
+/// Adds the entries of `file`, read from `path`, to `target`, recording in `origins` that
+/// `path` holds them.
+///
+/// # Errors
+/// Fails if a key of `file` is already held by another file.
+pub(crate) fn add(
+    target: &mut FileStructure,
+    origins: &mut Origins,
+    path: &Path,
+    file: &FileStructure,
+) -> Result<(), String> {
+    for (name, entries) in sections(file) {
+        for (key, value) in entries {
+            if let Some(origin) = origins.get(&(name, key.clone())) {
+                return Err(format!(
+                    "`{}` in [{}] is stored in both `{}` and `{}`: remove one of them",
+                    key,
+                    name,
+                    origin.display(),
+                    path.display()
+                ));
+            }
+            origins.insert((name, key.clone()), path.to_path_buf());
+            let entries = match name {
+                "unique_tags" => &mut target.unique_tags,
+                _ => &mut target.unique_type_tags,
+            };
+            entries.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Returns the TOML files of `directory`, sorted by path.
+pub(crate) fn files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|extension| extension == "toml")
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Returns the sections of `file_structure` with their names.
+fn sections(file_structure: &FileStructure) -> [(&'static str, &BTreeMap<String, toml::Value>); 2] {
+    [
+        ("unique_tags", &file_structure.unique_tags),
+        ("unique_type_tags", &file_structure.unique_type_tags),
+    ]
+}
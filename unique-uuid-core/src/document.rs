@@ -0,0 +1,220 @@
+//! The registry document: its content, and its canonical serialization.
+use std::{borrow::Cow, cmp::Ordering, collections::BTreeMap};
+
+use serde::{Deserialize, Serialize};
+
+/// The content of the registry file.
+///
+/// Entries are kept as raw TOML values so that structured entries (`{ uuid = "…", … }`)
+/// and any unknown top-level items survive a rewrite untouched.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileStructure {
+    /// The entries of `[unique_tags]`, by key.
+    #[serde(default)]
+    pub unique_tags: BTreeMap<String, toml::Value>,
+
+    /// The entries of `[unique_type_tags]`, by key.
+    #[serde(default)]
+    pub unique_type_tags: BTreeMap<String, toml::Value>,
+
+    /// The other top-level items, e.g. `[config]`.
+    #[serde(flatten)]
+    pub other: toml::Table,
+}
+
+impl FileStructure {
+    /// Serializes the registry in its canonical form: one table per section, keys
+    /// sorted, one entry per line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid_core::Registry;
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.unique_tags.insert(
+    ///     "orders.created".to_string(),
+    ///     "2af1216e-7042-406a-a577-c39a7e7c3faa".into(),
+    /// );
+    /// assert_eq!(
+    ///     registry.to_canonical_string(),
+    ///     "[unique_tags]\n\"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n\
+    ///      [unique_type_tags]\n"
+    /// );
+    /// ```
+    pub fn to_canonical_string(&self) -> String {
+        let mut output = String::new();
+        for (index, (name, entries)) in [
+            ("unique_tags", &self.unique_tags),
+            ("unique_type_tags", &self.unique_type_tags),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if index > 0 {
+                output.push('\n');
+            }
+            output.push_str(&section_string(name, entries));
+        }
+
+        if !self.other.is_empty() {
+            output.push('\n');
+            output.push_str(&toml::to_string(&self.other).unwrap_or_default());
+        }
+
+        output
+    }
+}
+
+/// Serializes the `[name]` section holding `entries` in its canonical form, as written in
+/// the registry file or alone in a file of its own.
+pub(crate) fn section_string(name: &str, entries: &BTreeMap<String, toml::Value>) -> String {
+    let mut output = format!("[{}]\n", name);
+    for (key, value) in entries {
+        output.push_str(&format!("{} = {}\n", format_key(key), value));
+    }
+    output
+}
+
+/// The byte order mark some editors write at the start of UTF-8 files.
+const BOM: char = '\u{feff}';
+
+/// How a registry file was laid out beyond its content: a leading byte order mark and
+/// the line endings, which editors on Windows tend to introduce, preserved when the file
+/// is rewritten.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::Layout;
+///
+/// let contents = "\u{feff}[unique_tags]\r\n";
+/// assert_eq!(Layout::strip(contents), "[unique_tags]\n");
+/// assert_eq!(Layout::detect(contents).apply("[unique_tags]\n".to_string()), contents);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Layout {
+    /// Whether the file starts with a byte order mark.
+    bom: bool,
+
+    /// Whether lines end with `\r\n` instead of `\n`, as decided by the first line.
+    crlf: bool,
+}
+
+impl Layout {
+    /// Detects the layout of `contents`.
+    pub fn detect(contents: &str) -> Self {
+        let body = contents.strip_prefix(BOM).unwrap_or(contents);
+        Self {
+            bom: body.len() != contents.len(),
+            crlf: body
+                .find('\n')
+                .is_some_and(|end| body[..end].ends_with('\r')),
+        }
+    }
+
+    /// Returns `contents` without its byte order mark, with `\n` line endings.
+    pub fn strip(contents: &str) -> Cow<'_, str> {
+        let contents = contents.strip_prefix(BOM).unwrap_or(contents);
+        if contents.contains('\r') {
+            Cow::Owned(contents.replace("\r\n", "\n"))
+        } else {
+            Cow::Borrowed(contents)
+        }
+    }
+
+    /// Lays out `contents`, with `\n` line endings, like the file this layout was
+    /// detected in.
+    pub fn apply(self, contents: String) -> String {
+        let contents = if self.crlf {
+            contents.replace('\n', "\r\n")
+        } else {
+            contents
+        };
+        if self.bom {
+            format!("{}{}", BOM, contents)
+        } else {
+            contents
+        }
+    }
+}
+
+/// Formats a key bare when possible, quoted otherwise.
+pub(crate) fn format_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
+/// Inserts the entry `key = value` in the `[section]` table of `contents`, a registry in
+/// canonical form, touching no other line.
+///
+/// The entry is placed by a binary search over the lines of the section, so inserting
+/// into a large registry does not re-serialize every entry. On a canonical registry, the
+/// result is the same as [`FileStructure::to_canonical_string`] after the
+/// insertion. Returns `None` when `contents` is not laid out canonically around the
+/// section, or already holds `key`, in which case the document must be rewritten.
+pub(crate) fn insert_entry(
+    contents: &str,
+    section: &str,
+    key: &str,
+    value: &toml::Value,
+) -> Option<String> {
+    let header = format!("[{}]\n", section);
+    let start = if contents.starts_with(&header) {
+        header.len()
+    } else {
+        contents.find(&format!("\n{}", header))? + 1 + header.len()
+    };
+
+    // The entries of the section, up to the blank line separating it from the next one
+    let mut lines = Vec::new();
+    let mut offset = start;
+    while offset < contents.len() {
+        let end = contents[offset..]
+            .find('\n')
+            .map_or(contents.len(), |end| offset + end + 1);
+        let line = &contents[offset..end];
+        if line.trim().is_empty() || line.starts_with('[') {
+            break;
+        }
+        lines.push((offset, line));
+        offset = end;
+    }
+
+    let mut low = 0;
+    let mut high = lines.len();
+    while low < high {
+        let middle = (low + high) / 2;
+        match line_key(lines[middle].1)?.as_str().cmp(key) {
+            Ordering::Less => low = middle + 1,
+            Ordering::Greater => high = middle,
+            Ordering::Equal => return None,
+        }
+    }
+    let position = lines.get(low).map_or(offset, |(offset, _)| *offset);
+
+    let line = format!("{} = {}\n", format_key(key), value);
+    let mut output = String::with_capacity(contents.len() + line.len());
+    output.push_str(&contents[..position]);
+    output.push_str(&line);
+    output.push_str(&contents[position..]);
+    Some(output)
+}
+
+/// Returns the key of an entry line, or `None` if it does not hold exactly one entry.
+fn line_key(line: &str) -> Option<String> {
+    let table: toml::Table = toml::from_str(line).ok()?;
+    let mut keys = table.into_iter().map(|(key, _)| key);
+    match (keys.next(), keys.next()) {
+        (Some(key), None) => Some(key),
+        _ => None,
+    }
+}
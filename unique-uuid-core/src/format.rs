@@ -0,0 +1,303 @@
+//! The storage formats of the registry file, chosen by its extension.
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    document::{self, FileStructure},
+    manifest, Section,
+};
+
+/// The storage format of a registry file, chosen by its extension.
+///
+/// A JSON registry holds the same document as a TOML one, with a section per object:
+///
+/// ```json
+/// {
+///   "unique_tags": {
+///     "orders.created": "2af1216e-7042-406a-a577-c39a7e7c3faa"
+///   },
+///   "unique_type_tags": {}
+/// }
+/// ```
+///
+/// It is written pretty-printed, with every key sorted, so that it does not churn
+/// either. The macros write the registry in the same format when it is configured with
+/// `UNIQUE_UUID_REGISTRY=types.json`.
+///
+/// With the `ron` feature, a `.ron` registry holds the same document in RON, as maps of
+/// strings with trailing commas, also pretty-printed with every key sorted. With the
+/// `yaml` feature, a `.yaml` or `.yml` registry holds it in YAML, in block style with every
+/// key sorted. Without the feature, parsing such a registry fails, so that it is never
+/// overwritten in another format.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::Format;
+///
+/// let contents = r#"{
+///   "unique_tags": {
+///     "orders.created": "2af1216e-7042-406a-a577-c39a7e7c3faa"
+///   },
+///   "unique_type_tags": {}
+/// }
+/// "#;
+///
+/// let registry = Format::Json.parse(contents).unwrap();
+/// assert!(registry.unique_tags.contains_key("orders.created"));
+/// assert_eq!(Format::Json.serialize(&registry), contents);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Format {
+    /// TOML, the format of `types.toml`.
+    #[default]
+    Toml,
+
+    /// The `[package.metadata.unique-uuid]` table of a Cargo manifest, for registries
+    /// whose file name is `Cargo.toml`.
+    ///
+    /// Its sections are `[package.metadata.unique-uuid.unique_tags]` and
+    /// `[package.metadata.unique-uuid.unique_type_tags]`. The manifest is edited in place
+    /// when the registry is written, touching only the entries that changed, and a
+    /// virtual workspace manifest, without `[package]`, is rejected.
+    ///
+    /// ```rust
+    /// use unique_uuid_core::Format;
+    ///
+    /// let manifest = "[package]\nname = \"billing\" # the crate\n\n\
+    ///                 [package.metadata.unique-uuid.unique_tags]\n\
+    ///                 'orders.created' = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n\
+    ///                 [dependencies]\n";
+    ///
+    /// let mut registry = Format::CargoMetadata.parse(manifest).unwrap();
+    /// registry.unique_tags.insert(
+    ///     "orders.paid".to_string(),
+    ///     "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24".into(),
+    /// );
+    /// assert_eq!(
+    ///     Format::CargoMetadata.rewrite(manifest, &registry).unwrap(),
+    ///     "[package]\nname = \"billing\" # the crate\n\n\
+    ///      [package.metadata.unique-uuid.unique_tags]\n\
+    ///      'orders.created' = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\
+    ///      \"orders.paid\" = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\n\n\
+    ///      [dependencies]\n"
+    /// );
+    ///
+    /// assert!(Format::CargoMetadata.parse("[workspace]\n").is_err());
+    /// ```
+    CargoMetadata,
+
+    /// JSON, for registries whose path ends with `.json`.
+    Json,
+
+    /// RON, for registries whose path ends with `.ron`, only parsed with the `ron`
+    /// feature.
+    Ron,
+
+    /// YAML, for registries whose path ends with `.yaml` or `.yml`, only parsed with the
+    /// `yaml` feature.
+    Yaml,
+}
+
+impl Format {
+    /// Returns the format of the registry file stored at `path`: the package metadata of
+    /// a `Cargo.toml`, JSON for a `.json` extension, RON for a `.ron` one, YAML for a
+    /// `.yaml` or `.yml` one, TOML otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid_core::Format;
+    ///
+    /// assert_eq!(Format::of("types.toml"), Format::Toml);
+    /// assert_eq!(Format::of("config/types.json"), Format::Json);
+    /// assert_eq!(Format::of("Cargo.toml"), Format::CargoMetadata);
+    /// ```
+    pub fn of(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if path
+            .file_name()
+            .is_some_and(|name| name == manifest::MANIFEST_FILE_NAME)
+        {
+            return Self::CargoMetadata;
+        }
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::Json,
+            Some("ron") => Self::Ron,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Toml,
+        }
+    }
+
+    /// Parses `contents`, without byte order mark and with `\n` line endings. An empty
+    /// document is an empty registry in every format.
+    ///
+    /// # Errors
+    /// Returns an error if `contents` is not a valid registry in this format, or if the
+    /// feature reading this format is disabled.
+    pub fn parse(self, contents: &str) -> Result<FileStructure, String> {
+        let document: serde_json::Value = match self {
+            Self::Toml => return toml::from_str(contents).map_err(|err| err.to_string()),
+            Self::CargoMetadata => {
+                return toml::Value::Table(manifest::registry_table(contents)?)
+                    .try_into()
+                    .map_err(|err: toml::de::Error| err.to_string())
+            }
+            _ if contents.trim().is_empty() => return Ok(FileStructure::default()),
+            Self::Json => serde_json::from_str(contents).map_err(|err| err.to_string())?,
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::from_str(contents).map_err(|err| err.to_string())?,
+            #[cfg(not(feature = "ron"))]
+            Self::Ron => {
+                return Err("RON registries require the `ron` feature of `unique-uuid`".to_string())
+            }
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::from_str(contents).map_err(|err| err.to_string())?,
+            #[cfg(not(feature = "yaml"))]
+            Self::Yaml => {
+                return Err(
+                    "YAML registries require the `yaml` feature of `unique-uuid`".to_string(),
+                )
+            }
+        };
+        if !document.is_object() {
+            return Err("the registry must be a map of sections".to_string());
+        }
+        from_json(document)?
+            .try_into()
+            .map_err(|err: toml::de::Error| err.to_string())
+    }
+
+    /// Serializes `file_structure` in the canonical form of this format, as a file holding
+    /// nothing else.
+    pub fn serialize(self, file_structure: &FileStructure) -> String {
+        match self {
+            Self::Toml => file_structure.to_canonical_string(),
+            Self::CargoMetadata => manifest::fragment(&document(file_structure)),
+            _ => self.serialize_value(&to_json(&toml::Value::Table(document(file_structure)))),
+        }
+    }
+
+    /// Serializes `file_structure` in its canonical form, to be written over `contents`,
+    /// the registry file as read without byte order mark and with `\n` line endings. Only
+    /// a manifest is edited from `contents`, which fails if it is not a package manifest.
+    ///
+    /// # Errors
+    /// Returns an error if this is [`Format::CargoMetadata`] and `contents` is not a
+    /// package manifest.
+    pub fn rewrite(self, contents: &str, file_structure: &FileStructure) -> Result<String, String> {
+        match self {
+            Self::CargoMetadata => manifest::edit(contents, &document(file_structure)),
+            _ => Ok(self.serialize(file_structure)),
+        }
+    }
+
+    /// Serializes `section`, holding `entries`, alone, for a file storing only that
+    /// section. Sections are never stored alone in a manifest.
+    pub fn serialize_section(
+        self,
+        section: Section,
+        entries: &BTreeMap<String, toml::Value>,
+    ) -> String {
+        let name = section.name();
+        match self {
+            Self::Toml | Self::CargoMetadata => document::section_string(name, entries),
+            _ => {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| (key.clone(), to_json(value)))
+                    .collect();
+                let mut document = serde_json::Map::new();
+                document.insert(name.to_string(), serde_json::Value::Object(entries));
+                self.serialize_value(&serde_json::Value::Object(document))
+            }
+        }
+    }
+
+    /// Serializes `document` in this format, pretty-printed, for the formats other than
+    /// TOML.
+    fn serialize_value(self, document: &serde_json::Value) -> String {
+        let mut serialized = match self {
+            #[cfg(feature = "ron")]
+            Self::Ron => {
+                let config = ron::ser::PrettyConfig::new().new_line("\n");
+                ron::ser::to_string_pretty(document, config).unwrap_or_default()
+            }
+            #[cfg(feature = "yaml")]
+            Self::Yaml => {
+                // Already ends with a newline
+                return serde_yaml::to_string(document).unwrap_or_default();
+            }
+            _ => serde_json::to_string_pretty(document).unwrap_or_default(),
+        };
+        serialized.push('\n');
+        serialized
+    }
+}
+
+/// Returns the document written back for `file_structure`: a table per section and the
+/// other top-level items.
+fn document(file_structure: &FileStructure) -> toml::Table {
+    let mut document = file_structure.other.clone();
+    for (name, entries) in [
+        ("unique_tags", &file_structure.unique_tags),
+        ("unique_type_tags", &file_structure.unique_type_tags),
+    ] {
+        let entries = entries
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        document.insert(name.to_string(), toml::Value::Table(entries));
+    }
+    document
+}
+
+/// Converts a TOML value to JSON, with the keys of tables sorted. Datetimes and
+/// non-finite floats, which JSON cannot represent, become strings. RON documents are
+/// serialized from the same JSON values, and so are YAML ones.
+fn to_json(value: &toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(value) => serde_json::Value::String(value.clone()),
+        toml::Value::Integer(value) => serde_json::Value::from(*value),
+        toml::Value::Float(value) => serde_json::Number::from_f64(*value)
+            .map_or_else(|| value.to_string().into(), serde_json::Value::Number),
+        toml::Value::Boolean(value) => serde_json::Value::Bool(*value),
+        toml::Value::Datetime(value) => serde_json::Value::String(value.to_string()),
+        toml::Value::Array(values) => values.iter().map(to_json).collect(),
+        toml::Value::Table(table) => {
+            let sorted: BTreeMap<&String, &toml::Value> = table.iter().collect();
+            serde_json::Value::Object(
+                sorted
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), to_json(value)))
+                    .collect(),
+            )
+        }
+    }
+}
+
+/// Converts a JSON value, also parsed from RON or YAML, to TOML, failing on `null` which TOML
+/// cannot represent.
+fn from_json(value: serde_json::Value) -> Result<toml::Value, String> {
+    Ok(match value {
+        serde_json::Value::Null => return Err("`null` is not a valid registry value".to_string()),
+        serde_json::Value::Bool(value) => toml::Value::Boolean(value),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(value) => toml::Value::Integer(value),
+            None => toml::Value::Float(number.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(value) => toml::Value::String(value),
+        serde_json::Value::Array(values) => toml::Value::Array(
+            values
+                .into_iter()
+                .map(from_json)
+                .collect::<Result<_, _>>()?,
+        ),
+        serde_json::Value::Object(entries) => toml::Value::Table(
+            entries
+                .into_iter()
+                .map(|(key, value)| Ok((key, from_json(value)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+    })
+}
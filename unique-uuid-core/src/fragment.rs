@@ -0,0 +1,249 @@
+//! The fragments of new entries, written without taking the registry lock.
+//!
+//! With `fragments = true` in the `[config]` table, each new entry is written to a file of
+//! its own in `<registry>.d/pending`, e.g. `types.toml.d/pending`, named after the time
+//! it was written, the process and a counter, so that no two writers ever share a file:
+//! ```text
+//! types.toml.d/pending/01760529600000000000-4242-0.toml
+//! ```
+//! A fragment holds one journal line (see [`journal`]). It is written to a temporary file
+//! first, then renamed in place, so that readers only observe complete fragments.
+//!
+//! Concurrent writers of the same key agree on one fragment through its claim, a hard
+//! link to the fragment in `<registry>.d/claims`, named after the key: the link is only
+//! created if no other fragment claimed the key, atomically, so the first writer to link
+//! its fragment wins. Lookups merge the claimed fragments over the registry file, whose
+//! entries take precedence.
+//!
+//! A compaction, under the registry lock, writes the claimed fragments into the registry
+//! file and only then deletes the fragments and their claims. It runs after each new
+//! fragment when the lock is free, and with the compaction of the journal
+//! ([`crate::compact`], `unique-uuid-admin compact`). Fragments that lost their claim are
+//! merged after the claimed ones, so they never override them.
+//!
+//! A process crashing after writing a fragment but before claiming it leaves a complete
+//! fragment, which the next compaction claims, in the order of the names, unless another
+//! fragment claimed its key meanwhile. Temporary files left by an interrupted write end
+//! with `.tmp`, are never read, and are deleted by compactions once older than a minute.
+use std::{
+    ffi::OsStr,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    document::{format_key, FileStructure},
+    journal,
+    stats::{self, Counter},
+};
+
+/// The age past which a temporary fragment is considered left by an interrupted write.
+const STALE: Duration = Duration::from_secs(60);
+
+/// The fragments written by this process, to name each of them differently.
+static WRITTEN: AtomicU64 = AtomicU64::new(0);
+
+/// Returns the directory of the pending fragments of the registry file at `path`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::Path;
+/// use unique_uuid_core::fragments_path;
+///
+/// assert_eq!(
+///     fragments_path(Path::new("types.toml")),
+///     Path::new("types.toml.d/pending")
+/// );
+/// ```
+pub fn fragments_path(path: &Path) -> PathBuf {
+    directory_of(path).join("pending")
+}
+
+/// Returns the directory of the claims of the fragments of the registry file at `path`.
+fn claims_path(path: &Path) -> PathBuf {
+    directory_of(path).join("claims")
+}
+
+/// Returns `<registry>.d`, the directory of the fragments of the registry file at `path`.
+fn directory_of(path: &Path) -> PathBuf {
+    let mut directory = path.as_os_str().to_owned();
+    directory.push(".d");
+    directory.into()
+}
+
+/// Returns the path of the claim of `key` in `[section]`, named after the FNV-1a hash of
+/// the entry, as keys may hold any character.
+fn claim_of(path: &Path, section: &str, key: &str) -> PathBuf {
+    let hash = format!("{}.{}", section, format_key(key))
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    claims_path(path).join(format!("{:016x}.toml", hash))
+}
+
+/// The fragments of a registry file, as read at a point in time.
+#[derive(Default)]
+pub(crate) struct Fragments {
+    /// The fragments and claims read, to delete once compacted.
+    files: Vec<PathBuf>,
+
+    /// The lines of the claimed fragments, then of the pending fragments if read, in the
+    /// order of their names.
+    contents: String,
+}
+
+impl Fragments {
+    /// Merges the fragments into `file_structure`, as [`journal::merge`] merges a journal.
+    pub(crate) fn merge(&self, file_structure: &mut FileStructure) {
+        journal::merge(file_structure, &self.contents);
+    }
+
+    /// Returns `true` if there was no fragment.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Deletes the fragments and their claims, once merged into the registry file, and
+    /// the temporary fragments left by interrupted writes. Fragments written since they
+    /// were read are kept for the next compaction.
+    pub(crate) fn remove(self, path: &Path) -> io::Result<()> {
+        for file in &self.files {
+            ignore_missing(fs::remove_file(file))?;
+        }
+        let now = SystemTime::now();
+        for file in list(&fragments_path(path), "tmp")? {
+            let stale = fs::metadata(&file)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| now.duration_since(modified).is_ok_and(|age| age > STALE));
+            if stale {
+                ignore_missing(fs::remove_file(&file))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the claimed fragments of the registry file at `path`, as lookups see them.
+///
+/// Claims must be read before the registry file: a claim deleted meanwhile by a
+/// compaction is skipped, as its entry is then in the registry file.
+pub(crate) fn read(path: &Path) -> io::Result<Fragments> {
+    let mut fragments = Fragments::default();
+    append(&mut fragments, list(&claims_path(path), "toml")?)?;
+    Ok(fragments)
+}
+
+/// Claims the pending fragments of the registry file at `path` whose key has neither an
+/// entry in `file_structure` nor a claim, e.g. left by a crashed process, then reads the
+/// claimed fragments and the pending ones, to compact them. The registry lock must be
+/// held.
+pub(crate) fn read_all(path: &Path, file_structure: &FileStructure) -> io::Result<Fragments> {
+    let pending = list(&fragments_path(path), "toml")?;
+    for file in &pending {
+        let contents = match fs::read_to_string(file) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        for (section, key, _) in journal::entries(&contents) {
+            let registered = match section.as_str() {
+                "unique_tags" => file_structure.unique_tags.contains_key(&key),
+                "unique_type_tags" => file_structure.unique_type_tags.contains_key(&key),
+                _ => true,
+            };
+            if !registered {
+                claim(path, file, &section, &key)?;
+            }
+        }
+    }
+
+    let mut fragments = read(path)?;
+    append(&mut fragments, pending)?;
+    Ok(fragments)
+}
+
+/// Writes the entry `key = value` of `[section]` to a new fragment of the registry file
+/// at `path`, and claims it unless another fragment claimed `key` first.
+pub(crate) fn write(path: &Path, section: &str, key: &str, value: &toml::Value) -> io::Result<()> {
+    let directory = fragments_path(path);
+    fs::create_dir_all(&directory)?;
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let name = format!(
+        "{:020}-{}-{}",
+        time,
+        std::process::id(),
+        WRITTEN.fetch_add(1, Ordering::Relaxed)
+    );
+
+    let temporary = directory.join(format!("{}.tmp", name));
+    let fragment = directory.join(format!("{}.toml", name));
+    stats::io(Counter::FileWrites, || {
+        let mut file = fs::File::create(&temporary)?;
+        file.write_all(journal::line(section, key, value).as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temporary, &fragment)?;
+        claim(path, &fragment, section, key)
+    })
+}
+
+/// Claims `key` of `[section]` for `fragment`, unless another fragment claimed it.
+fn claim(path: &Path, fragment: &Path, section: &str, key: &str) -> io::Result<()> {
+    fs::create_dir_all(claims_path(path))?;
+    match fs::hard_link(fragment, claim_of(path, section, key)) {
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => Ok(()),
+        result => result,
+    }
+}
+
+/// Reads `files`, appending their lines and paths to `fragments`. A file deleted
+/// meanwhile, by a concurrent compaction, is skipped.
+fn append(fragments: &mut Fragments, files: Vec<PathBuf>) -> io::Result<()> {
+    for file in files {
+        match stats::io(Counter::FileReads, || fs::read_to_string(&file)) {
+            Ok(contents) => {
+                fragments.contents.push_str(&contents);
+                if !contents.ends_with('\n') {
+                    fragments.contents.push('\n');
+                }
+                fragments.files.push(file);
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Lists the files of `directory` with the extension `extension`, in the order of their
+/// names, a missing directory being empty.
+fn list(directory: &Path, extension: &str) -> io::Result<Vec<PathBuf>> {
+    let mut files = match fs::read_dir(directory) {
+        Ok(listing) => listing
+            .map(|file| file.map(|file| file.path()))
+            .filter(|file| {
+                file.as_ref()
+                    .map_or(true, |file| file.extension() == Some(OsStr::new(extension)))
+            })
+            .collect::<io::Result<Vec<_>>>()?,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    files.sort();
+    Ok(files)
+}
+
+/// Maps a failure to find a file to a success, as the file was deleted concurrently.
+fn ignore_missing(result: io::Result<()>) -> io::Result<()> {
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
@@ -0,0 +1,113 @@
+//! The groups of an entry, recorded in its `groups` array:
+//!
+//! ```toml
+//! [unique_tags]
+//! "invoice.sent" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", groups = ["billing", "external"] }
+//! ```
+//!
+//! Groups are labels independent of the keys and of the parents, and never affect the
+//! UUID. The macros add the groups they are given and keep the others, so that a group
+//! is only removed by editing the registry. New group names follow the `tag_pattern`
+//! and `tag_style` of the registry, as new tags do.
+use crate::{config::Config, document::FileStructure};
+
+/// Returns the groups of the entry `value`, in the order of its `groups` array.
+pub(crate) fn of(value: &toml::Value) -> impl Iterator<Item = &str> {
+    value.as_table().into_iter().flat_map(groups)
+}
+
+/// Returns the groups listed by `entry`, the fields of a structured entry, in the order
+/// of its `groups` array.
+///
+/// # Example
+///
+/// ```rust
+/// let entry: toml::Table = toml::from_str(r#"
+///     uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+///     groups = ["billing", "external"]
+/// "#).unwrap();
+/// assert!(unique_uuid_core::groups(&entry).eq(["billing", "external"]));
+/// ```
+pub fn groups(entry: &toml::Table) -> impl Iterator<Item = &str> {
+    entry
+        .get("groups")
+        .and_then(toml::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(toml::Value::as_str)
+}
+
+/// Returns `value`, an entry, in structured form with `groups` added to its `groups`
+/// array, sorted, or `None` if it already has all of them.
+pub(crate) fn with_groups(value: &toml::Value, groups: &[String]) -> Option<toml::Value> {
+    if groups.iter().all(|group| of(value).any(|g| g == group)) {
+        return None;
+    }
+    let mut table = match value {
+        toml::Value::Table(table) => table.clone(),
+        toml::Value::String(uuid) => {
+            toml::Table::from_iter([("uuid".to_string(), toml::Value::String(uuid.clone()))])
+        }
+        _ => return None,
+    };
+    let mut all: Vec<&str> = of(value).chain(groups.iter().map(String::as_str)).collect();
+    all.sort_unstable();
+    all.dedup();
+    table.insert(
+        "groups".to_string(),
+        toml::Value::Array(
+            all.into_iter()
+                .map(|group| toml::Value::String(group.to_string()))
+                .collect(),
+        ),
+    );
+    Some(toml::Value::Table(table))
+}
+
+/// Checks the names of `groups` that no entry of `file_structure` or `others` has yet
+/// against the naming policy of the registry, returning the warnings to surface.
+///
+/// Fails if a new name does not match the `tag_pattern`, or does not follow the
+/// `tag_style` under `strict`.
+pub(crate) fn check_names(
+    groups: &[String],
+    config: &Config,
+    file_structure: &FileStructure,
+    others: &FileStructure,
+) -> Result<Vec<String>, String> {
+    let known = |group: &str| {
+        [file_structure, others].into_iter().any(|file_structure| {
+            file_structure
+                .unique_tags
+                .values()
+                .chain(file_structure.unique_type_tags.values())
+                .any(|value| of(value).any(|g| g == group))
+        })
+    };
+
+    let mut warnings = Vec::new();
+    for group in groups.iter().filter(|group| !known(group)) {
+        if let Some(pattern) = config.tag_pattern.as_ref().filter(|p| !p.is_match(group)) {
+            return Err(format!(
+                "The group `{}` does not match the `tag_pattern` of the registry, `{}`. \
+                 Existing groups are accepted, but new groups must match it.",
+                group,
+                pattern.as_str()
+            ));
+        }
+        if let Some(suggestion) = config.tag_style.suggest(group) {
+            let message = format!(
+                "The group `{}` does not follow the {} convention of the registry, \
+                 consider `{}`.",
+                group,
+                config.tag_style.name(),
+                suggestion
+            );
+            if config.strict {
+                return Err(message);
+            }
+            warnings.push(message);
+        }
+    }
+    Ok(warnings)
+}
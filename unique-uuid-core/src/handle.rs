@@ -0,0 +1,273 @@
+//! Handles on a registry, resolving the paths of the files it is stored in.
+use std::path::{Path, PathBuf};
+
+use crate::{
+    directory, manifest, memory, paths,
+    registry::{
+        self, EntryName, NamespaceTags, RegistryFingerprint, ReservedTags, Resolved, TagOptions,
+    },
+    Registry, Section, DEFAULT_TYPES_FILE_NAME,
+};
+
+/// The environment variable overriding the path of the registry file.
+const REGISTRY_VARIABLE: &str = "UNIQUE_UUID_REGISTRY";
+
+/// The file name of the test registry of a registry stored in a manifest.
+const DEFAULT_TEST_FILE_NAME: &str = "types.test.toml";
+
+/// A registry, designated by the path it is configured with: a file, or a registry
+/// directory (see `unique-uuid-derive`).
+///
+/// The macros resolve their registry from the environment with
+/// [`RegistryHandle::from_env`]. Tests and tools open a registry at an explicit path
+/// with [`RegistryHandle::open`] instead, so that a process may use several registries,
+/// and several handles the same registry, e.g. from several threads.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::{RegistryHandle, Section};
+///
+/// let directory = std::env::temp_dir().join("unique-uuid-core-doc-handle");
+/// # let _ = std::fs::remove_dir_all(&directory);
+/// std::fs::create_dir_all(&directory).unwrap();
+/// let registry = RegistryHandle::open(directory.join("types.toml"));
+///
+/// let uuid = registry.lookup_or_insert("orders.created", Section::UniqueTags).unwrap();
+/// let other = RegistryHandle::open(directory.join("types.toml"));
+/// assert_eq!(other.lookup_or_insert("orders.created", Section::UniqueTags).unwrap(), uuid);
+/// assert!(registry.read().unwrap().unique_tags.contains_key("orders.created"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryHandle {
+    /// The path of the registry as configured, possibly relative.
+    configured: PathBuf,
+
+    /// Whether the registry is kept in memory instead, see `UNIQUE_UUID_IN_MEMORY`.
+    in_memory: bool,
+}
+
+impl RegistryHandle {
+    /// Returns a handle on the registry at `path`, which is only opened when used. A
+    /// relative path is resolved against the current directory.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self {
+            configured: path.into(),
+            in_memory: false,
+        }
+    }
+
+    /// Returns a handle on the registry of the macros: the value of
+    /// `UNIQUE_UUID_REGISTRY`, with `${workspace_root}` expanded, the manifest of the
+    /// compiled crate if it stores the registry, or `types.toml`.
+    ///
+    /// When `UNIQUE_UUID_IN_MEMORY` is set, the registry is instead kept in the memory of
+    /// the process, overriding every configured path: no file is ever read or written,
+    /// and the UUIDs differ between builds.
+    pub fn from_env() -> Self {
+        if memory::enabled() {
+            return Self {
+                configured: PathBuf::from(DEFAULT_TYPES_FILE_NAME),
+                in_memory: true,
+            };
+        }
+        let configured = match std::env::var(REGISTRY_VARIABLE) {
+            Ok(path) if !path.is_empty() => paths::expand(&path),
+            _ => manifest::opted_in_manifest()
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_TYPES_FILE_NAME)),
+        };
+        Self::open(configured)
+    }
+
+    /// Returns a handle on the test registry of this registry, which holds the tags of
+    /// `unique_tag!(test, ...)` so that they never reach the registry itself: `.test` is
+    /// inserted before the extension of the configured path, e.g. `types.test.toml` for
+    /// `types.toml` and `types.test.d` for a registry directory, and a registry stored in
+    /// the manifest of the crate gets `types.test.toml` next to it.
+    ///
+    /// The test registry is a registry of its own, with its own `[config]` table, so
+    /// that e.g. `frozen_sections` can forbid new tags in the registry while allowing
+    /// new test tags.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::path::Path;
+    ///
+    /// use unique_uuid_core::RegistryHandle;
+    ///
+    /// let test = |path: &str| RegistryHandle::open(path).test_registry();
+    /// assert_eq!(test("types.toml").configured_path(), Path::new("types.test.toml"));
+    /// assert_eq!(test("data/types.json").configured_path(), Path::new("data/types.test.json"));
+    /// assert_eq!(test("types.d").configured_path(), Path::new("types.test.d"));
+    /// assert_eq!(test("app/Cargo.toml").configured_path(), Path::new("app/types.test.toml"));
+    /// ```
+    pub fn test_registry(&self) -> Self {
+        Self {
+            configured: test_registry_path(&self.configured),
+            in_memory: self.in_memory,
+        }
+    }
+
+    /// Returns `true` if the registry is kept in memory, see [`RegistryHandle::from_env`].
+    /// Its paths then designate no file.
+    pub fn is_in_memory(&self) -> bool {
+        self.in_memory
+    }
+
+    /// Returns the path of the registry as configured.
+    pub fn configured_path(&self) -> &Path {
+        &self.configured
+    }
+
+    /// Returns the configured registry, made absolute.
+    fn absolute_path(&self) -> PathBuf {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(&self.configured))
+            .unwrap_or_else(|_| self.configured.clone())
+    }
+
+    /// Returns the path of the registry file, made absolute so that generated code can
+    /// refer to it: the file of the compiled crate if the registry is a directory.
+    pub fn path(&self) -> PathBuf {
+        match self.directory() {
+            Some(directory) => directory::crate_file(&directory),
+            None => self.absolute_path(),
+        }
+    }
+
+    /// Returns the registry directory, made absolute, if the registry is one.
+    pub fn directory(&self) -> Option<PathBuf> {
+        if self.in_memory {
+            return None;
+        }
+        Some(self.absolute_path()).filter(|path| directory::is_directory(path))
+    }
+
+    /// Returns the UUID associated with `tag` in `section`, minting and persisting a new
+    /// one if needed, with the warnings to surface to the user.
+    ///
+    /// # Errors
+    /// Fails if the registry cannot be read, parsed or written, with a message describing
+    /// the registry and the cause, or if `tag` is new and breaks a policy of the registry
+    /// configured to fail the expansion, e.g. a `tag_style` under `strict`.
+    pub fn get_uuid_from_tag(
+        &self,
+        tag: &str,
+        section: Section,
+        options: &TagOptions,
+    ) -> Result<Resolved, String> {
+        registry::get_uuid_from_tag(self, tag, section, options)
+    }
+
+    /// Returns the UUID associated with `key` in `section`, minting and persisting a new
+    /// one if needed, as the macros do.
+    ///
+    /// This is [`RegistryHandle::get_uuid_from_tag`] with the default options,
+    /// discarding the warnings.
+    ///
+    /// # Errors
+    /// Fails as [`RegistryHandle::get_uuid_from_tag`] does.
+    pub fn lookup_or_insert(&self, key: &str, section: Section) -> Result<uuid::Uuid, String> {
+        self.get_uuid_from_tag(key, section, &TagOptions::default())
+            .map(|resolved| resolved.uuid)
+    }
+
+    /// Returns the UUIDs of `keys` in `[unique_tags]`, in the order of the keys, minting
+    /// the missing ones, with the warnings to surface to the user. `source` is recorded
+    /// in the new entries, as [`TagOptions::source`] is.
+    ///
+    /// Existing entries are kept, and nothing is written if every key has one. The new
+    /// entries are written at once, under a single lock of the registry file, unless
+    /// the registry records every new entry on its own: with a journal, pending
+    /// fragments or a `tags_file`.
+    ///
+    /// # Errors
+    /// Fails as [`RegistryHandle::get_uuid_from_tag`] does, for the first key that
+    /// fails.
+    pub fn reserve_tags(
+        &self,
+        keys: &[String],
+        source: Option<&str>,
+    ) -> Result<ReservedTags, String> {
+        registry::reserve_tags(self, keys, source)
+    }
+
+    /// Returns the `[unique_tags]` entries belonging to `namespace` or to one of its
+    /// nested namespaces, sorted by key. The registry is only read.
+    ///
+    /// # Errors
+    /// Fails if the registry cannot be read, or holds malformed entries.
+    pub fn namespace_tags(&self, namespace: &str) -> Result<NamespaceTags, String> {
+        registry::namespace_tags(self, namespace)
+    }
+
+    /// Returns the name hashed into the fingerprint of the registry, covering the entries
+    /// of `namespace` if given and every entry otherwise. The registry is only read.
+    ///
+    /// # Errors
+    /// Fails if the registry cannot be read, or holds malformed entries.
+    pub fn fingerprint(&self, namespace: Option<&str>) -> Result<RegistryFingerprint, String> {
+        registry::fingerprint(self, namespace)
+    }
+
+    /// Returns the canonical key of `key`, an entry of either section or an alias of one.
+    /// The registry is only read.
+    ///
+    /// # Errors
+    /// Fails if the registry cannot be read, holds malformed entries, or has no entry
+    /// for `key`.
+    pub fn canonical_key(&self, key: &str) -> Result<EntryName, String> {
+        registry::canonical_key(self, key)
+    }
+
+    /// Returns the key of the single entry of either section whose UUID is `uuid`. The
+    /// registry is only read.
+    ///
+    /// # Errors
+    /// Fails if the registry cannot be read, holds malformed entries, or if no entry or
+    /// several entries have `uuid`.
+    pub fn key_of_uuid(&self, uuid: uuid::Uuid) -> Result<EntryName, String> {
+        registry::key_of_uuid(self, uuid)
+    }
+
+    /// Reads the registry file, with its journal and the sections stored in files of
+    /// their own. A missing file has no entries.
+    ///
+    /// # Errors
+    /// Fails if a file of the registry cannot be read or parsed.
+    pub fn read(&self) -> Result<Registry, String> {
+        registry::read(self)
+    }
+}
+
+/// Returns the path of the test registry of the registry at `path`, see
+/// [`RegistryHandle::test_registry`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::Path;
+/// use unique_uuid_core::test_registry_path;
+///
+/// assert_eq!(test_registry_path(Path::new("types.toml")), Path::new("types.test.toml"));
+/// assert_eq!(test_registry_path(Path::new("app/Cargo.toml")), Path::new("app/types.test.toml"));
+/// ```
+pub fn test_registry_path(path: &Path) -> PathBuf {
+    let is_manifest = path
+        .file_name()
+        .is_some_and(|name| name == manifest::MANIFEST_FILE_NAME);
+    let file_name = match (path.file_stem(), path.extension()) {
+        (Some(stem), extension) if !is_manifest => {
+            let mut name = stem.to_os_string();
+            name.push(".test");
+            if let Some(extension) = extension {
+                name.push(".");
+                name.push(extension);
+            }
+            name
+        }
+        _ => DEFAULT_TEST_FILE_NAME.into(),
+    };
+    path.with_file_name(file_name)
+}
@@ -0,0 +1,121 @@
+//! Small integer ids allocated alongside the UUIDs, with `ids = true` in the `[config]`
+//! table of the registry:
+//!
+//! ```toml
+//! [unique_type_tags]
+//! "::Order" = { id = 17, uuid = "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db" }
+//!
+//! [ids]
+//! last = 17
+//!
+//! [config]
+//! ids = true
+//! ```
+//!
+//! Each entry resolved by the macros without an `id` field gets the next id, one above
+//! both the `last` id ever allocated and the greatest id of the registry, and `last` is
+//! updated in the same write. The ids of both sections share a single space. Since
+//! `last` is never decreased, the id of a pruned entry is never allocated again. Ids are
+//! allocated in the registry file only: `ids` cannot be combined with `journal`,
+//! `fragments`, `tags_file` or `type_tags_file`.
+use std::collections::BTreeMap;
+
+use crate::{document::FileStructure, Section};
+
+/// The top-level table holding the `last` id allocated.
+const TABLE: &str = "ids";
+
+/// Returns the `id` field of the entry `value`, if any.
+pub(crate) fn entry_id(value: &toml::Value) -> Option<u32> {
+    value
+        .get("id")
+        .and_then(toml::Value::as_integer)
+        .and_then(|id| u32::try_from(id).ok())
+}
+
+/// Returns `value`, an entry, in structured form with the id `id`.
+pub(crate) fn with_id(value: &toml::Value, id: u32) -> toml::Value {
+    let mut table = match value {
+        toml::Value::Table(table) => table.clone(),
+        toml::Value::String(uuid) => {
+            toml::Table::from_iter([("uuid".to_string(), toml::Value::String(uuid.clone()))])
+        }
+        _ => toml::Table::new(),
+    };
+    table.insert("id".to_string(), toml::Value::Integer(id.into()));
+    toml::Value::Table(table)
+}
+
+/// Returns the next id to allocate in `file_structure`, the registry file, given the
+/// entries of `others`, the files of the other crates of the registry directory.
+pub(crate) fn next(file_structure: &FileStructure, others: &FileStructure) -> Result<u32, String> {
+    let last = match file_structure.other.get(TABLE) {
+        None => 0,
+        Some(table) => table
+            .get("last")
+            .and_then(toml::Value::as_integer)
+            .and_then(|last| u32::try_from(last).ok())
+            .ok_or_else(|| {
+                format!(
+                    "`{}.last` must be a positive integer, found `{}`",
+                    TABLE, table
+                )
+            })?,
+    };
+    let greatest = [file_structure, others]
+        .into_iter()
+        .flat_map(|file_structure| sections(file_structure).into_iter().flatten())
+        .filter_map(|(_, value)| entry_id(value))
+        .max()
+        .unwrap_or(0);
+    last.max(greatest)
+        .checked_add(1)
+        .ok_or_else(|| "every id has been allocated".to_string())
+}
+
+/// Records `id` as the `last` id allocated in `items`, the top-level items of the registry
+/// file.
+pub(crate) fn record(items: &mut toml::Table, id: u32) {
+    let last = toml::Table::from_iter([("last".to_string(), toml::Value::Integer(id.into()))]);
+    items.insert(TABLE.to_string(), toml::Value::Table(last));
+}
+
+/// Fails if another entry of `file_structure` or `others` has `id`, the id of `tag` in
+/// `section`, naming both.
+pub(crate) fn check_unique(
+    tag: &str,
+    section: Section,
+    id: u32,
+    file_structure: &FileStructure,
+    others: &FileStructure,
+) -> Result<(), String> {
+    for file_structure in [file_structure, others] {
+        for (other_section, entries) in [Section::UniqueTags, Section::UniqueTypeTags]
+            .into_iter()
+            .zip(sections(file_structure))
+        {
+            let duplicate = entries.iter().find(|(key, value)| {
+                (other_section != section || key.as_str() != tag) && entry_id(value) == Some(id)
+            });
+            if let Some((key, _)) = duplicate {
+                return Err(format!(
+                    "`{}` and `{}` in [{}] both have the id {}: an id identifies a single \
+                     entry, remove one of them from the registry",
+                    tag,
+                    key,
+                    other_section.name(),
+                    id
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns both sections of `file_structure`.
+fn sections(file_structure: &FileStructure) -> [&BTreeMap<String, toml::Value>; 2] {
+    [
+        &file_structure.unique_tags,
+        &file_structure.unique_type_tags,
+    ]
+}
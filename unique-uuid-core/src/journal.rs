@@ -0,0 +1,228 @@
+//! The append-only journal of new entries, kept next to the registry file.
+//!
+//! Each line of the journal holds one entry, prefixed by its section:
+//! ```toml
+//! unique_tags."orders.created" = { crate = "billing", created = "…", uuid = "…" }
+//! ```
+//! Lines are appended with a single write to a file opened in append mode, under a
+//! shared lock, so concurrent writers never wait for each other. Compaction takes the
+//! registry lock (see [`lock`]), then the journal lock exclusively, writes the merged
+//! registry to a temporary file renamed over the registry file, and only then empties
+//! the journal: a crash at any point leaves either the journal entries or the merged
+//! registry (or both) in place. The pending fragments of the registry (see [`fragment`])
+//! are compacted along with the journal.
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    document::{format_key, FileStructure, Layout},
+    format::Format,
+    fragment,
+    handle::RegistryHandle,
+    lock,
+    registry::{decode_registry, parse_registry, refresh_cache, replace_file},
+    share, split,
+    stats::{self, Counter},
+};
+
+/// Returns the path of the journal of the registry file at `path`.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::Path;
+/// use unique_uuid_core::journal_path;
+///
+/// assert_eq!(journal_path(Path::new("types.toml")), Path::new("types.toml.journal"));
+/// ```
+pub fn journal_path(path: &Path) -> PathBuf {
+    let mut journal = path.as_os_str().to_owned();
+    journal.push(".journal");
+    journal.into()
+}
+
+/// Returns the line recording the entry `key = value` of `[section]`.
+pub(crate) fn line(section: &str, key: &str, value: &toml::Value) -> String {
+    format!("{}.{} = {}\n", section, format_key(key), value)
+}
+
+/// Parses the complete lines of `contents` into `(section, key, value)` entries, in
+/// order. A last line without a line feed, left by an interrupted write, and lines that
+/// are not entries are ignored.
+pub(crate) fn entries(contents: &str) -> Vec<(String, String, toml::Value)> {
+    let complete = contents.rfind('\n').map_or("", |end| &contents[..=end]);
+    complete
+        .lines()
+        .filter_map(|line| {
+            let table: toml::Table = toml::from_str(line).ok()?;
+            let (section, value) = table.into_iter().next()?;
+            let toml::Value::Table(entry) = value else {
+                return None;
+            };
+            let (key, value) = entry.into_iter().next()?;
+            Some((section, key, value))
+        })
+        .collect()
+}
+
+/// Merges the entries of the journal `contents` into `file_structure`.
+///
+/// Entries of the registry file take precedence over the journal, and the first
+/// occurrence of a key in the journal over the next ones, so the merge does not depend
+/// on how often the journal was compacted.
+pub(crate) fn merge(file_structure: &mut FileStructure, contents: &str) {
+    for (section, key, value) in entries(contents) {
+        let target = match section.as_str() {
+            "unique_tags" => &mut file_structure.unique_tags,
+            "unique_type_tags" => &mut file_structure.unique_type_tags,
+            _ => continue,
+        };
+        target.entry(key).or_insert(value);
+    }
+}
+
+/// Reads the journal, a missing journal being empty.
+pub(crate) fn read(handle: &RegistryHandle) -> io::Result<String> {
+    match stats::io(Counter::FileReads, || {
+        fs::read_to_string(journal_path(&handle.path()))
+    }) {
+        Ok(contents) => Ok(contents),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Appends the entry `key = value` of `[section]` to the journal, and returns the whole
+/// journal as read right after.
+///
+/// Reading the journal back lets concurrent writers of the same key agree on the first
+/// entry appended.
+pub(crate) fn append(
+    handle: &RegistryHandle,
+    section: &str,
+    key: &str,
+    value: &toml::Value,
+) -> io::Result<String> {
+    let mut journal = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .read(true)
+        .open(journal_path(&handle.path()))?;
+    journal.lock_shared()?;
+
+    // Terminate a line left incomplete by an interrupted write, so it does not swallow
+    // the entry
+    let mut line = line(section, key, value);
+    let length = journal.metadata()?.len();
+    if length > 0 {
+        let mut last = [0u8];
+        journal.seek(SeekFrom::Start(length - 1))?;
+        journal.read_exact(&mut last)?;
+        if last[0] != b'\n' {
+            line.insert(0, '\n');
+        }
+    }
+    journal.write_all(line.as_bytes())?;
+    read(handle)
+}
+
+/// Compacts the journal and the pending fragments into the registry file, applying
+/// `update` to the merged registry before it is written, and returns the registry as
+/// written.
+pub(crate) fn compact(
+    handle: &RegistryHandle,
+    update: impl FnOnce(&mut FileStructure),
+) -> io::Result<FileStructure> {
+    // The registry lock comes first, as for every write of the registry file
+    let _lock = lock::exclusive(&handle.path())?;
+    compact_locked(handle, update)
+}
+
+/// Compacts the journal and the pending fragments into the registry file as
+/// [`compact`] does, the registry lock being held by the caller.
+pub(crate) fn compact_locked(
+    handle: &RegistryHandle,
+    update: impl FnOnce(&mut FileStructure),
+) -> io::Result<FileStructure> {
+    // A registry without a journal does not get an empty one
+    let journal = match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(journal_path(&handle.path()))
+    {
+        Ok(journal) => Some(journal),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    let mut contents = String::new();
+    if let Some(mut journal) = journal.as_ref() {
+        journal.lock()?;
+        journal.read_to_string(&mut contents)?;
+    }
+
+    let registry = read_registry_file(handle)?;
+    let mut file_structure = parse(handle, &registry)?;
+    merge(&mut file_structure, &contents);
+    let fragments = fragment::read_all(&handle.path(), &file_structure)?;
+    fragments.merge(&mut file_structure);
+    update(&mut file_structure);
+    write_registry(
+        handle,
+        &registry,
+        Layout::detect(&registry),
+        &file_structure,
+    )?;
+
+    if let Some(journal) = journal {
+        journal.set_len(0)?;
+    }
+    fragments.remove(&handle.path())?;
+    Ok(file_structure)
+}
+
+/// Writes `file_structure` over `registry`, the registry file as read, in its format and
+/// in `layout`, with the sections stored in files of their own.
+pub(crate) fn write_registry(
+    handle: &RegistryHandle,
+    registry: &str,
+    layout: Layout,
+    file_structure: &FileStructure,
+) -> io::Result<()> {
+    split::write_sections(handle, file_structure)?;
+    let registry_file_structure = split::registry_file_structure(handle, file_structure);
+    let toml = Format::of(handle.path())
+        .rewrite(&Layout::strip(registry), &registry_file_structure)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let toml = layout.apply(toml);
+    replace_file(&handle.path(), &toml)?;
+    refresh_cache(handle, &toml, &registry_file_structure);
+    Ok(())
+}
+
+/// Reads and parses the registry file, a missing file being empty.
+pub(crate) fn read_registry(handle: &RegistryHandle) -> io::Result<FileStructure> {
+    parse(handle, &read_registry_file(handle)?)
+}
+
+/// Reads the registry file, a missing file being empty.
+fn read_registry_file(handle: &RegistryHandle) -> io::Result<String> {
+    match share::read(&handle.path()) {
+        Ok(bytes) => decode_registry(handle, bytes)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Parses `contents`, the content of the registry file, with the sections stored in
+/// files of their own.
+fn parse(handle: &RegistryHandle, contents: &str) -> io::Result<FileStructure> {
+    let mut file_structure = parse_registry(handle, contents)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    split::load(handle, &mut file_structure)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(file_structure)
+}
@@ -0,0 +1,122 @@
+//! The registry handling behind the `unique-uuid` macros.
+//!
+//! This crate holds everything the macros of `unique-uuid-derive` do with the registry
+//! file: resolving its path from the environment, reading and writing it in each of its
+//! formats and layouts, and minting the entries of new keys. The procedural macro crate
+//! only parses the macro inputs and generates code, so that this logic can be tested and
+//! reused outside of a macro expansion.
+//!
+//! A [`RegistryHandle`] designates a registry: the one of the macros, resolved from the
+//! environment, or a registry at an explicit path. With the `test-util` feature,
+//! `TempRegistry` creates a throwaway registry per test.
+//!
+//! The registry of the macros is resolved from `UNIQUE_UUID_REGISTRY` and the other
+//! environment variables documented by `unique-uuid-derive`, relative to the current
+//! directory. Tools read and write a registry at an explicit path with [`load`],
+//! [`save`], [`update`] and [`compact`], as the `registry` module of `unique-uuid` does;
+//! applications reading the registry at runtime should use that module instead.
+//!
+//! # Example
+//!
+//! ```rust
+//! use unique_uuid_core::{RegistryHandle, Section};
+//!
+//! let directory = std::env::temp_dir().join("unique-uuid-core-doc");
+//! # let _ = std::fs::remove_dir_all(&directory);
+//! std::fs::create_dir_all(&directory).unwrap();
+//! std::env::set_var("UNIQUE_UUID_REGISTRY", directory.join("types.toml"));
+//!
+//! // The registry of the macros
+//! let registry = RegistryHandle::from_env();
+//! let uuid = registry.lookup_or_insert("orders.created", Section::UniqueTags).unwrap();
+//! assert_eq!(registry.path(), directory.join("types.toml"));
+//! assert_eq!(registry.lookup_or_insert("orders.created", Section::UniqueTags).unwrap(), uuid);
+//! assert!(std::fs::read_to_string(directory.join("types.toml"))
+//!     .unwrap()
+//!     .contains(&uuid.to_string()));
+//! ```
+
+mod alias;
+mod cache;
+mod codegen;
+mod config;
+mod directory;
+mod document;
+mod format;
+mod fragment;
+mod group;
+mod handle;
+mod ids;
+mod journal;
+mod lock;
+mod log;
+mod manifest;
+mod memory;
+mod meta;
+mod metadata;
+mod minted;
+mod mirror;
+mod parent;
+mod paths;
+mod pattern;
+mod registry;
+mod rename;
+mod reserved;
+mod share;
+mod split;
+mod stats;
+mod store;
+mod style;
+#[cfg(feature = "test-util")]
+mod temp;
+mod usage;
+
+pub use codegen::{constant_name, rust_module, CodegenError};
+pub use config::{fold_key, CaseInsensitiveKeys, Config, PinConflict};
+pub use document::{FileStructure as Registry, Layout};
+pub use format::Format;
+pub use fragment::fragments_path;
+pub use group::groups;
+pub use handle::{test_registry_path, RegistryHandle};
+pub use journal::journal_path;
+pub use meta::MetaValue;
+pub use metadata::{new_entry, new_uuid};
+pub use mirror::{json_mirror, json_string};
+pub use registry::{
+    in_namespace, EntryName, NamespaceTags, RegistryFingerprint, ReservedTags, Resolved, TagOptions,
+};
+pub use store::{compact, load, save, update, Error};
+#[cfg(feature = "test-util")]
+pub use temp::TempRegistry;
+pub use usage::track_usage;
+
+static DEFAULT_TYPES_FILE_NAME: &str = "types.toml";
+
+/// The sections of the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Section {
+    /// `[unique_tags]`, the tags of `unique_tag!`.
+    UniqueTags,
+
+    /// `[unique_type_tags]`, the tags of `#[derive(UniqueTypeTag)]`.
+    UniqueTypeTags,
+}
+
+impl Section {
+    /// All the sections, in the order they appear in the registry file.
+    pub const ALL: [Section; 2] = [Section::UniqueTags, Section::UniqueTypeTags];
+
+    /// Returns the name of the registry section holding this type of tag.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Section::UniqueTags => "unique_tags",
+            Section::UniqueTypeTags => "unique_type_tags",
+        }
+    }
+}
+
+impl std::fmt::Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
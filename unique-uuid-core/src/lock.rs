@@ -0,0 +1,68 @@
+//! The lock serializing the writes of a registry file.
+//!
+//! Every write of the registry file, e.g. `types.toml`, happens under an exclusive lock of
+//! `<registry>.lock`, e.g. `types.toml.lock`, held from the time the registry is read
+//! until the new content is renamed over it: a macro minting an entry, a journal
+//! compaction, or a tool saving the registry. With `fragments = true`, the macros only
+//! take the lock if no other process holds it, see [`fragment`](crate::fragment). The lock is taken on a file of its own,
+//! since the registry file is replaced by each write: a process waiting for a lock of the
+//! replaced file would then read the registry as it was. The lock file holds nothing, can
+//! be deleted while no build runs, and belongs in `.gitignore`.
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::share;
+
+/// The exclusive lock of a registry file, released when dropped.
+#[must_use = "the lock is released when dropped"]
+pub(crate) struct RegistryLock {
+    _file: File,
+}
+
+/// Returns the path of the lock of the registry file at `path`.
+pub(crate) fn lock_of(path: &Path) -> PathBuf {
+    let mut lock = path.as_os_str().to_owned();
+    lock.push(".lock");
+    lock.into()
+}
+
+/// Takes the lock of the registry file at `path`, waiting for the process holding it.
+pub(crate) fn exclusive(path: &Path) -> io::Result<RegistryLock> {
+    let file = open(path)?;
+    file.lock()?;
+    Ok(RegistryLock { _file: file })
+}
+
+/// Takes the lock of the registry file at `path` if no other process holds it, without
+/// waiting.
+pub(crate) fn try_exclusive(path: &Path) -> io::Result<Option<RegistryLock>> {
+    let file = open(path)?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(RegistryLock { _file: file })),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(err)) => Err(err),
+    }
+}
+
+/// Opens the lock file of the registry file at `path`, creating it and its directory if
+/// needed.
+fn open(path: &Path) -> io::Result<File> {
+    let lock = lock_of(path);
+    if let Some(directory) = lock
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(directory)?;
+    }
+    share::open(
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false),
+        &lock,
+    )
+}
@@ -0,0 +1,153 @@
+//! Opt-in log of the operations of the macros on the registry.
+//!
+//! When the `UNIQUE_UUID_LOG` environment variable is set to `debug`, the process logs a
+//! line to the standard error for each registry file it opens and reads, each file it
+//! parses, each parse served by the sidecar cache, each entry it inserts and each file it
+//! writes, with the key being resolved, the path of the file and the time the operation
+//! took, e.g.
+//!
+//! ```text
+//! unique-uuid: event=open crate="billing" key="orders.paid" path="/app/types.toml" elapsed_us=31
+//! unique-uuid: event=parse crate="billing" key="orders.paid" path="/app/types.toml" elapsed_us=112
+//! unique-uuid: event=insert crate="billing" key="orders.paid" path="/app/types.toml" elapsed_us=190
+//! unique-uuid: event=write crate="billing" key="orders.paid" path="/app/types.toml" elapsed_us=644
+//! ```
+//!
+//! The time of an `insert` is the time since the resolution of the key started. Strings
+//! are quoted as in JSON, and `key` is omitted for the operations outside the resolution
+//! of a key. When `UNIQUE_UUID_LOG_FILE` is set to a path as well, the lines are appended
+//! to that file instead, a file that cannot be written never failing the build.
+//!
+//! Nothing is timed nor formatted unless the log is enabled.
+use std::{
+    cell::RefCell,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Instant,
+};
+
+static LOG_ENV: &str = "UNIQUE_UUID_LOG";
+static LOG_FILE_ENV: &str = "UNIQUE_UUID_LOG_FILE";
+
+/// A logged operation, named as in the log.
+#[derive(Clone, Copy)]
+pub(crate) enum Event {
+    /// A registry file opened and read.
+    Open,
+    /// A registry file parsed.
+    Parse,
+    /// A registry file parsed from its sidecar cache.
+    CacheHit,
+    /// An entry inserted.
+    Insert,
+    /// A registry file, journal, fragment or section file written.
+    Write,
+}
+
+impl Event {
+    fn name(self) -> &'static str {
+        match self {
+            Event::Open => "open",
+            Event::Parse => "parse",
+            Event::CacheHit => "cache_hit",
+            Event::Insert => "insert",
+            Event::Write => "write",
+        }
+    }
+}
+
+/// Where the log goes, once enabled.
+enum Sink {
+    Stderr,
+    File(PathBuf),
+}
+
+/// Returns where the log goes, if it is enabled.
+fn sink() -> Option<&'static Sink> {
+    static SINK: OnceLock<Option<Sink>> = OnceLock::new();
+    SINK.get_or_init(|| {
+        if !std::env::var(LOG_ENV).is_ok_and(|level| level.eq_ignore_ascii_case("debug")) {
+            return None;
+        }
+        Some(
+            match std::env::var_os(LOG_FILE_ENV).filter(|path| !path.is_empty()) {
+                Some(path) => Sink::File(PathBuf::from(path)),
+                None => Sink::Stderr,
+            },
+        )
+    })
+    .as_ref()
+}
+
+thread_local! {
+    /// The key being resolved by this thread, if the log is enabled.
+    static KEY: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Clears the key being resolved when dropped, see [`resolving`].
+pub(crate) struct Resolving;
+
+impl Drop for Resolving {
+    fn drop(&mut self) {
+        KEY.with(|key| key.borrow_mut().take());
+    }
+}
+
+/// Records `key` as the key being resolved by this thread, named by the lines logged
+/// until the returned guard is dropped.
+pub(crate) fn resolving(key: &str) -> Option<Resolving> {
+    sink()?;
+    KEY.with(|current| *current.borrow_mut() = Some(key.to_string()));
+    Some(Resolving)
+}
+
+/// Returns the start of an operation to log with [`event`], if the log is enabled.
+pub(crate) fn start() -> Option<Instant> {
+    sink().map(|_| Instant::now())
+}
+
+/// Logs `event` on the file at `path`, which started at `start`, if the log is enabled.
+pub(crate) fn event(event: Event, path: &Path, start: Option<Instant>) {
+    let (Some(sink), Some(start)) = (sink(), start) else {
+        return;
+    };
+    let elapsed = start.elapsed().as_micros();
+
+    let quote = |value: String| serde_json::Value::String(value).to_string();
+    let mut line = format!("unique-uuid: event={}", event.name());
+    if let Ok(name) = std::env::var("CARGO_PKG_NAME") {
+        line.push_str(&format!(" crate={}", quote(name)));
+    }
+    if let Some(key) = KEY.with(|key| key.borrow().clone()) {
+        line.push_str(&format!(" key={}", quote(key)));
+    }
+    line.push_str(&format!(
+        " path={} elapsed_us={}\n",
+        quote(path.display().to_string()),
+        elapsed
+    ));
+
+    // A failure to log must not fail the build
+    match sink {
+        Sink::Stderr => {
+            let _ = std::io::stderr().lock().write_all(line.as_bytes());
+        }
+        Sink::File(path) => {
+            if let Ok(mut file) = OpenOptions::new().append(true).create(true).open(path) {
+                if file.lock().is_ok() {
+                    let _ = file.write_all(line.as_bytes());
+                }
+            }
+        }
+    }
+}
+
+/// Runs `operation` on the file at `path`, logging it as `event` with the time it takes.
+pub(crate) fn io<T>(event: Event, path: &Path, operation: impl FnOnce() -> T) -> T {
+    let start = start();
+    let result = operation();
+    self::event(event, path, start);
+    result
+}
@@ -0,0 +1,146 @@
+//! Storage of the registry in the `[package.metadata.unique-uuid]` table of the manifest
+//! of the compiled crate, selected there with
+//! ```toml
+//! [package.metadata.unique-uuid.config]
+//! registry = "cargo-metadata"
+//! ```
+//!
+//! The manifest is edited in place: only the entries of the registry that were added,
+//! changed or removed are written, so the comments, formatting and other sections of the
+//! manifest are never disturbed.
+use std::path::PathBuf;
+
+use crate::document::Layout;
+
+/// The file name of Cargo manifests, which selects this storage.
+pub(crate) const MANIFEST_FILE_NAME: &str = "Cargo.toml";
+
+/// Returns the path of the manifest of the compiled crate if it stores the registry.
+pub(crate) fn opted_in_manifest() -> Option<PathBuf> {
+    let path = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?).join(MANIFEST_FILE_NAME);
+    let contents = std::fs::read_to_string(&path).ok()?;
+    if !contents.contains("cargo-metadata") {
+        return None;
+    }
+    let table = registry_table(&Layout::strip(&contents)).ok()?;
+    let registry = table.get("config")?.get("registry")?.as_str()?;
+    (registry == "cargo-metadata").then_some(path)
+}
+
+/// The path of the registry table in the manifest.
+const TABLE: [&str; 3] = ["package", "metadata", "unique-uuid"];
+
+/// Returns the registry table of the manifest `contents`, without byte order mark and
+/// with `\n` line endings, empty if the manifest has none.
+pub(crate) fn registry_table(contents: &str) -> Result<toml::Table, String> {
+    let mut table: toml::Table = toml::from_str(contents).map_err(|err| err.to_string())?;
+    if !table.contains_key(TABLE[0]) {
+        return Err(virtual_manifest());
+    }
+    for (depth, key) in TABLE.into_iter().enumerate() {
+        table = match table.remove(key) {
+            Some(toml::Value::Table(table)) => table,
+            Some(_) => return Err(format!("`{}` must be a table", TABLE[..=depth].join("."))),
+            None => return Ok(toml::Table::new()),
+        };
+    }
+    Ok(table)
+}
+
+/// Writes the registry `document`, with a table per section, into the manifest
+/// `contents`. Only the entries that were added, changed or removed are touched: new
+/// entries are inserted in key order, and new sections are appended to the manifest.
+pub(crate) fn edit(contents: &str, document: &toml::Table) -> Result<String, String> {
+    let previous = registry_table(contents)?;
+    let mut manifest: toml_edit::DocumentMut = contents
+        .parse()
+        .map_err(|err: toml_edit::TomlError| err.to_string())?;
+
+    let mut table = manifest.as_table_mut();
+    for (depth, key) in TABLE.into_iter().enumerate() {
+        table = table
+            .entry(key)
+            .or_insert_with(implicit_table)
+            .as_table_mut()
+            .ok_or_else(|| format!("`{}` must be a table", TABLE[..=depth].join(".")))?;
+    }
+    update(table, &previous, document, 0)?;
+    Ok(manifest.to_string())
+}
+
+/// Serializes the registry `document` as the tables of a manifest holding nothing else.
+pub(crate) fn fragment(document: &toml::Table) -> String {
+    let mut manifest = toml_edit::DocumentMut::new();
+    let mut table = manifest.as_table_mut();
+    for key in TABLE {
+        table = table
+            .entry(key)
+            .or_insert_with(implicit_table)
+            .as_table_mut()
+            .unwrap();
+    }
+    let _ = update(table, &toml::Table::new(), document, 0);
+    manifest.to_string()
+}
+
+/// Updates `table`, which holds `previous`, to hold `document`, leaving unchanged items
+/// as they are laid out. New tables are only written with a header directly under the
+/// registry table, e.g. the sections; structured entries are written inline.
+fn update(
+    table: &mut toml_edit::Table,
+    previous: &toml::Table,
+    document: &toml::Table,
+    depth: usize,
+) -> Result<(), String> {
+    table.retain(|key, _| document.contains_key(key));
+    let mut inserted = false;
+    for (key, value) in document {
+        if previous.get(key) == Some(value) {
+            continue;
+        }
+        match value {
+            toml::Value::Table(entries)
+                if table.get(key).map_or(depth == 0, |item| item.is_table()) =>
+            {
+                if entries.is_empty() && !table.contains_key(key) {
+                    continue;
+                }
+                let item = table
+                    .entry(key)
+                    .or_insert_with(|| toml_edit::Item::Table(toml_edit::Table::new()));
+                let previous = match previous.get(key) {
+                    Some(toml::Value::Table(previous)) => previous.clone(),
+                    _ => toml::Table::new(),
+                };
+                if let Some(item) = item.as_table_mut() {
+                    update(item, &previous, entries, depth + 1)?;
+                }
+            }
+            value => {
+                let value: toml_edit::Value = value
+                    .to_string()
+                    .parse()
+                    .map_err(|err: toml_edit::TomlError| err.to_string())?;
+                inserted |= !table.contains_key(key);
+                table.insert(key, toml_edit::Item::Value(value));
+            }
+        }
+    }
+    if inserted {
+        table.sort_values();
+    }
+    Ok(())
+}
+
+/// Returns a table that only appears in the headers of its subtables.
+fn implicit_table() -> toml_edit::Item {
+    let mut table = toml_edit::Table::new();
+    table.set_implicit(true);
+    toml_edit::Item::Table(table)
+}
+
+fn virtual_manifest() -> String {
+    "the manifest has no [package]: a virtual workspace manifest cannot hold the \
+     registry, store it in the manifest of a package instead"
+        .to_string()
+}
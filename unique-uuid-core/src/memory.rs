@@ -0,0 +1,86 @@
+//! The in-memory registry of the macros, for throwaway builds.
+//!
+//! When the `UNIQUE_UUID_IN_MEMORY` environment variable is set, e.g. to `1`,
+//! [`RegistryHandle::from_env`] returns a handle on a registry kept in the memory of the
+//! process only, whatever `UNIQUE_UUID_REGISTRY` or the manifest of the crate configure.
+//! No file is read, created or written: a key gets the same UUID for as long as the
+//! process, e.g. the proc-macro server of the compiler, lives, and a new one the next
+//! time. The UUIDs differ between builds, so that the code built this way must never be
+//! shipped, nor its tags persisted.
+//!
+//! [`RegistryHandle::from_env`]: crate::RegistryHandle::from_env
+use std::sync::{Mutex, OnceLock};
+
+use crate::{
+    alias,
+    config::Config,
+    document::FileStructure,
+    metadata,
+    registry::{entry_uuid, Resolved, TagOptions},
+    Section,
+};
+
+/// The environment variable selecting the in-memory registry.
+const IN_MEMORY_VARIABLE: &str = "UNIQUE_UUID_IN_MEMORY";
+
+/// Returns `true` if the macros use the in-memory registry.
+pub(crate) fn enabled() -> bool {
+    std::env::var_os(IN_MEMORY_VARIABLE).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Returns the entries resolved by this process.
+fn registry() -> &'static Mutex<FileStructure> {
+    static REGISTRY: OnceLock<Mutex<FileStructure>> = OnceLock::new();
+    REGISTRY.get_or_init(Mutex::default)
+}
+
+/// Returns the UUID of `tag` in `section` of the in-memory registry, minting a new one,
+/// or the pinned one, if needed.
+///
+/// The policies of the registry file, e.g. `tag_style` or reservations, do not apply, as
+/// no file is read.
+pub(crate) fn get_uuid_from_tag(tag: &str, section: Section, options: &TagOptions) -> Resolved {
+    let mut registry = registry().lock().unwrap_or_else(|err| err.into_inner());
+    let entries = match section {
+        Section::UniqueTags => &mut registry.unique_tags,
+        Section::UniqueTypeTags => &mut registry.unique_type_tags,
+    };
+    if let Some(entry) = options
+        .rename_from
+        .filter(|_| !entries.contains_key(tag))
+        .and_then(|previous| entries.remove(previous))
+    {
+        entries.insert(tag.to_string(), entry);
+    }
+
+    let existing = entries
+        .get(tag)
+        .or_else(|| alias::owner(entries, tag).map(|(_, entry)| entry))
+        .and_then(entry_uuid);
+    let uuid = match existing {
+        Some(uuid) => uuid,
+        None => {
+            let uuid = options
+                .pinned
+                .unwrap_or_else(|| metadata::new_uuid(&Config::default()));
+            let entry = toml::Value::String(uuid.to_string());
+            let entry = alias::with_aliases(&entry, options.aliases).unwrap_or(entry);
+            entries.insert(tag.to_string(), entry);
+            uuid
+        }
+    };
+    Resolved {
+        uuid,
+        id: None,
+        meta: options.meta.to_vec(),
+        warnings: Vec::new(),
+    }
+}
+
+/// Returns the entries resolved by this process, as the registry file would hold them.
+pub(crate) fn read() -> FileStructure {
+    registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+}
@@ -0,0 +1,135 @@
+//! Named scalar values of an entry, recorded in its `meta` table:
+//!
+//! ```toml
+//! [unique_type_tags]
+//! "::OrderCreated" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", meta = { code = 7 } }
+//!
+//! [config]
+//! unique_meta = ["code"]
+//! ```
+//!
+//! The macros set the values they are given and keep the others, so that a value moved
+//! out of the source keeps its place in the registry. The values of the names listed by
+//! `unique_meta` must differ between the entries of the registry.
+use std::fmt;
+
+use crate::{document::FileStructure, Section};
+
+/// A value of the `meta` table of an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MetaValue {
+    /// A non-negative integer, e.g. a legacy discriminant.
+    Integer(u32),
+
+    /// A boolean.
+    Boolean(bool),
+
+    /// A string.
+    String(String),
+}
+
+impl MetaValue {
+    /// Reads `value`, a value of a `meta` table, if it is a supported scalar.
+    pub fn from_toml(value: &toml::Value) -> Option<Self> {
+        match value {
+            toml::Value::Integer(integer) => u32::try_from(*integer).ok().map(Self::Integer),
+            toml::Value::Boolean(boolean) => Some(Self::Boolean(*boolean)),
+            toml::Value::String(string) => Some(Self::String(string.clone())),
+            _ => None,
+        }
+    }
+
+    fn to_toml(&self) -> toml::Value {
+        match self {
+            Self::Integer(integer) => toml::Value::Integer((*integer).into()),
+            Self::Boolean(boolean) => toml::Value::Boolean(*boolean),
+            Self::String(string) => toml::Value::String(string.clone()),
+        }
+    }
+}
+
+impl fmt::Display for MetaValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_toml())
+    }
+}
+
+/// Returns the `meta` table of the entry `value`, with the values that are not supported
+/// scalars left out, in name order.
+pub(crate) fn of(value: &toml::Value) -> Vec<(String, MetaValue)> {
+    value
+        .get("meta")
+        .and_then(toml::Value::as_table)
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, value)| Some((name.clone(), MetaValue::from_toml(value)?)))
+        .collect()
+}
+
+/// Returns `value`, an entry, in structured form with `meta` set in its `meta` table,
+/// or `None` if it already holds these values. The other values are kept.
+pub(crate) fn with_meta(value: &toml::Value, meta: &[(String, MetaValue)]) -> Option<toml::Value> {
+    let current = value.get("meta").and_then(toml::Value::as_table);
+    let changed = meta
+        .iter()
+        .any(|(name, meta)| current.and_then(|table| table.get(name)) != Some(&meta.to_toml()));
+    if !changed {
+        return None;
+    }
+    let mut table = match value {
+        toml::Value::Table(table) => table.clone(),
+        toml::Value::String(uuid) => {
+            toml::Table::from_iter([("uuid".to_string(), toml::Value::String(uuid.clone()))])
+        }
+        _ => return None,
+    };
+    let mut values = current.cloned().unwrap_or_default();
+    values.extend(
+        meta.iter()
+            .map(|(name, meta)| (name.clone(), meta.to_toml())),
+    );
+    table.insert("meta".to_string(), toml::Value::Table(values));
+    Some(toml::Value::Table(table))
+}
+
+/// Fails if another entry of `file_structure` or `others` has one of the values of
+/// `meta`, given to `tag` in `section`, for a name listed in `unique`.
+pub(crate) fn check_unique(
+    tag: &str,
+    section: Section,
+    meta: &[(String, MetaValue)],
+    unique: &[String],
+    file_structure: &FileStructure,
+    others: &FileStructure,
+) -> Result<(), String> {
+    for (name, value) in meta.iter().filter(|(name, _)| unique.contains(name)) {
+        for file_structure in [file_structure, others] {
+            for (other_section, entries) in [
+                (Section::UniqueTags, &file_structure.unique_tags),
+                (Section::UniqueTypeTags, &file_structure.unique_type_tags),
+            ] {
+                let duplicate = entries.iter().find(|(key, entry)| {
+                    (other_section != section || key.as_str() != tag)
+                        && entry
+                            .get("meta")
+                            .and_then(|meta| meta.get(name))
+                            .and_then(MetaValue::from_toml)
+                            .as_ref()
+                            == Some(value)
+                });
+                if let Some((key, _)) = duplicate {
+                    return Err(format!(
+                        "`{}` cannot have `{} = {}`: `{}` in [{}] already has it, and \
+                         `unique_meta` requires distinct values",
+                        tag,
+                        name,
+                        value,
+                        key,
+                        other_section.name()
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! Metadata recorded alongside newly minted entries.
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+
+/// Returns a new random UUID for an entry minted by the crate being compiled, starting
+/// with the prefix the registry reserves for it, if any.
+///
+/// The prefix only overwrites leading bytes, before the version and variant bits, so the
+/// UUID remains a valid v4 UUID with 8 fewer random bits per byte of prefix.
+pub fn new_uuid(config: &Config) -> uuid::Uuid {
+    let uuid = uuid::Uuid::new_v4();
+    let name = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+    match config.prefix(&name) {
+        Some(prefix) => with_prefix(uuid, prefix),
+        None => uuid,
+    }
+}
+
+/// Returns `uuid` with its leading bytes replaced by `prefix`.
+fn with_prefix(uuid: uuid::Uuid, prefix: &[u8]) -> uuid::Uuid {
+    let mut bytes = *uuid.as_bytes();
+    bytes[..prefix.len()].copy_from_slice(prefix);
+    uuid::Uuid::from_bytes(bytes)
+}
+
+/// Returns the structured entry written for a newly minted `uuid`.
+///
+/// Besides the UUID, the entry records when it was created and, if known, the name of
+/// the crate being compiled. The metadata is informative only, and never read back to
+/// resolve a key.
+pub fn new_entry(uuid: uuid::Uuid) -> toml::Value {
+    let mut table = toml::Table::new();
+    table.insert("uuid".to_string(), toml::Value::String(uuid.to_string()));
+    table.insert("created".to_string(), toml::Value::String(now_rfc3339()));
+    if let Ok(name) = std::env::var("CARGO_PKG_NAME") {
+        table.insert("crate".to_string(), toml::Value::String(name));
+    }
+    toml::Value::Table(table)
+}
+
+/// Returns `entry` with its `source` field set to `source`, the location of the macro
+/// invocation, if known. Like the other metadata, it is never read back to resolve a key.
+pub(crate) fn with_source(mut entry: toml::Value, source: Option<&str>) -> toml::Value {
+    if let (toml::Value::Table(table), Some(source)) = (&mut entry, source) {
+        table.insert(
+            "source".to_string(),
+            toml::Value::String(source.to_string()),
+        );
+    }
+    entry
+}
+
+/// Formats the current time as an RFC 3339 UTC timestamp, to the second.
+fn now_rfc3339() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let (days, time) = (seconds / 86_400, seconds % 86_400);
+
+    // Civil date from the number of days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
@@ -0,0 +1,66 @@
+//! Notices of the entries minted by the macros, so that new entries never reach the
+//! registry unnoticed and get committed along with the code using them.
+use std::sync::Mutex;
+
+use crate::{config::Config, handle::RegistryHandle, Section};
+
+/// The environment variable turning the notice into an error, so that no entry is
+/// minted.
+const DENY_VARIABLE: &str = "UNIQUE_UUID_DENY_NEW";
+
+/// Keys of the entries minted by this process, in the order they were minted.
+static SESSION_MINTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Fails if minting an entry for `tag` is forbidden by `UNIQUE_UUID_DENY_NEW`, or by the
+/// frozen sections and namespaces of the registry.
+pub(crate) fn check_allowed(
+    handle: &RegistryHandle,
+    config: &Config,
+    tag: &str,
+    r#type: &Section,
+) -> Result<(), String> {
+    if let Some(frozen) = config.frozen(tag, *r#type) {
+        return Err(format!(
+            "unique-uuid: `{}` is not in [{}] of `{}`, and {} in the [config] of that file: \
+             the macros may only look up its existing entries. Use an existing key, or have \
+             the owners of the section add the entry to the registry.",
+            tag,
+            r#type.name(),
+            handle.path().display(),
+            frozen
+        ));
+    }
+    if std::env::var_os(DENY_VARIABLE).is_none_or(|value| value.is_empty()) {
+        return Ok(());
+    }
+    Err(format!(
+        "unique-uuid: `{}` is not in [{}] of `{}`, and `{}` forbids minting new entries. \
+         Add it to the registry, or unset `{}`.",
+        tag,
+        r#type.name(),
+        handle.path().display(),
+        DENY_VARIABLE,
+        DENY_VARIABLE
+    ))
+}
+
+/// Records that `tag` was minted with `uuid`, returning the notice to emit, which lists
+/// every entry this process minted so far.
+pub(crate) fn notice(handle: &RegistryHandle, tag: &str, uuid: &uuid::Uuid) -> String {
+    let mut minted = SESSION_MINTED.lock().unwrap_or_else(|err| err.into_inner());
+    minted.push(tag.to_string());
+    let count = match minted.len() {
+        1 => "1 new entry".to_string(),
+        count => format!("{} new entries", count),
+    };
+    format!(
+        "unique-uuid: minted `{}` = {}, {} in `{}` ({}). Commit the registry along with the \
+         code using them, or set `{}=1` to forbid new entries.",
+        tag,
+        uuid,
+        count,
+        handle.path().display(),
+        minted.join(", "),
+        DENY_VARIABLE
+    )
+}
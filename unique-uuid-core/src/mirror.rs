@@ -0,0 +1,100 @@
+//! The JSON mirror of the registry for non-Rust consumers, and its opt-in refresh.
+//!
+//! When the `UNIQUE_UUID_EMIT_JSON` environment variable is set to a path (which may use
+//! the `${workspace_root}` placeholder), every write of the registry also writes the
+//! [`json_mirror`] of the registry at that path.
+use std::fs;
+
+use crate::{document::FileStructure, paths, registry::entry_uuid};
+
+static EMIT_JSON_ENV: &str = "UNIQUE_UUID_EMIT_JSON";
+
+/// Rewrites the JSON mirror of the registry, if enabled.
+///
+/// Returns a warning to surface if the mirror cannot be written.
+pub(crate) fn refresh_json_mirror(file_structure: &FileStructure) -> Option<String> {
+    let path = paths::expand(&std::env::var(EMIT_JSON_ENV).ok()?);
+
+    let mut temporary = path.clone().into_os_string();
+    temporary.push(".tmp");
+    fs::write(&temporary, json_mirror(file_structure))
+        .and_then(|()| fs::rename(&temporary, &path))
+        .err()
+        .map(|err| {
+            format!(
+                "The JSON mirror `{}` could not be written: {}",
+                path.display(),
+                err
+            )
+        })
+}
+
+/// Returns the JSON mirror of `file_structure`, mapping the keys of each section to
+/// their UUID, malformed entries left out, with a `schema` version.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::{json_mirror, Registry};
+///
+/// let mut registry = Registry::default();
+/// registry.unique_tags.insert(
+///     "orders.created".to_string(),
+///     "2af1216e-7042-406a-a577-c39a7e7c3faa".into(),
+/// );
+/// assert_eq!(
+///     json_mirror(&registry),
+///     "{\n  \"unique_tags\": {\n    \
+///      \"orders.created\": \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n  },\n  \
+///      \"unique_type_tags\": {},\n  \"schema\": 1\n}\n"
+/// );
+/// ```
+pub fn json_mirror(file_structure: &FileStructure) -> String {
+    let mut json = String::from("{\n");
+    for (name, entries) in [
+        ("unique_tags", &file_structure.unique_tags),
+        ("unique_type_tags", &file_structure.unique_type_tags),
+    ] {
+        json.push_str(&format!("  \"{}\": {{", name));
+        let entries: Vec<String> = entries
+            .iter()
+            .filter_map(|(key, value)| {
+                let uuid = entry_uuid(value)?;
+                Some(format!("\n    {}: \"{}\"", json_string(key), uuid))
+            })
+            .collect();
+        json.push_str(&entries.join(","));
+        if !entries.is_empty() {
+            json.push_str("\n  ");
+        }
+        json.push_str("},\n");
+    }
+    json.push_str("  \"schema\": 1\n}\n");
+    json
+}
+
+/// Writes `value` as a JSON string literal, escaping quotes, backslashes and control
+/// characters.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(unique_uuid_core::json_string("say \"hi\"\n"), r#""say \"hi\"\n""#);
+/// ```
+pub fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
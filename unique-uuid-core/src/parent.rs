@@ -0,0 +1,32 @@
+//! The parent of an entry, recorded in its `parent` field:
+//!
+//! ```toml
+//! [unique_tags]
+//! payments = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+//! "payments.captured" = { uuid = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24", parent = "payments" }
+//! ```
+//!
+//! The parent is the key of another `[unique_tags]` entry, and never affects the UUID of
+//! the child. It is not checked when the child is minted, since the parent may be minted
+//! later in the same build; `unique_uuid::registry::Registry::validate` reports dangling
+//! parents and cycles. Must stay in sync with `unique_uuid::registry::Entry::parent`.
+
+/// Returns `value`, an entry, in structured form with its `parent` field set to
+/// `parent`, or `None` if it already records that parent.
+pub(crate) fn with_parent(value: &toml::Value, parent: &str) -> Option<toml::Value> {
+    if value.get("parent").and_then(toml::Value::as_str) == Some(parent) {
+        return None;
+    }
+    let mut table = match value {
+        toml::Value::Table(table) => table.clone(),
+        toml::Value::String(uuid) => {
+            toml::Table::from_iter([("uuid".to_string(), toml::Value::String(uuid.clone()))])
+        }
+        _ => return None,
+    };
+    table.insert(
+        "parent".to_string(),
+        toml::Value::String(parent.to_string()),
+    );
+    Some(toml::Value::Table(table))
+}
@@ -0,0 +1,34 @@
+//! Resolution of the paths given to the macros through environment variables.
+use std::path::{Path, PathBuf};
+
+/// Expands the `${workspace_root}` placeholder in `value`.
+///
+/// The workspace root is the closest ancestor of the compiled crate's manifest directory
+/// whose `Cargo.toml` declares a `[workspace]`, or the manifest directory itself.
+pub(crate) fn expand(value: &str) -> PathBuf {
+    const WORKSPACE_ROOT: &str = "${workspace_root}";
+    if !value.contains(WORKSPACE_ROOT) {
+        return PathBuf::from(value);
+    }
+    PathBuf::from(value.replace(WORKSPACE_ROOT, &workspace_root().to_string_lossy()))
+}
+
+fn workspace_root() -> PathBuf {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+
+    manifest_dir
+        .ancestors()
+        .find(|dir| declares_workspace(dir))
+        .unwrap_or(&manifest_dir)
+        .to_path_buf()
+}
+
+fn declares_workspace(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+        .is_some_and(|manifest| manifest.contains_key("workspace"))
+}
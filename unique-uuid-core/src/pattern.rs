@@ -0,0 +1,24 @@
+//! The `tag_pattern` setting: a regular expression every new tag must match.
+use std::sync::Mutex;
+
+use regex::Regex;
+
+/// The patterns compiled by this process, so that each is compiled once however many
+/// expansions read it.
+static COMPILED: Mutex<Vec<(String, Regex)>> = Mutex::new(Vec::new());
+
+/// Compiles `pattern`, describing why it is not a valid regular expression on failure.
+pub(crate) fn compile(pattern: &str) -> Result<Regex, String> {
+    let mut compiled = COMPILED.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some((_, regex)) = compiled.iter().find(|(source, _)| source == pattern) {
+        return Ok(regex.clone());
+    }
+    let regex = Regex::new(pattern).map_err(|err| {
+        format!(
+            "`config.tag_pattern` is not a valid regular expression: {}",
+            err
+        )
+    })?;
+    compiled.push((pattern.to_string(), regex.clone()));
+    Ok(regex)
+}
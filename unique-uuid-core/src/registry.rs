@@ -0,0 +1,1510 @@
+//! Reading and writing the registry file.
+use std::{
+    collections::BTreeMap,
+    fs::OpenOptions,
+    io::{Read, Write},
+};
+
+use crate::{
+    alias, cache, codegen,
+    config::{CaseInsensitiveKeys, Config, PinConflict},
+    directory,
+    document::{self, FileStructure, Layout},
+    format::Format,
+    fragment, group,
+    handle::RegistryHandle,
+    ids, journal, lock,
+    log::{self, Event},
+    memory,
+    meta::{self, MetaValue},
+    metadata, minted, mirror, parent, rename, reserved, share, split,
+    stats::{self, Counter},
+    store, usage, Section,
+};
+
+/// Extracts the UUID of an entry, either in plain or in structured form.
+pub(crate) fn entry_uuid(value: &toml::Value) -> Option<uuid::Uuid> {
+    parse_entry(value).ok()
+}
+
+/// Extracts the UUID of an entry, describing why it is malformed on failure.
+fn parse_entry(value: &toml::Value) -> Result<uuid::Uuid, String> {
+    let uuid = match value {
+        toml::Value::String(uuid) => uuid,
+        toml::Value::Table(table) => match table.get("uuid") {
+            Some(toml::Value::String(uuid)) => uuid,
+            Some(other) => return Err(format!("`uuid` must be a string, found `{}`", other)),
+            None => return Err("the entry has no `uuid` field".to_string()),
+        },
+        other => return Err(format!("expected a UUID or a table, found `{}`", other)),
+    };
+    uuid::Uuid::parse_str(uuid).map_err(|err| format!("`{}` is not a valid UUID ({})", uuid, err))
+}
+
+/// Checks every entry of the registry, naming the section and the key of the malformed
+/// ones.
+///
+/// Malformed entries fail the expansion, unless the `UNIQUE_UUID_LENIENT` environment
+/// variable is set: they are then reported as warnings, and otherwise ignored.
+fn check_entries(
+    handle: &RegistryHandle,
+    file_structure: &FileStructure,
+) -> Result<Vec<String>, String> {
+    let malformed: Vec<String> = [
+        ("unique_tags", &file_structure.unique_tags),
+        ("unique_type_tags", &file_structure.unique_type_tags),
+    ]
+    .into_iter()
+    .flat_map(|(section, entries)| {
+        entries.iter().filter_map(move |(key, value)| {
+            let reason = parse_entry(value).err()?;
+            Some(malformed_entry(section, key, &reason))
+        })
+    })
+    .collect();
+
+    if malformed.is_empty() {
+        Ok(malformed)
+    } else if std::env::var_os(LENIENT_VARIABLE).is_some() {
+        Ok(malformed
+            .into_iter()
+            .map(|entry| format!("Ignoring a malformed entry: {}", entry))
+            .collect())
+    } else {
+        Err(format!(
+            "The registry `{}` holds malformed entries:\n{}\nFix them, or set `{}=1` to \
+             ignore them.",
+            handle.path().display(),
+            malformed.join("\n"),
+            LENIENT_VARIABLE
+        ))
+    }
+}
+
+/// Describes the malformed entry of `key` in `[section]`.
+fn malformed_entry(section: &str, key: &str, reason: &str) -> String {
+    format!("`{}` in [{}] is malformed: {}", key, section, reason)
+}
+
+/// The environment variable allowing the expansion to proceed despite malformed entries.
+const LENIENT_VARIABLE: &str = "UNIQUE_UUID_LENIENT";
+
+/// Options altering how a key is resolved.
+#[derive(Default)]
+pub struct TagOptions<'a> {
+    /// A previous key of the entry. If `tag` is missing but this key exists, the entry
+    /// is moved to `tag`, keeping its UUID.
+    pub rename_from: Option<&'a str>,
+
+    /// The UUID the key is pinned to. A new entry gets this UUID instead of a random
+    /// one, and an existing entry with another UUID is handled according to the
+    /// `pin_conflict` setting of the registry. Pinning a UUID used by another key always
+    /// fails.
+    pub pinned: Option<uuid::Uuid>,
+
+    /// Alternative keys of the entry, sharing its UUID. They are added to the `aliases`
+    /// field of the entry, and must be neither the key nor an alias of another entry.
+    pub aliases: &'a [String],
+
+    /// The key of the parent of the entry, recorded in its `parent` field. The parent is
+    /// not required to exist, and never affects the UUID.
+    pub parent: Option<&'a str>,
+
+    /// Named scalar values to set in the `meta` table of the entry, keeping its other
+    /// values. Their names must be distinct.
+    pub meta: &'a [(String, MetaValue)],
+
+    /// The groups to add to the `groups` array of the entry, keeping its other groups.
+    /// New group names must follow the naming policy of the registry, as new tags do.
+    pub groups: &'a [String],
+
+    /// Where the key is used, e.g. `src/events.rs:42`. It is recorded in the `source`
+    /// field of a new entry, or of an entry rewritten with its pinned UUID, and never
+    /// affects the UUID.
+    pub source: Option<&'a str>,
+}
+
+/// The outcome of resolving a key.
+pub struct Resolved {
+    /// The UUID associated with the key.
+    pub uuid: uuid::Uuid,
+
+    /// The small integer id of the entry, if it has one, see `ids = true`.
+    pub id: Option<u32>,
+
+    /// The `meta` table of the entry, in name order, without the values that are not
+    /// supported scalars.
+    pub meta: Vec<(String, MetaValue)>,
+
+    /// Warnings to surface to the user.
+    pub warnings: Vec<String>,
+}
+
+/// Returns the UUID associated with `tag` in the registry of `handle`, minting and
+/// persisting a new one if needed, see [`RegistryHandle::get_uuid_from_tag`].
+pub(crate) fn get_uuid_from_tag(
+    handle: &RegistryHandle,
+    tag: &str,
+    r#type: Section,
+    options: &TagOptions,
+) -> Result<Resolved, String> {
+    stats::add(Counter::Expansions, 1);
+    if handle.is_in_memory() {
+        return Ok(memory::get_uuid_from_tag(tag, r#type, options));
+    }
+    let usage = usage::track_usage(tag, &r#type);
+    let mut resolved = resolve(handle, tag, r#type, options)?;
+    resolved.warnings.extend(usage.err());
+    Ok(resolved)
+}
+
+/// Resolves `tag` in the registry file of `handle`, see [`get_uuid_from_tag`].
+fn resolve(
+    handle: &RegistryHandle,
+    tag: &str,
+    r#type: Section,
+    options: &TagOptions,
+) -> Result<Resolved, String> {
+    let started = log::start();
+    let _logged = log::resolving(tag);
+    if let Section::UniqueTypeTags = r#type {
+        rename::record_session_key(tag);
+    }
+
+    let file_path = handle.path();
+    let format = Format::of(&file_path);
+    if let Some(directory) = handle.directory() {
+        std::fs::create_dir_all(directory).map_err(|err| access_error(handle, "create", &err))?;
+    }
+    // Held until the entry is written, so that concurrent resolutions do not overwrite
+    // each other. A registry writing fragments is read without waiting for it
+    let mut wait = false;
+    let (lock, fragments, contents, mut file_structure, config) = loop {
+        let lock = if wait {
+            Some(lock_registry(handle)?)
+        } else {
+            lock::try_exclusive(&file_path).map_err(|err| access_error(handle, "lock", &err))?
+        };
+        // Read before the registry file, which holds the fragments compacted meanwhile
+        let fragments = fragment::read(&file_path).map_err(|err| fragment_error(handle, &err))?;
+        let opened = log::start();
+        let mut file = share::open(
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false),
+            &file_path,
+        )
+        .map_err(|err| access_error(handle, "open", &err))?;
+
+        // Read the TOML file
+        let mut bytes = Vec::new();
+        stats::io(Counter::FileReads, || file.read_to_end(&mut bytes))
+            .map_err(|err| access_error(handle, "read", &err))?;
+        log::event(Event::Open, &file_path, opened);
+        let contents = decode_registry(handle, bytes)?;
+
+        // Deserialize the registry file, whose layout is preserved when it is written back
+        let file_structure = parse_registry(handle, &contents).map_err(|err| {
+            format!(
+                "Cannot parse the registry `{}`: {}",
+                file_path.display(),
+                err
+            )
+        })?;
+        let config = Config::from_items(&file_structure.other).map_err(|err| {
+            format!(
+                "Invalid configuration in the registry `{}`: {}",
+                file_path.display(),
+                err
+            )
+        })?;
+        if lock.is_some() || config.fragments {
+            break (lock, fragments, contents, file_structure, config);
+        }
+        // The lock is held by another process, and must be waited for
+        wait = true;
+    };
+    // A journal is appended to without waiting, and fragments are written without the
+    // lock: their compaction takes it again
+    let _lock = lock.filter(|_| !config.journal && !config.fragments);
+    split::load(handle, &mut file_structure).map_err(|err| {
+        format!(
+            "Cannot read the registry `{}`: {}",
+            file_path.display(),
+            err
+        )
+    })?;
+    let journal = journal::read(handle).map_err(|err| journal_error(handle, &err))?;
+    journal::merge(&mut file_structure, &journal);
+    fragments.merge(&mut file_structure);
+    let mut others = directory::load_others(handle, &file_structure).map_err(|err| {
+        format!(
+            "Cannot read the registry `{}`: {}",
+            handle.configured_path().display(),
+            err
+        )
+    })?;
+    let mut warnings = check_entries(handle, &file_structure)?;
+    warnings.extend(check_entries(handle, &others)?);
+    reserved::check(
+        tag,
+        r#type,
+        options.rename_from,
+        &[&file_structure, &others].map(|file_structure| entries(file_structure, r#type)),
+    )?;
+
+    if !options.aliases.is_empty() {
+        let sections = [&file_structure, &others].map(|file_structure| match r#type {
+            Section::UniqueTags => &file_structure.unique_tags,
+            Section::UniqueTypeTags => &file_structure.unique_type_tags,
+        });
+        alias::check(tag, r#type, options.aliases, options.rename_from, &sections)?;
+        let [own, other] = sections;
+        if let Some(value) = own.get(tag) {
+            if alias::with_aliases(value, options.aliases).is_some() {
+                warnings.extend(rewrite_entry(
+                    handle,
+                    tag,
+                    r#type,
+                    &config,
+                    &mut file_structure,
+                    &contents,
+                    |value| alias::with_aliases(value, options.aliases).unwrap_or(value.clone()),
+                )?);
+            }
+        } else if let Some(value) = other.get(tag) {
+            if alias::with_aliases(value, options.aliases).is_some() {
+                warnings.push(format!(
+                    "The aliases of `{}` are not recorded: its entry is in the file of another \
+                     crate of the registry directory, which only that crate can rewrite.",
+                    tag
+                ));
+            }
+        }
+    }
+
+    if let Some(parent) = options.parent {
+        if split::section(&mut file_structure, &r#type)
+            .get(tag)
+            .is_some_and(|value| parent::with_parent(value, parent).is_some())
+        {
+            warnings.extend(rewrite_entry(
+                handle,
+                tag,
+                r#type,
+                &config,
+                &mut file_structure,
+                &contents,
+                |value| parent::with_parent(value, parent).unwrap_or(value.clone()),
+            )?);
+        } else if split::section(&mut others, &r#type)
+            .get(tag)
+            .is_some_and(|value| parent::with_parent(value, parent).is_some())
+        {
+            warnings.push(format!(
+                "The parent of `{}` is not recorded: its entry is in the file of another \
+                 crate of the registry directory, which only that crate can rewrite.",
+                tag
+            ));
+        }
+    }
+
+    if !options.meta.is_empty() {
+        meta::check_unique(
+            tag,
+            r#type,
+            options.meta,
+            &config.unique_meta,
+            &file_structure,
+            &others,
+        )?;
+        if split::section(&mut file_structure, &r#type)
+            .get(tag)
+            .is_some_and(|value| meta::with_meta(value, options.meta).is_some())
+        {
+            warnings.extend(rewrite_entry(
+                handle,
+                tag,
+                r#type,
+                &config,
+                &mut file_structure,
+                &contents,
+                |value| meta::with_meta(value, options.meta).unwrap_or(value.clone()),
+            )?);
+        } else if split::section(&mut others, &r#type)
+            .get(tag)
+            .is_some_and(|value| meta::with_meta(value, options.meta).is_some())
+        {
+            warnings.push(format!(
+                "The `meta` values of `{}` are not recorded: its entry is in the file of \
+                 another crate of the registry directory, which only that crate can rewrite.",
+                tag
+            ));
+        }
+    }
+
+    if !options.groups.is_empty() {
+        warnings.extend(group::check_names(
+            options.groups,
+            &config,
+            &file_structure,
+            &others,
+        )?);
+        if split::section(&mut file_structure, &r#type)
+            .get(tag)
+            .is_some_and(|value| group::with_groups(value, options.groups).is_some())
+        {
+            warnings.extend(rewrite_entry(
+                handle,
+                tag,
+                r#type,
+                &config,
+                &mut file_structure,
+                &contents,
+                |value| group::with_groups(value, options.groups).unwrap_or(value.clone()),
+            )?);
+        } else if split::section(&mut others, &r#type)
+            .get(tag)
+            .is_some_and(|value| group::with_groups(value, options.groups).is_some())
+        {
+            warnings.push(format!(
+                "The groups of `{}` are not recorded: its entry is in the file of another \
+                 crate of the registry directory, which only that crate can rewrite.",
+                tag
+            ));
+        }
+    }
+
+    if config.ids
+        && split::section(&mut file_structure, &r#type)
+            .get(tag)
+            .is_some_and(|value| ids::entry_id(value).is_none())
+    {
+        // An entry written before the ids were enabled
+        let id = ids::next(&file_structure, &others)?;
+        ids::record(&mut file_structure.other, id);
+        warnings.extend(rewrite_entry(
+            handle,
+            tag,
+            r#type,
+            &config,
+            &mut file_structure,
+            &contents,
+            |value| ids::with_id(value, id),
+        )?);
+    }
+
+    if let Some(pinned) = options.pinned {
+        let rewrite = check_pin(
+            tag,
+            r#type,
+            pinned,
+            &config,
+            &file_structure,
+            &others,
+            &mut warnings,
+        )?;
+        if rewrite {
+            warnings.extend(rewrite_entry(
+                handle,
+                tag,
+                r#type,
+                &config,
+                &mut file_structure,
+                &contents,
+                |value| metadata::with_source(pinned_entry(value, pinned), options.source),
+            )?);
+            return Ok(Resolved {
+                uuid: pinned,
+                id: resolved_id(tag, r#type, &file_structure, &others)?,
+                meta: resolved_meta(tag, r#type, &file_structure, &others),
+                warnings,
+            });
+        }
+    }
+
+    let next_id = if config.ids {
+        Some(ids::next(&file_structure, &others)?)
+    } else {
+        None
+    };
+    let target = match r#type {
+        Section::UniqueTags => &mut file_structure.unique_tags,
+        Section::UniqueTypeTags => &mut file_structure.unique_type_tags,
+    };
+    let uuid = if let Some(value) = target.get(tag) {
+        match parse_entry(value) {
+            Ok(uuid) => uuid,
+            Err(reason) => return Err(malformed_entry(r#type.name(), tag, &reason)),
+        }
+    } else if let Some(value) = split::section(&mut others, &r#type).get(tag) {
+        // Stored in the file of another crate of the registry directory
+        match parse_entry(value) {
+            Ok(uuid) => uuid,
+            Err(reason) => return Err(malformed_entry(r#type.name(), tag, &reason)),
+        }
+    } else if let Some((_, value)) = alias::owner(target, tag)
+        .or_else(|| alias::owner(split::section(&mut others, &r#type), tag))
+    {
+        // An alias of another entry, which shares its UUID
+        match parse_entry(value) {
+            Ok(uuid) => uuid,
+            Err(reason) => return Err(malformed_entry(r#type.name(), tag, &reason)),
+        }
+    } else if let Some(value) = options.rename_from.and_then(|old| target.remove(old)) {
+        let uuid = match parse_entry(&value) {
+            Ok(uuid) => uuid,
+            Err(reason) => {
+                return Err(malformed_entry(
+                    r#type.name(),
+                    options.rename_from.unwrap(),
+                    &reason,
+                ))
+            }
+        };
+        if config.journal || config.fragments {
+            let moved = |file_structure: &mut FileStructure| {
+                let target = match r#type {
+                    Section::UniqueTags => &mut file_structure.unique_tags,
+                    Section::UniqueTypeTags => &mut file_structure.unique_type_tags,
+                };
+                if let Some(value) = target.remove(options.rename_from.unwrap()) {
+                    let value = alias::with_aliases(&value, options.aliases).unwrap_or(value);
+                    target.insert(tag.to_string(), value);
+                }
+            };
+            match journal::compact(handle, moved) {
+                Ok(file_structure) => warnings.extend(refresh_artifacts(&file_structure)),
+                Err(err) => return Err(journal_error(handle, &err)),
+            }
+        } else if let Some(path) = split::section_path(handle, &config, &r#type) {
+            let moved = |entries: &mut BTreeMap<String, toml::Value>| {
+                if let Some(value) = entries.remove(options.rename_from.unwrap()) {
+                    let value = alias::with_aliases(&value, options.aliases).unwrap_or(value);
+                    entries.insert(tag.to_string(), value);
+                }
+            };
+            *target = match split::update(handle, &path, &r#type, moved) {
+                Ok(entries) => entries,
+                Err(err) => return Err(section_error(handle, &path, &err)),
+            };
+            warnings.extend(refresh_artifacts(&file_structure));
+        } else {
+            let mut value = alias::with_aliases(&value, options.aliases).unwrap_or(value);
+            if let Some(id) = next_id.filter(|_| ids::entry_id(&value).is_none()) {
+                value = ids::with_id(&value, id);
+                ids::record(&mut file_structure.other, id);
+            }
+            target.insert(tag.to_string(), value);
+            warnings.extend(write_file(handle, &contents, None, &file_structure)?);
+        }
+        uuid
+    } else {
+        if let Section::UniqueTypeTags = r#type {
+            for candidate in rename::rename_candidates(tag, target.keys()) {
+                warnings.push(format!(
+                    "`{}` gets a new UUID, but the registry holds a similar entry `{}` that \
+                     is not used anymore. If the type was renamed, add \
+                     `#[unique_type_tag(rename = \"{}\")]` to keep its UUID.",
+                    tag, candidate, candidate
+                ));
+            }
+        }
+
+        let keys = target
+            .keys()
+            .chain(split::section(&mut others, &r#type).keys());
+        check_new_key(handle, &config, tag, r#type, keys, &mut warnings)?;
+        let uuid = options
+            .pinned
+            .unwrap_or_else(|| metadata::new_uuid(&config));
+        stats::add(Counter::Minted, 1);
+        log::event(Event::Insert, &file_path, started);
+        let mut entry = metadata::with_source(metadata::new_entry(uuid), options.source);
+        if options.pinned.is_some() {
+            entry = pinned_entry(&entry, uuid);
+        }
+        if let Some(aliased) = alias::with_aliases(&entry, options.aliases) {
+            entry = aliased;
+        }
+        if let Some(parent) = options.parent {
+            entry = parent::with_parent(&entry, parent).unwrap_or(entry);
+        }
+        if let Some(with_meta) = meta::with_meta(&entry, options.meta) {
+            entry = with_meta;
+        }
+        if let Some(grouped) = group::with_groups(&entry, options.groups) {
+            entry = grouped;
+        }
+        if let Some(id) = next_id {
+            entry = ids::with_id(&entry, id);
+            ids::record(&mut file_structure.other, id);
+        }
+        if config.journal {
+            let mut resolved = append_to_journal(handle, tag, r#type, entry, &config, warnings)?;
+            if resolved.uuid == uuid {
+                resolved.warnings.push(minted::notice(handle, tag, &uuid));
+            }
+            return Ok(resolved);
+        }
+        if config.fragments {
+            let mut resolved = write_fragment(handle, tag, r#type, entry, warnings)?;
+            if resolved.uuid == uuid {
+                resolved.warnings.push(minted::notice(handle, tag, &uuid));
+            }
+            return Ok(resolved);
+        }
+        if let Some(path) = split::section_path(handle, &config, &r#type) {
+            // Another process may have added the entry first, its UUID is used instead
+            let inserted = |entries: &mut BTreeMap<String, toml::Value>| {
+                entries.entry(tag.to_string()).or_insert(entry);
+            };
+            *target = match split::update(handle, &path, &r#type, inserted) {
+                Ok(entries) => entries,
+                Err(err) => return Err(section_error(handle, &path, &err)),
+            };
+            warnings.extend(refresh_artifacts(&file_structure));
+            let target = split::section(&mut file_structure, &r#type);
+            let winner = target.get(tag).and_then(entry_uuid).unwrap_or(uuid);
+            if winner == uuid {
+                warnings.push(minted::notice(handle, tag, &uuid));
+            }
+            return Ok(Resolved {
+                uuid: winner,
+                id: target.get(tag).and_then(ids::entry_id),
+                meta: target.get(tag).map(meta::of).unwrap_or_default(),
+                warnings,
+            });
+        }
+        let inserted = match format {
+            // The `[ids]` table must be rewritten as well
+            Format::Toml if next_id.is_none() => {
+                document::insert_entry(&Layout::strip(&contents), r#type.name(), tag, &entry)
+            }
+            Format::Toml | Format::CargoMetadata | Format::Json | Format::Ron | Format::Yaml => {
+                None
+            }
+        };
+        target.insert(tag.to_string(), entry);
+        warnings.extend(write_file(handle, &contents, inserted, &file_structure)?);
+        warnings.push(minted::notice(handle, tag, &uuid));
+        uuid
+    };
+
+    Ok(Resolved {
+        uuid,
+        id: resolved_id(tag, r#type, &file_structure, &others)?,
+        meta: resolved_meta(tag, r#type, &file_structure, &others),
+        warnings,
+    })
+}
+
+/// Returns the UUIDs of `keys` in `[unique_tags]`, in order, minting the missing ones,
+/// see [`RegistryHandle::reserve_tags`].
+///
+/// The registry is only read if every key has an entry. Otherwise the missing entries
+/// are written at once, under a single lock of the registry file. A registry with a
+/// journal, pending fragments or a `tags_file`, which record every new entry on its own,
+/// and the in-memory registry, resolve the keys one by one instead.
+pub(crate) fn reserve_tags(
+    handle: &RegistryHandle,
+    keys: &[String],
+    source: Option<&str>,
+) -> Result<ReservedTags, String> {
+    let options = TagOptions {
+        source,
+        ..TagOptions::default()
+    };
+    let one_by_one = || {
+        let mut reserved = ReservedTags {
+            uuids: Vec::with_capacity(keys.len()),
+            warnings: Vec::new(),
+        };
+        for key in keys {
+            let resolved = get_uuid_from_tag(handle, key, Section::UniqueTags, &options)?;
+            reserved.uuids.push(resolved.uuid);
+            for warning in resolved.warnings {
+                if !reserved.warnings.contains(&warning) {
+                    reserved.warnings.push(warning);
+                }
+            }
+        }
+        Ok(reserved)
+    };
+    if handle.is_in_memory() {
+        return one_by_one();
+    }
+    let (file_structure, warnings) = read_all(handle)?;
+    let config = Config::from_items(&file_structure.other).map_err(|err| {
+        format!(
+            "Invalid configuration in the registry `{}`: {}",
+            handle.path().display(),
+            err
+        )
+    })?;
+    if config.journal
+        || config.fragments
+        || split::section_path(handle, &config, &Section::UniqueTags).is_some()
+    {
+        return one_by_one();
+    }
+
+    let usage: Vec<String> = keys
+        .iter()
+        .filter_map(|key| usage::track_usage(key, &Section::UniqueTags).err())
+        .collect();
+    let existing = keys
+        .iter()
+        .map(|key| reserved_uuid(key, &file_structure, &FileStructure::default()))
+        .collect::<Result<Option<Vec<_>>, _>>()?;
+    if let Some(uuids) = existing {
+        let warnings = warnings.into_iter().chain(usage).collect();
+        return Ok(ReservedTags { uuids, warnings });
+    }
+
+    // Held until the entries are written, so that concurrent resolutions do not
+    // overwrite each other
+    let _lock = lock_registry(handle)?;
+    let file_path = handle.path();
+    let opened = log::start();
+    let contents = match share::read(&file_path) {
+        Ok(bytes) => decode_registry(handle, bytes)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(access_error(handle, "read", &err)),
+    };
+    log::event(Event::Open, &file_path, opened);
+    let mut file_structure = parse_registry(handle, &contents).map_err(|err| {
+        format!(
+            "Cannot parse the registry `{}`: {}",
+            file_path.display(),
+            err
+        )
+    })?;
+    let others = directory::load_others(handle, &file_structure).map_err(|err| {
+        format!(
+            "Cannot read the registry `{}`: {}",
+            handle.configured_path().display(),
+            err
+        )
+    })?;
+    let mut warnings = check_entries(handle, &file_structure)?;
+    warnings.extend(check_entries(handle, &others)?);
+    warnings.extend(usage);
+
+    let mut uuids = Vec::with_capacity(keys.len());
+    let mut minted = Vec::new();
+    for key in keys {
+        if let Some(uuid) = reserved_uuid(key, &file_structure, &others)? {
+            uuids.push(uuid);
+            continue;
+        }
+        let _logged = log::resolving(key);
+        let started = log::start();
+        let keys = file_structure
+            .unique_tags
+            .keys()
+            .chain(others.unique_tags.keys());
+        check_new_key(
+            handle,
+            &config,
+            key,
+            Section::UniqueTags,
+            keys,
+            &mut warnings,
+        )?;
+        let uuid = metadata::new_uuid(&config);
+        stats::add(Counter::Minted, 1);
+        log::event(Event::Insert, &file_path, started);
+        let mut entry = metadata::with_source(metadata::new_entry(uuid), source);
+        if config.ids {
+            let id = ids::next(&file_structure, &others)?;
+            entry = ids::with_id(&entry, id);
+            ids::record(&mut file_structure.other, id);
+        }
+        file_structure.unique_tags.insert(key.clone(), entry);
+        uuids.push(uuid);
+        minted.push((key, uuid));
+    }
+
+    warnings.extend(write_file(handle, &contents, None, &file_structure)?);
+    for (key, uuid) in minted {
+        warnings.push(minted::notice(handle, key, &uuid));
+    }
+    Ok(ReservedTags { uuids, warnings })
+}
+
+/// Returns the UUID of the entry `key` resolves to in the `[unique_tags]` of
+/// `file_structure` or `others`, if any, see [`resolved_entry`].
+///
+/// # Errors
+/// Fails if the entry is reserved for future use or malformed.
+fn reserved_uuid(
+    key: &str,
+    file_structure: &FileStructure,
+    others: &FileStructure,
+) -> Result<Option<uuid::Uuid>, String> {
+    reserved::check(
+        key,
+        Section::UniqueTags,
+        None,
+        &[&file_structure.unique_tags, &others.unique_tags],
+    )?;
+    resolved_entry(key, Section::UniqueTags, file_structure, others)
+        .map(|(owner, value)| {
+            parse_entry(value).map_err(|reason| malformed_entry("unique_tags", owner, &reason))
+        })
+        .transpose()
+}
+
+/// Checks that `tag`, a new key of `r#type`, may be minted: that it follows the
+/// `tag_pattern` and `tag_style` of the registry, does not collide with `keys`, the keys
+/// of the section, under `case_insensitive_keys`, and that new entries are allowed.
+///
+/// # Errors
+/// Fails if a policy configured to fail the expansion is broken, and pushes a warning
+/// for the others.
+fn check_new_key<'a>(
+    handle: &RegistryHandle,
+    config: &Config,
+    tag: &str,
+    r#type: Section,
+    keys: impl IntoIterator<Item = &'a String>,
+    warnings: &mut Vec<String>,
+) -> Result<(), String> {
+    if let Section::UniqueTags = r#type {
+        if let Some(pattern) = config.tag_pattern.as_ref().filter(|p| !p.is_match(tag)) {
+            return Err(format!(
+                "`{}` does not match the `tag_pattern` of the registry, `{}`. Existing \
+                 entries are accepted, but new tags must match it.",
+                tag,
+                pattern.as_str()
+            ));
+        }
+        if let Some(suggestion) = config.tag_style.suggest(tag) {
+            let message = format!(
+                "`{}` does not follow the {} convention of the registry, consider `{}`.",
+                tag,
+                config.tag_style.name(),
+                suggestion
+            );
+            if config.strict {
+                return Err(message);
+            }
+            warnings.push(message);
+        }
+    }
+
+    if let Some(message) = config.case_collision(tag, r#type, keys) {
+        if config.case_insensitive_keys == CaseInsensitiveKeys::Error {
+            return Err(message);
+        }
+        warnings.push(message);
+    }
+
+    minted::check_allowed(handle, config, tag, &r#type)
+}
+
+/// Returns the key and the entry `tag` resolves to in `file_structure` or `others`: its
+/// own entry, or the entry listing it as an alias.
+fn resolved_entry<'a>(
+    tag: &str,
+    r#type: Section,
+    file_structure: &'a FileStructure,
+    others: &'a FileStructure,
+) -> Option<(&'a String, &'a toml::Value)> {
+    let [own, other] =
+        [file_structure, others].map(|file_structure| entries(file_structure, r#type));
+    own.get_key_value(tag)
+        .or_else(|| other.get_key_value(tag))
+        .or_else(|| alias::owner(own, tag))
+        .or_else(|| alias::owner(other, tag))
+}
+
+/// Returns the `meta` table of the entry `tag` resolves to, see [`resolved_entry`].
+fn resolved_meta(
+    tag: &str,
+    r#type: Section,
+    file_structure: &FileStructure,
+    others: &FileStructure,
+) -> Vec<(String, MetaValue)> {
+    resolved_entry(tag, r#type, file_structure, others)
+        .map(|(_, value)| meta::of(value))
+        .unwrap_or_default()
+}
+
+/// Returns the id of the entry `tag` resolves to, see [`resolved_entry`], if it has one.
+/// Fails if another entry has the same id.
+fn resolved_id(
+    tag: &str,
+    r#type: Section,
+    file_structure: &FileStructure,
+    others: &FileStructure,
+) -> Result<Option<u32>, String> {
+    let resolved = resolved_entry(tag, r#type, file_structure, others);
+    let Some((key, id)) = resolved.and_then(|(key, value)| Some((key, ids::entry_id(value)?)))
+    else {
+        return Ok(None);
+    };
+    ids::check_unique(key, r#type, id, file_structure, others)?;
+    Ok(Some(id))
+}
+
+/// Returns the entries of `section` in `file_structure`.
+fn entries(file_structure: &FileStructure, section: Section) -> &BTreeMap<String, toml::Value> {
+    match section {
+        Section::UniqueTags => &file_structure.unique_tags,
+        Section::UniqueTypeTags => &file_structure.unique_type_tags,
+    }
+}
+
+/// Checks `pinned`, the UUID `tag` is pinned to, against the registry, and returns
+/// whether the entry of `tag` must be rewritten with it.
+///
+/// Fails if another key has the pinned UUID, whatever the `pin_conflict` setting, and if
+/// the entry of `tag` has another UUID and the setting does not say how to resolve it.
+fn check_pin(
+    tag: &str,
+    r#type: Section,
+    pinned: uuid::Uuid,
+    config: &Config,
+    file_structure: &FileStructure,
+    others: &FileStructure,
+    warnings: &mut Vec<String>,
+) -> Result<bool, String> {
+    let own = entries(file_structure, r#type)
+        .get(tag)
+        .and_then(entry_uuid);
+    let existing = own.or_else(|| entries(others, r#type).get(tag).and_then(entry_uuid));
+
+    let mut owner = None;
+    for file_structure in [file_structure, others] {
+        for section in [Section::UniqueTags, Section::UniqueTypeTags] {
+            owner = owner.or_else(|| {
+                entries(file_structure, section)
+                    .iter()
+                    .find(|(key, value)| {
+                        (section != r#type || key.as_str() != tag)
+                            && entry_uuid(value) == Some(pinned)
+                    })
+                    .map(|(key, _)| (section, key))
+            });
+        }
+    }
+    if let Some((owner_section, owner)) = owner {
+        let current = match existing {
+            Some(uuid) => format!(", and assigns {} to `{}`", uuid, tag),
+            None => String::new(),
+        };
+        return Err(format!(
+            "`{}` is pinned to {}, but the registry already assigns {} to `{}` in [{}]{}. \
+             A UUID identifies a single key: pin `{}` to another UUID.",
+            tag,
+            pinned,
+            pinned,
+            owner,
+            owner_section.name(),
+            current,
+            tag
+        ));
+    }
+
+    let Some(uuid) = existing.filter(|uuid| *uuid != pinned) else {
+        return Ok(false);
+    };
+    let conflict = format!(
+        "`{}` is pinned to {}, but the registry assigns {} to `{}`",
+        tag, pinned, uuid, tag
+    );
+    match config.pin_conflict {
+        PinConflict::Error => Err(format!(
+            "{}. Set `pin_conflict = \"prefer-pinned\"` in the [config] of the registry to \
+             rewrite the entry, or `pin_conflict = \"prefer-registry\"` to keep it.",
+            conflict
+        )),
+        PinConflict::PreferRegistry => {
+            warnings.push(format!(
+                "{}: the pin is ignored, as `pin_conflict = \"prefer-registry\"`.",
+                conflict
+            ));
+            Ok(false)
+        }
+        PinConflict::PreferPinned if own.is_none() => Err(format!(
+            "{}, in the file of another crate of the registry directory: only that crate \
+             can rewrite it.",
+            conflict
+        )),
+        PinConflict::PreferPinned => {
+            warnings.push(format!(
+                "{}: the entry is rewritten with the pinned UUID, as \
+                 `pin_conflict = \"prefer-pinned\"`.",
+                conflict
+            ));
+            Ok(true)
+        }
+    }
+}
+
+/// Rewrites the entry of `tag` with `edit`, wherever its section is stored, returning
+/// warnings for the artifacts that could not be refreshed. Fails if the journal, the
+/// section file or the registry file cannot be updated.
+#[allow(clippy::too_many_arguments)]
+fn rewrite_entry(
+    handle: &RegistryHandle,
+    tag: &str,
+    r#type: Section,
+    config: &Config,
+    file_structure: &mut FileStructure,
+    contents: &str,
+    edit: impl Fn(&toml::Value) -> toml::Value,
+) -> Result<Vec<String>, String> {
+    let rewrite = |entries: &mut BTreeMap<String, toml::Value>| {
+        if let Some(value) = entries.get_mut(tag) {
+            *value = edit(value);
+        }
+    };
+    if config.journal || config.fragments {
+        match journal::compact(handle, |file_structure| {
+            rewrite(split::section(file_structure, &r#type))
+        }) {
+            Ok(file_structure) => Ok(refresh_artifacts(&file_structure)),
+            Err(err) => Err(journal_error(handle, &err)),
+        }
+    } else if let Some(path) = split::section_path(handle, config, &r#type) {
+        *split::section(file_structure, &r#type) =
+            split::update(handle, &path, &r#type, rewrite)
+                .map_err(|err| section_error(handle, &path, &err))?;
+        Ok(refresh_artifacts(file_structure))
+    } else {
+        rewrite(split::section(file_structure, &r#type));
+        write_file(handle, contents, None, file_structure)
+    }
+}
+
+/// Returns `value`, an entry, in structured form with the UUID `pinned` and
+/// `pinned = true`, keeping its other fields.
+fn pinned_entry(value: &toml::Value, pinned: uuid::Uuid) -> toml::Value {
+    let mut table = match value {
+        toml::Value::Table(table) => table.clone(),
+        _ => toml::Table::new(),
+    };
+    table.insert("uuid".to_string(), toml::Value::String(pinned.to_string()));
+    table.insert("pinned".to_string(), toml::Value::Boolean(true));
+    toml::Value::Table(table)
+}
+
+/// Records the new entry `entry` of `tag` in the journal, compacting the journal once it
+/// holds more than `config.journal_limit` lines.
+///
+/// If another process appended an entry for `tag` first, its UUID is used instead, so
+/// that every process agrees on the first entry.
+fn append_to_journal(
+    handle: &RegistryHandle,
+    tag: &str,
+    r#type: Section,
+    entry: toml::Value,
+    config: &Config,
+    mut warnings: Vec<String>,
+) -> Result<Resolved, String> {
+    let journal = log::io(Event::Write, &journal::journal_path(&handle.path()), || {
+        stats::io(Counter::FileWrites, || {
+            journal::append(handle, r#type.name(), tag, &entry)
+        })
+    })
+    .map_err(|err| journal_error(handle, &err))?;
+    let file_structure = if journal.lines().count() > config.journal_limit {
+        journal::compact(handle, |_| {})
+    } else {
+        journal::read_registry(handle).map(|mut file_structure| {
+            journal::merge(&mut file_structure, &journal);
+            file_structure
+        })
+    }
+    .map_err(|err| journal_error(handle, &err))?;
+
+    let target = match r#type {
+        Section::UniqueTags => &file_structure.unique_tags,
+        Section::UniqueTypeTags => &file_structure.unique_type_tags,
+    };
+    let uuid = target
+        .get(tag)
+        .and_then(entry_uuid)
+        .or_else(|| entry_uuid(&entry))
+        .unwrap();
+    let id = target.get(tag).and_then(ids::entry_id);
+    let meta = target.get(tag).map(meta::of).unwrap_or_default();
+    warnings.extend(refresh_artifacts(&file_structure));
+    Ok(Resolved {
+        uuid,
+        id,
+        meta,
+        warnings,
+    })
+}
+
+/// Records the new entry `entry` of `tag` in a fragment, then compacts the pending
+/// fragments into the registry file if its lock is free.
+///
+/// If another process recorded an entry for `tag` first, in the registry file or in a
+/// fragment whose name sorts first, its UUID is used instead, so that every process
+/// agrees on the entry the compaction keeps.
+fn write_fragment(
+    handle: &RegistryHandle,
+    tag: &str,
+    r#type: Section,
+    entry: toml::Value,
+    mut warnings: Vec<String>,
+) -> Result<Resolved, String> {
+    let path = handle.path();
+    log::io(Event::Write, &path, || {
+        fragment::write(&path, r#type.name(), tag, &entry)
+    })
+    .map_err(|err| fragment_error(handle, &err))?;
+    let file_structure = match lock::try_exclusive(&path) {
+        Ok(Some(_lock)) => journal::compact_locked(handle, |_| {}),
+        Ok(None) => fragment::read(&path).and_then(|fragments| {
+            let mut file_structure = journal::read_registry(handle)?;
+            fragments.merge(&mut file_structure);
+            Ok(file_structure)
+        }),
+        Err(err) => Err(err),
+    }
+    .map_err(|err| fragment_error(handle, &err))?;
+
+    let target = match r#type {
+        Section::UniqueTags => &file_structure.unique_tags,
+        Section::UniqueTypeTags => &file_structure.unique_type_tags,
+    };
+    let uuid = target
+        .get(tag)
+        .and_then(entry_uuid)
+        .or_else(|| entry_uuid(&entry))
+        .unwrap();
+    let id = target.get(tag).and_then(ids::entry_id);
+    let meta = target.get(tag).map(meta::of).unwrap_or_default();
+    warnings.extend(refresh_artifacts(&file_structure));
+    Ok(Resolved {
+        uuid,
+        id,
+        meta,
+        warnings,
+    })
+}
+
+/// Describes a failure to update the file at `path` storing a section of the registry.
+fn section_error(handle: &RegistryHandle, path: &std::path::Path, err: &std::io::Error) -> String {
+    format!(
+        "Cannot update `{}`, which stores a section of the registry `{}`: {}",
+        path.display(),
+        handle.path().display(),
+        err
+    )
+}
+
+/// Describes a failure to use the journal.
+fn journal_error(handle: &RegistryHandle, err: &std::io::Error) -> String {
+    format!(
+        "Cannot update the journal `{}` of the registry: {}",
+        journal::journal_path(&handle.path()).display(),
+        err
+    )
+}
+
+/// Describes a failure to use the pending fragments.
+fn fragment_error(handle: &RegistryHandle, err: &std::io::Error) -> String {
+    format!(
+        "Cannot update the pending fragments `{}` of the registry: {}",
+        fragment::fragments_path(&handle.path()).display(),
+        err
+    )
+}
+
+/// Writes `file_structure` over `contents`, the registry file as read, in its format and
+/// layout, or `inserted` if the new entry could be inserted in place, returning warnings
+/// for the artifacts that could not be refreshed.
+///
+/// The registry file is replaced atomically, so that the processes reading it without
+/// the lock never observe a partially written file. Fails if it cannot be written.
+fn write_file(
+    handle: &RegistryHandle,
+    contents: &str,
+    inserted: Option<String>,
+    file_structure: &FileStructure,
+) -> Result<Vec<String>, String> {
+    let path = handle.path();
+    let format = Format::of(&path);
+    let registry = split::registry_file_structure(handle, file_structure);
+    let toml = match inserted {
+        Some(toml) => Ok(toml),
+        None => format.rewrite(&Layout::strip(contents), &registry),
+    };
+    let toml = match toml {
+        Ok(toml) => Layout::detect(contents).apply(toml),
+        Err(err) => {
+            return Err(format!(
+                "Cannot write the registry `{}`: {}",
+                path.display(),
+                err
+            ))
+        }
+    };
+
+    log::io(Event::Write, &path, || {
+        stats::io(Counter::FileWrites, || replace_file(&path, &toml))
+    })
+    .map_err(|err| access_error(handle, "write", &err))?;
+    refresh_cache(handle, &toml, &registry);
+    Ok(refresh_artifacts(file_structure))
+}
+
+/// Replaces the file at `path` with `contents` by renaming a temporary file over it, so
+/// that readers never observe a partially written file.
+pub(crate) fn replace_file(path: &std::path::Path, contents: &str) -> std::io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let mut file = std::fs::File::create(&temporary)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&temporary, path)
+}
+
+/// Parses `contents`, the content of the registry file, from the sidecar cache when it
+/// is up to date, refreshing the cache otherwise if the registry enables it.
+///
+/// A leading byte order mark and `\r\n` line endings are accepted.
+pub(crate) fn parse_registry(
+    handle: &RegistryHandle,
+    contents: &str,
+) -> Result<FileStructure, String> {
+    let path = handle.path();
+    let start = log::start();
+    if let Some(file_structure) = cache::load(&path, contents) {
+        stats::add(Counter::CacheHits, 1);
+        log::event(Event::CacheHit, &path, start);
+        return Ok(file_structure);
+    }
+    let file_structure = Format::of(&path).parse(&Layout::strip(contents))?;
+    log::event(Event::Parse, &path, start);
+    refresh_cache(handle, contents, &file_structure);
+    Ok(file_structure)
+}
+
+/// Stores `file_structure`, the parsed registry file `contents`, in the sidecar cache if
+/// the registry enables it.
+pub(crate) fn refresh_cache(
+    handle: &RegistryHandle,
+    contents: &str,
+    file_structure: &FileStructure,
+) {
+    if Config::from_items(&file_structure.other).is_ok_and(|config| config.cache) {
+        cache::store(&handle.path(), contents, file_structure);
+    }
+}
+
+/// Refreshes the artifacts derived from the registry, returning warnings for those that
+/// could not be refreshed.
+fn refresh_artifacts(file_structure: &FileStructure) -> Vec<String> {
+    [
+        codegen::refresh_rust_module(file_structure),
+        mirror::refresh_json_mirror(file_structure),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Decodes `bytes`, the content of the registry file, describing where it is not valid
+/// UTF-8 on failure, as [`store::Error::Encoding`] does.
+pub(crate) fn decode_registry(handle: &RegistryHandle, bytes: Vec<u8>) -> Result<String, String> {
+    String::from_utf8(bytes).map_err(|err| store::encoding_error(&handle.path(), err).to_string())
+}
+
+/// Takes the lock serializing the writes of the registry file of `handle`.
+///
+/// # Errors
+/// Fails if the lock cannot be taken, with a message describing the registry.
+fn lock_registry(handle: &RegistryHandle) -> Result<lock::RegistryLock, String> {
+    lock::exclusive(&handle.path()).map_err(|err| access_error(handle, "lock", &err))
+}
+
+/// Describes a failure to `action` the registry file, naming its absolute path and
+/// explaining the most common causes.
+fn access_error(handle: &RegistryHandle, action: &str, err: &std::io::Error) -> String {
+    let path = handle.path();
+    let cause = if path.is_dir() {
+        format!(
+            "`{}` is a directory; the registry must be a file",
+            path.display()
+        )
+    } else if share::is_sharing_violation(err) {
+        format!(
+            "another process, e.g. an editor, holds `{}` open without sharing it; close it \
+             and build again",
+            path.display()
+        )
+    } else if err.kind() == std::io::ErrorKind::PermissionDenied {
+        format!(
+            "the current user is not allowed to access `{}`",
+            path.display()
+        )
+    } else if let Some(parent) = path.parent().filter(|parent| !parent.exists()) {
+        format!("the directory `{}` does not exist", parent.display())
+    } else {
+        err.to_string()
+    };
+    format!(
+        "Cannot {} the registry `{}`: {}. The registry is `{}`, resolved against the \
+         directory the compiler runs in: the root of the workspace, or of the package.",
+        action,
+        path.display(),
+        cause,
+        handle.configured_path().display()
+    )
+}
+
+/// Reads the registry file of `handle`, with its journal, its pending fragments and the
+/// sections stored in files of their own, a missing file having no entries.
+pub(crate) fn read(handle: &RegistryHandle) -> Result<FileStructure, String> {
+    stats::add(Counter::Expansions, 1);
+    if handle.is_in_memory() {
+        return Ok(memory::read());
+    }
+    // Read before the registry file, which holds the fragments compacted meanwhile
+    let fragments = fragment::read(&handle.path()).map_err(|err| fragment_error(handle, &err))?;
+    let contents = match share::read(&handle.path()) {
+        Ok(bytes) => decode_registry(handle, bytes)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(access_error(handle, "read", &err)),
+    };
+    let mut file_structure = parse_registry(handle, &contents)
+        .map_err(|err| format!("Error parsing `{}`: {}", handle.path().display(), err))?;
+    split::load(handle, &mut file_structure)
+        .map_err(|err| format!("Error reading `{}`: {}", handle.path().display(), err))?;
+    let journal = journal::read(handle).map_err(|err| journal_error(handle, &err))?;
+    journal::merge(&mut file_structure, &journal);
+    fragments.merge(&mut file_structure);
+    Ok(file_structure)
+}
+
+/// Returns the `[unique_tags]` entries belonging to `namespace` or to one of its nested
+/// namespaces, sorted by key, see [`in_namespace`].
+///
+/// The registry is only read, a missing file has no entries. Malformed entries are
+/// handled as by the other macros, the warnings are returned with the entries.
+pub(crate) fn namespace_tags(
+    handle: &RegistryHandle,
+    namespace: &str,
+) -> Result<NamespaceTags, String> {
+    let (file_structure, warnings) = read_all(handle)?;
+
+    let tags = file_structure
+        .unique_tags
+        .iter()
+        .filter(|(key, _)| in_namespace(key, namespace))
+        .filter_map(|(key, value)| Some((key.clone(), entry_uuid(value)?)))
+        .collect();
+    Ok(NamespaceTags { tags, warnings })
+}
+
+/// Returns `true` if `key` belongs to `namespace` or to one of its nested namespaces.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::in_namespace;
+///
+/// assert!(in_namespace("orders.created", "orders"));
+/// assert!(in_namespace("orders.v2.created", "orders"));
+/// assert!(in_namespace("::shop::Order", "::shop"));
+/// assert!(!in_namespace("orders", "orders"));
+/// assert!(!in_namespace("orders_archive.created", "orders"));
+/// ```
+pub fn in_namespace(key: &str, namespace: &str) -> bool {
+    key.strip_prefix(namespace)
+        .is_some_and(|rest| rest.len() > 1 && (rest.starts_with('.') || rest.starts_with("::")))
+}
+
+impl FileStructure {
+    /// Returns the name hashed into the fingerprint of the registry: a line
+    /// `<section>\t<key>\t<uuid>\n` for every entry, of `namespace` only if given (see
+    /// [`in_namespace`]), with the entries of `[unique_tags]` first and each section
+    /// sorted by key. Malformed entries are left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid_core::Registry;
+    ///
+    /// let mut registry = Registry::default();
+    /// registry.unique_tags.insert(
+    ///     "orders.created".to_string(),
+    ///     "2af1216e-7042-406a-a577-c39a7e7c3faa".into(),
+    /// );
+    /// registry.unique_tags.insert("payments.refunded".to_string(), "malformed".into());
+    /// assert_eq!(
+    ///     registry.fingerprint_name(Some("orders")),
+    ///     "unique_tags\torders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa\n"
+    /// );
+    /// ```
+    pub fn fingerprint_name(&self, namespace: Option<&str>) -> String {
+        let mut name = String::new();
+        for (section, entries) in [
+            (Section::UniqueTags, &self.unique_tags),
+            (Section::UniqueTypeTags, &self.unique_type_tags),
+        ] {
+            let entries = entries
+                .iter()
+                .filter(|(key, _)| namespace.is_none_or(|namespace| in_namespace(key, namespace)))
+                .filter_map(|(key, value)| Some((key, entry_uuid(value)?)));
+            for (key, uuid) in entries {
+                name.push_str(&format!("{}\t{}\t{}\n", section, key, uuid));
+            }
+        }
+        name
+    }
+}
+
+/// Returns the name hashed into the fingerprint of the registry, see
+/// [`FileStructure::fingerprint_name`].
+///
+/// The registry is only read, a missing file has no entries. Malformed entries are
+/// handled as by the other macros, the warnings are returned with the name.
+pub(crate) fn fingerprint(
+    handle: &RegistryHandle,
+    namespace: Option<&str>,
+) -> Result<RegistryFingerprint, String> {
+    let (file_structure, warnings) = read_all(handle)?;
+    let name = file_structure.fingerprint_name(namespace);
+    Ok(RegistryFingerprint { name, warnings })
+}
+
+/// Reads the registry of `handle` with the files of the other crates of its registry
+/// directory, returning their entries together and the warnings about the malformed
+/// ones.
+fn read_all(handle: &RegistryHandle) -> Result<(FileStructure, Vec<String>), String> {
+    let mut file_structure = read(handle)?;
+    let others = directory::load_others(handle, &file_structure).map_err(|err| {
+        format!(
+            "Error reading `{}`: {}",
+            handle.configured_path().display(),
+            err
+        )
+    })?;
+    file_structure.unique_tags.extend(others.unique_tags);
+    file_structure
+        .unique_type_tags
+        .extend(others.unique_type_tags);
+    let warnings = check_entries(handle, &file_structure)?;
+    Ok((file_structure, warnings))
+}
+
+/// Returns the canonical key of `key`: `key` itself if it is an entry of either section,
+/// or the key of the entry listing it as an alias. The registry is only read.
+pub(crate) fn canonical_key(handle: &RegistryHandle, key: &str) -> Result<EntryName, String> {
+    let (file_structure, warnings) = read_all(handle)?;
+    let sections = [
+        (Section::UniqueTags, &file_structure.unique_tags),
+        (Section::UniqueTypeTags, &file_structure.unique_type_tags),
+    ];
+    let found = sections
+        .iter()
+        .find(|(_, entries)| entries.contains_key(key))
+        .map(|(section, _)| (*section, key.to_string()))
+        .or_else(|| {
+            sections.iter().find_map(|(section, entries)| {
+                alias::owner(entries, key).map(|(owner, _)| (*section, owner.clone()))
+            })
+        });
+    let Some((section, key)) = found else {
+        return Err(format!(
+            "`{}` is not an entry of the registry: mint it with `unique_tag!` or \
+             `#[derive(UniqueTypeTag)]` first",
+            key
+        ));
+    };
+    Ok(EntryName {
+        key,
+        section,
+        warnings,
+    })
+}
+
+/// Returns the key of the entry whose UUID is `uuid`, searching both sections. The
+/// registry is only read.
+///
+/// Fails if no entry has `uuid`, or if several do, naming them.
+pub(crate) fn key_of_uuid(handle: &RegistryHandle, uuid: uuid::Uuid) -> Result<EntryName, String> {
+    let (file_structure, warnings) = read_all(handle)?;
+    let mut found: Vec<(Section, &String)> = [
+        (Section::UniqueTags, &file_structure.unique_tags),
+        (Section::UniqueTypeTags, &file_structure.unique_type_tags),
+    ]
+    .into_iter()
+    .flat_map(|(section, entries)| {
+        entries
+            .iter()
+            .filter(|(_, value)| entry_uuid(value) == Some(uuid))
+            .map(move |(key, _)| (section, key))
+    })
+    .collect();
+    match found.len() {
+        0 => Err(format!("no entry of the registry has the UUID {}", uuid)),
+        1 => {
+            let (section, key) = found.remove(0);
+            Ok(EntryName {
+                key: key.clone(),
+                section,
+                warnings,
+            })
+        }
+        _ => Err(format!(
+            "the UUID {} is ambiguous, several entries have it: {}",
+            uuid,
+            found
+                .iter()
+                .map(|(section, key)| format!("`{}` in [{}]", key, section.name()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// An entry found by [`canonical_key`] or [`key_of_uuid`].
+pub struct EntryName {
+    /// The key of the entry.
+    pub key: String,
+
+    /// The section holding the entry.
+    pub section: Section,
+
+    /// Warnings about the malformed entries that were ignored.
+    pub warnings: Vec<String>,
+}
+
+/// The entries of a registry fingerprint, see [`fingerprint`].
+pub struct RegistryFingerprint {
+    /// The name to hash into the fingerprint, listing the key and UUID of every entry.
+    pub name: String,
+
+    /// Warnings about the malformed entries that were ignored.
+    pub warnings: Vec<String>,
+}
+
+/// The tags reserved as a block, see [`reserve_tags`].
+pub struct ReservedTags {
+    /// The UUID of every key, in the order of the keys.
+    pub uuids: Vec<uuid::Uuid>,
+
+    /// Warnings to surface to the user.
+    pub warnings: Vec<String>,
+}
+
+/// The tags of a namespace, see [`namespace_tags`].
+pub struct NamespaceTags {
+    /// The key and UUID of every tag of the namespace, sorted by key.
+    pub tags: Vec<(String, uuid::Uuid)>,
+
+    /// Warnings about the malformed entries that were ignored.
+    pub warnings: Vec<String>,
+}
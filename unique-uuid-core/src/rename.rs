@@ -0,0 +1,80 @@
+//! Detection of entries orphaned by a type rename.
+//!
+//! Renaming a tagged type changes its registry key, which silently mints a new UUID
+//! and leaves the old entry behind. When a new type key is inserted, the registry is
+//! scanned for entries that look like an older name of the same type, so that a
+//! warning can suggest `#[unique_type_tag(rename = "…")]` instead.
+use std::{collections::HashSet, sync::Mutex};
+
+/// The type keys resolved by this process.
+static SESSION_KEYS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Records that a type key was resolved during this build session.
+pub(crate) fn record_session_key(key: &str) {
+    SESSION_KEYS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .get_or_insert_with(HashSet::new)
+        .insert(key.to_string());
+}
+
+/// Returns the existing keys that could be an older name of `new_key`.
+///
+/// A key is a candidate if its trailing identifier is not the one of a key resolved
+/// during this session, `new_key` aside, and is close to the one of `new_key`:
+/// identical (the prefix changed), one containing the other, or within a small edit
+/// distance. This is a heuristic which must only ever be used to emit warnings.
+pub(crate) fn rename_candidates<'a>(
+    new_key: &str,
+    existing: impl Iterator<Item = &'a String>,
+) -> Vec<String> {
+    let session = SESSION_KEYS.lock().unwrap_or_else(|err| err.into_inner());
+    // The key being minted is recorded already, and must not hide its older names
+    let session: HashSet<&str> = session
+        .iter()
+        .flatten()
+        .filter(|key| key.as_str() != new_key)
+        .map(|key| trailing_identifier(key))
+        .collect();
+    let new_identifier = trailing_identifier(new_key).to_lowercase();
+
+    existing
+        .filter(|key| key.as_str() != new_key)
+        .filter(|key| {
+            let identifier = trailing_identifier(key);
+            if session.contains(identifier) {
+                return false;
+            }
+
+            let identifier = identifier.to_lowercase();
+            let (shorter, longer) = if identifier.len() < new_identifier.len() {
+                (&identifier, &new_identifier)
+            } else {
+                (&new_identifier, &identifier)
+            };
+            identifier == new_identifier
+                || (shorter.chars().count() >= 4 && longer.contains(shorter.as_str()))
+                || edit_distance(&identifier, &new_identifier) <= 2
+        })
+        .cloned()
+        .collect()
+}
+
+fn trailing_identifier(key: &str) -> &str {
+    key.rsplit("::").next().unwrap_or(key)
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,62 @@
+//! Entries reserved for future use, marked `reserved = true`, with the optional `owner`
+//! of the reservation:
+//!
+//! ```toml
+//! [unique_tags]
+//! "handshake.v3" = { uuid = "ce4baeef-bd9c-4932-88be-24eb1033f695", reserved = true, owner = "protocol-team" }
+//! ```
+//!
+//! The macros refuse to resolve a reserved key, or an alias of a reserved entry, so that
+//! no invocation adopts a pre-allocated UUID by accident. The reservation is lifted by
+//! removing `reserved = true` from the entry. Must stay in sync with
+//! `unique_uuid::registry::Entry::is_reserved`.
+use std::collections::BTreeMap;
+
+use crate::{alias, Section};
+
+/// Returns `true` if the entry `value` is reserved.
+fn is_reserved(value: &toml::Value) -> bool {
+    value.get("reserved").and_then(toml::Value::as_bool) == Some(true)
+}
+
+/// Checks that `tag`, and `rename_from`, the key about to be moved to `tag`, do not
+/// resolve to a reserved entry of `sections`, the entries of `section` in each file of
+/// the registry.
+///
+/// # Errors
+/// Fails with a message naming the reserved entry and the owner of the reservation.
+pub(crate) fn check(
+    tag: &str,
+    section: Section,
+    rename_from: Option<&str>,
+    sections: &[&BTreeMap<String, toml::Value>],
+) -> Result<(), String> {
+    for key in [Some(tag), rename_from].into_iter().flatten() {
+        let entry = sections.iter().find_map(|entries| {
+            entries
+                .get_key_value(key)
+                .or_else(|| alias::owner(entries, key))
+        });
+        let Some((owner_key, value)) = entry.filter(|(_, value)| is_reserved(value)) else {
+            continue;
+        };
+        let through = if owner_key != key {
+            format!(", an alias of `{}`,", owner_key)
+        } else {
+            String::new()
+        };
+        let lift = match value.get("owner").and_then(toml::Value::as_str) {
+            Some(owner) => format!(
+                "Contact `{}`, the owner of the reservation, to lift it by removing",
+                owner
+            ),
+            None => "Lift the reservation by removing".to_string(),
+        };
+        return Err(format!(
+            "`{}`{} in [{}] is reserved for future use, and cannot be used by the macros. \
+             {} `reserved = true` from the entry, e.g. with `unique-uuid-admin unreserve {}`.",
+            key, through, section, lift, owner_key
+        ));
+    }
+    Ok(())
+}
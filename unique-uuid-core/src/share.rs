@@ -0,0 +1,63 @@
+//! Opening the registry files while other processes hold them.
+//!
+//! On Windows, opening a file fails with a sharing violation while another process holds
+//! it open without sharing it, e.g. another compilation or an editor, even when no data
+//! race would occur. The files are therefore opened sharing reads, writes and deletions
+//! with the other processes (the locks of the registry serialize the actual updates),
+//! and the opening is retried for a short while before giving up. On other platforms
+//! files are always shared, and the first failure is returned.
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use crate::stats::{self, Counter};
+
+/// The delay before the first retry, doubled after each attempt.
+const FIRST_DELAY: Duration = Duration::from_millis(5);
+
+/// The number of retries, waiting about 2.5 seconds overall.
+const RETRIES: u32 = 9;
+
+/// Opens `path` with `options`, sharing it with the other processes, and retrying while
+/// another process holds it without sharing it.
+pub(crate) fn open(options: &mut OpenOptions, path: &Path) -> io::Result<File> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        const FILE_SHARE_READ: u32 = 0x1;
+        const FILE_SHARE_WRITE: u32 = 0x2;
+        const FILE_SHARE_DELETE: u32 = 0x4;
+        options.share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE);
+    }
+    let mut delay = FIRST_DELAY;
+    for _ in 0..RETRIES {
+        match options.open(path) {
+            Err(err) if is_sharing_violation(&err) => thread::sleep(delay),
+            result => return result,
+        }
+        delay *= 2;
+    }
+    options.open(path)
+}
+
+/// Reads the whole content of `path`, as [`std::fs::read`] does, despite sharing
+/// violations.
+pub(crate) fn read(path: &Path) -> io::Result<Vec<u8>> {
+    stats::io(Counter::FileReads, || {
+        let mut bytes = Vec::new();
+        open(OpenOptions::new().read(true), path)?.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    })
+}
+
+/// Returns `true` if `err` reports that another process holds the file without sharing
+/// it (`ERROR_SHARING_VIOLATION` or `ERROR_LOCK_VIOLATION`). Always `false` outside of
+/// Windows.
+pub(crate) fn is_sharing_violation(err: &io::Error) -> bool {
+    cfg!(windows) && matches!(err.raw_os_error(), Some(32 | 33))
+}
@@ -0,0 +1,198 @@
+//! Sections stored in files of their own, e.g. so that each has its own owners:
+//! ```toml
+//! [config]
+//! tags_file = "protocol/tags.toml"
+//! type_tags_file = "types.toml"
+//! ```
+//! The paths are relative to the directory of the registry file, and a path naming the
+//! registry file itself keeps the section there. A section file holds only its section,
+//! in the format of its extension, and is locked while an entry is added to it,
+//! independently of the registry file and of the other section.
+use std::{
+    collections::BTreeMap,
+    fs::OpenOptions,
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::Config,
+    document::{FileStructure, Layout},
+    format::Format,
+    handle::RegistryHandle,
+    log::{self, Event},
+    registry::decode_registry,
+    share,
+    stats::{self, Counter},
+    Section,
+};
+
+/// Returns the path of the file storing the section of `r#type`, if it is not the
+/// registry file.
+pub(crate) fn section_path(
+    handle: &RegistryHandle,
+    config: &Config,
+    r#type: &Section,
+) -> Option<PathBuf> {
+    let file = match r#type {
+        Section::UniqueTags => config.tags_file.as_ref(),
+        Section::UniqueTypeTags => config.type_tags_file.as_ref(),
+    }?;
+    let registry = handle.path();
+    let path = registry
+        .parent()
+        .map_or_else(|| file.into(), |dir| dir.join(file));
+    (path != registry).then_some(path)
+}
+
+/// Reads the sections of `file_structure`, the registry file, that are stored in files of
+/// their own.
+pub(crate) fn load(
+    handle: &RegistryHandle,
+    file_structure: &mut FileStructure,
+) -> Result<(), String> {
+    let config = Config::from_items(&file_structure.other)?;
+    for r#type in [Section::UniqueTags, Section::UniqueTypeTags] {
+        let Some(path) = section_path(handle, &config, &r#type) else {
+            continue;
+        };
+        let target = section(file_structure, &r#type);
+        if !target.is_empty() {
+            return Err(format!(
+                "the registry holds [{}] entries, but the section is stored in `{}`: move \
+                 them there",
+                r#type.name(),
+                path.display()
+            ));
+        }
+        let contents = match share::read(&path) {
+            Ok(bytes) => decode_registry(handle, bytes)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("cannot read `{}`: {}", path.display(), err)),
+        };
+        *target = parse(&path, &contents, &r#type)?;
+    }
+    Ok(())
+}
+
+/// Applies `update` to the entries of the section of `r#type`, stored in the file at
+/// `path`, returning them as written. The file is locked from the moment it is read until
+/// it is written, and only written if `update` changed it.
+pub(crate) fn update(
+    handle: &RegistryHandle,
+    path: &Path,
+    r#type: &Section,
+    update: impl FnOnce(&mut BTreeMap<String, toml::Value>),
+) -> io::Result<BTreeMap<String, toml::Value>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = share::open(
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false),
+        path,
+    )?;
+    file.lock()?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+    let contents = decode_registry(handle, bytes).map_err(invalid)?;
+    let mut entries = parse(path, &contents, r#type).map_err(invalid)?;
+    let before = entries.clone();
+    update(&mut entries);
+    if entries != before {
+        let serialized = Format::of(path).serialize_section(*r#type, &entries);
+        let serialized = Layout::detect(&contents).apply(serialized);
+        log::io(Event::Write, path, || {
+            stats::io(Counter::FileWrites, || {
+                file.set_len(0)?;
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(serialized.as_bytes())
+            })
+        })?;
+    }
+    Ok(entries)
+}
+
+/// Parses `contents`, the content of the file at `path` storing the section of `r#type`,
+/// which must hold nothing else.
+fn parse(
+    path: &Path,
+    contents: &str,
+    r#type: &Section,
+) -> Result<BTreeMap<String, toml::Value>, String> {
+    let format = Format::of(path);
+    if format == Format::CargoMetadata {
+        return Err(format!(
+            "[{}] cannot be stored alone in the manifest `{}`",
+            r#type.name(),
+            path.display()
+        ));
+    }
+    let mut file_structure = format
+        .parse(&Layout::strip(contents))
+        .map_err(|err| format!("cannot parse `{}`: {}", path.display(), err))?;
+    let entries = std::mem::take(section(&mut file_structure, r#type));
+    if !file_structure.unique_tags.is_empty()
+        || !file_structure.unique_type_tags.is_empty()
+        || !file_structure.other.is_empty()
+    {
+        return Err(format!(
+            "`{}` must only hold [{}]",
+            path.display(),
+            r#type.name()
+        ));
+    }
+    Ok(entries)
+}
+
+/// Returns the entries of the section of `r#type` in `file_structure`.
+pub(crate) fn section<'a>(
+    file_structure: &'a mut FileStructure,
+    r#type: &Section,
+) -> &'a mut BTreeMap<String, toml::Value> {
+    match r#type {
+        Section::UniqueTags => &mut file_structure.unique_tags,
+        Section::UniqueTypeTags => &mut file_structure.unique_type_tags,
+    }
+}
+
+/// Returns `file_structure` without the sections stored in files of their own: what the
+/// registry file holds.
+pub(crate) fn registry_file_structure(
+    handle: &RegistryHandle,
+    file_structure: &FileStructure,
+) -> FileStructure {
+    let config = Config::from_items(&file_structure.other).unwrap_or_default();
+    let mut registry_file_structure = file_structure.clone();
+    for r#type in [Section::UniqueTags, Section::UniqueTypeTags] {
+        if section_path(handle, &config, &r#type).is_some() {
+            section(&mut registry_file_structure, &r#type).clear();
+        }
+    }
+    registry_file_structure
+}
+
+/// Writes the sections of `file_structure` that are stored in files of their own, each
+/// under its lock, as the journal is compacted.
+pub(crate) fn write_sections(
+    handle: &RegistryHandle,
+    file_structure: &FileStructure,
+) -> io::Result<()> {
+    let config = Config::from_items(&file_structure.other).unwrap_or_default();
+    for (r#type, entries) in [
+        (Section::UniqueTags, &file_structure.unique_tags),
+        (Section::UniqueTypeTags, &file_structure.unique_type_tags),
+    ] {
+        if let Some(path) = section_path(handle, &config, &r#type) {
+            update(handle, &path, &r#type, |section| {
+                section.clone_from(entries)
+            })?;
+        }
+    }
+    Ok(())
+}
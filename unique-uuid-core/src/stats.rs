@@ -0,0 +1,146 @@
+//! Opt-in counters of the work the macros do on the registry.
+//!
+//! When the `UNIQUE_UUID_STATS` environment variable is set to a path, the process
+//! counts its expansions, the expansions whose registry came from the sidecar cache,
+//! the reads and writes of the registry files, the entries minted, and the time spent
+//! reading and writing. When the compilation ends, it appends a JSON line with these
+//! counters to that file, e.g.
+//!
+//! ```json
+//! {"crate":"billing","expansions":12,"cache_hits":12,"file_reads":12,"file_writes":1,"minted":1,"io_micros":840}
+//! ```
+//!
+//! The counters are flushed when the thread of the compiler expanding the macros exits.
+//! A compiler expanding macros on several threads appends a line per thread, whose
+//! counters add up.
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::Instant,
+};
+
+static STATS_ENV: &str = "UNIQUE_UUID_STATS";
+
+/// The counters, in the order of [`Counter`].
+static COUNTERS: [AtomicU64; 6] = [const { AtomicU64::new(0) }; 6];
+
+/// A counter of the report, named as in the report.
+#[derive(Clone, Copy)]
+pub(crate) enum Counter {
+    /// Resolutions of keys against the registry.
+    Expansions,
+    /// Registry files parsed from their sidecar cache.
+    CacheHits,
+    /// Reads of a registry file.
+    FileReads,
+    /// Writes of a registry file.
+    FileWrites,
+    /// Entries minted.
+    Minted,
+    /// Microseconds spent reading and writing registry files.
+    IoMicros,
+}
+
+impl Counter {
+    const ALL: [Counter; 6] = [
+        Counter::Expansions,
+        Counter::CacheHits,
+        Counter::FileReads,
+        Counter::FileWrites,
+        Counter::Minted,
+        Counter::IoMicros,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Counter::Expansions => "expansions",
+            Counter::CacheHits => "cache_hits",
+            Counter::FileReads => "file_reads",
+            Counter::FileWrites => "file_writes",
+            Counter::Minted => "minted",
+            Counter::IoMicros => "io_micros",
+        }
+    }
+}
+
+/// Returns the path of the report, if the counters are enabled.
+fn report_path() -> Option<&'static PathBuf> {
+    static PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PATH.get_or_init(|| {
+        std::env::var_os(STATS_ENV)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    })
+    .as_ref()
+}
+
+/// Flushes the counters when dropped, see [`Flush::flush`].
+struct Flush;
+
+impl Drop for Flush {
+    fn drop(&mut self) {
+        Flush::flush();
+    }
+}
+
+impl Flush {
+    /// Appends the counters to the report and resets them, unless nothing was counted.
+    fn flush() {
+        let Some(path) = report_path() else {
+            return;
+        };
+        let counts =
+            Counter::ALL.map(|counter| COUNTERS[counter as usize].swap(0, Ordering::Relaxed));
+        if counts.iter().all(|count| *count == 0) {
+            return;
+        }
+
+        let name = std::env::var("CARGO_PKG_NAME").unwrap_or_default();
+        let mut line = format!("{{\"crate\":{}", serde_json::Value::String(name));
+        for (counter, count) in Counter::ALL.into_iter().zip(counts) {
+            line.push_str(&format!(",\"{}\":{}", counter.name(), count));
+        }
+        line.push_str("}\n");
+
+        // A failure to report must not fail the build
+        if let Ok(mut file) = OpenOptions::new().append(true).create(true).open(path) {
+            if file.lock().is_ok() {
+                let _ = file.write_all(line.as_bytes());
+            }
+        }
+    }
+}
+
+thread_local! {
+    static FLUSH: Flush = const { Flush };
+}
+
+/// Adds `count` to `counter`, if the counters are enabled.
+pub(crate) fn add(counter: Counter, count: u64) {
+    if report_path().is_some() {
+        // Registers the flush on the exit of the thread
+        FLUSH.with(|_| {});
+        COUNTERS[counter as usize].fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+/// Runs `operation`, a read or a write of a registry file counted by `counter`, adding
+/// the time it takes to the I/O time.
+pub(crate) fn io<T>(counter: Counter, operation: impl FnOnce() -> T) -> T {
+    if report_path().is_none() {
+        return operation();
+    }
+    let start = Instant::now();
+    let result = operation();
+    add(counter, 1);
+    add(
+        Counter::IoMicros,
+        u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX),
+    );
+    result
+}
@@ -0,0 +1,523 @@
+//! Registries loaded and saved at an explicit path, by the tools built on the registry
+//! rather than by the macros: the `Registry` of `unique-uuid`, its build scripts, and
+//! `unique-uuid-admin`.
+//!
+//! [`load`] reads a registry as the macros see it: the registry file, or each file of a
+//! registry directory, with the sections stored in files of their own, the journal and
+//! the pending fragments.
+//! [`save`] writes it back in the format of its file. [`update`] and [`compact`] read and
+//! write the registry under the registry lock the macros write under, moving the journal
+//! and the pending fragments into the registry file, so that neither they nor the macros lose the entries written
+//! meanwhile.
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    string::FromUtf8Error,
+};
+
+use crate::{
+    directory,
+    document::{FileStructure, Layout},
+    format::Format,
+    fragment,
+    handle::RegistryHandle,
+    journal, lock,
+    registry::parse_registry,
+    share, split,
+};
+
+/// An error that occurred while loading or saving a registry.
+///
+/// I/O errors name the absolute path of the registry, and explain the most common
+/// causes: a directory in place of the file, missing permissions, or a missing parent
+/// directory.
+///
+/// ```rust
+/// use unique_uuid_core::{load, save, Layout, Registry};
+///
+/// let directory = std::env::temp_dir().join("unique-uuid-core-doc-error").join("types.toml");
+/// std::fs::create_dir_all(&directory).unwrap();
+/// let err = load(&directory).unwrap_err();
+/// assert!(err.to_string().ends_with("is a directory; the registry must be a file)"));
+///
+/// let missing = std::env::temp_dir().join("unique-uuid-core-doc-missing").join("types.toml");
+/// let err = save(&missing, &Registry::default(), Layout::default()).unwrap_err();
+/// assert!(err.to_string().ends_with("unique-uuid-core-doc-missing` does not exist)"));
+/// ```
+///
+/// A registry that is not valid UTF-8 is reported with the offset of the first invalid
+/// byte and the bytes around it, and points to a valid `.bak` copy if there is one:
+///
+/// ```rust
+/// let directory = std::env::temp_dir().join("unique-uuid-core-doc-encoding");
+/// std::fs::create_dir_all(&directory).unwrap();
+/// let path = directory.join("types.toml");
+/// std::fs::write(&path, b"[unique_tags]\n\"caf\xe9\" = \"\"\n").unwrap();
+/// std::fs::write(directory.join("types.toml.bak"), "[unique_tags]\n").unwrap();
+///
+/// let message = unique_uuid_core::load(&path).unwrap_err().to_string();
+/// assert!(message.contains("invalid UTF-8 at byte 18 (`67 73 5d 0a 22 63 61 66 [e9] 22 20 3d 20 22 22 0a`)"));
+/// assert!(message.contains("types.toml.bak` is a valid registry"));
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// The registry file could not be read.
+    Io {
+        /// The path of the registry file.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// The registry file could not be written.
+    Write {
+        /// The path of the registry file.
+        path: PathBuf,
+        /// The underlying I/O error.
+        source: io::Error,
+    },
+
+    /// The registry file is not valid UTF-8.
+    Encoding {
+        /// The path of the registry file.
+        path: PathBuf,
+        /// The offset of the first invalid byte.
+        offset: usize,
+        /// The bytes around the first invalid byte, in hexadecimal, the invalid byte
+        /// between brackets.
+        excerpt: String,
+        /// A backup of the registry next to it, `<path>.bak`, if it is a valid registry.
+        backup: Option<PathBuf>,
+    },
+
+    /// The registry is not a valid TOML (or JSON) document.
+    Parse {
+        /// The path of the registry file, if loaded from disk.
+        path: Option<PathBuf>,
+        /// A description of the syntax error.
+        message: String,
+    },
+
+    /// The registry refused to mint an entry, e.g. for a key breaking a policy of its
+    /// `[config]` table, as it would fail the expansion of a macro.
+    Rejected {
+        /// The path of the registry.
+        path: PathBuf,
+        /// The reason given by the registry.
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                let path = absolute(path);
+                write!(f, "cannot read registry `{}`: {}", path.display(), source)?;
+                write_hint(f, &path, source)
+            }
+            Error::Write { path, source } => {
+                let path = absolute(path);
+                write!(f, "cannot write registry `{}`: {}", path.display(), source)?;
+                write_hint(f, &path, source)
+            }
+            Error::Encoding {
+                path,
+                offset,
+                excerpt,
+                backup,
+            } => {
+                let path = absolute(path);
+                write!(
+                    f,
+                    "cannot read registry `{}`: invalid UTF-8 at byte {} (`{}`); restore it \
+                     from a backup or from git, e.g. with `git checkout -- {}`",
+                    path.display(),
+                    offset,
+                    excerpt,
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                )?;
+                if let Some(backup) = backup {
+                    write!(
+                        f,
+                        ". `{}` is a valid registry: copy it over `{}` to recover",
+                        absolute(backup).display(),
+                        path.display()
+                    )?;
+                }
+                Ok(())
+            }
+            Error::Parse {
+                path: Some(path),
+                message,
+            } => write!(f, "cannot parse registry `{}`: {}", path.display(), message),
+            Error::Parse {
+                path: None,
+                message,
+            } => write!(f, "cannot parse registry: {}", message),
+            Error::Rejected { path, message } => write!(
+                f,
+                "cannot mint in registry `{}`: {}",
+                absolute(path).display(),
+                message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } | Error::Write { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Loads the registry at `path`, a registry file or a registry directory, returning its
+/// entries and the layout of its file.
+///
+/// The sections stored in files of their own are read from them, and the entries of the
+/// journal and of the pending fragments are merged over the registry file, as the macros
+/// see them. The files of a
+/// registry directory are merged in the order of their names, each with its journal, the
+/// top-level items other than the
+/// sections, e.g. `[config]`, being taken from the first file holding them.
+///
+/// # Errors
+/// Returns an error if a file of the registry cannot be read or parsed, and if a key is
+/// stored in two files of a registry directory.
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs;
+/// use unique_uuid_core::journal_path;
+///
+/// let directory = std::env::temp_dir().join("unique-uuid-core-doc-load");
+/// fs::create_dir_all(&directory).unwrap();
+/// let path = directory.join("types.toml");
+/// fs::write(&path, "[unique_tags]\r\na = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\r\n").unwrap();
+/// fs::write(
+///     journal_path(&path),
+///     "unique_tags.b = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\n",
+/// )
+/// .unwrap();
+///
+/// let (registry, layout) = unique_uuid_core::load(&path).unwrap();
+/// assert_eq!(registry.unique_tags.len(), 2);
+/// assert_eq!(layout.apply("a\n".to_string()), "a\r\n");
+/// ```
+pub fn load(path: impl AsRef<Path>) -> Result<(FileStructure, Layout), Error> {
+    let path = path.as_ref();
+    if directory::is_directory(path) {
+        return load_directory(path).map(|registry| (registry, Layout::default()));
+    }
+    // Read before the registry file, which holds the fragments compacted meanwhile
+    let fragments = fragment::read(path).map_err(read_error(&fragment::fragments_path(path)))?;
+    let contents = read_file(path)?;
+    let handle = RegistryHandle::open(path);
+    let mut registry = parse(&handle, path, &contents)?;
+    let pending = journal::read(&handle).map_err(read_error(&journal::journal_path(path)))?;
+    journal::merge(&mut registry, &pending);
+    fragments.merge(&mut registry);
+    Ok((registry, Layout::detect(&contents)))
+}
+
+/// Saves `registry` at `path`, in the format of its extension and in `layout`, with the
+/// sections stored in files of their own. A registry stored in a manifest replaces the
+/// `[package.metadata.unique-uuid]` table of the manifest, in the layout of the
+/// manifest.
+///
+/// The file is written to a temporary file next to `path` first, then moved in place.
+/// The registry lock is not taken: see [`update`] to modify a registry the macros may be
+/// writing.
+///
+/// # Errors
+/// Returns an error if the registry cannot be written.
+pub fn save(path: impl AsRef<Path>, registry: &FileStructure, layout: Layout) -> Result<(), Error> {
+    let handle = RegistryHandle::open(path.as_ref());
+    let file = handle.path();
+    let (contents, layout) = match Format::of(&file) {
+        Format::CargoMetadata => {
+            let manifest = read_file(&file)?;
+            let layout = Layout::detect(&manifest);
+            (manifest, layout)
+        }
+        _ => (String::new(), layout),
+    };
+    write(&handle, &contents, layout, registry)
+}
+
+/// Applies `f` to the registry at `path` under the registry lock, and saves it unless
+/// `f` fails, returning the result of `f`. A missing registry has no entries.
+///
+/// The registry lock the macros write under is held from the time the registry is read
+/// until it is written, which makes the macros wait. The journal is locked too, as for a
+/// compaction, and its entries and the pending fragments are moved into the registry
+/// file. For a registry
+/// directory, the file of the compiled package is updated.
+///
+/// # Errors
+/// Returns the error of `f`, or an error if the registry cannot be read or written.
+///
+/// # Example
+///
+/// ```rust
+/// use std::fs;
+/// use unique_uuid_core::{journal_path, Error};
+///
+/// let directory = std::env::temp_dir().join("unique-uuid-core-doc-update");
+/// # let _ = fs::remove_dir_all(&directory);
+/// fs::create_dir_all(&directory).unwrap();
+/// let path = directory.join("types.toml");
+/// fs::write(
+///     journal_path(&path),
+///     "unique_tags.a = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n",
+/// )
+/// .unwrap();
+///
+/// let count = unique_uuid_core::update(&path, |registry| {
+///     registry.unique_tags.insert(
+///         "b".to_string(),
+///         "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24".into(),
+///     );
+///     Ok::<_, Error>(registry.unique_tags.len())
+/// })
+/// .unwrap();
+/// assert_eq!(count, 2);
+/// assert_eq!(fs::read_to_string(journal_path(&path)).unwrap(), "");
+/// assert_eq!(unique_uuid_core::load(&path).unwrap().0.unique_tags.len(), 2);
+/// ```
+pub fn update<T, E: From<Error>>(
+    path: impl AsRef<Path>,
+    f: impl FnOnce(&mut FileStructure) -> Result<T, E>,
+) -> Result<T, E> {
+    let (value, _) = locked(path.as_ref(), true, f)?;
+    Ok(value)
+}
+
+/// Moves the entries of the journal of the registry at `path`, and of its pending
+/// fragments, into the registry file, under the registry lock, returning the number of
+/// entries added to it.
+///
+/// The merged registry is written to a temporary file renamed over the registry file,
+/// and the journal is only emptied, and the fragments deleted, afterwards: if the
+/// compaction is interrupted, the registry file, the journal and the fragments still
+/// hold every entry, and the next compaction completes it. Nothing is written if there
+/// is nothing to move.
+///
+/// # Errors
+/// Returns an error if the registry or the journal cannot be read or written.
+pub fn compact(path: impl AsRef<Path>) -> Result<usize, Error> {
+    let ((), merged) = locked(path.as_ref(), false, |_| Ok::<_, Error>(()))?;
+    Ok(merged)
+}
+
+/// Applies `f` to the registry at `path` with its journal and its pending fragments,
+/// under the registry lock, and writes it if `always` or if anything was pending.
+/// Returns the result of `f` and the number of entries merged from the journal and the
+/// fragments.
+fn locked<T, E: From<Error>>(
+    path: &Path,
+    always: bool,
+    f: impl FnOnce(&mut FileStructure) -> Result<T, E>,
+) -> Result<(T, usize), E> {
+    let handle = RegistryHandle::open(path);
+    let file = handle.path();
+    // The registry lock comes first, as for the macros
+    let _lock = lock::exclusive(&file).map_err(write_error(&file))?;
+
+    let journal_path = journal::journal_path(&file);
+    let journal = match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&journal_path)
+    {
+        Ok(journal) => Some(journal),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(source) => return Err(read_error(&journal_path)(source).into()),
+    };
+    let mut pending = String::new();
+    if let Some(mut journal) = journal.as_ref() {
+        journal.lock().map_err(write_error(&journal_path))?;
+        journal
+            .read_to_string(&mut pending)
+            .map_err(read_error(&journal_path))?;
+    }
+
+    let contents = match read_file(&file) {
+        Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => String::new(),
+        contents => contents?,
+    };
+    let mut registry = parse(&handle, &file, &contents)?;
+    let before = len(&registry);
+    journal::merge(&mut registry, &pending);
+    let fragments_path = fragment::fragments_path(&file);
+    let fragments = fragment::read_all(&file, &registry).map_err(read_error(&fragments_path))?;
+    fragments.merge(&mut registry);
+    let merged = len(&registry) - before;
+
+    let value = f(&mut registry)?;
+    if always || !pending.is_empty() || !fragments.is_empty() {
+        write(&handle, &contents, Layout::detect(&contents), &registry)?;
+    }
+    if let Some(journal) = journal {
+        journal.set_len(0).map_err(write_error(&journal_path))?;
+    }
+    fragments
+        .remove(&file)
+        .map_err(write_error(&fragments_path))?;
+    Ok((value, merged))
+}
+
+/// Loads the registry directory at `path`, merging its `.toml` files, each with its
+/// sections and journal, in the order of their names.
+fn load_directory(path: &Path) -> Result<FileStructure, Error> {
+    let mut registry = FileStructure::default();
+    let mut origins = directory::Origins::new();
+    for file in directory::files(path).map_err(read_error(path))? {
+        let (loaded, _) = load(&file)?;
+        directory::add(&mut registry, &mut origins, &file, &loaded).map_err(|message| {
+            Error::Parse {
+                path: Some(path.to_path_buf()),
+                message,
+            }
+        })?;
+        for (key, value) in loaded.other {
+            registry.other.entry(key).or_insert(value);
+        }
+    }
+    Ok(registry)
+}
+
+/// Parses `contents`, the content of the registry file at `path`, with the sections
+/// stored in files of their own.
+fn parse(handle: &RegistryHandle, path: &Path, contents: &str) -> Result<FileStructure, Error> {
+    let parse_error = |message| Error::Parse {
+        path: Some(path.to_path_buf()),
+        message,
+    };
+    let mut registry = parse_registry(handle, contents).map_err(parse_error)?;
+    split::load(handle, &mut registry).map_err(parse_error)?;
+    Ok(registry)
+}
+
+/// Writes `registry` over `contents`, the registry file of `handle` as read, in `layout`.
+fn write(
+    handle: &RegistryHandle,
+    contents: &str,
+    layout: Layout,
+    registry: &FileStructure,
+) -> Result<(), Error> {
+    let file = handle.path();
+    journal::write_registry(handle, contents, layout, registry).map_err(|source| {
+        if source.kind() == io::ErrorKind::InvalidData {
+            Error::Parse {
+                path: Some(file.clone()),
+                message: source.to_string(),
+            }
+        } else {
+            Error::Write {
+                path: file.clone(),
+                source,
+            }
+        }
+    })
+}
+
+/// Reads the registry file at `path`, reporting where it is not valid UTF-8.
+fn read_file(path: &Path) -> Result<String, Error> {
+    let bytes = share::read(path).map_err(read_error(path))?;
+    String::from_utf8(bytes).map_err(|err| encoding_error(path, err))
+}
+
+/// Describes `err`, the failure to decode the registry file at `path`: the offset of the
+/// first invalid byte, the bytes around it, and the `.bak` copy next to the file if it is
+/// a valid registry.
+pub(crate) fn encoding_error(path: &Path, err: FromUtf8Error) -> Error {
+    let offset = err.utf8_error().valid_up_to();
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    let valid = fs::read_to_string(&backup)
+        .is_ok_and(|contents| Format::of(path).parse(&Layout::strip(&contents)).is_ok());
+    Error::Encoding {
+        path: path.to_path_buf(),
+        offset,
+        excerpt: hex_excerpt(err.as_bytes(), offset),
+        backup: valid.then_some(backup),
+    }
+}
+
+/// Dumps the bytes of `bytes` around `offset` in hexadecimal, the byte at `offset`
+/// between brackets.
+fn hex_excerpt(bytes: &[u8], offset: usize) -> String {
+    let start = offset.saturating_sub(8);
+    let end = bytes.len().min(offset + 8);
+    (start..end)
+        .map(|index| {
+            if index == offset {
+                format!("[{:02x}]", bytes[index])
+            } else {
+                format!("{:02x}", bytes[index])
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the number of entries of `registry`.
+fn len(registry: &FileStructure) -> usize {
+    registry.unique_tags.len() + registry.unique_type_tags.len()
+}
+
+/// Returns a function turning a failure to read `path` into an [`Error`].
+fn read_error(path: &Path) -> impl Fn(io::Error) -> Error + '_ {
+    move |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+/// Returns a function turning a failure to write `path` into an [`Error`].
+fn write_error(path: &Path) -> impl Fn(io::Error) -> Error + '_ {
+    move |source| Error::Write {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+/// Returns `path` made absolute, for error messages.
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Explains the most common causes of a failure to access the registry at `path`.
+fn write_hint(f: &mut fmt::Formatter<'_>, path: &Path, source: &io::Error) -> fmt::Result {
+    if path.is_dir() {
+        write!(
+            f,
+            " (`{}` is a directory; the registry must be a file)",
+            path.display()
+        )
+    } else if share::is_sharing_violation(source) {
+        write!(
+            f,
+            " (another process, e.g. an editor, holds `{}` open without sharing it)",
+            path.display()
+        )
+    } else if source.kind() == io::ErrorKind::PermissionDenied {
+        write!(
+            f,
+            " (the current user is not allowed to access `{}`)",
+            path.display()
+        )
+    } else if let Some(parent) = path.parent().filter(|parent| !parent.exists()) {
+        write!(f, " (the directory `{}` does not exist)", parent.display())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+//! The naming conventions new tags can be required to follow.
+
+/// A naming convention for the segments of a tag, i.e. the parts of its key separated
+/// by `.` or `::`.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TagStyle {
+    /// Any key is accepted.
+    #[default]
+    None,
+
+    /// Lowercase words separated by `_`: `order_created`.
+    SnakeCase,
+
+    /// Lowercase words separated by `-`: `order-created`.
+    KebabCase,
+}
+
+impl TagStyle {
+    /// Parses the value of the `tag_style` setting.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(Self::None),
+            "snake_case" => Some(Self::SnakeCase),
+            "kebab-case" => Some(Self::KebabCase),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the style, as written in the `tag_style` setting.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::SnakeCase => "snake_case",
+            Self::KebabCase => "kebab-case",
+        }
+    }
+
+    /// Returns `key` rewritten in this style, or `None` if it already follows it.
+    pub(crate) fn suggest(self, key: &str) -> Option<String> {
+        let separator = match self {
+            Self::None => return None,
+            Self::SnakeCase => '_',
+            Self::KebabCase => '-',
+        };
+
+        let mut suggestion = String::with_capacity(key.len());
+        let mut rest = key;
+        while !rest.is_empty() {
+            let end = [rest.find('.'), rest.find("::")]
+                .into_iter()
+                .flatten()
+                .min()
+                .unwrap_or(rest.len());
+            suggestion.push_str(&restyle(&rest[..end], separator));
+            rest = &rest[end..];
+            let delimiter = if rest.starts_with("::") {
+                "::"
+            } else if rest.starts_with('.') {
+                "."
+            } else {
+                ""
+            };
+            suggestion.push_str(delimiter);
+            rest = &rest[delimiter.len()..];
+        }
+        (suggestion != key).then_some(suggestion)
+    }
+}
+
+/// Splits `segment` into lowercase words, at `_`, `-`, spaces and lowercase-to-uppercase
+/// transitions, and joins them with `separator`.
+fn restyle(segment: &str, separator: char) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut previous: Option<char> = None;
+    for c in segment.chars() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+        } else {
+            let boundary = c.is_uppercase()
+                && previous.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit());
+            if boundary && !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            word.extend(c.to_lowercase());
+        }
+        previous = Some(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words.join(&separator.to_string())
+}
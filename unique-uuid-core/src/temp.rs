@@ -0,0 +1,98 @@
+//! Throwaway registries for tests, each in a directory of its own.
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::RegistryHandle;
+
+/// The registries created by this process, to name their directories.
+static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+/// A registry `types.toml` in a new temporary directory, removed with everything in it
+/// when the `TempRegistry` is dropped.
+///
+/// It dereferences to a [`RegistryHandle`] on the registry, and [`TempRegistry::handle`]
+/// opens other handles on it, e.g. one per thread. Every `TempRegistry` has a directory
+/// of its own, so tests using them can run in parallel.
+///
+/// This type is only available with the `test-util` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid_core::{Section, TempRegistry};
+///
+/// let registry = TempRegistry::new("[unique_tags]\n");
+/// let uuid = registry.lookup_or_insert("orders.created", Section::UniqueTags).unwrap();
+/// assert!(registry.contents().contains(&uuid.to_string()));
+///
+/// let directory = registry.directory().to_path_buf();
+/// drop(registry);
+/// assert!(!directory.exists());
+/// ```
+#[derive(Debug)]
+pub struct TempRegistry {
+    directory: PathBuf,
+    handle: RegistryHandle,
+}
+
+impl TempRegistry {
+    /// Creates a registry holding `contents`.
+    ///
+    /// # Panics
+    /// Panics if the directory or the registry cannot be created.
+    pub fn new(contents: &str) -> Self {
+        let registry = Self::empty();
+        fs::write(registry.handle.path(), contents).unwrap();
+        registry
+    }
+
+    /// Creates a registry whose file does not exist yet, as in a new workspace.
+    ///
+    /// # Panics
+    /// Panics if the directory cannot be created.
+    pub fn empty() -> Self {
+        let directory = std::env::temp_dir().join(format!(
+            "unique-uuid-registry-{}-{}",
+            std::process::id(),
+            CREATED.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        let handle = RegistryHandle::open(directory.join("types.toml"));
+        Self { directory, handle }
+    }
+
+    /// Returns the directory of the registry, where its journal and the files of its
+    /// sections are stored as well.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Opens a new handle on the registry.
+    pub fn handle(&self) -> RegistryHandle {
+        self.handle.clone()
+    }
+
+    /// Returns the content of the registry file, empty if it does not exist.
+    pub fn contents(&self) -> String {
+        fs::read_to_string(self.handle.path()).unwrap_or_default()
+    }
+}
+
+impl Deref for TempRegistry {
+    type Target = RegistryHandle;
+
+    fn deref(&self) -> &RegistryHandle {
+        &self.handle
+    }
+}
+
+impl Drop for TempRegistry {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.directory);
+    }
+}
@@ -0,0 +1,74 @@
+//! Opt-in tracking of the registry keys used by macro expansions.
+//!
+//! When the `UNIQUE_UUID_TRACK_USAGE` environment variable is set to a path, every
+//! expansion records the key it resolved in that file, one `<section>\t<key>` line per
+//! key. After a clean build (cached expansions are not replayed), the log therefore
+//! lists every entry of the registry that is still referenced by the code;
+//! `unique_uuid::registry::UsageLog` reads it back.
+use std::{
+    collections::HashSet,
+    fs::OpenOptions,
+    io::{Read, Write},
+    sync::Mutex,
+};
+
+use crate::{memory, Section};
+
+static TRACK_USAGE_ENV: &str = "UNIQUE_UUID_TRACK_USAGE";
+
+/// Keys already recorded by this process, to avoid reopening the log for each expansion.
+static RECORDED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Records that `tag` was resolved in the given section, if usage tracking is enabled.
+///
+/// The log is locked while it is read and appended to, so that concurrent compilations
+/// never write the same line twice. Nothing is recorded for the in-memory registry, which
+/// leaves no file behind.
+///
+/// # Errors
+/// Fails if the log cannot be opened, locked, read or written, with a message naming it,
+/// which the macros report as a warning: the log then misses `tag`, but the expansion
+/// goes on.
+pub fn track_usage(tag: &str, r#type: &Section) -> Result<(), String> {
+    let Some(path) = std::env::var_os(TRACK_USAGE_ENV) else {
+        return Ok(());
+    };
+    if memory::enabled() {
+        return Ok(());
+    }
+
+    let line = format!("{}\t{}", r#type.name(), tag);
+    let mut recorded = RECORDED.lock().unwrap_or_else(|err| err.into_inner());
+    let recorded = recorded.get_or_insert_with(HashSet::new);
+    if recorded.contains(&line) {
+        return Ok(());
+    }
+
+    let append = || -> std::io::Result<()> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        file.lock()?;
+
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        if !contents.lines().any(|existing| existing == line) {
+            file.write_all(format!("{}\n", line).as_bytes())?;
+        }
+        Ok(())
+    };
+    append().map_err(|err| {
+        format!(
+            "Cannot record the use of `{}` in the usage log `{}`: {}. The log set by `{}` \
+             is incomplete.",
+            tag,
+            path.to_string_lossy(),
+            err,
+            TRACK_USAGE_ENV
+        )
+    })?;
+    recorded.insert(line);
+    Ok(())
+}
@@ -0,0 +1,1091 @@
+//! Resolution of keys against throwaway registries, without expanding the macros.
+use std::{collections::BTreeSet, thread};
+
+use unique_uuid_core::{
+    fragments_path, MetaValue, RegistryHandle, Section, TagOptions, TempRegistry,
+};
+
+const CANONICAL: &str = "[unique_tags]\n\
+                         \"orders.cancelled\" = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\n\
+                         \"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n\
+                         [unique_type_tags]\n\
+                         \"::Order\" = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\"\n";
+
+const JOURNALED: &str = "[unique_tags]\n\n[config]\njournal = true\njournal_limit = 4\n";
+
+const FRAGMENTED: &str = "[unique_tags]\n\n[config]\nfragments = true\n";
+
+#[test]
+fn existing_entries_are_only_read() {
+    let registry = TempRegistry::new(CANONICAL);
+    let uuid = registry
+        .lookup_or_insert("orders.created", Section::UniqueTags)
+        .unwrap();
+    assert_eq!(uuid.to_string(), "2af1216e-7042-406a-a577-c39a7e7c3faa");
+    let uuid = registry
+        .lookup_or_insert("::Order", Section::UniqueTypeTags)
+        .unwrap();
+    assert_eq!(uuid.to_string(), "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db");
+    assert_eq!(registry.contents(), CANONICAL);
+}
+
+#[test]
+fn new_keys_are_minted_once() {
+    let registry = TempRegistry::empty();
+    let resolved = registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &TagOptions::default())
+        .unwrap();
+    assert_eq!(resolved.uuid.get_version_num(), 4);
+    assert!(resolved.warnings[0].contains("minted `orders.paid`"));
+
+    // Read back by another handle, with the metadata of the new entry
+    assert_eq!(
+        registry
+            .handle()
+            .lookup_or_insert("orders.paid", Section::UniqueTags)
+            .unwrap(),
+        resolved.uuid
+    );
+    let contents = registry.contents();
+    assert!(contents.starts_with("[unique_tags]\n\"orders.paid\" = { crate = "));
+    assert!(contents.contains(&format!("uuid = \"{}\"", resolved.uuid)));
+}
+
+#[test]
+fn renamed_types_keep_their_uuid() {
+    let registry = TempRegistry::new(CANONICAL);
+    let options = TagOptions {
+        rename_from: Some("::Order"),
+        ..TagOptions::default()
+    };
+    let resolved = registry
+        .get_uuid_from_tag("::shop::Order", Section::UniqueTypeTags, &options)
+        .unwrap();
+    assert_eq!(
+        resolved.uuid.to_string(),
+        "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db"
+    );
+    assert!(registry.contents().ends_with(
+        "[unique_type_tags]\n\"::shop::Order\" = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\"\n"
+    ));
+}
+
+#[test]
+fn namespaces_list_their_tags_in_key_order() {
+    let registry = TempRegistry::new(CANONICAL);
+    let tags = registry.namespace_tags("orders").unwrap();
+    let keys: Vec<&str> = tags.tags.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["orders.cancelled", "orders.created"]);
+    assert!(tags.warnings.is_empty());
+    assert!(registry.namespace_tags("invoices").unwrap().tags.is_empty());
+}
+
+#[test]
+fn journaled_entries_leave_the_registry_file_untouched() {
+    let registry = TempRegistry::new(&format!("{}\n[config]\njournal = true\n", CANONICAL));
+    let before = registry.contents();
+    let uuid = registry
+        .lookup_or_insert("orders.paid", Section::UniqueTags)
+        .unwrap();
+    assert_eq!(registry.contents(), before);
+    let journal = std::fs::read_to_string(registry.directory().join("types.toml.journal"));
+    assert!(journal.unwrap().contains(&uuid.to_string()));
+    assert!(registry
+        .read()
+        .unwrap()
+        .unique_tags
+        .contains_key("orders.paid"));
+}
+
+/// Resolves `keys` from `threads` threads at once, each with a handle of its own.
+fn resolve_concurrently(
+    registry: &TempRegistry,
+    threads: usize,
+    keys: impl Fn(usize) -> Vec<String> + Sync,
+) -> Vec<Vec<uuid::Uuid>> {
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|index| {
+                let handle: RegistryHandle = registry.handle();
+                let keys = keys(index);
+                scope.spawn(move || {
+                    keys.iter()
+                        .map(|key| handle.lookup_or_insert(key, Section::UniqueTags).unwrap())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+#[test]
+fn concurrent_handles_keep_every_entry() {
+    let registry = TempRegistry::new(JOURNALED);
+    let minted = resolve_concurrently(&registry, 8, |index| {
+        (0..4)
+            .map(|key| format!("thread{}.key{}", index, key))
+            .collect()
+    });
+
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(entries.len(), 32);
+    let uuids: BTreeSet<_> = minted.into_iter().flatten().collect();
+    assert_eq!(uuids.len(), 32);
+}
+
+#[test]
+fn concurrent_handles_agree_on_new_keys() {
+    let registry = TempRegistry::new(JOURNALED);
+    let minted = resolve_concurrently(&registry, 8, |_| vec!["orders.paid".to_string()]);
+
+    let uuids: BTreeSet<_> = minted.into_iter().flatten().collect();
+    assert_eq!(uuids.len(), 1, "{:?}", uuids);
+    assert_eq!(
+        registry
+            .lookup_or_insert("orders.paid", Section::UniqueTags)
+            .unwrap(),
+        *uuids.first().unwrap()
+    );
+}
+
+#[test]
+fn concurrent_handles_keep_every_entry_of_the_registry_file() {
+    let registry = TempRegistry::new("[unique_tags]\n");
+    let minted = resolve_concurrently(&registry, 8, |index| {
+        let mut keys: Vec<_> = (0..4)
+            .map(|key| format!("thread{}.key{}", index, key))
+            .collect();
+        keys.push("orders.paid".to_string());
+        keys
+    });
+
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(entries.len(), 33);
+    let paid: BTreeSet<_> = minted.iter().map(|uuids| uuids[4]).collect();
+    assert_eq!(paid.len(), 1, "{:?}", paid);
+    let uuids: BTreeSet<_> = minted.into_iter().flatten().collect();
+    assert_eq!(uuids.len(), 33);
+}
+
+#[test]
+fn fragments_are_written_while_the_lock_is_held() {
+    let registry = TempRegistry::new(FRAGMENTED);
+    let path = registry.directory().join("types.toml");
+    let lock = std::fs::File::create(registry.directory().join("types.toml.lock")).unwrap();
+    lock.lock().unwrap();
+
+    let resolved = registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &TagOptions::default())
+        .unwrap();
+    assert!(resolved.warnings[0].contains("minted `orders.paid`"));
+    assert_eq!(registry.contents(), FRAGMENTED);
+    let pending: Vec<_> = std::fs::read_dir(fragments_path(&path))
+        .unwrap()
+        .map(|file| file.unwrap().path())
+        .collect();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].extension().unwrap(), "toml");
+
+    // Lookups see the pending fragment
+    assert_eq!(
+        registry
+            .handle()
+            .lookup_or_insert("orders.paid", Section::UniqueTags)
+            .unwrap(),
+        resolved.uuid
+    );
+    assert!(registry
+        .read()
+        .unwrap()
+        .unique_tags
+        .contains_key("orders.paid"));
+
+    // The next resolution finding the lock free merges it
+    drop(lock);
+    let shipped = registry
+        .lookup_or_insert("orders.shipped", Section::UniqueTags)
+        .unwrap();
+    let contents = registry.contents();
+    assert!(contents.contains(&resolved.uuid.to_string()));
+    assert!(contents.contains(&shipped.to_string()));
+    assert_eq!(std::fs::read_dir(fragments_path(&path)).unwrap().count(), 0);
+}
+
+#[test]
+fn leftover_fragments_converge_on_the_first() {
+    let registry = TempRegistry::new(FRAGMENTED);
+    let path = registry.directory().join("types.toml");
+    let pending = fragments_path(&path);
+    std::fs::create_dir_all(&pending).unwrap();
+    // Left unclaimed by crashed processes
+    std::fs::write(
+        pending.join("01760529600000000002-7-0.toml"),
+        "unique_tags.\"orders.paid\" = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        pending.join("01760529600000000001-9-0.toml"),
+        "unique_tags.\"orders.paid\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n",
+    )
+    .unwrap();
+    // Left by an interrupted write, never read
+    std::fs::write(
+        pending.join("01760529600000000000-5-0.tmp"),
+        "unique_tags.\"orders.paid\" = \"a8dfa7b5",
+    )
+    .unwrap();
+
+    assert_eq!(unique_uuid_core::compact(&path).unwrap(), 1);
+    let first = "2af1216e-7042-406a-a577-c39a7e7c3faa";
+    assert!(registry
+        .contents()
+        .contains(&format!("\"orders.paid\" = \"{}\"", first)));
+    let left: Vec<_> = std::fs::read_dir(&pending)
+        .unwrap()
+        .map(|file| file.unwrap().file_name())
+        .collect();
+    assert_eq!(left, ["01760529600000000000-5-0.tmp"]);
+    assert_eq!(
+        registry
+            .lookup_or_insert("orders.paid", Section::UniqueTags)
+            .unwrap()
+            .to_string(),
+        first
+    );
+}
+
+#[test]
+fn concurrent_fragments_agree_on_new_keys() {
+    let registry = TempRegistry::new(FRAGMENTED);
+    let minted = resolve_concurrently(&registry, 8, |index| {
+        vec![format!("thread{}.key", index), "orders.paid".to_string()]
+    });
+
+    let paid: BTreeSet<_> = minted.iter().map(|uuids| uuids[1]).collect();
+    assert_eq!(paid.len(), 1, "{:?}", paid);
+    unique_uuid_core::compact(registry.directory().join("types.toml")).unwrap();
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(entries.len(), 9);
+    assert_eq!(
+        registry
+            .lookup_or_insert("orders.paid", Section::UniqueTags)
+            .unwrap(),
+        *paid.first().unwrap()
+    );
+}
+
+#[test]
+fn reserved_prefixes_lead_the_minted_uuids() {
+    let registry = TempRegistry::new(
+        "[unique_tags]\n\n[config.prefixes]\nunique-uuid-core = \"c0de\"\nbilling = \"2af1\"\n",
+    );
+    let uuid = registry
+        .lookup_or_insert("orders.paid", Section::UniqueTags)
+        .unwrap();
+    assert!(uuid.to_string().starts_with("c0de"), "{}", uuid);
+    assert_eq!(uuid.get_version_num(), 4);
+}
+
+#[test]
+fn overlapping_prefixes_are_rejected() {
+    let registry = TempRegistry::new(
+        "[unique_tags]\n\n[config.prefixes]\nbilling = \"2a\"\nshipping = \"2af1\"\n",
+    );
+    let err = registry
+        .lookup_or_insert("orders.paid", Section::UniqueTags)
+        .unwrap_err();
+    assert!(
+        err.contains("the prefixes of `billing` and `shipping` overlap"),
+        "{}",
+        err
+    );
+}
+
+/// Resolves `key` of `section` pinned to `uuid`.
+fn resolve_pinned(
+    registry: &TempRegistry,
+    key: &str,
+    section: Section,
+    uuid: &str,
+) -> Result<unique_uuid_core::Resolved, String> {
+    let options = TagOptions {
+        pinned: Some(uuid.parse().unwrap()),
+        ..TagOptions::default()
+    };
+    registry.get_uuid_from_tag(key, section, &options)
+}
+
+const PINNED: &str = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50";
+
+#[test]
+fn pinned_keys_are_minted_with_their_uuid() {
+    let registry = TempRegistry::empty();
+    let resolved = resolve_pinned(&registry, "::Invoice", Section::UniqueTypeTags, PINNED);
+    assert_eq!(resolved.unwrap().uuid.to_string(), PINNED);
+    let entries = registry.read().unwrap().unique_type_tags;
+    assert_eq!(entries["::Invoice"]["pinned"].as_bool(), Some(true));
+
+    // Resolving it again finds the entry
+    let resolved = resolve_pinned(&registry, "::Invoice", Section::UniqueTypeTags, PINNED);
+    assert!(resolved.unwrap().warnings.is_empty());
+}
+
+#[test]
+fn pin_conflicts_fail_by_default() {
+    let registry = TempRegistry::new(CANONICAL);
+    let err = resolve_pinned(&registry, "orders.created", Section::UniqueTags, PINNED)
+        .err()
+        .unwrap();
+    assert!(err.contains(PINNED), "{}", err);
+    assert!(
+        err.contains("2af1216e-7042-406a-a577-c39a7e7c3faa"),
+        "{}",
+        err
+    );
+    assert!(err.contains("pin_conflict = \"prefer-pinned\""), "{}", err);
+    assert_eq!(registry.contents(), CANONICAL);
+}
+
+#[test]
+fn prefer_pinned_rewrites_the_entry() {
+    let registry = TempRegistry::new(&format!(
+        "{}\n[config]\npin_conflict = \"prefer-pinned\"\n",
+        CANONICAL
+    ));
+    let resolved = resolve_pinned(&registry, "::Order", Section::UniqueTypeTags, PINNED).unwrap();
+    assert_eq!(resolved.uuid.to_string(), PINNED);
+    assert!(resolved.warnings[0].contains("a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db"));
+    assert!(registry.contents().contains(&format!(
+        "\"::Order\" = {{ pinned = true, uuid = \"{}\" }}",
+        PINNED
+    )));
+}
+
+#[test]
+fn prefer_registry_ignores_the_pin() {
+    let registry = TempRegistry::new(&format!(
+        "{}\n[config]\npin_conflict = \"prefer-registry\"\n",
+        CANONICAL
+    ));
+    let before = registry.contents();
+    let resolved =
+        resolve_pinned(&registry, "orders.created", Section::UniqueTags, PINNED).unwrap();
+    assert_eq!(
+        resolved.uuid.to_string(),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+    assert!(resolved.warnings[0].contains(PINNED));
+    assert!(resolved.warnings[0].contains("the pin is ignored"));
+    assert_eq!(registry.contents(), before);
+}
+
+#[test]
+fn pinning_the_uuid_of_another_key_fails_under_every_policy() {
+    for policy in ["error", "prefer-pinned", "prefer-registry"] {
+        let registry = TempRegistry::new(&format!(
+            "{}\n[config]\npin_conflict = \"{}\"\n",
+            CANONICAL, policy
+        ));
+        let before = registry.contents();
+        let err = resolve_pinned(
+            &registry,
+            "orders.created",
+            Section::UniqueTags,
+            "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db",
+        )
+        .err()
+        .unwrap();
+        assert!(err.contains("`::Order` in [unique_type_tags]"), "{}", err);
+        assert!(
+            err.contains("2af1216e-7042-406a-a577-c39a7e7c3faa"),
+            "{}",
+            err
+        );
+        assert_eq!(registry.contents(), before, "{}", policy);
+    }
+}
+
+#[test]
+fn frozen_sections_reject_new_entries_only() {
+    let registry = TempRegistry::new(&format!(
+        "{}\n[config]\nfrozen_sections = [\"unique_tags\"]\n",
+        CANONICAL
+    ));
+    let err = registry
+        .lookup_or_insert("orders.paid", Section::UniqueTags)
+        .err()
+        .unwrap();
+    assert!(
+        err.contains("`orders.paid` is not in [unique_tags]"),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains("`frozen_sections` freezes [unique_tags]"),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains(&registry.path().display().to_string()),
+        "{}",
+        err
+    );
+
+    // Existing entries resolve, and the other section is still writable
+    assert!(registry
+        .lookup_or_insert("orders.created", Section::UniqueTags)
+        .is_ok());
+    assert!(registry
+        .lookup_or_insert("::Invoice", Section::UniqueTypeTags)
+        .is_ok());
+    assert!(!registry.contents().contains("orders.paid"));
+}
+
+#[test]
+fn frozen_namespaces_reject_new_entries_within() {
+    let registry =
+        TempRegistry::new("[unique_tags]\n\n[config]\nfrozen_namespaces = [\"payments\"]\n");
+    let err = registry
+        .lookup_or_insert("payments.v2.refunded", Section::UniqueTags)
+        .err()
+        .unwrap();
+    assert!(
+        err.contains("`frozen_namespaces` freezes the `payments` namespace"),
+        "{}",
+        err
+    );
+    assert!(registry
+        .lookup_or_insert("payments_archive.created", Section::UniqueTags)
+        .is_ok());
+}
+
+const PATTERNED: &str =
+    "[unique_tags]\n\"Legacy.Tag\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n\
+                         [config]\ntag_pattern = '^[a-z0-9_]+(\\.[a-z0-9_]+)*$'\n";
+
+#[test]
+fn tag_patterns_reject_new_tags_only() {
+    let registry = TempRegistry::new(PATTERNED);
+    let err = registry
+        .lookup_or_insert("orders.Paid", Section::UniqueTags)
+        .err()
+        .unwrap();
+    assert!(err.contains("`orders.Paid` does not match"), "{}", err);
+    assert!(err.contains("`^[a-z0-9_]+(\\.[a-z0-9_]+)*$`"), "{}", err);
+
+    // Existing entries are grandfathered, and type tags are not checked
+    assert!(registry
+        .lookup_or_insert("Legacy.Tag", Section::UniqueTags)
+        .is_ok());
+    assert!(registry
+        .lookup_or_insert("orders.paid", Section::UniqueTags)
+        .is_ok());
+    assert!(registry
+        .lookup_or_insert("::shop::Order", Section::UniqueTypeTags)
+        .is_ok());
+}
+
+#[test]
+fn invalid_tag_patterns_are_reported() {
+    let registry = TempRegistry::new("[unique_tags]\n\n[config]\ntag_pattern = '^[a-z'\n");
+    let err = registry
+        .lookup_or_insert("orders.paid", Section::UniqueTags)
+        .unwrap_err();
+    assert!(
+        err.contains("`config.tag_pattern` is not a valid regular expression"),
+        "{}",
+        err
+    );
+}
+
+const CASED: &str = "[unique_tags]\n\
+                     OrderCreated = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\
+                     \"Straße\" = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\n\n\
+                     [unique_type_tags]\n\
+                     \"::Order\" = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\"\n";
+
+fn case_policy(policy: &str) -> TempRegistry {
+    TempRegistry::new(&format!(
+        "{}\n[config]\ncase_insensitive_keys = \"{}\"\n",
+        CASED, policy
+    ))
+}
+
+#[test]
+fn case_collisions_fail_under_the_error_policy() {
+    let registry = case_policy("error");
+    let before = registry.contents();
+    for (tag, existing) in [("ordercreated", "OrderCreated"), ("STRASSE", "Straße")] {
+        let err = registry
+            .lookup_or_insert(tag, Section::UniqueTags)
+            .unwrap_err();
+        assert!(
+            err.contains(&format!(
+                "`{}` only differs by case from `{}`",
+                tag, existing
+            )),
+            "{}",
+            err
+        );
+    }
+    let err = registry
+        .lookup_or_insert("::ORDER", Section::UniqueTypeTags)
+        .unwrap_err();
+    assert!(
+        err.contains("`::Order`, already in [unique_type_tags]"),
+        "{}",
+        err
+    );
+    assert_eq!(registry.contents(), before, "nothing is written");
+
+    // Existing keys, and keys of the other section, are accepted
+    assert!(registry
+        .lookup_or_insert("OrderCreated", Section::UniqueTags)
+        .is_ok());
+    assert!(registry
+        .lookup_or_insert("::ordercreated", Section::UniqueTypeTags)
+        .is_ok());
+}
+
+#[test]
+fn case_collisions_are_reported_under_the_warn_policy() {
+    let registry = case_policy("warn");
+    let resolved = registry
+        .get_uuid_from_tag("straße", Section::UniqueTags, &TagOptions::default())
+        .unwrap();
+    assert!(resolved.warnings[0].contains("`straße` only differs by case from `Straße`"));
+
+    // Both keys are kept as they are
+    let contents = registry.contents();
+    assert!(contents.contains("\"Straße\" = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\""));
+    assert!(contents.contains("\"straße\" = { crate = "));
+}
+
+#[test]
+fn case_collisions_are_accepted_by_default() {
+    for registry in [TempRegistry::new(CASED), case_policy("off")] {
+        let resolved = registry
+            .get_uuid_from_tag("ordercreated", Section::UniqueTags, &TagOptions::default())
+            .unwrap();
+        assert_eq!(resolved.warnings.len(), 1);
+        assert!(resolved.warnings[0].contains("minted `ordercreated`"));
+    }
+}
+
+/// Resolves the type `key` with the aliases `aliases`.
+fn resolve_aliased(
+    registry: &TempRegistry,
+    key: &str,
+    aliases: &[&str],
+) -> Result<unique_uuid_core::Resolved, String> {
+    let aliases: Vec<String> = aliases.iter().map(|alias| alias.to_string()).collect();
+    let options = TagOptions {
+        aliases: &aliases,
+        ..TagOptions::default()
+    };
+    registry.get_uuid_from_tag(key, Section::UniqueTypeTags, &options)
+}
+
+#[test]
+fn aliases_are_recorded_with_the_entry() {
+    let registry = TempRegistry::new(CANONICAL);
+    let resolved = resolve_aliased(&registry, "::Order", &["::OrderRecord"]).unwrap();
+    assert_eq!(
+        resolved.uuid.to_string(),
+        "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db"
+    );
+    assert!(registry.contents().contains(
+        "\"::Order\" = { aliases = [\"::OrderRecord\"], uuid = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\" }"
+    ));
+
+    // Aliases share the UUID of the entry, and are only added
+    let uuid = registry
+        .lookup_or_insert("::OrderRecord", Section::UniqueTypeTags)
+        .unwrap();
+    assert_eq!(uuid, resolved.uuid);
+    resolve_aliased(&registry, "::Order", &["::Purchase"]).unwrap();
+    let entries = registry.read().unwrap().unique_type_tags;
+    assert_eq!(
+        entries["::Order"]["aliases"],
+        toml::Value::from(vec!["::OrderRecord", "::Purchase"])
+    );
+
+    // New entries are minted with their aliases
+    let minted = resolve_aliased(&registry, "::Invoice", &["::Bill"]).unwrap();
+    let uuid = registry
+        .lookup_or_insert("::Bill", Section::UniqueTypeTags)
+        .unwrap();
+    assert_eq!(uuid, minted.uuid);
+}
+
+#[test]
+fn aliases_naming_another_entry_are_rejected() {
+    let registry = TempRegistry::new(&format!(
+        "{}\"::Invoice\" = {{ uuid = \"{}\", aliases = [\"::Bill\"] }}\n",
+        CANONICAL, PINNED
+    ));
+    let contents = registry.contents();
+    let err = resolve_aliased(&registry, "::Receipt", &["::Order"])
+        .err()
+        .unwrap();
+    assert!(
+        err.contains("already an entry of [unique_type_tags]"),
+        "{}",
+        err
+    );
+    let err = resolve_aliased(&registry, "::Receipt", &["::Bill"])
+        .err()
+        .unwrap();
+    assert!(err.contains("already an alias of `::Invoice`"), "{}", err);
+    let err = resolve_aliased(&registry, "::Receipt", &["::Receipt"])
+        .err()
+        .unwrap();
+    assert!(err.contains("cannot be an alias of itself"), "{}", err);
+    assert_eq!(registry.contents(), contents);
+
+    // Unless the entry is renamed to the key
+    let aliases = ["::Order".to_string()];
+    let options = TagOptions {
+        rename_from: Some("::Order"),
+        aliases: &aliases,
+        ..TagOptions::default()
+    };
+    let resolved = registry
+        .get_uuid_from_tag("::shop::Order", Section::UniqueTypeTags, &options)
+        .unwrap();
+    assert_eq!(
+        resolved.uuid.to_string(),
+        "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db"
+    );
+    let entries = registry.read().unwrap().unique_type_tags;
+    assert_eq!(
+        entries["::shop::Order"]["aliases"],
+        toml::Value::from(vec!["::Order"])
+    );
+}
+
+#[test]
+fn sources_are_recorded_when_minting_and_pinning() {
+    let registry = TempRegistry::new(&format!(
+        "{}\n[config]\npin_conflict = \"prefer-pinned\"\n",
+        CANONICAL
+    ));
+    let options = TagOptions {
+        source: Some("src/events.rs:42"),
+        ..TagOptions::default()
+    };
+    let minted = registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &options)
+        .unwrap();
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(
+        entries["orders.paid"]["source"].as_str(),
+        Some("src/events.rs:42")
+    );
+
+    // Existing entries keep their source, which never affects the UUID
+    let options = TagOptions {
+        source: Some("src/other.rs:7"),
+        ..TagOptions::default()
+    };
+    let resolved = registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &options)
+        .unwrap();
+    assert_eq!(resolved.uuid, minted.uuid);
+    registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(
+        entries["orders.paid"]["source"].as_str(),
+        Some("src/events.rs:42")
+    );
+    assert!(entries["orders.created"].get("source").is_none());
+
+    // Unless they are re-pinned explicitly
+    let options = TagOptions {
+        pinned: Some(PINNED.parse().unwrap()),
+        ..options
+    };
+    registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(
+        entries["orders.created"]["source"].as_str(),
+        Some("src/other.rs:7")
+    );
+}
+
+#[test]
+fn parents_are_recorded_with_the_entry() {
+    let registry = TempRegistry::new(CANONICAL);
+    let options = TagOptions {
+        parent: Some("orders"),
+        ..TagOptions::default()
+    };
+    let resolved = registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    assert_eq!(
+        resolved.uuid.to_string(),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+    registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &options)
+        .unwrap();
+
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(entries["orders.created"]["parent"].as_str(), Some("orders"));
+    assert_eq!(entries["orders.paid"]["parent"].as_str(), Some("orders"));
+    assert!(entries["orders.cancelled"].get("parent").is_none());
+}
+
+fn resolve_id(registry: &TempRegistry, key: &str, section: Section) -> Result<u32, String> {
+    let resolved = registry.get_uuid_from_tag(key, section, &TagOptions::default())?;
+    Ok(resolved.id.unwrap())
+}
+
+#[test]
+fn ids_are_allocated_monotonically() {
+    let registry = TempRegistry::new(&format!("{}\n[config]\nids = true\n", CANONICAL));
+
+    // Existing entries get an id when resolved, new ones when minted
+    assert_eq!(
+        resolve_id(&registry, "orders.created", Section::UniqueTags),
+        Ok(1)
+    );
+    assert_eq!(
+        resolve_id(&registry, "::Order", Section::UniqueTypeTags),
+        Ok(2)
+    );
+    assert_eq!(
+        resolve_id(&registry, "orders.paid", Section::UniqueTags),
+        Ok(3)
+    );
+    assert_eq!(
+        resolve_id(&registry, "orders.created", Section::UniqueTags),
+        Ok(1)
+    );
+    let file_structure = registry.read().unwrap();
+    assert_eq!(
+        file_structure.unique_tags["orders.created"]["id"].as_integer(),
+        Some(1)
+    );
+    assert_eq!(file_structure.other["ids"]["last"].as_integer(), Some(3));
+
+    // The ids of removed entries are never allocated again
+    let contents = registry
+        .contents()
+        .lines()
+        .filter(|line| !line.starts_with("\"orders.paid\""))
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(registry.handle().path(), contents).unwrap();
+    assert_eq!(
+        resolve_id(&registry, "orders.refunded", Section::UniqueTags),
+        Ok(4)
+    );
+    assert_eq!(
+        resolve_id(&registry, "orders.cancelled", Section::UniqueTags),
+        Ok(5)
+    );
+}
+
+#[test]
+fn duplicate_ids_are_rejected() {
+    let registry = TempRegistry::new(
+        "[unique_tags]\n\
+         \"orders.created\" = { uuid = \"2af1216e-7042-406a-a577-c39a7e7c3faa\", id = 1 }\n\n\
+         [unique_type_tags]\n\
+         \"::Order\" = { uuid = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\", id = 1 }\n\n\
+         [config]\nids = true\n",
+    );
+    let err = resolve_id(&registry, "orders.created", Section::UniqueTags).unwrap_err();
+    assert!(
+        err.contains("`orders.created` and `::Order` in [unique_type_tags] both have the id 1"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn ids_require_the_registry_file() {
+    let registry = TempRegistry::new(&format!("{}ids = true\n", JOURNALED));
+    let err = registry
+        .lookup_or_insert("orders.paid", Section::UniqueTags)
+        .unwrap_err();
+    assert!(
+        err.contains("`config.ids` cannot be combined with `journal`"),
+        "{}",
+        err
+    );
+}
+
+fn code(code: u32) -> Vec<(String, MetaValue)> {
+    vec![("code".to_string(), MetaValue::Integer(code))]
+}
+
+#[test]
+fn meta_values_are_updated_and_kept() {
+    let registry = TempRegistry::new(CANONICAL);
+    let meta = code(7);
+    let options = TagOptions {
+        meta: &meta,
+        ..TagOptions::default()
+    };
+    let resolved = registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    assert_eq!(resolved.meta, meta);
+    registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &options)
+        .unwrap();
+
+    // Changing a value rewrites the entry, keeping its UUID
+    let meta = code(8);
+    let options = TagOptions {
+        meta: &meta,
+        ..TagOptions::default()
+    };
+    let resolved = registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    assert_eq!(
+        resolved.uuid.to_string(),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+    assert_eq!(resolved.meta, meta);
+
+    // Without `meta(...)`, the recorded values are left untouched
+    let contents = registry.contents();
+    let resolved = registry
+        .get_uuid_from_tag(
+            "orders.created",
+            Section::UniqueTags,
+            &TagOptions::default(),
+        )
+        .unwrap();
+    assert_eq!(resolved.meta, meta);
+    assert_eq!(registry.contents(), contents);
+
+    let entries = registry.read().unwrap().unique_tags;
+    assert_eq!(
+        entries["orders.created"]["meta"]["code"].as_integer(),
+        Some(8)
+    );
+    assert_eq!(entries["orders.paid"]["meta"]["code"].as_integer(), Some(7));
+}
+
+#[test]
+fn groups_are_added_and_kept() {
+    let registry = TempRegistry::new(CANONICAL);
+    let groups = ["external".to_string(), "billing".to_string()];
+    let options = TagOptions {
+        groups: &groups,
+        ..TagOptions::default()
+    };
+    registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &options)
+        .unwrap();
+
+    // Other groups are added to the recorded ones, and the array stays sorted
+    let groups = ["retention".to_string()];
+    let options = TagOptions {
+        groups: &groups,
+        ..TagOptions::default()
+    };
+    let resolved = registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    assert_eq!(
+        resolved.uuid.to_string(),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+    let contents = registry.contents();
+    registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    assert_eq!(registry.contents(), contents);
+
+    let entries = registry.read().unwrap().unique_tags;
+    let groups_of = |key: &str| -> Vec<String> {
+        entries[key]["groups"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|group| group.as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(
+        groups_of("orders.created"),
+        ["billing", "external", "retention"]
+    );
+    assert_eq!(groups_of("orders.paid"), ["billing", "external"]);
+}
+
+#[test]
+fn new_groups_follow_the_naming_policy() {
+    let registry = TempRegistry::new(&format!(
+        "{}
+[config]
+tag_pattern = '^[a-z_]+(\\.[a-z_]+)*$'
+",
+        CANONICAL
+    ));
+    let groups = ["ExternalFacing".to_string()];
+    let options = TagOptions {
+        groups: &groups,
+        ..TagOptions::default()
+    };
+    let Err(err) = registry.get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+    else {
+        panic!("the group is accepted");
+    };
+    assert!(
+        err.contains("The group `ExternalFacing` does not match"),
+        "{}",
+        err
+    );
+    assert_eq!(
+        registry.contents(),
+        format!(
+            "{}
+[config]
+tag_pattern = '^[a-z_]+(\\.[a-z_]+)*$'
+",
+            CANONICAL
+        )
+    );
+
+    // Groups already in the registry are accepted
+    let registry = TempRegistry::new(
+        "[unique_tags]
+         \"orders.created\" = { groups = [\"ExternalFacing\"], uuid = \"2af1216e-7042-406a-a577-c39a7e7c3faa\" }
+
+         [config]
+         tag_style = \"snake_case\"
+         strict = true
+",
+    );
+    registry
+        .get_uuid_from_tag("orders.paid", Section::UniqueTags, &options)
+        .unwrap();
+    let groups = ["ExternalFacing".to_string(), "PiiData".to_string()];
+    let options = TagOptions {
+        groups: &groups,
+        ..TagOptions::default()
+    };
+    let Err(err) = registry.get_uuid_from_tag("orders.paid", Section::UniqueTags, &options) else {
+        panic!("the group is accepted");
+    };
+    assert!(err.contains("consider `pii_data`"), "{}", err);
+}
+
+#[test]
+fn unique_meta_values_are_rejected_twice() {
+    let registry = TempRegistry::new(&format!(
+        "{}\n[config]\nunique_meta = [\"code\"]\n",
+        CANONICAL
+    ));
+    let meta = code(7);
+    let options = TagOptions {
+        meta: &meta,
+        ..TagOptions::default()
+    };
+    registry
+        .get_uuid_from_tag("orders.created", Section::UniqueTags, &options)
+        .unwrap();
+    let Err(err) = registry.get_uuid_from_tag("::Order", Section::UniqueTypeTags, &options) else {
+        panic!("`::Order` was given the `code` of `orders.created`");
+    };
+    assert!(err.contains("`orders.created` in [unique_tags]"), "{}", err);
+    assert!(registry.read().unwrap().unique_type_tags["::Order"]
+        .get("meta")
+        .is_none());
+}
+
+#[test]
+fn reserved_entries_cannot_be_claimed() {
+    const RESERVED: &str = "[unique_tags]\n\
+                            \"handshake.v2\" = { owner = \"protocol-team\", reserved = true, uuid = \"ce4baeef-bd9c-4932-88be-24eb1033f695\" }\n\
+                            \"handshake.v3\" = { aliases = [\"handshake.next\"], reserved = true, uuid = \"8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4\" }\n";
+    let registry = TempRegistry::new(RESERVED);
+
+    let err = registry
+        .lookup_or_insert("handshake.v2", Section::UniqueTags)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "`handshake.v2` in [unique_tags] is reserved for future use, and cannot be used by the \
+         macros. Contact `protocol-team`, the owner of the reservation, to lift it by removing \
+         `reserved = true` from the entry, e.g. with `unique-uuid-admin unreserve handshake.v2`."
+    );
+    let err = registry
+        .lookup_or_insert("handshake.next", Section::UniqueTags)
+        .unwrap_err();
+    assert!(
+        err.starts_with(
+            "`handshake.next`, an alias of `handshake.v3`, in [unique_tags] is reserved"
+        ),
+        "{}",
+        err
+    );
+    assert!(err.contains("Lift the reservation by removing"), "{}", err);
+
+    // Moving a reserved entry to a new key would claim it as well
+    let options = TagOptions {
+        rename_from: Some("handshake.v2"),
+        ..TagOptions::default()
+    };
+    assert!(registry
+        .get_uuid_from_tag("handshake.final", Section::UniqueTags, &options)
+        .is_err());
+    assert_eq!(registry.contents(), RESERVED);
+
+    // Once lifted, the pre-allocated UUID is used
+    std::fs::write(
+        registry.handle().path(),
+        RESERVED.replace("owner = \"protocol-team\", reserved = true, ", ""),
+    )
+    .unwrap();
+    let uuid = registry
+        .lookup_or_insert("handshake.v2", Section::UniqueTags)
+        .unwrap();
+    assert_eq!(uuid.to_string(), "ce4baeef-bd9c-4932-88be-24eb1033f695");
+}
+
+#[cfg(windows)]
+#[test]
+fn registries_held_without_sharing_are_waited_for() {
+    use std::{fs::OpenOptions, os::windows::fs::OpenOptionsExt, sync::mpsc, time::Duration};
+
+    let registry = TempRegistry::new(CANONICAL);
+    let path = registry.handle().path();
+    let (opened, wait) = mpsc::channel();
+    let holder = thread::spawn(move || {
+        // An editor or another compilation, sharing nothing
+        let file = OpenOptions::new()
+            .read(true)
+            .share_mode(0)
+            .open(path)
+            .unwrap();
+        opened.send(()).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        drop(file);
+    });
+    wait.recv().unwrap();
+
+    let uuid = registry
+        .lookup_or_insert("orders.created", Section::UniqueTags)
+        .unwrap();
+    assert_eq!(uuid.to_string(), "2af1216e-7042-406a-a577-c39a7e7c3faa");
+    holder.join().unwrap();
+}
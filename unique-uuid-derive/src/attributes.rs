@@ -0,0 +1,115 @@
+//! Parsing of the `#[unique_type_tag(...)]` helper attribute of the derive.
+use syn::{Attribute, Lit, LitStr, Path};
+use unique_uuid_core::MetaValue;
+
+use crate::{input, meta};
+
+/// The options given to the derive through `#[unique_type_tag(...)]` attributes.
+#[derive(Default)]
+pub(crate) struct TypeTagAttributes {
+    /// `rename = "..."`: the previous key of the type.
+    pub(crate) rename: Option<LitStr>,
+
+    /// `uuid = "..."`: the UUID the type is pinned to.
+    pub(crate) uuid: Option<LitStr>,
+
+    /// `export_c = "..."`: the symbol of an `extern "C"` accessor for the tag.
+    pub(crate) export_c: Option<LitStr>,
+
+    /// `compose`: combine the tag of the type with the tags of its type parameters.
+    pub(crate) compose: Option<Path>,
+
+    /// `bevy`: also implement `bevy_reflect::TypeUuid`.
+    pub(crate) bevy: Option<Path>,
+
+    /// `transparent`: use the tag of the single field of the type.
+    pub(crate) transparent: Option<Path>,
+
+    /// `selftest`: generate a test checking the tag of the type against the registry.
+    pub(crate) selftest: Option<Path>,
+
+    /// `factory`: register a constructor of the type, building its `Default` value.
+    pub(crate) factory: Option<Path>,
+
+    /// `alias = "..."`, repeatable: alternative keys of the type, sharing its UUID.
+    pub(crate) aliases: Vec<LitStr>,
+
+    /// `meta(...)`: the values to record in the `meta` table of the entry.
+    pub(crate) meta: Vec<(String, MetaValue)>,
+
+    /// `groups(...)`: the groups to add to the entry.
+    pub(crate) groups: Option<Vec<String>>,
+}
+
+impl TypeTagAttributes {
+    /// Collects the options of every `#[unique_type_tag(...)]` attribute in `attrs`.
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs
+            .iter()
+            .filter(|a| a.path().is_ident("unique_type_tag"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let flag = if meta.path.is_ident("compose") {
+                    Some(&mut options.compose)
+                } else if meta.path.is_ident("bevy") {
+                    Some(&mut options.bevy)
+                } else if meta.path.is_ident("transparent") {
+                    Some(&mut options.transparent)
+                } else if meta.path.is_ident("selftest") {
+                    Some(&mut options.selftest)
+                } else if meta.path.is_ident("factory") {
+                    Some(&mut options.factory)
+                } else {
+                    None
+                };
+                if let Some(flag) = flag {
+                    if flag.is_some() {
+                        return Err(meta.error("duplicate `unique_type_tag` option"));
+                    }
+                    *flag = Some(meta.path);
+                    return Ok(());
+                }
+                if meta.path.is_ident("alias") {
+                    let alias: LitStr = meta.value()?.parse()?;
+                    if options.aliases.iter().any(|a| a.value() == alias.value()) {
+                        return Err(syn::Error::new(alias.span(), "duplicate alias"));
+                    }
+                    options.aliases.push(alias);
+                    return Ok(());
+                }
+                if meta.path.is_ident("meta") {
+                    return meta.parse_nested_meta(|item| {
+                        let name = item.path.require_ident()?;
+                        let value: Lit = item.value()?.parse()?;
+                        meta::push(&mut options.meta, name, &value)
+                    });
+                }
+                if meta.path.is_ident("groups") {
+                    if options.groups.is_some() {
+                        return Err(meta.error("duplicate `unique_type_tag` option"));
+                    }
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    options.groups = Some(input::parse_groups(&content)?);
+                    return Ok(());
+                }
+                let slot = if meta.path.is_ident("rename") {
+                    &mut options.rename
+                } else if meta.path.is_ident("uuid") {
+                    &mut options.uuid
+                } else if meta.path.is_ident("export_c") {
+                    &mut options.export_c
+                } else {
+                    return Err(meta.error("unknown `unique_type_tag` option"));
+                };
+                if slot.is_some() {
+                    return Err(meta.error("duplicate `unique_type_tag` option"));
+                }
+                *slot = Some(meta.value()?.parse()?);
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+}
@@ -0,0 +1,36 @@
+//! Generation of `bevy_reflect::TypeUuid` implementations for type tags.
+use proc_macro2::TokenStream;
+use syn::{Generics, Ident, Path};
+
+/// Returns an implementation of `bevy_reflect::TypeUuid` for `ident`, whose `TYPE_UUID`
+/// is the UUID of its `TYPE_TAG`.
+///
+/// # Errors
+/// Fails if the `bevy` feature of `unique-uuid` is disabled, `flag` being the option
+/// requesting the implementation.
+#[cfg(feature = "bevy")]
+pub(crate) fn type_uuid(
+    _flag: &Path,
+    ident: &Ident,
+    generics: &Generics,
+) -> syn::Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote::quote! {
+        impl #impl_generics unique_uuid::bevy_reflect::TypeUuid for #ident #ty_generics #where_clause {
+            const TYPE_UUID: unique_uuid::uuid::Uuid =
+                <Self as unique_uuid::UniqueTypeTag>::TYPE_TAG.0;
+        }
+    })
+}
+
+#[cfg(not(feature = "bevy"))]
+pub(crate) fn type_uuid(
+    flag: &Path,
+    _ident: &Ident,
+    _generics: &Generics,
+) -> syn::Result<TokenStream> {
+    Err(syn::Error::new_spanned(
+        flag,
+        "`bevy` requires the `bevy` feature of `unique-uuid`",
+    ))
+}
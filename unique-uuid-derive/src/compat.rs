@@ -0,0 +1,41 @@
+//! The guard checking that `unique-uuid` supports the expansions of this crate, see
+//! `unique_uuid::__compat`.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::LitInt;
+
+/// The version of the interface with `unique-uuid` the expansions are written for.
+///
+/// Bump it, and add the marker of the new version to `unique_uuid::__compat`, whenever
+/// the expansions start relying on items that earlier releases of `unique-uuid` lack.
+pub(crate) const INTERFACE: u32 = 1;
+
+/// Returns the guard of an expansion, failing the build with a message naming both
+/// releases if `unique-uuid` does not support version `interface` of the interface.
+pub(crate) fn guard(interface: u32) -> TokenStream {
+    let marker = format_ident!("V{}", interface);
+    let version = env!("CARGO_PKG_VERSION");
+    quote! {
+        const _: () = {
+            // Shadowed by the marker of `unique-uuid`, if it supports the interface
+            #[allow(non_snake_case, dead_code)]
+            const fn #marker() {
+                unique_uuid::__compat::incompatible(#version, #interface)
+            }
+            {
+                #[allow(unused_imports)]
+                use unique_uuid::__compat::*;
+                #marker()
+            }
+        };
+    }
+}
+
+/// Parses the input of `__compat_guard!`: the version of the interface to check, or
+/// nothing for [`INTERFACE`].
+pub(crate) fn parse(input: proc_macro::TokenStream) -> syn::Result<u32> {
+    if input.is_empty() {
+        return Ok(INTERFACE);
+    }
+    syn::parse::<LitInt>(input)?.base10_parse()
+}
@@ -0,0 +1,42 @@
+//! Combined tags of generic types, for `#[unique_type_tag(compose)]`.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, GenericParam, Generics, Path};
+
+/// Returns the expression of the tag of a generic type, combining its `base` tag with
+/// the tag of each type parameter, and bounds every type parameter of `generics` by
+/// `UniqueTypeTag`.
+pub(crate) fn type_tag(
+    compose: &Path,
+    generics: &mut Generics,
+    base: TokenStream,
+) -> syn::Result<TokenStream> {
+    if let Some(param) = generics.const_params().next() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "`compose` does not support const parameters",
+        ));
+    }
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+    if params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            compose,
+            "`compose` requires at least one type parameter",
+        ));
+    }
+
+    for param in generics.params.iter_mut() {
+        if let GenericParam::Type(param) = param {
+            param.bounds.push(parse_quote!(unique_uuid::UniqueTypeTag));
+        }
+    }
+    Ok(quote! {
+        unique_uuid::UniqueTag::combine_all(&[
+            #base,
+            #(<#params as unique_uuid::UniqueTypeTag>::TYPE_TAG,)*
+        ])
+    })
+}
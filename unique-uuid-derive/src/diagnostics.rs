@@ -0,0 +1,18 @@
+//! Compile-time diagnostics that do not abort the build.
+use proc_macro2::{Span, TokenStream};
+
+/// Returns tokens that make the compiler emit `message` as a warning at `span`.
+///
+/// Stable Rust offers no API for proc-macro warnings, so this relies on the
+/// deprecation lint: a deprecated item carrying the message is declared and used.
+pub(crate) fn warning(message: &str, span: Span) -> TokenStream {
+    let name = syn::Ident::new("unique_uuid_warning", span);
+    quote::quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_upper_case_globals)]
+            const #name: () = ();
+            #name
+        };
+    }
+}
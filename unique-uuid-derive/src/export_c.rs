@@ -0,0 +1,59 @@
+//! Generation of `extern "C"` accessors for type tags.
+use std::{collections::HashMap, sync::Mutex};
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Ident, LitStr};
+
+/// The symbols exported by this compilation, with the key of the type exporting each.
+static EXPORTED: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Returns an `extern "C"` function named `symbol` writing the tag of `ident`.
+///
+/// # Errors
+/// Fails if `symbol` is not a valid C identifier, or if another type of the
+/// compilation already exports it.
+pub(crate) fn accessor(symbol: &LitStr, ident: &Ident, key: &str) -> syn::Result<TokenStream> {
+    let name = symbol.value();
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(syn::Error::new(
+            symbol.span(),
+            format!("`{}` is not a valid C identifier", name),
+        ));
+    }
+
+    let mut exported = EXPORTED.lock().unwrap_or_else(|err| err.into_inner());
+    let owner = exported
+        .get_or_insert_with(HashMap::new)
+        .entry(name.clone())
+        .or_insert_with(|| key.to_string());
+    if owner != key {
+        return Err(syn::Error::new(
+            symbol.span(),
+            format!("the symbol `{}` is already exported by `{}`", name, owner),
+        ));
+    }
+
+    let function = format_ident!("{}", name, span = symbol.span());
+    let doc = format!(
+        " Writes the 16 bytes of the tag of [`{}`], in big-endian order, to `out`.",
+        ident
+    );
+    Ok(quote! {
+        #[doc = #doc]
+        ///
+        /// Does nothing if `out` is null.
+        ///
+        /// # Safety
+        /// `out` must be null or valid for writes of 16 bytes.
+        #[unsafe(no_mangle)]
+        pub unsafe extern "C" fn #function(out: *mut u8) {
+            if !out.is_null() {
+                let bytes = <#ident as unique_uuid::UniqueTypeTag>::TYPE_TAG.0.into_bytes();
+                unsafe { ::core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len()) };
+            }
+        }
+    })
+}
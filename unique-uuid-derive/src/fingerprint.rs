@@ -0,0 +1,75 @@
+//! Generation of the fingerprint of the registry.
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitByteStr, LitStr, Token,
+};
+
+use unique_uuid_core::{RegistryFingerprint, RegistryHandle};
+
+use crate::diagnostics;
+
+/// The input of `registry_fingerprint!`: nothing, or `namespace = "<namespace>"`.
+pub(crate) struct Fingerprint {
+    namespace: Option<LitStr>,
+}
+
+impl Parse for Fingerprint {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(Self { namespace: None });
+        }
+        let option: Ident = input.parse()?;
+        if option != "namespace" {
+            return Err(syn::Error::new_spanned(
+                option,
+                "expected `namespace = \"…\"`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let namespace = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self {
+            namespace: Some(namespace),
+        })
+    }
+}
+
+impl Fingerprint {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let span = self
+            .namespace
+            .as_ref()
+            .map_or_else(Span::call_site, LitStr::span);
+        let namespace = self.namespace.as_ref().map(LitStr::value);
+        let registry = RegistryHandle::from_env();
+        let RegistryFingerprint { name, warnings } = registry
+            .fingerprint(namespace.as_deref())
+            .map_err(|err| syn::Error::new(span, err))?;
+        let warnings = warnings
+            .iter()
+            .map(|warning| diagnostics::warning(warning, span));
+        let name = LitByteStr::new(name.as_bytes(), span);
+
+        // Reading the registry through `include_bytes!` makes the compiler re-expand the
+        // macro whenever the registry changes, a missing or in-memory registry has no file
+        let path = registry.path();
+        let dependency = (!registry.is_in_memory() && path.is_file()).then(|| {
+            let path = path.to_string_lossy().into_owned();
+            quote!(
+                const _: &[u8] = include_bytes!(#path);
+            )
+        });
+
+        Ok(quote! {
+            /// The fingerprint of the registry this crate was built against, to compare
+            /// with the one of a peer through `unique_uuid::check_fingerprint`.
+            pub const REGISTRY_FINGERPRINT: unique_uuid::UniqueTag =
+                unique_uuid::UniqueTag::new_v5(unique_uuid::fingerprint::NAMESPACE, #name);
+
+            #dependency
+            #(#warnings)*
+        })
+    }
+}
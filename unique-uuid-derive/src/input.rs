@@ -0,0 +1,205 @@
+//! Parsing of the arguments of `unique_tag!`.
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Ident, LitStr, Token,
+};
+use unique_uuid_core::{MetaValue, RegistryHandle, Resolved, Section, TagOptions};
+
+use crate::{location, meta};
+
+/// The input of `unique_tag!`: `"<tag>"`, optionally preceded by `test,` and
+/// `parent = "<parent>",` and followed by `, uuid = "<uuid>"`,
+/// `, meta(<name> = <value>, ...)`, `, groups = ["<group>", ...]` and a comma.
+///
+/// Errors are spanned on the token that failed to parse, so that they point at the
+/// offending argument rather than at the whole invocation.
+pub(crate) struct TagInput {
+    /// `test`: the tag belongs to the test registry.
+    pub(crate) test: bool,
+
+    /// `parent = "..."`: the key of the parent of the tag.
+    pub(crate) parent: Option<LitStr>,
+
+    /// The key of the tag, relative to its parent if any.
+    pub(crate) tag: LitStr,
+
+    /// `uuid = "..."`: the UUID the tag is pinned to.
+    pub(crate) uuid: Option<LitStr>,
+
+    /// `meta(...)`: the values to record in the `meta` table of the entry.
+    pub(crate) meta: Vec<(String, MetaValue)>,
+
+    /// `groups = [...]`: the groups to add to the entry.
+    pub(crate) groups: Vec<String>,
+}
+
+impl Parse for TagInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(input.error("expected a string literal naming the tag"));
+        }
+        let mut test = false;
+        let mut parent = None;
+        while input.peek(Ident) && (input.peek2(Token![=]) || input.peek2(Token![,])) {
+            let name: Ident = input.parse()?;
+            if name == "test" && !test && parent.is_none() {
+                test = true;
+            } else if name == "parent" && parent.is_none() {
+                input.parse::<Token![=]>()?;
+                parent = Some(input.parse::<LitStr>()?);
+            } else if name == "test" || name == "parent" {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    format!("`{}` must be given once before the tag, `test` first", name),
+                ));
+            } else {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    format!(
+                        "unknown `unique_tag!` parameter `{}` before the tag, expected `test` \
+                         or `parent`",
+                        name
+                    ),
+                ));
+            }
+            input.parse::<Token![,]>()?;
+        }
+        let tag = if input.peek(LitStr) {
+            input.parse()?
+        } else {
+            let token: proc_macro2::TokenTree = input.parse()?;
+            return Err(syn::Error::new_spanned(
+                token,
+                "expected a string literal naming the tag",
+            ));
+        };
+
+        let mut uuid = None;
+        let mut meta = None;
+        let mut groups = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let name: Ident = input.parse()?;
+            if name == "meta" {
+                if meta.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &name,
+                        "duplicate `unique_tag!` parameter `meta`",
+                    ));
+                }
+                let content;
+                syn::parenthesized!(content in input);
+                meta = Some(meta::parse(&content)?);
+                continue;
+            }
+            if name == "groups" {
+                if groups.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &name,
+                        "duplicate `unique_tag!` parameter `groups`",
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                let content;
+                syn::bracketed!(content in input);
+                groups = Some(parse_groups(&content)?);
+                continue;
+            }
+            if name != "uuid" {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    format!("unknown `unique_tag!` parameter `{}`", name),
+                ));
+            }
+            if uuid.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    "duplicate `unique_tag!` parameter `uuid`",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            uuid = Some(input.parse()?);
+        }
+        Ok(Self {
+            test,
+            parent,
+            tag,
+            uuid,
+            meta: meta.unwrap_or_default(),
+            groups: groups.unwrap_or_default(),
+        })
+    }
+}
+
+impl TagInput {
+    /// Returns the registry the tag belongs to: the test registry of the registry of the
+    /// macros with `test`, or that registry.
+    pub(crate) fn registry(&self) -> RegistryHandle {
+        let registry = RegistryHandle::from_env();
+        if self.test {
+            registry.test_registry()
+        } else {
+            registry
+        }
+    }
+
+    /// Resolves the tag against its registry, minting its entry if needed. Errors are
+    /// spanned on the tag.
+    pub(crate) fn resolve(&self) -> syn::Result<Resolved> {
+        let pinned = self.uuid.as_ref().map(pinned_uuid).transpose()?;
+        let source = location::source(self.tag.span());
+        let parent = self.parent.as_ref().map(LitStr::value);
+        let options = TagOptions {
+            pinned,
+            parent: parent.as_deref(),
+            meta: &self.meta,
+            groups: &self.groups,
+            source: source.as_deref(),
+            ..TagOptions::default()
+        };
+        self.registry()
+            .get_uuid_from_tag(&self.key(), Section::UniqueTags, &options)
+            .map_err(|err| syn::Error::new(self.tag.span(), err))
+    }
+
+    /// Returns the key of the tag: `<parent>.<tag>` if it has a parent.
+    pub(crate) fn key(&self) -> String {
+        match &self.parent {
+            Some(parent) => format!("{}.{}", parent.value(), self.tag.value()),
+            None => self.tag.value(),
+        }
+    }
+}
+
+/// Parses a comma-separated list of group names, rejecting empty and duplicate names.
+pub(crate) fn parse_groups(input: ParseStream) -> syn::Result<Vec<String>> {
+    let mut groups: Vec<String> = Vec::new();
+    for group in Punctuated::<LitStr, Token![,]>::parse_terminated(input)? {
+        let name = group.value();
+        if name.is_empty() {
+            return Err(syn::Error::new_spanned(
+                group,
+                "group names cannot be empty",
+            ));
+        }
+        if groups.contains(&name) {
+            return Err(syn::Error::new_spanned(group, "duplicate group"));
+        }
+        groups.push(name);
+    }
+    Ok(groups)
+}
+
+/// Parses the UUID a key is pinned to, spanning the error on the literal.
+pub(crate) fn pinned_uuid(uuid: &LitStr) -> syn::Result<uuid::Uuid> {
+    uuid::Uuid::parse_str(&uuid.value()).map_err(|err| {
+        syn::Error::new_spanned(
+            uuid,
+            format!("`{}` is not a valid UUID ({})", uuid.value(), err),
+        )
+    })
+}
@@ -28,33 +28,234 @@
 //! The crate maintains a TOML file with the following structure:
 //! ```toml
 //! [unique_tags]
-//! "tag_name" = "uuid"
+//! "tag_name" = { crate = "my-crate", created = "2024-06-01T12:00:00Z", uuid = "uuid" }
 //!
 //! [unique_type_tags]
 //! "type_name" = "uuid"
 //! ```
 //!
+//! New entries record the crate that minted them and when. Entries in the plain
+//! `"key" = "uuid"` form, written by earlier versions, are read as-is and never
+//! rewritten.
+//!
+//! A registry saved with a leading byte order mark or `\r\n` line endings, as some
+//! Windows editors do, is read transparently and written back with the same layout.
+//!
+//! # Registry Location and Format
+//! The registry is `types.toml`, resolved against the directory the compiler runs in.
+//! The `UNIQUE_UUID_REGISTRY` environment variable overrides its path, which may start
+//! with `${workspace_root}`. A path ending with `.json` stores the same document as JSON
+//! (`{"unique_tags": {...}, "unique_type_tags": {...}}`), pretty-printed with sorted
+//! keys. With the `ron` feature of `unique-uuid`, a path ending with `.ron` stores it as
+//! RON, and with its `yaml` feature, a path ending with `.yaml` or `.yml` stores it as
+//! block-style YAML. The journal, the cache and every setting work the same for
+//! every format (see `unique_uuid::registry::Format`).
+//!
+//! A crate setting `registry = "cargo-metadata"` in the
+//! `[package.metadata.unique-uuid.config]` table of its `Cargo.toml` keeps the registry
+//! there instead, in `[package.metadata.unique-uuid.unique_tags]` and
+//! `[package.metadata.unique-uuid.unique_type_tags]`. The manifest is edited in place,
+//! touching only the entries that change, and replaced atomically. A `Cargo.toml` given
+//! as `UNIQUE_UUID_REGISTRY` is used the same way, and rejected if it is a virtual
+//! workspace manifest.
+//!
+//! A `UNIQUE_UUID_REGISTRY` ending with `.d`, e.g. `${workspace_root}/types.d`, is a
+//! registry directory holding one TOML file per crate, named after its package, e.g.
+//! `types.d/billing.toml`. The macros of a crate only write its own file, which is the
+//! registry file for every other purpose, and look up the entries of the other files
+//! too. A key stored in two files fails the expansion, naming both files.
+//! `unique-uuid-admin flatten` merges the directory into a single registry file.
+//!
+//! With the `UNIQUE_UUID_IN_MEMORY` environment variable set, e.g. to `1`, the registry
+//! is kept in the memory of the compiler instead, for throwaway builds such as
+//! `cargo check` in an ephemeral container: it overrides every configured path, and no
+//! file is ever read or written. A key gets the same UUID for as long as the compiler
+//! process lives, but **the UUIDs differ between builds**, so that nothing built this
+//! way may be shipped or persist its tags. The policies of the `[config]` table do not
+//! apply, and `selftest` generates no test.
+//!
+//! # New Entries
+//! Every entry minted by the macros is reported as a warning naming its key, its UUID
+//! and the registry, with every entry minted so far by the build, e.g. "unique-uuid:
+//! minted `orders.paid` = …, 2 new entries in `/path/types.toml` (::Invoice,
+//! orders.paid)", so that the registry is committed along with the code using them. With
+//! the `UNIQUE_UUID_DENY_NEW` environment variable set, e.g. in CI, a key missing from
+//! the registry fails the expansion instead, and nothing is written.
+//!
+//! # Malformed Entries
+//! An entry whose UUID cannot be parsed fails the expansion, with an error naming its
+//! section, its key and the offending value. When the `UNIQUE_UUID_LENIENT` environment
+//! variable is set, malformed entries are instead reported as warnings and ignored,
+//! except for the entry the macro resolves, which still fails. Malformed entries are
+//! never rewritten.
+//!
+//! A registry that is not valid UTF-8, e.g. after a bad merge, fails the expansion with
+//! the offset and a hex dump of the first invalid bytes. If a `types.toml.bak` copy next
+//! to it is a valid registry, the error suggests restoring it.
+//!
+//! # Configuration
+//! The registry may hold a `[config]` table, read by the macros:
+//! ```toml
+//! [config]
+//! tag_style = "snake_case"
+//! strict = true
+//! ```
+//! - `tag_style` (`"snake_case"`, `"kebab-case"` or `"none"`, the default): the naming
+//!   convention of the segments of new tags, i.e. the parts of their keys separated by
+//!   `.` or `::`. A new tag breaking it gets a warning suggesting the conforming key.
+//!   Existing entries are never checked.
+//! - `tag_pattern`: a regular expression every new `unique_tag!` key must match, e.g.
+//!   `'^[a-z0-9_]+(\.[a-z0-9_]+)*$'` (a TOML literal string, so that backslashes are
+//!   kept). It is matched as written, so anchor it with `^` and `$`. A new tag that does
+//!   not match fails the expansion with an error quoting the pattern; existing entries
+//!   are accepted, and only reported by `Registry::validate`. Type tags are not checked,
+//!   their keys being derived from identifiers. An invalid expression fails every
+//!   expansion, with the error of the regular expression parser.
+//! - `strict` (`false` by default): turns these warnings into errors, so that the tag is
+//!   not minted.
+//! - `journal` (`false` by default): appends new entries to `types.toml.journal`, one
+//!   line per entry, instead of writing the registry file. Appends do not wait for each
+//!   other, which avoids contention between parallel builds; the journal is read along
+//!   with the registry file, whose entries take precedence.
+//! - `journal_limit` (`256` by default): the number of journal lines past which the
+//!   journal is compacted into the registry file. `unique-uuid-admin compact` compacts
+//!   it on demand.
+//! - `fragments` (`false` by default): writes each new entry to a file of its own in
+//!   `types.toml.d/pending/` instead of waiting for the lock of the registry file. The
+//!   pending fragments are read along with the registry file, whose entries take
+//!   precedence. Concurrent builds minting the same key converge on the fragment that
+//!   claimed it first, by linking it into `types.toml.d/claims/`. The first build to
+//!   find the lock free merges them into the registry file, as does
+//!   `unique-uuid-admin compact`. Cannot be combined with `journal`.
+//! - `cache` (`false` by default): keeps the parsed registry in `types.toml.cache`, a
+//!   binary sidecar that decodes several times faster than the TOML file is parsed. The
+//!   cache is only used while the size, modification time and hash of the registry file
+//!   match the ones it was built from, and is rebuilt otherwise; it is never the source
+//!   of truth, can be deleted at any time, and is ignored if it is corrupted. It should
+//!   not be committed.
+//! - `pin_conflict` (`"error"` by default): what to do when a key is pinned to a UUID
+//!   with `uuid = "..."`, but the registry assigns it another one. `"error"` fails the
+//!   expansion, `"prefer-pinned"` rewrites the entry with the pinned UUID, and
+//!   `"prefer-registry"` keeps the UUID of the registry with a warning. The diagnostics
+//!   name both UUIDs. Pinning a UUID that the registry assigns to another key fails under
+//!   every policy, naming both keys.
+//! - `case_insensitive_keys` (`"off"` by default): whether a new key only differing by
+//!   case from an existing key of its section, e.g. `ordercreated` next to
+//!   `OrderCreated`, gets a warning (`"warn"`) or fails the expansion (`"error"`), naming
+//!   both keys. Keys are compared under Unicode case folding, so `straße` and `STRASSE`
+//!   collide, and are never rewritten. With `"error"`, `unique_uuid::registry::Registry`
+//!   also refuses to load a registry already holding such keys.
+//! - `frozen_sections` and `frozen_namespaces`: arrays of sections (`"unique_tags"`,
+//!   `"unique_type_tags"`) and namespaces (e.g. `"payments"`) the macros must not add
+//!   entries to, e.g. because new tags go through a review. A new key in one of them
+//!   fails the expansion, naming the key, the section and the setting; existing entries
+//!   resolve as usual, and the other sections and namespaces stay writable.
+//! - `tags_file` and `type_tags_file`: store `[unique_tags]` or `[unique_type_tags]` in
+//!   a file of their own, e.g. `tags_file = "protocol/tags.toml"`, relative to the
+//!   directory of the registry file and in the format of its extension. A section file
+//!   holds only its section and is locked while an entry is added to it, independently
+//!   of the other files, so that teams owning different sections do not contend. The
+//!   registry file must not hold entries of a section stored elsewhere, and UUIDs are
+//!   unique across all the files.
+//! - `prefixes`: a table reserving the leading bytes of the UUIDs of each crate, 1 to 4
+//!   bytes in hexadecimal, e.g. `billing = "2af1"` under `[config.prefixes]`. The UUIDs
+//!   minted while compiling `billing` then start with `2af1`, so their origin is visible
+//!   at a glance; crates without a prefix mint fully random UUIDs. Each byte of prefix
+//!   leaves 8 fewer random bits out of the 122 of a v4 UUID, so 4 bytes still leave 90.
+//!   Prefixes that overlap, e.g. `2a` and `2af1`, are rejected when the registry is
+//!   read, and `Registry::validate` reports the entries whose UUID does not start with
+//!   the prefix of their `crate`.
+//! - `ids` (`false` by default): gives every entry the macros resolve a small integer
+//!   `id` alongside its UUID, one above the last id ever allocated, which is recorded as
+//!   `last` in a top-level `[ids]` table so that the ids of removed entries are never
+//!   reused. Ids are unique across both sections: an id used twice fails the expansion,
+//!   naming both keys. The derive then implements `unique_uuid::UniqueTypeId`, and
+//!   [`unique_tag_id!`] expands to the id of a tag. Ids are allocated in the registry
+//!   file, so `ids` cannot be combined with `journal`, `fragments`, `tags_file` or
+//!   `type_tags_file`.
+//! - `unique_meta`: an array of `meta` names whose values must differ between entries,
+//!   e.g. `unique_meta = ["code"]` for wire codes. Setting a value another entry already
+//!   has fails the expansion, naming both keys, and `Registry::validate` reports
+//!   registries breaking the rule.
+//!
 //! # Implementation Details
 //! - UUIDs are generated using UUID v4 (random)
 //! - File operations are performed with proper error handling
+//! - A new entry is inserted in place as a single line, the rest of the file is left
+//!   untouched; the result is the same as a full canonical rewrite
 //! - The system supports both string tags and type tags
 //!
+//! # Usage Tracking
+//! When the `UNIQUE_UUID_TRACK_USAGE` environment variable is set to a path, every
+//! expansion appends the key it resolved to that file (once per key). After a clean build
+//! the file lists every live entry, which `unique_uuid::registry::UsageLog` can compare
+//! against the registry to find stale entries. A log that cannot be written is reported
+//! as a warning, and the in-memory registry records nothing.
+//!
+//! # Expansion Statistics
+//! When the `UNIQUE_UUID_STATS` environment variable is set to a path, each compilation
+//! appends a JSON line to that file when it ends, counting the expansions, those whose
+//! registry came from the sidecar cache, the reads and writes of the registry files, the
+//! entries minted, and the time spent reading and writing them, e.g.
+//! `{"crate":"billing","expansions":12,"cache_hits":12,"file_reads":12,"file_writes":1,"minted":1,"io_micros":840}`.
+//! Counters are only kept while the variable is set, and a file that cannot be written
+//! never fails the build.
+//!
+//! # Operation Log
+//! When the `UNIQUE_UUID_LOG` environment variable is set to `debug`, every expansion
+//! logs a line to the standard error for each registry file it opens, parses or reads
+//! from the sidecar cache, each entry it inserts and each file it writes, with the key,
+//! the path of the file and the time the operation took, e.g.
+//! `unique-uuid: event=open crate="billing" key="orders.paid" path="/app/types.toml" elapsed_us=31`.
+//! When `UNIQUE_UUID_LOG_FILE` is set to a path as well, the lines are appended to that
+//! file instead. Nothing is timed nor formatted while the log is disabled.
+//!
+//! # Generated Rust Module
+//! When the `UNIQUE_UUID_RUST_MODULE` environment variable is set to a path, every write
+//! of the registry also regenerates a Rust module at that path, with a `pub const` for
+//! every entry (see `unique_uuid::registry::Registry::to_rust_module`). Entries that
+//! cannot be turned into distinct identifiers are reported as warnings, and leave the
+//! previous module in place.
+//!
+//! # JSON Mirror
+//! When the `UNIQUE_UUID_EMIT_JSON` environment variable is set to a path, every write of
+//! the registry also writes a JSON mirror of it at that path, for consumers written in
+//! other languages (see `unique_uuid::registry::Registry::to_json_mirror` for the
+//! format). The path may start with `${workspace_root}`, e.g.
+//! `UNIQUE_UUID_EMIT_JSON='${workspace_root}/web/tags.json'`.
+//!
 //! # Safety
 //! This crate performs file I/O operations during compilation, which may fail if:
 //! - The process lacks file system permissions
 //! - The TOML file becomes corrupted
 //! - Concurrent compilation attempts cause file access conflicts
-use std::{
-    collections::HashMap,
-    fs::OpenOptions,
-    io::{Read, Seek, Write},
-};
-
 use proc_macro::TokenStream;
-use serde::{Deserialize, Serialize};
-use syn::spanned::Spanned;
+use syn::{ext::IdentExt, spanned::Spanned};
 
-static DEFAULT_TYPES_FILE_NAME: &str = "types.toml";
+use attributes::TypeTagAttributes;
+use unique_uuid_core::{RegistryHandle, Section, TagOptions};
+
+mod attributes;
+mod bevy;
+mod compat;
+mod compose;
+mod diagnostics;
+mod export_c;
+mod fingerprint;
+mod input;
+mod location;
+mod match_tag;
+mod meta;
+mod module_tags;
+mod phf_map;
+mod register;
+mod reserve_tags;
+mod selftest;
+mod tag_dispatch;
+mod tag_name;
+mod tag_type;
+mod tags_enum;
+mod transparent;
 
 /// A procedural macro that generates a unique UUID for a given string tag.
 /// The generated UUID is persisted in a TOML file to ensure consistency across
@@ -80,25 +281,281 @@ static DEFAULT_TYPES_FILE_NAME: &str = "types.toml";
 /// let my_uuid = unique_tag!("my_custom_tag");
 /// ```
 ///
-/// # Panics
-/// This macro will panic if:
-/// * The TOML file cannot be opened or created
+/// # Pinning
+/// `uuid = "..."` pins the tag to an explicit UUID, e.g. one already used by another
+/// system, instead of minting a random one. The entry is recorded with `pinned = true`:
+/// ```rust
+/// use unique_uuid_derive::unique_tag;
+///
+/// let tag = unique_tag!("legacy.order_placed", uuid = "ff187986-b4ae-44dc-ac88-cccaa1228e07");
+/// assert_eq!(tag.0.to_string(), "ff187986-b4ae-44dc-ac88-cccaa1228e07");
+/// ```
+/// If the registry already assigns another UUID to the tag, the `pin_conflict` setting
+/// decides (see the crate documentation). Pinning a UUID the registry assigns to another
+/// key always fails, whatever the setting.
+///
+/// # Parents
+/// `parent = "..."`, before the tag, makes it a child of another tag: its key is
+/// `<parent>.<tag>`, and its entry records `parent = "<parent>"`. The UUID is minted and
+/// persisted as for any other tag. `unique_uuid::registry::Registry::children_of` and
+/// `is_descendant` query the hierarchy, and `Registry::validate` reports parents missing
+/// from the registry and cycles.
+/// ```rust
+/// use unique_uuid_derive::unique_tag;
+///
+/// assert_eq!(
+///     unique_tag!(test, parent = "plugins", "audio"),
+///     unique_tag!(test, "plugins.audio")
+/// );
+/// ```
+///
+/// # Test Tags
+/// `test`, before the tag, writes the tag to the test registry instead of the registry:
+/// `unique_tag!(test, "fixture.order")` resolves `fixture.order` in `types.test.toml`
+/// next to `types.toml` (see `unique_uuid_core::RegistryHandle::test_registry`). A
+/// macro cannot see whether it expands under `cfg(test)`, hence the explicit flag: the
+/// helper tags of tests then stay out of the registry, its validation and its exports.
+/// The test registry has its own `[config]` table, so that freezing the registry
+/// (`frozen_sections`) still lets tests mint new tags, and the other way around; `test`
+/// may be combined with `parent`, after it.
+///
+/// # Metadata
+/// `meta(name = value, ...)`, after the tag, records named integers (fitting in a
+/// `u32`), booleans and strings in the `meta` table of the entry, e.g.
+/// `unique_tag!("orders.created", meta(code = 7))`. A changed value is rewritten in the
+/// registry, and values missing from the invocation are left as they are. An expression
+/// cannot define constants, so the values are read back at runtime with
+/// `unique_uuid::registry::Entry::meta`; see [`UniqueTypeTag`] for the constants of
+/// types.
+///
+/// # Groups
+/// `groups = ["...", ...]`, after the tag, adds labels to the `groups` array of the
+/// entry, e.g. `unique_tag!("invoice.sent", groups = ["billing", "external"])`. Groups
+/// are independent of the key and the parent, and never affect the UUID. Groups missing
+/// from the invocation are kept, and a group no entry has yet must follow the
+/// `tag_pattern` and `tag_style` of the registry, as a new tag does.
+/// `unique_uuid::registry::Registry::tags_in_group` and `groups_of` query them at
+/// runtime.
+///
+/// # Errors
+/// The macro fails to expand with a `compile_error!` spanned on the tag if:
+/// * The TOML file cannot be opened, created or written
 /// * There are permission issues with the file system
 /// * The TOML file is corrupted or invalid
+/// * The tag is new and breaks a policy of the registry, e.g. a `tag_style` under `strict`
 ///
 /// # File Storage
 /// The UUID-tag mapping is stored in the `types.toml` file under the `[unique_tags]` section.
 #[proc_macro]
 pub fn unique_tag(input: TokenStream) -> TokenStream {
-    let string = syn::parse_macro_input!(input as syn::LitStr);
-    let uuid = get_uuid_from_tag(&string.value(), UType::UniqueTags).to_string();
-    let uuid = syn::LitStr::new(&uuid, string.span());
+    let input = syn::parse_macro_input!(input as input::TagInput);
+    let string = &input.tag;
+    let resolved = match input.resolve() {
+        Ok(resolved) => resolved,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let uuid = syn::LitStr::new(&resolved.uuid.to_string(), string.span());
+    let tag = quote::quote! {
+        unique_uuid::UniqueTag(unique_uuid::uuid::uuid!(#uuid))
+    };
 
+    if resolved.warnings.is_empty() {
+        return TokenStream::from(tag);
+    }
+    let warnings = resolved
+        .warnings
+        .iter()
+        .map(|warning| diagnostics::warning(warning, string.span()));
     TokenStream::from(quote::quote! {
-        unique_uuid::UniqueTag(unique_uuid::uuid::uuid!(#uuid))
+        {
+            #(#warnings)*
+            #tag
+        }
+    })
+}
+
+/// A procedural macro reserving a block of related string tags at once, e.g. the
+/// opcodes of a protocol.
+///
+/// `reserve_tags!("<prefix>", <start>..<end>)` resolves the keys `<prefix>.<start>` to
+/// `<prefix>.<end - 1>` in the `[unique_tags]` section, as [`unique_tag!`] does, and
+/// expands to a `[unique_uuid::UniqueTag; <end - start>]` array, whose element `i` is
+/// the tag of `<prefix>.<start + i>`: with a range starting at `0`, the array is indexed
+/// by the number itself. The range must be made of integer literals, and `..=` is
+/// accepted as well. `test`, before the prefix, reserves the block in the test registry,
+/// as for [`unique_tag!`].
+///
+/// The missing entries are minted and written at once, under a single lock of the
+/// registry, so that the whole block lands in the registry in one change, and nothing
+/// is written if every key has an entry. Existing entries are never rewritten: widening
+/// the range later mints the new keys only, and narrowing it leaves the entries out of
+/// the range in the registry.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{reserve_tags, unique_tag, UniqueTag};
+///
+/// const OPCODES: [UniqueTag; 16] = reserve_tags!(test, "handshake.opcode", 0..16);
+///
+/// assert_eq!(OPCODES[3], unique_tag!(test, "handshake.opcode.3"));
+/// assert_eq!(reserve_tags!(test, "handshake.opcode", 2..=3), [OPCODES[2], OPCODES[3]]);
+/// ```
+///
+/// # Errors
+/// The expansion fails as [`unique_tag!`] does, for the first key that fails, and if the
+/// range is empty.
+#[proc_macro]
+pub fn reserve_tags(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as reserve_tags::ReserveTags);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A procedural macro declaring a unit struct that stands for a single string tag.
+///
+/// `unique_tag_type!(<vis> struct <Name> = <tag>)` resolves `<tag>`, which accepts
+/// everything [`unique_tag!`] does, and declares `<vis> struct <Name>;` with:
+/// * `pub const TAG: unique_uuid::UniqueTag`, the tag
+/// * `From<Name> for unique_uuid::UniqueTag`
+/// * `Debug`, `Clone`, `Copy`, `Default`, `PartialEq`, `Eq`, `Hash`, `PartialOrd` and `Ord`
+///
+/// The struct has no field, so taking it as a parameter costs nothing while letting the
+/// signature say which tag it expects. Attributes, e.g. doc comments, are forwarded to
+/// the struct.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{unique_tag, unique_tag_type, UniqueTag};
+///
+/// unique_tag_type!(pub struct CustomTag = "my_custom_tag");
+///
+/// fn emit(tag: impl Into<UniqueTag>) -> UniqueTag {
+///     tag.into()
+/// }
+///
+/// assert_eq!(CustomTag::TAG, unique_tag!("my_custom_tag"));
+/// assert_eq!(emit(CustomTag), unique_tag!("my_custom_tag"));
+/// ```
+///
+/// # Errors
+/// The expansion fails as [`unique_tag!`] does. Declaring two structs with the same name
+/// in a module fails as any other duplicate definition does.
+/// ```rust,compile_fail
+/// use unique_uuid::unique_tag_type;
+///
+/// unique_tag_type!(struct CustomTag = "my_custom_tag");
+/// unique_tag_type!(struct CustomTag = "test1");
+/// ```
+#[proc_macro]
+pub fn unique_tag_type(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as tag_type::TagType);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A procedural macro that expands to the small integer id of a string tag, as a `u32`.
+///
+/// The tag is resolved as by [`unique_tag!`], minting its entry if needed, and the macro
+/// expands to the `id` field of the entry. Ids are only allocated with `ids = true` in
+/// the `[config]` table of the registry: every entry resolved by the macros then gets
+/// the id following the last one ever allocated, recorded in the `[ids]` table of the
+/// registry, so that ids are never reassigned.
+/// ```toml
+/// [unique_tags]
+/// "orders.created" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", id = 1 }
+///
+/// [ids]
+/// last = 1
+///
+/// [config]
+/// ids = true
+/// ```
+///
+/// # Example
+/// ```rust,compile_fail
+/// use unique_uuid::unique_tag_id;
+///
+/// // Fails: the registry of this crate does not enable `ids`
+/// const ORDER_CREATED: u32 = unique_tag_id!("my_custom_tag");
+/// ```
+///
+/// # Errors
+/// The expansion fails as [`unique_tag!`] does, if the entry has no id, or if another
+/// entry of the registry has the same id.
+#[proc_macro]
+pub fn unique_tag_id(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as input::TagInput);
+    let string = &input.tag;
+    let key = input.key();
+    let resolved = match input.resolve() {
+        Ok(resolved) => resolved,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let Some(id) = resolved.id else {
+        return syn::Error::new(
+            string.span(),
+            format!(
+                "`{}` has no id: set `ids = true` in the [config] table of the registry",
+                key
+            ),
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let warnings = resolved
+        .warnings
+        .iter()
+        .map(|warning| diagnostics::warning(warning, string.span()));
+    TokenStream::from(quote::quote! {
+        {
+            #(#warnings)*
+            #id
+        }
     })
 }
 
+/// A procedural macro that expands to the key of a registry entry, as a
+/// `&'static str`.
+///
+/// `tag_name_of!("<key>")` expands to the key itself, after checking that the registry
+/// has it, in either section; an alias expands to the key of the entry listing it.
+/// `tag_name_of!(uuid = "<uuid>")` searches both sections for the entry with that UUID
+/// and expands to its key, so that tables generated from UUIDs can carry their
+/// human-readable names. The registry is only read, so the entries have to be minted
+/// beforehand.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{tag_name_of, unique_tag};
+///
+/// const NAME: &str = tag_name_of!(uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa");
+/// assert_eq!(NAME, "test1");
+/// assert_eq!(unique_tag!("test1").0.to_string(), "2af1216e-7042-406a-a577-c39a7e7c3faa");
+/// assert_eq!(tag_name_of!("build.greeting"), "build.greeting");
+/// ```
+///
+/// # Errors
+/// The expansion fails if the registry cannot be read, if the key is not in the
+/// registry, or if no entry or several entries have the UUID, naming them.
+/// ```rust,compile_fail
+/// use unique_uuid::tag_name_of;
+///
+/// const NAME: &str = tag_name_of!("never.minted");
+/// ```
+#[proc_macro]
+pub fn tag_name_of(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as tag_name::TagName);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
 /// A derive macro that automatically generates a unique UUID for a type.
 /// The generated UUID is associated with the type name and persisted in a TOML file
 /// to ensure consistency across multiple compilations and crate boundaries.
@@ -106,9 +563,13 @@ pub fn unique_tag(input: TokenStream) -> TokenStream {
 /// This macro implements the [`unique_uuid::UniqueTypeTag`] trait for the decorated type,
 /// providing a constant `TYPE_TAG` that contains a unique UUID.
 ///
+/// The key of the type is its name prefixed with `::`, e.g. `::MyStruct`. Raw
+/// identifiers are keyed without their `r#`, so that `r#type` and `type` share the key
+/// `::type`; entries keyed `::r#type` by earlier versions are moved to the new key.
+///
 /// # Example
 /// ```rust
-/// use unique_uuid_derive::UniqueTypeTag;
+/// use unique_uuid::UniqueTypeTag;
 ///
 /// #[derive(UniqueTypeTag)]
 /// struct MyStruct;
@@ -122,77 +583,695 @@ pub fn unique_tag(input: TokenStream) -> TokenStream {
 /// * Stores the UUID in `types.toml` under the `[unique_type_tags]` section
 /// * Uses the type's name as the key for UUID mapping
 ///
-/// # Panics
-/// This macro will panic if:
-/// * The TOML file cannot be opened or created
+/// # Renaming a Type
+/// Renaming a type changes its key, and would therefore give it a new UUID. To keep
+/// the existing UUID, name the previous key with the `rename` attribute; the entry is
+/// moved to the new key the next time the type is compiled:
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(rename = "::OrderRecord")]
+/// struct Order;
+/// ```
+/// The attribute can stay in place afterward, it has no effect once the old key is gone.
+///
+/// # Aliases
+/// `#[unique_type_tag(alias = "...")]`, which may be repeated, records alternative keys
+/// for the type in the `aliases` field of its entry. An alias resolves to the UUID of the
+/// type, both in the macros and in `unique_uuid::registry::Registry::get`, and is listed
+/// in `TYPE_TAG_ALIASES`. The expansion fails if an alias is already the key or an alias
+/// of another entry; to take over an existing key, use `rename` instead. Aliases are
+/// never removed from the registry, as other crates may still use them.
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(alias = "::SalesReceipt", alias = "::Till")]
+/// struct Receipt;
+///
+/// assert_eq!(Receipt::TYPE_TAG_ALIASES, ["::SalesReceipt", "::Till"]);
+/// ```
+///
+/// # Pinning
+/// `#[unique_type_tag(uuid = "...")]` pins the type to an explicit UUID, as `uuid` does
+/// for [`unique_tag!`]:
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(uuid = "d743ab86-e823-4d16-b03c-523bb73c4f8c")]
+/// struct LegacyInvoice;
+///
+/// assert_eq!(LegacyInvoice::TYPE_TAG.0.to_string(), "d743ab86-e823-4d16-b03c-523bb73c4f8c");
+/// ```
+///
+/// # Metadata
+/// `#[unique_type_tag(meta(name = value, ...))]` records named integers (fitting in a
+/// `u32`), booleans and strings in the `meta` table of the entry, as `meta(...)` does
+/// for [`unique_tag!`]. Every value of the entry, whether set by the attribute or
+/// already in the registry, is emitted as an associated constant `TYPE_TAG_<NAME>`: a
+/// `u32`, a `bool` or a `&'static str`. Removing the attribute keeps the recorded values,
+/// and their constants. Likewise, `#[unique_type_tag(groups("billing", ...))]` adds
+/// groups to the entry, as `groups = [...]` does for [`unique_tag!`].
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(meta(code = 7, retry = true))]
+/// struct Alarm;
+///
+/// assert_eq!(Alarm::TYPE_TAG_CODE, 7);
+/// assert!(Alarm::TYPE_TAG_RETRY);
+/// ```
+///
+/// When a type gets a new key while the registry holds a similar key that no type of
+/// the current build uses, the derive emits a warning suggesting the attribute. This
+/// detection is a heuristic, and never fails the build.
+///
+/// # C Accessor
+/// `#[unique_type_tag(export_c = "symbol")]` also generates an unmangled
+/// `unsafe extern "C" fn symbol(out: *mut u8)`, which writes the 16 bytes of the tag,
+/// in big-endian order, to `out`. `out` must be null (the function then does nothing)
+/// or valid for writes of 16 bytes. The symbol must be a valid C identifier, and may
+/// only be exported by one type of the crate.
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(export_c = "my_exported_type_tag")]
+/// struct MyExportedType;
+///
+/// // The function a C caller would declare as `void my_exported_type_tag(uint8_t *out);`
+/// let mut bytes = [0u8; 16];
+/// unsafe { my_exported_type_tag(bytes.as_mut_ptr()) };
+/// assert_eq!(&bytes, MyExportedType::TYPE_TAG.0.as_bytes());
+///
+/// unsafe { my_exported_type_tag(std::ptr::null_mut()) };
+/// ```
+///
+/// # Generic Types
+/// A generic type has a single tag, shared by all its instantiations. With
+/// `#[unique_type_tag(compose)]`, the tag of each instantiation is instead combined, at
+/// compile time, from the registered tag of the type and the `TYPE_TAG` of each of its
+/// type parameters, in order. Every type parameter must then implement
+/// [`unique_uuid::UniqueTypeTag`]; lifetimes are ignored, and const parameters are not
+/// supported. The tags are combined with [`unique_uuid::UniqueTag::combine_all`], and
+/// only the tag of the type itself is stored in the registry.
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Order;
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Invoice;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(compose)]
+/// struct Envelope<T>(T);
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(compose)]
+/// struct Pair<A, B>(A, B);
+///
+/// assert_ne!(Envelope::<Order>::TYPE_TAG, Envelope::<Invoice>::TYPE_TAG);
+/// assert_eq!(Envelope::<Order>::TYPE_TAG, Envelope::<Order>::TYPE_TAG);
+/// assert_ne!(Pair::<Order, Invoice>::TYPE_TAG, Pair::<Invoice, Order>::TYPE_TAG);
+///
+/// // Without `compose`, every instantiation shares the tag of the type
+/// #[derive(UniqueTypeTag)]
+/// struct Shared<T>(T);
+///
+/// assert_eq!(Shared::<Order>::TYPE_TAG, Shared::<Invoice>::TYPE_TAG);
+/// ```
+///
+/// # Bevy
+/// With the `bevy` feature of `unique-uuid`, `#[unique_type_tag(bevy)]` also implements
+/// `bevy_reflect::TypeUuid` (`bevy_reflect` 0.10 to 0.12, re-exported as
+/// `unique_uuid::bevy_reflect`), whose `TYPE_UUID` is the UUID of `TYPE_TAG`, so that
+/// Bevy and the registry agree on the identity of the type. It may be combined with
+/// `compose`.
+/// ```rust
+/// use unique_uuid::{bevy_reflect::TypeUuid, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(bevy)]
+/// struct MyAsset;
+///
+/// assert_eq!(MyAsset::TYPE_UUID, MyAsset::TYPE_TAG.0);
+/// ```
+///
+/// # Transparent Wrappers
+/// `#[unique_type_tag(transparent)]` gives a struct with a single field the tag of the
+/// type of that field, which must implement [`unique_uuid::UniqueTypeTag`]; nothing is
+/// written to the registry for the wrapper. This suits newtypes that should be
+/// indistinguishable from the type they wrap. It cannot be combined with other options,
+/// and is an error on enums and on structs with several fields.
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Order;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(transparent)]
+/// struct Checked(Order);
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(transparent)]
+/// struct Logged<T> {
+///     inner: T,
+/// }
+///
+/// assert_eq!(Checked::TYPE_TAG, Order::TYPE_TAG);
+/// assert_eq!(Logged::<Checked>::TYPE_TAG, Order::TYPE_TAG);
+/// ```
+///
+/// # Self-Test
+/// With the `registry-file` feature of `unique-uuid`, `#[unique_type_tag(selftest)]`
+/// also generates a `#[cfg(test)]` test, `unique_type_tag_selftest_<Type>`, asserting
+/// that the registry still stores `TYPE_TAG` under the key of the type. `cargo test`
+/// then catches a hand-edit of the registry changing the UUID of a type after it was
+/// compiled, with a message naming the key, both UUIDs and the registry. The registry is
+/// read from the path it had when the type was compiled. `selftest` is not supported on
+/// generic types.
+/// ```rust
+/// use unique_uuid::UniqueTypeTag;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(selftest)]
+/// struct Shipment;
+/// ```
+///
+/// # Registration
+/// With the `linkme` feature of `unique-uuid`, every non-generic type is also registered
+/// in the running binary, with its key and path, whatever the crate it is defined in;
+/// `unique_uuid::registry::iter_type_tags` and `unique_uuid::registry::find_by_tag` read
+/// the registrations back.
+///
+/// With the `linkme` feature, `#[unique_type_tag(factory)]` also registers a constructor
+/// of the type, which must implement `Default`, so that `unique_uuid::registry::construct`
+/// and `unique_uuid::registry::construct_as` build a value of the type from its tag
+/// alone. A tag registered by two types makes the first lookup panic, naming both.
+/// `factory` is not supported on generic types.
+/// ```rust
+/// use unique_uuid::{registry::construct_as, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag, Default)]
+/// #[unique_type_tag(factory)]
+/// struct Manifest {
+///     lines: Vec<String>,
+/// }
+///
+/// let manifest: Manifest = construct_as(Manifest::TYPE_TAG).unwrap();
+/// assert!(manifest.lines.is_empty());
+/// ```
+///
+/// With the `tag-section` feature of `unique-uuid`, every non-generic type also gets a
+/// record of its tag and key in a linker section of the binary, which
+/// `unique-uuid-admin inspect <binary>` lists without running the binary (see
+/// `unique_uuid::tag_section`).
+///
+/// # Errors
+/// The derive fails to expand with a `compile_error!` spanned on the type name if:
+/// * The TOML file cannot be opened, created or written
 /// * There are permission issues with the file system
 /// * The TOML file is corrupted or invalid
-///
-#[proc_macro_derive(UniqueTypeTag)]
+/// * The tag is new and breaks a policy of the registry, e.g. a `tag_style` under `strict`
+#[proc_macro_derive(UniqueTypeTag, attributes(unique_type_tag))]
 pub fn unique_type_tag(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
-    let tag = format!("{}::{}", "", input.ident);
+    // `r#Type` and `Type` are the same type, and must get the same key
+    let tag = format!("{}::{}", "", input.ident.unraw());
+
+    let attributes = match TypeTagAttributes::parse(&input.attrs) {
+        Ok(attributes) => attributes,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if let Some(flag) = &attributes.transparent {
+        return transparent::implementation(flag, &input, &attributes)
+            .unwrap_or_else(syn::Error::into_compile_error)
+            .into();
+    }
+    let export_c = match &attributes.export_c {
+        Some(symbol) if input.generics.type_params().next().is_some() => {
+            return syn::Error::new(
+                symbol.span(),
+                "`export_c` is not supported on generic types",
+            )
+            .to_compile_error()
+            .into();
+        }
+        Some(symbol) => match export_c::accessor(symbol, &input.ident, &tag) {
+            Ok(accessor) => Some(accessor),
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => None,
+    };
 
-    let uuid = get_uuid_from_tag(&tag, UType::UniqueTypeTags).to_string();
-    let uuid = syn::LitStr::new(&uuid, input.span());
+    // Keys of raw identifiers used to keep their `r#`, such entries are moved to the new key
+    let raw_key = format!("{}::{}", "", input.ident);
+    let rename_from = match &attributes.rename {
+        Some(rename) => Some(rename.value()),
+        None => (raw_key != tag).then_some(raw_key),
+    };
+    let pinned = match attributes.uuid.as_ref().map(input::pinned_uuid).transpose() {
+        Ok(pinned) => pinned,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let aliases: Vec<String> = attributes.aliases.iter().map(syn::LitStr::value).collect();
+    let source = location::source(input.ident.span());
+    let options = TagOptions {
+        rename_from: rename_from.as_deref(),
+        pinned,
+        aliases: &aliases,
+        parent: None,
+        meta: &attributes.meta,
+        groups: attributes.groups.as_deref().unwrap_or_default(),
+        source: source.as_deref(),
+    };
+    let registry = RegistryHandle::from_env();
+    let resolved = match registry.get_uuid_from_tag(&tag, Section::UniqueTypeTags, &options) {
+        Ok(resolved) => resolved,
+        Err(err) => {
+            return syn::Error::new(input.ident.span(), err)
+                .to_compile_error()
+                .into()
+        }
+    };
+    let uuid = syn::LitStr::new(&resolved.uuid.to_string(), input.span());
+    let warnings = resolved
+        .warnings
+        .iter()
+        .map(|warning| diagnostics::warning(warning, input.ident.span()))
+        .collect::<Vec<_>>();
+
+    let base = quote::quote! { unique_uuid::UniqueTag(unique_uuid::uuid::uuid!(#uuid)) };
+    let mut generics = input.generics.clone();
+    let type_tag = match &attributes.compose {
+        Some(compose) => match compose::type_tag(compose, &mut generics, base) {
+            Ok(type_tag) => type_tag,
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => base,
+    };
+    let bevy = match &attributes.bevy {
+        Some(flag) => match bevy::type_uuid(flag, &input.ident, &generics) {
+            Ok(type_uuid) => Some(type_uuid),
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => None,
+    };
+    let selftest = match &attributes.selftest {
+        Some(flag) if !input.generics.params.is_empty() => {
+            return syn::Error::new_spanned(flag, "`selftest` is not supported on generic types")
+                .to_compile_error()
+                .into();
+        }
+        Some(flag) => match selftest::test(flag, &input.ident, &tag, &registry) {
+            Ok(test) => Some(test),
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => None,
+    };
+    let factory = match &attributes.factory {
+        Some(flag) if !input.generics.params.is_empty() => {
+            return syn::Error::new_spanned(flag, "`factory` is not supported on generic types")
+                .to_compile_error()
+                .into();
+        }
+        Some(flag) => match register::factory(flag, &input.ident) {
+            Ok(factory) => Some(factory),
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => None,
+    };
+    let type_tag_aliases = (!aliases.is_empty()).then(|| {
+        quote::quote! { const TYPE_TAG_ALIASES: &'static [&'static str] = &[#(#aliases),*]; }
+    });
+    let registration = register::registration(&input.ident, &input.generics, &tag);
+    let section_record = register::section_record(&input.ident, &input.generics, &tag);
+    // A composed tag differs between instantiations, unlike the id of the entry
+    let type_id = resolved.id.filter(|_| attributes.compose.is_none()).map(|id| {
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let ident = &input.ident;
+        quote::quote! {
+            impl #impl_generics unique_uuid::UniqueTypeId for #ident #ty_generics #where_clause {
+                const TYPE_ID: u32 = #id;
+            }
+        }
+    });
+    let constants = meta::constants(&resolved.meta, input.ident.span());
+    let meta_constants = (!constants.is_empty()).then(|| {
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let ident = &input.ident;
+        quote::quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #(#constants)*
+            }
+        }
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let guard = compat::guard(compat::INTERFACE);
 
     let input_ident = input.ident;
 
     TokenStream::from(quote::quote! {
-        impl unique_uuid::UniqueTypeTag for #input_ident {
-            const TYPE_TAG: unique_uuid::UniqueTag = unique_uuid::UniqueTag(unique_uuid::uuid::uuid!(#uuid));
+        impl #impl_generics unique_uuid::UniqueTypeTag for #input_ident #ty_generics #where_clause {
+            const TYPE_TAG: unique_uuid::UniqueTag = #type_tag;
+            #type_tag_aliases
         }
+        #type_id
+        #meta_constants
+        #export_c
+        #bevy
+        #selftest
+        #registration
+        #section_record
+        #factory
+        #guard
+        #(#warnings)*
     })
 }
 
-enum UType {
-    UniqueTags,
-    UniqueTypeTags,
+/// An attribute macro deriving [`UniqueTypeTag`](derive@UniqueTypeTag) for every struct
+/// and enum of an inline module.
+///
+/// Each type gets the key and the UUID the derive would give it, and the module is
+/// otherwise left unchanged. Nested inline modules are walked as well, while the content
+/// of `mod name;` modules is out of reach of the macro. A type is left alone if it
+/// already derives `UniqueTypeTag`, if the module implements `UniqueTypeTag` for it by
+/// hand, or if it is marked `#[unique_type_tag(skip)]`. The other options of
+/// `#[unique_type_tag(...)]`, for instance `rename`, apply as with the derive.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{unique_type_tags, UniqueTag, UniqueTypeTag};
+///
+/// #[unique_type_tags]
+/// mod events {
+///     pub struct OrderCreated;
+///
+///     pub enum Checkout {
+///         Started,
+///         Completed,
+///     }
+///
+///     pub mod shipping {
+///         pub struct Shipment;
+///     }
+///
+///     #[unique_type_tag(skip)]
+///     pub struct Draft;
+/// }
+///
+/// #[derive(UniqueTypeTag)]
+/// struct OrderCreated;
+///
+/// assert_eq!(events::OrderCreated::TYPE_TAG, OrderCreated::TYPE_TAG);
+/// assert_ne!(events::Checkout::TYPE_TAG, events::shipping::Shipment::TYPE_TAG);
+/// ```
+///
+/// # Errors
+/// The expansion fails if the module is not inline, or if `skip` is combined with other
+/// options or with the derive. Each type is otherwise tagged exactly as by the derive,
+/// with the same errors and warnings.
+#[proc_macro_attribute]
+pub fn unique_type_tags(attr: TokenStream, item: TokenStream) -> TokenStream {
+    syn::parse_macro_input!(attr as syn::parse::Nothing);
+    let input = syn::parse_macro_input!(item as module_tags::ModuleTags);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
-#[derive(Default, Serialize, Deserialize)]
-#[serde(default)]
-struct FileStructure {
-    #[serde(default)]
-    unique_tags: HashMap<String, uuid::Uuid>,
+/// A procedural macro that generates an enum with one variant per tag of a registry
+/// namespace.
+///
+/// Every key of the `[unique_tags]` section belonging to the namespace (or to one of its
+/// nested namespaces, see `unique_uuid::registry::in_namespace`) becomes a unit variant,
+/// named after the rest of the key in `UpperCamelCase`: `events.order_created` becomes
+/// `OrderCreated`. The enum comes with:
+/// * derived `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq`, `Hash`, `PartialOrd` and `Ord`
+/// * an `ALL` constant listing every variant, in key order
+/// * a `const fn tag(&self) -> UniqueTag` method
+/// * a `const fn from_tag(UniqueTag) -> Option<Enum>` function, a binary search of the
+///   variants sorted by tag
+/// * `TryFrom<UniqueTag>`, failing with [`unique_uuid::UnknownTag`] for any other tag
+/// * `From<Enum> for UniqueTag`
+///
+/// The registry is only read, so the tags have to be minted beforehand (e.g. by
+/// [`unique_tag!`] or by editing the registry). Since the enum is exhaustive, adding a
+/// key to the namespace makes every `match` that does not handle it fail to compile.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{tags_enum, unique_tag, UniqueTag};
+///
+/// tags_enum!(pub enum Message, namespace = "build.messages");
+///
+/// let tag = Message::Hello.tag();
+/// assert_eq!(tag, unique_tag!("build.messages.hello"));
+/// assert_eq!(Message::try_from(tag), Ok(Message::Hello));
+/// assert!(Message::try_from(unique_tag!("my_custom_tag")).is_err());
+///
+/// const GOODBYE: Option<Message> = Message::from_tag(unique_tag!("build.messages.goodbye"));
+/// const _: () = assert!(matches!(GOODBYE, Some(Message::Goodbye)));
+///
+/// match Message::try_from(tag).unwrap() {
+///     Message::Goodbye => {}
+///     Message::Hello => {}
+/// }
+/// ```
+///
+/// # Errors
+/// The expansion fails if the registry cannot be read, if the namespace has no tag,
+/// or if two keys map to the same variant name.
+#[proc_macro]
+pub fn tags_enum(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as tags_enum::TagsEnum);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    #[serde(default)]
-    unique_type_tags: HashMap<String, uuid::Uuid>,
+/// A derive macro routing tags to the variants of an enum whose variants each wrap a
+/// tagged payload, e.g. `enum Message { Created(OrderCreated), Paid(OrderPaid) }`.
+///
+/// Every variant must have a single field, named or not, whose type implements
+/// [`unique_uuid::UniqueTypeTag`]. The derive generates, for an enum `Message`:
+/// * a fieldless `MessageKind` enum with the same variants and visibility, its `ALL`
+///   variants, and a `tag` method returning the `TYPE_TAG` of the wrapped type;
+/// * `TryFrom<UniqueTag>` for `MessageKind`, failing with `unique_uuid::UnknownTag` for
+///   the tags of no variant;
+/// * `Message::SUPPORTED_TAGS`, the tags of the variants in declaration order, and the
+///   `kind` and `tag_of_variant` methods of `Message`.
+///
+/// The registry is not involved: the tags are those of the wrapped types.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{TagDispatch, UniqueTag, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Order;
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Invoice;
+///
+/// #[derive(TagDispatch)]
+/// enum Message {
+///     Order(Order),
+///     Invoice { invoice: Invoice },
+/// }
+///
+/// let message = Message::Invoice { invoice: Invoice };
+/// assert_eq!(message.kind(), MessageKind::Invoice);
+/// assert_eq!(message.tag_of_variant(), Invoice::TYPE_TAG);
+/// assert_eq!(Message::SUPPORTED_TAGS, [Order::TYPE_TAG, Invoice::TYPE_TAG]);
+///
+/// assert_eq!(MessageKind::try_from(Order::TYPE_TAG), Ok(MessageKind::Order));
+/// assert!(MessageKind::try_from(UniqueTag(unique_uuid::uuid::Uuid::nil())).is_err());
+/// ```
+///
+/// # Errors
+/// The expansion fails on structs, unions and generic enums, on variants that do not
+/// wrap exactly one type, and when two variants wrap the same type. A wrapped type that
+/// does not implement [`unique_uuid::UniqueTypeTag`] fails to compile at that type,
+/// and distinct types sharing a tag (e.g. through `#[unique_type_tag(transparent)]`)
+/// fail the evaluation of a constant.
+/// ```rust,compile_fail
+/// use unique_uuid::{TagDispatch, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Order;
+///
+/// #[derive(UniqueTypeTag)]
+/// #[unique_type_tag(transparent)]
+/// struct Checked(Order);
+///
+/// #[derive(TagDispatch)]
+/// enum Message {
+///     Order(Order),
+///     Checked(Checked),
+/// }
+/// ```
+#[proc_macro_derive(TagDispatch)]
+pub fn tag_dispatch(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    tag_dispatch::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
-fn get_uuid_from_tag(tag: &str, r#type: UType) -> uuid::Uuid {
-    let file_path = DEFAULT_TYPES_FILE_NAME;
-    let mut file = match OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .open(file_path)
-    {
-        Ok(file) => file,
-        Err(err) => {
-            panic!("Error opening file: {}", err);
-        }
-    };
+/// A procedural macro that matches a [`unique_uuid::UniqueTag`] against tags of the
+/// registry, as a `match` would.
+///
+/// Each arm names one or more tags, separated by `|`, resolved through the registry as
+/// by [`unique_tag!`], and the last arm `_ => ...`, which is mandatory, handles every
+/// other tag. The macro expands to a `match` over the `u128` value of the tag, which the
+/// compiler turns into a comparison chain or a lookup; it evaluates to the value of the
+/// matching arm.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{match_tag, unique_tag, UniqueTag};
+///
+/// fn describe(incoming: UniqueTag) -> String {
+///     match_tag!(incoming, {
+///         "build.messages.hello" | "build.messages.goodbye" => "a message".to_string(),
+///         "my_custom_tag" => "the custom tag".to_string(),
+///         _ => format!("unknown tag {}", incoming.0),
+///     })
+/// }
+///
+/// assert_eq!(describe(unique_tag!("build.messages.goodbye")), "a message");
+/// assert_eq!(describe(unique_tag!("my_custom_tag")), "the custom tag");
+/// assert!(describe(unique_tag!("test1")).starts_with("unknown tag"));
+/// ```
+///
+/// # Errors
+/// The expansion fails if the `_` arm is missing or is not the last arm, or if a tag is
+/// matched by two arms. A tag is otherwise resolved exactly as by [`unique_tag!`], with
+/// the same errors and warnings.
+#[proc_macro]
+pub fn match_tag(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as match_tag::MatchTag);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    // Read the TOML file
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
+/// A procedural macro declaring `pub const REGISTRY_FINGERPRINT: UniqueTag`, the
+/// fingerprint of the registry the crate is built against.
+///
+/// Binaries built against registries that were not shared, or that were forked, may give
+/// a UUID different meanings without failing. Services exchanging the fingerprint during
+/// their handshake, and checking the one of their peer with
+/// `unique_uuid::check_fingerprint`, detect it. The fingerprint is a version 5 UUID
+/// over the keys and UUIDs of the entries (see `unique_uuid::fingerprint` for the
+/// algorithm), which `unique_uuid::registry::Registry::fingerprint` computes at runtime.
+///
+/// The fingerprint covers every entry visible to the crate, including those of the other
+/// crates of a registry directory, rather than the keys the crate resolves, which would
+/// depend on the order in which the compiler expands the macros. With
+/// `namespace = "..."`, it only covers the entries of the namespace, e.g. those of the
+/// protocol spoken by the peers, so that unrelated entries do not change it. The
+/// registry is only read.
+///
+/// This macro requires the `fingerprint` feature of `unique-uuid`.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{check_fingerprint, registry_fingerprint};
+///
+/// mod messages {
+///     unique_uuid::registry_fingerprint!(namespace = "build.messages");
+/// }
+///
+/// registry_fingerprint!();
+///
+/// assert_eq!(check_fingerprint(REGISTRY_FINGERPRINT, REGISTRY_FINGERPRINT), Ok(()));
+/// assert!(check_fingerprint(REGISTRY_FINGERPRINT, messages::REGISTRY_FINGERPRINT).is_err());
+/// ```
+///
+/// # Errors
+/// The expansion fails if the registry cannot be read. Malformed entries are left out,
+/// with a warning.
+#[proc_macro]
+pub fn registry_fingerprint(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as fingerprint::Fingerprint);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    // Deserialize the TOML file
-    let mut file_structure: FileStructure = toml::from_str(&contents).unwrap();
+/// A procedural macro that builds a static map keyed by tags, with a perfect hash
+/// function computed at compile time.
+///
+/// Each key is a tag resolved through the registry, as by [`unique_tag!`], and each value
+/// an expression; with `namespace = "..."`, the map holds every `[unique_tags]` key of
+/// the namespace instead (see [`tags_enum!`]), each mapped to its key as a
+/// `&'static str`. The macro evaluates to a `unique_uuid::phf_map::TagPhfMap<V>`, whose
+/// entries are laid out at compile time, so that it can be stored in a `static` when the
+/// values are constant expressions. A lookup hashes the 16 bytes of the tag once and
+/// compares a single entry.
+///
+/// This macro requires the `phf` feature of `unique-uuid`.
+///
+/// # Example
+/// ```rust
+/// use unique_uuid::{phf_map::TagPhfMap, tag_phf_map, unique_tag};
+///
+/// static PRIORITIES: TagPhfMap<u16> = tag_phf_map! {
+///     "build.messages.hello" => 10,
+///     "build.messages.goodbye" => 20,
+///     "my_custom_tag" => 0,
+/// };
+/// assert_eq!(PRIORITIES.get(&unique_tag!("build.messages.hello")), Some(&10));
+/// assert!(PRIORITIES.contains(&unique_tag!("my_custom_tag")));
+///
+/// static MESSAGES: TagPhfMap<&str> = tag_phf_map!(namespace = "build.messages");
+/// let goodbye = MESSAGES.get(&unique_tag!("build.messages.goodbye"));
+/// assert_eq!(goodbye, Some(&"build.messages.goodbye"));
+/// assert_eq!(MESSAGES.get(&unique_tag!("my_custom_tag")), None);
+/// ```
+///
+/// # Errors
+/// The expansion fails if a tag is given twice, or if two tags share a UUID, naming
+/// both. A tag is otherwise resolved exactly as by [`unique_tag!`], with the same errors
+/// and warnings; with `namespace`, the registry is only read, and the expansion fails if
+/// the namespace has no tag.
+/// ```rust,compile_fail
+/// use unique_uuid::{phf_map::TagPhfMap, tag_phf_map};
+///
+/// static PRIORITIES: TagPhfMap<u16> = tag_phf_map! {
+///     "build.messages.hello" => 10,
+///     "build.messages.hello" => 20,
+/// };
+/// ```
+#[proc_macro]
+pub fn tag_phf_map(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as phf_map::TagPhfMap);
+    input
+        .expand()
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    let target = match r#type {
-        UType::UniqueTags => &mut file_structure.unique_tags,
-        UType::UniqueTypeTags => &mut file_structure.unique_type_tags,
-    };
-    if let Some(uuid) = target.get(tag) {
-        uuid.clone()
-    } else {
-        let uuid = uuid::Uuid::new_v4();
-        target.insert(tag.to_string(), uuid);
-        let toml = toml::to_string(&file_structure).unwrap();
-        file.seek(std::io::SeekFrom::Start(0)).unwrap();
-        file.write_all(toml.as_bytes()).unwrap();
-        uuid
+/// Expands to the guard the derive macros emit, checking that `unique-uuid` supports
+/// the given version of the interface between both crates (by default, the one of this
+/// release). Only meant for the tests of the guard, see `unique_uuid::__compat`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __compat_guard(input: TokenStream) -> TokenStream {
+    match compat::parse(input) {
+        Ok(interface) => compat::guard(interface).into(),
+        Err(err) => err.to_compile_error().into(),
     }
 }
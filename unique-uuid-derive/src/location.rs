@@ -0,0 +1,22 @@
+//! The location of the macro invocations, recorded with the entries they mint.
+use std::path::Path;
+
+use proc_macro2::Span;
+
+/// Returns `file:line` for `span`, with `file` relative to the crate being compiled, e.g.
+/// `src/events.rs:42`.
+///
+/// Returns `None` if the span has no file on disk, e.g. for tokens produced by another
+/// macro, or if the file is outside the crate, so that no absolute path of the machine
+/// ends up in the registry.
+pub(crate) fn source(span: Span) -> Option<String> {
+    let span = span.unwrap();
+    let file = std::env::current_dir().ok()?.join(span.local_file()?);
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")?;
+    let relative = file.strip_prefix(Path::new(&manifest_dir)).ok()?;
+    let components: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    Some(format!("{}:{}", components.join("/"), span.line()))
+}
@@ -0,0 +1,140 @@
+//! Generation of a `match` over the tags of the registry.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    Expr, LitStr, Token,
+};
+
+use unique_uuid_core::{RegistryHandle, Section, TagOptions};
+
+use crate::{diagnostics, location};
+
+/// The input of `match_tag!`: `<expr>, { "<tag>" | "<tag>" => <expr>, ..., _ => <expr> }`.
+pub(crate) struct MatchTag {
+    scrutinee: Expr,
+    arms: Vec<(Vec<LitStr>, Expr)>,
+    default: Expr,
+}
+
+impl Parse for MatchTag {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let scrutinee = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let content;
+        braced!(content in input);
+        input.parse::<Option<Token![,]>>()?;
+
+        let mut arms = Vec::new();
+        let mut default = None;
+        while !content.is_empty() {
+            if content.peek(Token![_]) {
+                let underscore = content.parse::<Token![_]>()?;
+                content.parse::<Token![=>]>()?;
+                default = Some(content.parse()?);
+                content.parse::<Option<Token![,]>>()?;
+                if !content.is_empty() {
+                    return Err(syn::Error::new(
+                        underscore.span,
+                        "the `_` arm must be the last arm of `match_tag!`",
+                    ));
+                }
+                break;
+            }
+            let mut tags = vec![content.parse::<LitStr>()?];
+            while content.parse::<Option<Token![|]>>()?.is_some() {
+                tags.push(content.parse()?);
+            }
+            content.parse::<Token![=>]>()?;
+            let body = content.parse()?;
+            content.parse::<Option<Token![,]>>()?;
+            arms.push((tags, body));
+        }
+        let default = default.ok_or_else(|| {
+            syn::Error::new(
+                content.span(),
+                "`match_tag!` requires a last `_ => …` arm, for the other tags",
+            )
+        })?;
+
+        Ok(Self {
+            scrutinee,
+            arms,
+            default,
+        })
+    }
+}
+
+impl MatchTag {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let mut seen: Vec<&LitStr> = Vec::new();
+        for tag in self.arms.iter().flat_map(|(tags, _)| tags) {
+            if seen.iter().any(|other| other.value() == tag.value()) {
+                return Err(syn::Error::new(
+                    tag.span(),
+                    format!(
+                        "the tag `{}` is already matched by a previous arm",
+                        tag.value()
+                    ),
+                ));
+            }
+            seen.push(tag);
+        }
+
+        let registry = RegistryHandle::from_env();
+        let mut resolved: Vec<(&LitStr, uuid::Uuid)> = Vec::with_capacity(seen.len());
+        let mut warnings = Vec::new();
+        let mut arms = Vec::with_capacity(self.arms.len());
+        for (tags, body) in &self.arms {
+            let mut values = Vec::with_capacity(tags.len());
+            for tag in tags {
+                let source = location::source(tag.span());
+                let options = TagOptions {
+                    source: source.as_deref(),
+                    ..TagOptions::default()
+                };
+                let entry = registry
+                    .get_uuid_from_tag(&tag.value(), Section::UniqueTags, &options)
+                    .map_err(|err| syn::Error::new(tag.span(), err))?;
+                let previous = resolved.iter().find(|(_, uuid)| *uuid == entry.uuid);
+                if let Some((other, _)) = previous {
+                    return Err(syn::Error::new(
+                        tag.span(),
+                        format!(
+                            "`{}` and `{}` are both tagged `{}`",
+                            other.value(),
+                            tag.value(),
+                            entry.uuid
+                        ),
+                    ));
+                }
+                warnings.extend(
+                    entry
+                        .warnings
+                        .iter()
+                        .map(|warning| diagnostics::warning(warning, tag.span())),
+                );
+                resolved.push((tag, entry.uuid));
+                values.push(entry.uuid.as_u128());
+            }
+            arms.push(quote! { #(#values)|* => #body, });
+        }
+
+        let MatchTag {
+            scrutinee, default, ..
+        } = self;
+        Ok(quote! {
+            {
+                #(#warnings)*
+                match {
+                    let tag: unique_uuid::UniqueTag = #scrutinee;
+                    tag.0.as_u128()
+                } {
+                    #(#arms)*
+                    _ => #default,
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,87 @@
+//! Parsing of `meta(...)`, the named scalar values recorded in the `meta` table of an
+//! entry, and the constants generated for them.
+use proc_macro2::{Span, TokenStream};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Ident, Lit, Token,
+};
+use unique_uuid_core::MetaValue;
+
+/// A `name = value` item of `meta(...)`.
+struct MetaItem {
+    name: Ident,
+    value: Lit,
+}
+
+impl Parse for MetaItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self {
+            name,
+            value: input.parse()?,
+        })
+    }
+}
+
+/// Parses the content of `meta(...)`, e.g. `code = 7, legacy = true`.
+pub(crate) fn parse(input: ParseStream) -> syn::Result<Vec<(String, MetaValue)>> {
+    let mut meta = Vec::new();
+    for item in Punctuated::<MetaItem, Token![,]>::parse_terminated(input)? {
+        push(&mut meta, &item.name, &item.value)?;
+    }
+    Ok(meta)
+}
+
+/// Adds `name = value` to `meta`.
+///
+/// Fails if `name` is already given, or if `value` is neither a non-negative integer
+/// fitting in a `u32`, nor a boolean, nor a string.
+pub(crate) fn push(
+    meta: &mut Vec<(String, MetaValue)>,
+    name: &Ident,
+    value: &Lit,
+) -> syn::Result<()> {
+    let key = name.to_string();
+    if meta.iter().any(|(other, _)| *other == key) {
+        return Err(syn::Error::new(
+            name.span(),
+            format!("duplicate `meta` value `{}`", key),
+        ));
+    }
+    let value = match value {
+        Lit::Int(int) if int.suffix().is_empty() => MetaValue::Integer(int.base10_parse()?),
+        Lit::Bool(boolean) => MetaValue::Boolean(boolean.value),
+        Lit::Str(string) => MetaValue::String(string.value()),
+        _ => {
+            return Err(syn::Error::new(
+                value.span(),
+                "`meta` values must be unsuffixed integers fitting in a `u32`, booleans or \
+                 strings",
+            ))
+        }
+    };
+    meta.push((key, value));
+    Ok(())
+}
+
+/// Returns a `pub const TYPE_TAG_<NAME>` constant for each value of `meta`, the `meta`
+/// table of the entry: a `u32` for an integer, a `bool` for a boolean and a
+/// `&'static str` for a string. Names that do not form an identifier get no constant.
+pub(crate) fn constants(meta: &[(String, MetaValue)], span: Span) -> Vec<TokenStream> {
+    meta.iter()
+        .filter_map(|(name, value)| {
+            let name = format!("TYPE_TAG_{}", name.to_uppercase());
+            let name = syn::parse_str::<Ident>(&name).ok()?;
+            let name = Ident::new(&name.to_string(), span);
+            Some(match value {
+                MetaValue::Integer(integer) => quote::quote! { pub const #name: u32 = #integer; },
+                MetaValue::Boolean(boolean) => quote::quote! { pub const #name: bool = #boolean; },
+                MetaValue::String(string) => {
+                    quote::quote! { pub const #name: &'static str = #string; }
+                }
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,140 @@
+//! Derivation of `UniqueTypeTag` for every type of a module.
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Ident, Item, ItemMod, Path, Token,
+};
+
+/// The module annotated with `#[unique_type_tags]`.
+pub(crate) struct ModuleTags {
+    module: ItemMod,
+}
+
+impl Parse for ModuleTags {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let module: ItemMod = input.parse()?;
+        if module.content.is_none() {
+            return Err(syn::Error::new(
+                module.ident.span(),
+                "`#[unique_type_tags]` requires an inline module, `mod name { ... }`",
+            ));
+        }
+        Ok(Self { module })
+    }
+}
+
+impl ModuleTags {
+    pub(crate) fn expand(mut self) -> syn::Result<TokenStream> {
+        if let Some((_, items)) = &mut self.module.content {
+            tag_items(items)?;
+        }
+        Ok(self.module.into_token_stream())
+    }
+}
+
+/// Adds `#[derive(unique_uuid::UniqueTypeTag)]` to the structs and enums of `items` and of
+/// their inline modules, except those already implementing the trait and those marked
+/// `#[unique_type_tag(skip)]`.
+fn tag_items(items: &mut [Item]) -> syn::Result<()> {
+    let implemented: Vec<Ident> = items.iter().filter_map(manual_impl).collect();
+    for item in items {
+        let (attrs, ident) = match item {
+            Item::Struct(item) => (&mut item.attrs, &item.ident),
+            Item::Enum(item) => (&mut item.attrs, &item.ident),
+            Item::Mod(ItemMod {
+                content: Some((_, items)),
+                ..
+            }) => {
+                tag_items(items)?;
+                continue;
+            }
+            _ => continue,
+        };
+        let skip = take_skip(attrs)?;
+        let derived = attrs.iter().any(derives_type_tag);
+        if let Some(skip) = skip {
+            if derived {
+                return Err(syn::Error::new(
+                    skip.span(),
+                    "`skip` cannot be combined with `#[derive(UniqueTypeTag)]`",
+                ));
+            }
+            continue;
+        }
+        if derived || implemented.contains(ident) {
+            continue;
+        }
+        attrs.insert(0, parse_quote!(#[derive(unique_uuid::UniqueTypeTag)]));
+    }
+    Ok(())
+}
+
+/// Returns the type of `item` if it is a manual implementation of `UniqueTypeTag`.
+fn manual_impl(item: &Item) -> Option<Ident> {
+    let Item::Impl(item) = item else {
+        return None;
+    };
+    let (_, r#trait, _) = item.trait_.as_ref()?;
+    if !is_type_tag(r#trait) {
+        return None;
+    }
+    match &*item.self_ty {
+        syn::Type::Path(ty) => ty.path.segments.last().map(|last| last.ident.clone()),
+        _ => None,
+    }
+}
+
+/// Returns `true` if `attr` is a `#[derive(...)]` including `UniqueTypeTag`.
+fn derives_type_tag(attr: &Attribute) -> bool {
+    attr.path().is_ident("derive")
+        && attr
+            .parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+            .is_ok_and(|derives| derives.iter().any(is_type_tag))
+}
+
+/// Returns `true` if `path` names the `UniqueTypeTag` trait or derive.
+fn is_type_tag(path: &Path) -> bool {
+    path.segments
+        .last()
+        .is_some_and(|last| last.ident == "UniqueTypeTag")
+}
+
+/// Removes the `#[unique_type_tag(skip)]` attribute of `attrs`, returning its `skip`.
+///
+/// Fails if `skip` is combined with other options, which only apply to tagged types.
+fn take_skip(attrs: &mut Vec<Attribute>) -> syn::Result<Option<Path>> {
+    let mut skip = None;
+    let mut other = None;
+    for (index, attr) in attrs.iter().enumerate() {
+        if !attr.path().is_ident("unique_type_tag") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = Some((index, meta.path));
+            } else {
+                other.get_or_insert(meta.path.span());
+                // The value of the option is left to the derive
+                if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<syn::Expr>()?;
+                }
+            }
+            Ok(())
+        })?;
+    }
+    match (skip, other) {
+        (Some(_), Some(span)) => Err(syn::Error::new(
+            span,
+            "`skip` cannot be combined with other `unique_type_tag` options",
+        )),
+        (Some((index, skip)), None) => {
+            attrs.remove(index);
+            Ok(Some(skip))
+        }
+        (None, _) => Ok(None),
+    }
+}
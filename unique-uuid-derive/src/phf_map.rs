@@ -0,0 +1,181 @@
+//! Generation of static maps keyed by tags, with a perfect hash function.
+use proc_macro2::TokenStream;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, Ident, LitStr, Token,
+};
+
+/// The input of `tag_phf_map!`: `"<tag>" => <value>, ...`, or `namespace = "<namespace>"`.
+#[cfg_attr(not(feature = "phf"), allow(dead_code))]
+pub(crate) enum TagPhfMap {
+    Entries(Punctuated<MapEntry, Token![,]>),
+    Namespace(LitStr),
+}
+
+/// An entry `"<tag>" => <value>` of `tag_phf_map!`.
+#[cfg_attr(not(feature = "phf"), allow(dead_code))]
+pub(crate) struct MapEntry {
+    key: LitStr,
+    value: Expr,
+}
+
+impl Parse for MapEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let value = input.parse()?;
+        Ok(Self { key, value })
+    }
+}
+
+impl Parse for TagPhfMap {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if !input.peek(Ident) {
+            return Ok(Self::Entries(Punctuated::parse_terminated(input)?));
+        }
+        let option: Ident = input.parse()?;
+        if option != "namespace" {
+            return Err(syn::Error::new_spanned(
+                option,
+                "expected `\"tag\" => value` entries or `namespace = \"…\"`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let namespace = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self::Namespace(namespace))
+    }
+}
+
+impl TagPhfMap {
+    #[cfg(feature = "phf")]
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        use quote::{quote, ToTokens};
+        use unique_uuid_core::{NamespaceTags, RegistryHandle, Section, TagOptions};
+
+        use crate::{diagnostics, location};
+
+        let registry = RegistryHandle::from_env();
+        let mut entries: Vec<(LitStr, uuid::Uuid, TokenStream)> = Vec::new();
+        let mut warnings = Vec::new();
+        let mut dependency = None;
+        match self {
+            Self::Entries(input) => {
+                for MapEntry { key, value } in input {
+                    if entries
+                        .iter()
+                        .any(|(other, ..)| other.value() == key.value())
+                    {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            format!("the tag `{}` is already a key of the map", key.value()),
+                        ));
+                    }
+                    let source = location::source(key.span());
+                    let options = TagOptions {
+                        source: source.as_deref(),
+                        ..TagOptions::default()
+                    };
+                    let resolved = registry
+                        .get_uuid_from_tag(&key.value(), Section::UniqueTags, &options)
+                        .map_err(|err| syn::Error::new(key.span(), err))?;
+                    warnings.extend(
+                        resolved
+                            .warnings
+                            .iter()
+                            .map(|warning| diagnostics::warning(warning, key.span())),
+                    );
+                    entries.push((key, resolved.uuid, value.into_token_stream()));
+                }
+            }
+            Self::Namespace(namespace) => {
+                let NamespaceTags {
+                    tags,
+                    warnings: ignored,
+                } = registry
+                    .namespace_tags(&namespace.value())
+                    .map_err(|err| syn::Error::new(namespace.span(), err))?;
+                warnings.extend(
+                    ignored
+                        .iter()
+                        .map(|warning| diagnostics::warning(warning, namespace.span())),
+                );
+                if tags.is_empty() {
+                    return Err(syn::Error::new(
+                        namespace.span(),
+                        format!(
+                            "the registry has no tag in the `{}` namespace",
+                            namespace.value()
+                        ),
+                    ));
+                }
+                for (key, uuid) in tags {
+                    if let Err(warning) = unique_uuid_core::track_usage(&key, &Section::UniqueTags)
+                    {
+                        warnings.push(diagnostics::warning(&warning, namespace.span()));
+                    }
+                    let key = LitStr::new(&key, namespace.span());
+                    entries.push((key.clone(), uuid, key.into_token_stream()));
+                }
+
+                // Reading the registry through `include_bytes!` makes the compiler
+                // re-expand the macro whenever the registry changes, an in-memory registry
+                // has no file
+                if !registry.is_in_memory() {
+                    let registry = registry.path().to_string_lossy().into_owned();
+                    dependency = Some(quote! { const _: &[u8] = include_bytes!(#registry); });
+                }
+            }
+        }
+        for (index, (key, uuid, _)) in entries.iter().enumerate() {
+            if let Some((other, ..)) = entries[..index].iter().find(|(_, other, _)| other == uuid) {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "`{}` and `{}` are both tagged `{}`",
+                        other.value(),
+                        key.value(),
+                        uuid
+                    ),
+                ));
+            }
+        }
+
+        let keys: Vec<[u8; 16]> = entries
+            .iter()
+            .map(|(_, uuid, _)| *uuid.as_bytes())
+            .collect();
+        let state = phf_generator::generate_hash(&keys);
+        let hash_key = state.key;
+        let disps = state
+            .disps
+            .iter()
+            .map(|(first, second)| quote! { (#first, #second) });
+        let slots = state.map.iter().map(|&index| {
+            let (_, uuid, value) = &entries[index];
+            let bytes = uuid.as_bytes();
+            quote! { ([#(#bytes),*], #value) }
+        });
+
+        Ok(quote! {
+            {
+                #dependency
+                #(#warnings)*
+                unique_uuid::phf_map::TagPhfMap::from_phf(unique_uuid::phf::Map {
+                    key: #hash_key,
+                    disps: &[#(#disps),*],
+                    entries: &[#(#slots),*],
+                })
+            }
+        })
+    }
+
+    #[cfg(not(feature = "phf"))]
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`tag_phf_map!` requires the `phf` feature of `unique-uuid`",
+        ))
+    }
+}
@@ -0,0 +1,100 @@
+//! Registration of the derived types in the distributed slices of `unique-uuid`.
+use proc_macro2::TokenStream;
+use syn::{Generics, Ident, Path};
+
+/// Returns the registration of `ident`, whose registry key is `key`, in
+/// `unique_uuid::registry::TYPE_TAGS`.
+///
+/// Only non-generic types are registered, and only with the `linkme` feature of
+/// `unique-uuid`: without it, the slice does not exist.
+pub(crate) fn registration(ident: &Ident, generics: &Generics, key: &str) -> Option<TokenStream> {
+    if !cfg!(feature = "linkme") || !generics.params.is_empty() {
+        return None;
+    }
+    Some(quote::quote! {
+        const _: () = {
+            #[unique_uuid::linkme::distributed_slice(unique_uuid::registry::TYPE_TAGS)]
+            #[linkme(crate = unique_uuid::linkme)]
+            static TYPE_TAG_ENTRY: unique_uuid::registry::TypeTagEntry =
+                unique_uuid::registry::TypeTagEntry {
+                    tag: <#ident as unique_uuid::UniqueTypeTag>::TYPE_TAG,
+                    key: #key,
+                    type_name: ::core::concat!(
+                        ::core::module_path!(),
+                        "::",
+                        ::core::stringify!(#ident)
+                    ),
+                    crate_name: ::core::env!("CARGO_PKG_NAME"),
+                };
+        };
+    })
+}
+
+/// Returns the record of `ident`, whose registry key is `key`, in the linker section
+/// listing the type tags of the binary, see `unique_uuid::tag_section`.
+///
+/// Only non-generic types get a record, and only with the `tag-section` feature of
+/// `unique-uuid`. The section names must stay in sync with the constants of
+/// `unique_uuid::tag_section`.
+pub(crate) fn section_record(ident: &Ident, generics: &Generics, key: &str) -> Option<TokenStream> {
+    if !cfg!(feature = "tag-section") || !generics.params.is_empty() {
+        return None;
+    }
+    Some(quote::quote! {
+        #[cfg(not(target_family = "wasm"))]
+        const _: () = {
+            const KEY: &str = #key;
+            const LENGTH: usize = unique_uuid::tag_section::record_len(KEY);
+            #[used]
+            #[cfg_attr(
+                any(target_os = "macos", target_os = "ios"),
+                link_section = "__DATA,__uu_tags"
+            )]
+            #[cfg_attr(windows, link_section = ".uu_tags")]
+            #[cfg_attr(
+                not(any(target_os = "macos", target_os = "ios", windows)),
+                link_section = ".unique_uuid_tags"
+            )]
+            static TAG_RECORD: [u8; LENGTH] = unique_uuid::tag_section::record(
+                <#ident as unique_uuid::UniqueTypeTag>::TYPE_TAG,
+                KEY,
+            );
+        };
+    })
+}
+
+/// Returns the registration of the constructor of `ident` in
+/// `unique_uuid::registry::FACTORIES`, building its `Default` value.
+///
+/// # Errors
+/// Fails if the `linkme` feature of `unique-uuid` is disabled, `flag` being the option
+/// requesting the registration.
+#[cfg(feature = "linkme")]
+pub(crate) fn factory(_flag: &Path, ident: &Ident) -> syn::Result<TokenStream> {
+    Ok(quote::quote! {
+        const _: () = {
+            #[unique_uuid::linkme::distributed_slice(unique_uuid::registry::FACTORIES)]
+            #[linkme(crate = unique_uuid::linkme)]
+            static FACTORY_ENTRY: unique_uuid::registry::FactoryEntry =
+                unique_uuid::registry::FactoryEntry {
+                    tag: <#ident as unique_uuid::UniqueTypeTag>::TYPE_TAG,
+                    type_name: ::core::concat!(
+                        ::core::module_path!(),
+                        "::",
+                        ::core::stringify!(#ident)
+                    ),
+                    construct: || {
+                        ::std::boxed::Box::new(<#ident as ::core::default::Default>::default())
+                    },
+                };
+        };
+    })
+}
+
+#[cfg(not(feature = "linkme"))]
+pub(crate) fn factory(flag: &Path, _ident: &Ident) -> syn::Result<TokenStream> {
+    Err(syn::Error::new_spanned(
+        flag,
+        "`factory` requires the `linkme` feature of `unique-uuid`",
+    ))
+}
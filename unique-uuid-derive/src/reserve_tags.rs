@@ -0,0 +1,120 @@
+//! Generation of a block of related tags reserved at once.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitInt, LitStr, RangeLimits, Token,
+};
+use unique_uuid_core::RegistryHandle;
+
+use crate::{diagnostics, location};
+
+/// The input of `reserve_tags!`: `"<prefix>", <start>..<end>` or
+/// `"<prefix>", <start>..=<end>`, with integer literals, optionally preceded by `test,`
+/// and followed by a comma.
+pub(crate) struct ReserveTags {
+    test: bool,
+    prefix: LitStr,
+    start: u64,
+    end: u64,
+}
+
+impl Parse for ReserveTags {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(input.error("expected a string literal naming the tags"));
+        }
+        let test = input.peek(Ident) && input.peek2(Token![,]);
+        if test {
+            let name: Ident = input.parse()?;
+            if name != "test" {
+                return Err(syn::Error::new_spanned(
+                    &name,
+                    format!(
+                        "unknown `reserve_tags!` parameter `{}` before the tags, expected `test`",
+                        name
+                    ),
+                ));
+            }
+            input.parse::<Token![,]>()?;
+        }
+        let prefix = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let start: LitInt = input.parse().map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "expected a range of integer literals, e.g. `0..16`",
+            )
+        })?;
+        let limits: RangeLimits = input.parse()?;
+        let end: LitInt = input.parse().map_err(|err| {
+            syn::Error::new(
+                err.span(),
+                "expected a range of integer literals, e.g. `0..16`",
+            )
+        })?;
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the range"));
+        }
+
+        let end_value = end.base10_parse::<u64>()?;
+        let end_value = match limits {
+            RangeLimits::HalfOpen(_) => end_value,
+            RangeLimits::Closed(_) => end_value
+                .checked_add(1)
+                .ok_or_else(|| syn::Error::new_spanned(&end, "the range is too large"))?,
+        };
+        let start_value = start.base10_parse::<u64>()?;
+        if start_value >= end_value {
+            return Err(syn::Error::new_spanned(
+                quote!(#start #limits #end),
+                "the range is empty",
+            ));
+        }
+        Ok(Self {
+            test,
+            prefix,
+            start: start_value,
+            end: end_value,
+        })
+    }
+}
+
+impl ReserveTags {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let span = self.prefix.span();
+        let prefix = self.prefix.value();
+        let keys: Vec<String> = (self.start..self.end)
+            .map(|index| format!("{}.{}", prefix, index))
+            .collect();
+        let source = location::source(span);
+        let registry = RegistryHandle::from_env();
+        let registry = if self.test {
+            registry.test_registry()
+        } else {
+            registry
+        };
+        let reserved = registry
+            .reserve_tags(&keys, source.as_deref())
+            .map_err(|err| syn::Error::new(span, err))?;
+
+        let warnings = reserved
+            .warnings
+            .iter()
+            .map(|warning| diagnostics::warning(warning, span));
+        let tags = reserved.uuids.iter().map(|uuid| {
+            let uuid = LitStr::new(&uuid.to_string(), span);
+            quote!(unique_uuid::UniqueTag(unique_uuid::uuid::uuid!(#uuid)))
+        });
+        Ok(quote! {
+            {
+                #(#warnings)*
+                [#(#tags),*]
+            }
+        })
+    }
+}
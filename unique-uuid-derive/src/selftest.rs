@@ -0,0 +1,56 @@
+//! Generation of the registry consistency tests of `#[unique_type_tag(selftest)]`.
+use proc_macro2::TokenStream;
+use syn::{Ident, Path};
+use unique_uuid_core::RegistryHandle;
+
+/// Returns a test, compiled under `#[cfg(test)]`, asserting that the registry still
+/// stores the `TYPE_TAG` of `ident` under `key`. The test is named after `ident`, so that
+/// several types of a module may be tested. An in-memory registry, which no test could
+/// read back, gets no test.
+///
+/// # Errors
+/// Fails if the `registry-file` feature of `unique-uuid` is disabled, `flag` being the
+/// option requesting the test.
+#[cfg(feature = "registry-file")]
+pub(crate) fn test(
+    _flag: &Path,
+    ident: &Ident,
+    key: &str,
+    registry: &RegistryHandle,
+) -> syn::Result<TokenStream> {
+    use syn::ext::IdentExt;
+
+    if registry.is_in_memory() {
+        return Ok(TokenStream::new());
+    }
+    let name = quote::format_ident!("unique_type_tag_selftest_{}", ident.unraw());
+    // A registry directory is loaded whole, as the key may be stored by another crate
+    let path = registry.directory().unwrap_or_else(|| registry.path());
+    let path = path.to_string_lossy();
+    Ok(quote::quote! {
+        #[cfg(test)]
+        #[test]
+        #[allow(non_snake_case)]
+        fn #name() {
+            unique_uuid::registry::assert_type_tag(
+                #path,
+                #key,
+                ::core::concat!(::core::module_path!(), "::", ::core::stringify!(#ident)),
+                <#ident as unique_uuid::UniqueTypeTag>::TYPE_TAG,
+            );
+        }
+    })
+}
+
+#[cfg(not(feature = "registry-file"))]
+pub(crate) fn test(
+    flag: &Path,
+    _ident: &Ident,
+    _key: &str,
+    _registry: &RegistryHandle,
+) -> syn::Result<TokenStream> {
+    Err(syn::Error::new_spanned(
+        flag,
+        "`selftest` requires the `registry-file` feature of `unique-uuid`",
+    ))
+}
@@ -0,0 +1,154 @@
+//! Dispatch enums over tagged payloads, for `#[derive(TagDispatch)]`.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{spanned::Spanned, Data, DeriveInput, Fields, Ident, Type};
+
+use crate::compat;
+
+/// Returns the kind enum of `input`, an enum whose variants each wrap a single tagged
+/// type, and the methods dispatching between its variants by tag.
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "`TagDispatch` is only supported on enums",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`TagDispatch` does not support generic enums",
+        ));
+    }
+    if data.variants.is_empty() {
+        return Err(syn::Error::new(
+            input.ident.span(),
+            "`TagDispatch` requires at least one variant",
+        ));
+    }
+
+    let mut variants: Vec<(&Ident, &Type, String)> = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        let mut fields = match &variant.fields {
+            Fields::Unnamed(fields) => fields.unnamed.iter(),
+            Fields::Named(fields) => fields.named.iter(),
+            Fields::Unit => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "`TagDispatch` variants must wrap a single tagged type",
+                ))
+            }
+        };
+        let (Some(field), None) = (fields.next(), fields.next()) else {
+            return Err(syn::Error::new_spanned(
+                &variant.fields,
+                "`TagDispatch` variants must wrap a single tagged type",
+            ));
+        };
+        // Types are compared as written, aliases are caught by the check of the tags
+        let ty = &field.ty;
+        let written = quote!(#ty).to_string();
+        if let Some((other, ..)) = variants.iter().find(|(.., w)| *w == written) {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                format!("this type is already wrapped by the variant `{}`", other),
+            ));
+        }
+        variants.push((&variant.ident, &field.ty, written));
+    }
+
+    let ident = &input.ident;
+    let vis = &input.vis;
+    let kind = format_ident!("{}Kind", ident);
+    let count = variants.len();
+    let names: Vec<&Ident> = variants.iter().map(|(name, ..)| *name).collect();
+    // Spanned on the field, which is where a missing implementation must be reported
+    let tags: Vec<TokenStream> = variants
+        .iter()
+        .map(|(_, ty, _)| {
+            quote_spanned! {ty.span()=> <#ty as unique_uuid::UniqueTypeTag>::TYPE_TAG }
+        })
+        .collect();
+    let kind_doc = format!(" The variants of [`{}`], without their payload.", ident);
+    let variant_docs = names
+        .iter()
+        .map(|name| format!(" The variant [`{}::{}`].", ident, name));
+    let guard = compat::guard(compat::INTERFACE);
+
+    Ok(quote! {
+        #[doc = #kind_doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #vis enum #kind {
+            #(
+                #[doc = #variant_docs]
+                #names,
+            )*
+        }
+
+        impl #kind {
+            /// Every variant, in declaration order.
+            pub const ALL: [#kind; #count] = [#(#kind::#names),*];
+
+            /// Returns the tag of the type wrapped by the variant.
+            pub const fn tag(&self) -> unique_uuid::UniqueTag {
+                match self {
+                    #(#kind::#names => #tags,)*
+                }
+            }
+        }
+
+        impl ::core::convert::TryFrom<unique_uuid::UniqueTag> for #kind {
+            type Error = unique_uuid::UnknownTag;
+
+            fn try_from(tag: unique_uuid::UniqueTag) -> ::core::result::Result<Self, Self::Error> {
+                #(
+                    if tag == #tags {
+                        return ::core::result::Result::Ok(#kind::#names);
+                    }
+                )*
+                ::core::result::Result::Err(unique_uuid::UnknownTag(tag))
+            }
+        }
+
+        impl ::core::convert::From<#kind> for unique_uuid::UniqueTag {
+            fn from(value: #kind) -> Self {
+                value.tag()
+            }
+        }
+
+        impl #ident {
+            /// The tags of the types wrapped by the variants, in declaration order.
+            pub const SUPPORTED_TAGS: &'static [unique_uuid::UniqueTag] = &[#(#tags),*];
+
+            /// Returns the variant, without its payload.
+            pub const fn kind(&self) -> #kind {
+                match self {
+                    #(#ident::#names { .. } => #kind::#names,)*
+                }
+            }
+
+            /// Returns the tag of the type wrapped by the variant.
+            pub const fn tag_of_variant(&self) -> unique_uuid::UniqueTag {
+                self.kind().tag()
+            }
+        }
+
+        // Distinct types may still share a tag, e.g. through a transparent wrapper
+        const _: () = {
+            let tags = #ident::SUPPORTED_TAGS;
+            let mut i = 0;
+            while i < tags.len() {
+                let mut j = i + 1;
+                while j < tags.len() {
+                    ::core::assert!(
+                        !tags[i].const_eq(&tags[j]),
+                        "two variants of a `TagDispatch` enum wrap types with the same tag"
+                    );
+                    j += 1;
+                }
+                i += 1;
+            }
+        };
+        #guard
+    })
+}
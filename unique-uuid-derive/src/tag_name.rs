@@ -0,0 +1,66 @@
+//! Lookup of the key of a registry entry at expansion time.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, LitStr, Token,
+};
+
+use unique_uuid_core::{EntryName, RegistryHandle};
+
+use crate::{diagnostics, input};
+
+/// The input of `tag_name_of!`: `"<key>"` or `uuid = "<uuid>"`.
+pub(crate) enum TagName {
+    /// A key of the registry, or an alias of one.
+    Key(LitStr),
+
+    /// The UUID of an entry of the registry.
+    Uuid(LitStr),
+}
+
+impl Parse for TagName {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = if input.peek(LitStr) {
+            TagName::Key(input.parse()?)
+        } else {
+            let option: Ident = input.parse()?;
+            if option != "uuid" {
+                return Err(syn::Error::new_spanned(
+                    option,
+                    "expected a key or `uuid = \"…\"`",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            TagName::Uuid(input.parse()?)
+        };
+        input.parse::<Option<Token![,]>>()?;
+        Ok(name)
+    }
+}
+
+impl TagName {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let registry = RegistryHandle::from_env();
+        let (literal, found) = match &self {
+            TagName::Key(key) => (key, registry.canonical_key(&key.value())),
+            TagName::Uuid(uuid) => (uuid, registry.key_of_uuid(input::pinned_uuid(uuid)?)),
+        };
+        let EntryName {
+            key,
+            section,
+            mut warnings,
+        } = found.map_err(|err| syn::Error::new(literal.span(), err))?;
+        warnings.extend(unique_uuid_core::track_usage(&key, &section).err());
+
+        let warnings = warnings
+            .iter()
+            .map(|warning| diagnostics::warning(warning, literal.span()));
+        Ok(quote! {
+            {
+                #(#warnings)*
+                #key
+            }
+        })
+    }
+}
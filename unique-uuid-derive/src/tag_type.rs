@@ -0,0 +1,77 @@
+//! Generation of a marker type standing for a single tag.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Attribute, Ident, Token, Visibility,
+};
+
+use crate::{diagnostics, input::TagInput};
+
+/// The input of `unique_tag_type!`: `<attrs> <vis> struct <Name> = <tag>`, where `<tag>`
+/// is the input of `unique_tag!`.
+pub(crate) struct TagType {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    tag: TagInput,
+}
+
+impl Parse for TagType {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let tag = input.parse()?;
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            tag,
+        })
+    }
+}
+
+impl TagType {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let resolved = self.tag.resolve()?;
+        let span = self.tag.tag.span();
+        let warnings = resolved
+            .warnings
+            .iter()
+            .map(|warning| diagnostics::warning(warning, span));
+
+        let TagType {
+            attrs, vis, ident, ..
+        } = self;
+        // Documented unless the invocation documents the type itself
+        let doc = (!attrs.iter().any(|attr| attr.path().is_ident("doc")))
+            .then(|| format!(" The tag `{}` (`{}`).", self.tag.key(), resolved.uuid))
+            .map(|doc| quote!(#[doc = #doc]));
+        let uuid = syn::LitStr::new(&resolved.uuid.to_string(), span);
+
+        Ok(quote! {
+            #(#attrs)*
+            #doc
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            #vis struct #ident;
+
+            impl #ident {
+                /// The tag this type stands for.
+                pub const TAG: unique_uuid::UniqueTag =
+                    unique_uuid::UniqueTag(unique_uuid::uuid::uuid!(#uuid));
+            }
+
+            impl ::core::convert::From<#ident> for unique_uuid::UniqueTag {
+                fn from(_: #ident) -> Self {
+                    #ident::TAG
+                }
+            }
+
+            #(#warnings)*
+        })
+    }
+}
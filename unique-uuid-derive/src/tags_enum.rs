@@ -0,0 +1,185 @@
+//! Generation of an enum over the tags of a registry namespace.
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    Attribute, Ident, LitStr, Token, Visibility,
+};
+
+use unique_uuid_core::{NamespaceTags, RegistryHandle, Section};
+
+use crate::diagnostics;
+
+/// The input of `tags_enum!`: `<attrs> <vis> enum <Name>, namespace = "<namespace>"`.
+pub(crate) struct TagsEnum {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    ident: Ident,
+    namespace: LitStr,
+}
+
+impl Parse for TagsEnum {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![enum]>()?;
+        let ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+
+        let option: Ident = input.parse()?;
+        if option != "namespace" {
+            return Err(syn::Error::new_spanned(
+                option,
+                "expected `namespace = \"…\"`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let namespace = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            namespace,
+        })
+    }
+}
+
+impl TagsEnum {
+    pub(crate) fn expand(self) -> syn::Result<TokenStream> {
+        let namespace = self.namespace.value();
+        let registry = RegistryHandle::from_env();
+        let NamespaceTags { tags, warnings } = registry
+            .namespace_tags(&namespace)
+            .map_err(|err| syn::Error::new(self.namespace.span(), err))?;
+        let mut warnings = warnings
+            .iter()
+            .map(|warning| diagnostics::warning(warning, self.namespace.span()))
+            .collect::<Vec<_>>();
+        if tags.is_empty() {
+            return Err(syn::Error::new(
+                self.namespace.span(),
+                format!("the registry has no tag in the `{}` namespace", namespace),
+            ));
+        }
+
+        let mut variants: Vec<(Ident, &str, uuid::Uuid)> = Vec::with_capacity(tags.len());
+        for (key, uuid) in &tags {
+            if let Err(warning) = unique_uuid_core::track_usage(key, &Section::UniqueTags) {
+                warnings.push(diagnostics::warning(&warning, self.namespace.span()));
+            }
+
+            let name = variant_name(&key[namespace.len()..]);
+            if let Some((_, other, _)) = variants.iter().find(|(ident, ..)| *ident == name) {
+                return Err(syn::Error::new(
+                    self.namespace.span(),
+                    format!(
+                        "`{}` and `{}` both map to the variant `{}`",
+                        other, key, name
+                    ),
+                ));
+            }
+            variants.push((format_ident!("{}", name), key, *uuid));
+        }
+
+        let TagsEnum {
+            attrs, vis, ident, ..
+        } = self;
+        let count = variants.len();
+        let docs = variants
+            .iter()
+            .map(|(_, key, uuid)| format!(" `{}` (`{}`)", key, uuid));
+        let names: Vec<&Ident> = variants.iter().map(|(ident, ..)| ident).collect();
+        let uuids: Vec<String> = variants.iter().map(|(.., uuid)| uuid.to_string()).collect();
+        let mut by_tag: Vec<&(Ident, &str, uuid::Uuid)> = variants.iter().collect();
+        by_tag.sort_by_key(|(.., uuid)| *uuid);
+        let by_tag = by_tag.into_iter().map(|(ident, ..)| ident);
+
+        // Reading the registry through `include_bytes!` makes the compiler re-expand the
+        // macro whenever the registry changes, an in-memory registry has no file
+        let dependency = (!registry.is_in_memory()).then(|| {
+            let registry = registry.path().to_string_lossy().into_owned();
+            quote!(
+                const _: &[u8] = include_bytes!(#registry);
+            )
+        });
+
+        Ok(quote! {
+            #(#attrs)*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            #vis enum #ident {
+                #(
+                    #[doc = #docs]
+                    #names,
+                )*
+            }
+
+            impl #ident {
+                /// Every variant, in key order.
+                pub const ALL: [#ident; #count] = [#(#ident::#names),*];
+
+                /// Returns the tag of the variant.
+                pub const fn tag(&self) -> unique_uuid::UniqueTag {
+                    match self {
+                        #(
+                            #ident::#names => unique_uuid::UniqueTag(unique_uuid::uuid::uuid!(#uuids)),
+                        )*
+                    }
+                }
+
+                /// Returns the variant of `tag`, if any.
+                pub const fn from_tag(tag: unique_uuid::UniqueTag) -> ::core::option::Option<Self> {
+                    // The variants sorted by tag, searched by halves
+                    const BY_TAG: [#ident; #count] = [#(#ident::#by_tag),*];
+                    let (mut low, mut high) = (0, BY_TAG.len());
+                    while low < high {
+                        let middle = low + (high - low) / 2;
+                        match BY_TAG[middle].tag().const_cmp(&tag) {
+                            ::core::cmp::Ordering::Less => low = middle + 1,
+                            ::core::cmp::Ordering::Greater => high = middle,
+                            ::core::cmp::Ordering::Equal => {
+                                return ::core::option::Option::Some(BY_TAG[middle]);
+                            }
+                        }
+                    }
+                    ::core::option::Option::None
+                }
+            }
+
+            impl ::core::convert::TryFrom<unique_uuid::UniqueTag> for #ident {
+                type Error = unique_uuid::UnknownTag;
+
+                fn try_from(tag: unique_uuid::UniqueTag) -> ::core::result::Result<Self, Self::Error> {
+                    #ident::from_tag(tag).ok_or(unique_uuid::UnknownTag(tag))
+                }
+            }
+
+            impl ::core::convert::From<#ident> for unique_uuid::UniqueTag {
+                fn from(value: #ident) -> Self {
+                    value.tag()
+                }
+            }
+
+            #dependency
+            #(#warnings)*
+        })
+    }
+}
+
+/// Turns the part of a key following its namespace into an `UpperCamelCase` variant
+/// name, e.g. `.order_created` into `OrderCreated`.
+fn variant_name(suffix: &str) -> String {
+    let mut name = String::with_capacity(suffix.len());
+    for word in suffix.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
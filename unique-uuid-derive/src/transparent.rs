@@ -0,0 +1,71 @@
+//! Tags delegated to the wrapped type, for `#[unique_type_tag(transparent)]`.
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Path};
+
+use crate::{attributes::TypeTagAttributes, compat};
+
+/// Returns the implementation of `UniqueTypeTag` for `input`, a struct with a single
+/// field, whose tag is the tag of the type of that field.
+///
+/// The registry is not involved, so `transparent` cannot be combined with the options
+/// describing the entry of the type.
+pub(crate) fn implementation(
+    transparent: &Path,
+    input: &DeriveInput,
+    attributes: &TypeTagAttributes,
+) -> syn::Result<TokenStream> {
+    let combined = attributes.rename.is_some()
+        || attributes.uuid.is_some()
+        || attributes.export_c.is_some()
+        || attributes.compose.is_some()
+        || attributes.bevy.is_some()
+        || attributes.selftest.is_some()
+        || attributes.factory.is_some()
+        || !attributes.aliases.is_empty()
+        || !attributes.meta.is_empty()
+        || attributes.groups.is_some();
+    if combined {
+        return Err(syn::Error::new_spanned(
+            transparent,
+            "`transparent` cannot be combined with other `unique_type_tag` options",
+        ));
+    }
+
+    let mut fields = match &input.data {
+        Data::Struct(data) => data.fields.iter(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                transparent,
+                "`transparent` is only supported on structs",
+            ))
+        }
+    };
+    let field = match (fields.next(), fields.next()) {
+        (Some(field), None) => field,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                transparent,
+                "`transparent` requires a struct with exactly one field",
+            ))
+        }
+    };
+
+    let inner = &field.ty;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(#inner: unique_uuid::UniqueTypeTag));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = &input.ident;
+    let guard = compat::guard(compat::INTERFACE);
+
+    Ok(quote! {
+        impl #impl_generics unique_uuid::UniqueTypeTag for #ident #ty_generics #where_clause {
+            const TYPE_TAG: unique_uuid::UniqueTag =
+                <#inner as unique_uuid::UniqueTypeTag>::TYPE_TAG;
+        }
+        #guard
+    })
+}
@@ -0,0 +1,171 @@
+//! Runs the same scenarios against every storage backend of the registry, which must
+//! behave identically.
+mod common;
+
+use common::{Backend, Scratch};
+use unique_uuid::registry::{Entry, Registry, Section};
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+fn check(scratch: &Scratch, source: &str, env: &[(&str, &str)]) -> String {
+    let output = scratch.check(source, env);
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn new_entries_are_persisted_canonically() {
+    for backend in Backend::ALL {
+        let scratch = Scratch::with_backend("backend-entries", "canonical.toml", backend);
+        check(&scratch, SOURCE, &[]);
+
+        let registry = scratch.load();
+        assert_eq!(
+            registry.get("orders.created").unwrap().tag.0.to_string(),
+            "2af1216e-7042-406a-a577-c39a7e7c3faa",
+            "{:?}",
+            backend
+        );
+        assert!(registry.get("orders.paid").is_some(), "{:?}", backend);
+        assert!(registry.get("::Invoice").is_some(), "{:?}", backend);
+        let contents = scratch.registry();
+        assert_eq!(scratch.canonical(&registry), contents, "{:?}", backend);
+
+        // Expanding again leaves the registry untouched
+        check(&scratch, SOURCE, &[]);
+        assert_eq!(scratch.registry(), contents, "{:?}", backend);
+    }
+}
+
+#[test]
+fn renamed_types_keep_their_uuid() {
+    for backend in Backend::ALL {
+        let scratch = Scratch::with_backend("backend-rename", "canonical.toml", backend);
+        check(
+            &scratch,
+            "#[derive(unique_uuid::UniqueTypeTag)]\n\
+             #[unique_type_tag(rename = \"::Order\")]\nstruct Purchase;\n\nfn main() {}\n",
+            &[],
+        );
+
+        let registry = scratch.load();
+        assert_eq!(
+            registry.get("::Purchase").unwrap().tag.0.to_string(),
+            "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db",
+            "{:?}",
+            backend
+        );
+        assert!(registry.get("::Order").is_none(), "{:?}", backend);
+        assert_eq!(scratch.canonical(&registry), scratch.registry());
+    }
+}
+
+#[test]
+fn journal_defers_writes_until_compaction() {
+    for backend in Backend::ALL {
+        let scratch = Scratch::with_backend("backend-journal", "journal.toml", backend);
+        let before = scratch.registry();
+        check(&scratch, SOURCE, &[]);
+
+        assert_eq!(scratch.registry(), before, "{:?}", backend);
+        assert_eq!(scratch.journal().lines().count(), 2, "{:?}", backend);
+        let registry = scratch.load();
+        assert!(registry.get("orders.paid").is_some(), "{:?}", backend);
+
+        let path = scratch.path(backend.file_name());
+        assert_eq!(Registry::compact(path).unwrap(), 2, "{:?}", backend);
+        assert_eq!(scratch.journal(), "", "{:?}", backend);
+        assert_eq!(scratch.load(), registry, "{:?}", backend);
+        assert_eq!(scratch.canonical(&registry), scratch.registry());
+    }
+}
+
+#[test]
+fn stale_caches_are_ignored() {
+    for backend in Backend::ALL {
+        let scratch = Scratch::with_backend("backend-cache", "cache.toml", backend);
+        let _ = std::fs::remove_file(scratch.sidecar("cache"));
+        check(&scratch, SOURCE, &[]);
+        assert!(scratch.sidecar("cache").exists(), "{:?}", backend);
+
+        // An entry added by hand makes the cache stale: it must be seen, not re-minted
+        let mut registry = scratch.load();
+        let uuid = "ce4baeef-bd9c-4932-88be-24eb1033f695".parse().unwrap();
+        registry.insert(Section::UniqueTags, "orders.refunded", Entry::new(uuid));
+        let edited = scratch.canonical(&registry);
+        std::fs::write(scratch.path(backend.file_name()), &edited).unwrap();
+        check(
+            &scratch,
+            "fn main() {\n    let _ = unique_uuid::unique_tag!(\"orders.refunded\");\n}\n",
+            &[],
+        );
+        assert_eq!(scratch.registry(), edited, "{:?}", backend);
+    }
+}
+
+#[test]
+fn malformed_entries_are_reported() {
+    for backend in Backend::ALL {
+        let scratch = Scratch::with_backend("backend-malformed", "one_malformed.toml", backend);
+        let output = scratch.check(SOURCE, &[]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "{}", stderr);
+        assert!(
+            stderr.contains("`orders.refunded` in [unique_tags] is malformed"),
+            "{}",
+            stderr
+        );
+
+        let stderr = check(&scratch, SOURCE, &[("UNIQUE_UUID_LENIENT", "1")]);
+        assert!(stderr.contains("Ignoring a malformed entry"), "{}", stderr);
+        assert!(scratch
+            .load()
+            .validate()
+            .iter()
+            .any(|issue| issue.to_string().contains("orders.refunded")));
+    }
+}
+
+#[test]
+fn strict_tag_style_is_enforced() {
+    for backend in Backend::ALL {
+        let scratch = Scratch::with_backend("backend-style", "tag_style_strict.toml", backend);
+        let before = scratch.registry();
+        let output = scratch.check(
+            "fn main() {\n    let _ = unique_uuid::unique_tag!(\"orders.order_shipped\");\n}\n",
+            &[],
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "{}", stderr);
+        assert!(
+            stderr.contains("consider `orders.order-shipped`"),
+            "{}",
+            stderr
+        );
+        assert_eq!(scratch.registry(), before, "{:?}", backend);
+    }
+}
+
+#[test]
+fn ron_registries_require_the_feature() {
+    let scratch = Scratch::new("backend-ron-disabled", "canonical.toml");
+    let contents = scratch.registry();
+    std::fs::write(scratch.path("types.ron"), "{}\n").unwrap();
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_REGISTRY", "types.ron")]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("RON registries require the `ron` feature of `unique-uuid`"),
+        "{}",
+        stderr
+    );
+    assert_eq!(scratch.registry(), contents);
+    assert_eq!(
+        std::fs::read_to_string(scratch.path("types.ron")).unwrap(),
+        "{}\n"
+    );
+}
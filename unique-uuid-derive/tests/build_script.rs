@@ -0,0 +1,51 @@
+//! Registries resolved by a build script with `unique_uuid::build`, which the macros then
+//! only read.
+mod common;
+
+use std::fs;
+
+use common::{fixture, Scratch};
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+#[test]
+fn macros_only_read_entries_minted_by_the_build_script() {
+    let scratch = Scratch::new("build-script", "canonical.toml");
+    let path = scratch.path("types.toml");
+    unique_uuid::build::ensure_tags(&path, &["orders.created", "orders.paid"]).unwrap();
+    unique_uuid::build::ensure_type_tags(&path, &["Invoice"]).unwrap();
+    let minted = scratch.registry();
+    assert_eq!(scratch.canonical(&scratch.load()), minted);
+
+    // Already resolved, the registry is left untouched by both
+    unique_uuid::build::ensure_tags(&path, &["orders.paid"]).unwrap();
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(scratch.registry(), minted);
+}
+
+#[test]
+fn build_scripts_append_to_the_journal_like_the_macros() {
+    let scratch = Scratch::new("build-script-journal", "journal.toml");
+    let _ = fs::remove_file(scratch.path("types.toml.journal"));
+    let path = scratch.path("types.toml");
+    unique_uuid::build::ensure_tags(&path, &["orders.paid"]).unwrap();
+    unique_uuid::build::ensure_type_tags(&path, &["Invoice"]).unwrap();
+
+    assert_eq!(scratch.registry(), fixture("journal.toml"));
+    assert!(scratch.journal().contains("\"orders.paid\""));
+    assert!(scratch.journal().contains("\"::Invoice\""));
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,62 @@
+//! Expands the macros against a registry configured with `cache = true`, whose sidecar
+//! cache must never change the outcome.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+
+const SOURCE: &str = "fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+fn check(scratch: &Scratch, source: &str) {
+    let output = scratch.check(source, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn cache_is_rebuilt_when_stale_or_corrupted() {
+    let scratch = Scratch::new("cache", "cache.toml");
+    let cache = scratch.path("types.toml.cache");
+    let _ = fs::remove_file(&cache);
+
+    check(&scratch, SOURCE);
+    assert!(cache.exists());
+    let registry = scratch.registry();
+    assert!(registry.contains("\"orders.paid\" = "));
+
+    // An entry added by hand makes the cache stale: it must be seen, not re-minted
+    let edited = registry.replace(
+        "\"orders.paid\"",
+        "\"orders.refunded\" = \"ce4baeef-bd9c-4932-88be-24eb1033f695\"\n\"orders.paid\"",
+    );
+    fs::write(scratch.path("types.toml"), &edited).unwrap();
+    check(
+        &scratch,
+        "fn main() {\n    let _ = unique_uuid::unique_tag!(\"orders.refunded\");\n}\n",
+    );
+    assert_eq!(scratch.registry(), edited);
+
+    // A corrupted or deleted cache falls back to the registry file
+    for corrupted in [
+        &b"UUCACHE1"[..],
+        b"garbage",
+        &fs::read(&cache).unwrap()[..40],
+    ] {
+        fs::write(&cache, corrupted).unwrap();
+        check(
+            &scratch,
+            "fn main() {\n    let _ = unique_uuid::unique_tag!(\"orders.refunded\");\n}\n",
+        );
+        assert_eq!(scratch.registry(), edited);
+    }
+    fs::remove_file(&cache).unwrap();
+    check(&scratch, SOURCE);
+    assert_eq!(scratch.registry(), edited);
+    assert!(cache.exists());
+}
@@ -0,0 +1,196 @@
+//! Scratch crates expanding the macros against a fixture registry.
+//!
+//! Every test binary compiles this module, but not all of them use every helper.
+#![allow(dead_code)]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use unique_uuid::registry::{Format, Registry};
+
+/// A storage format of the registry, selected by the extension of its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `types.toml`, the default.
+    Toml,
+    /// `types.json`, selected with `UNIQUE_UUID_REGISTRY`.
+    Json,
+    /// `types.ron`, selected with `UNIQUE_UUID_REGISTRY` and the `ron` feature.
+    Ron,
+    /// `types.yaml`, selected with `UNIQUE_UUID_REGISTRY` and the `yaml` feature.
+    Yaml,
+}
+
+impl Backend {
+    /// Every backend, to run a test against each.
+    pub const ALL: [Self; 4] = [Self::Toml, Self::Json, Self::Ron, Self::Yaml];
+
+    /// Returns the name of the registry file.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Toml => "types.toml",
+            Self::Json => "types.json",
+            Self::Ron => "types.ron",
+            Self::Yaml => "types.yaml",
+        }
+    }
+
+    fn format(self) -> Format {
+        Format::of(self.file_name())
+    }
+}
+
+/// A crate depending on `unique-uuid`, next to a copy of a fixture registry.
+pub struct Scratch {
+    directory: PathBuf,
+    backend: Backend,
+}
+
+impl Scratch {
+    /// Creates the crate `name` with a copy of `tests/fixtures/<fixture>` as registry.
+    pub fn new(name: &str, fixture: &str) -> Self {
+        Self::with_backend(name, fixture, Backend::Toml)
+    }
+
+    /// Creates the crate `name` with the registry `tests/fixtures/<fixture>`, a TOML
+    /// file, converted to `backend`.
+    pub fn with_backend(name: &str, fixture: &str, backend: Backend) -> Self {
+        let (name, features) = match backend {
+            Backend::Toml => (name.to_string(), "[]"),
+            Backend::Json => (format!("{}-json", name), "[]"),
+            Backend::Ron => (format!("{}-ron", name), "[\"ron\"]"),
+            Backend::Yaml => (format!("{}-yaml", name), "[\"yaml\"]"),
+        };
+        let directory = std::env::temp_dir().join(format!("unique-uuid-derive-{}", name));
+        fs::create_dir_all(directory.join("src")).unwrap();
+        fs::write(
+            directory.join("Cargo.toml"),
+            format!(
+                "[package]\nname = {:?}\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+                 [dependencies]\nunique-uuid = {{ path = {:?}, features = {} }}\n\n\
+                 [workspace]\n",
+                name,
+                manifest_dir().join("../unique-uuid"),
+                features
+            ),
+        )
+        .unwrap();
+        let registry = directory.join(backend.file_name());
+        match backend {
+            Backend::Toml => {
+                fs::copy(fixture_path(fixture), registry).unwrap();
+            }
+            Backend::Json | Backend::Ron | Backend::Yaml => {
+                let contents = Registry::parse(&self::fixture(fixture)).unwrap();
+                fs::write(registry, contents.to_format_string(backend.format())).unwrap();
+            }
+        }
+        Self { directory, backend }
+    }
+
+    /// Checks the crate with `source` as its `main.rs`. The source is rewritten every
+    /// time, so the crate is never considered fresh.
+    pub fn check(&self, source: &str, env: &[(&str, &str)]) -> Output {
+        fs::write(self.directory.join("src/main.rs"), source).unwrap();
+        self.cargo("check", env)
+    }
+
+    /// Runs the tests of the crate with `source` as its `main.rs`. Unlike
+    /// [`Scratch::check`], an unchanged source is not rewritten, so that the crate is not
+    /// expanded again after an edit of the registry.
+    pub fn test(&self, source: &str, env: &[(&str, &str)]) -> Output {
+        let main = self.directory.join("src/main.rs");
+        if fs::read_to_string(&main).ok().as_deref() != Some(source) {
+            fs::write(main, source).unwrap();
+        }
+        self.cargo("test", env)
+    }
+
+    /// Runs the crate with `source` as its `main.rs`, rewritten as by [`Scratch::check`].
+    pub fn run(&self, source: &str, env: &[(&str, &str)]) -> Output {
+        fs::write(self.directory.join("src/main.rs"), source).unwrap();
+        self.cargo("run", env)
+    }
+
+    /// Runs `cargo <subcommand>` in the crate, with only the `UNIQUE_UUID_*` variables
+    /// of `env`.
+    fn cargo(&self, subcommand: &str, env: &[(&str, &str)]) -> Output {
+        let mut command = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+        command
+            .arg(subcommand)
+            .current_dir(&self.directory)
+            .env(
+                "CARGO_TARGET_DIR",
+                std::env::temp_dir().join("unique-uuid-derive-scratch-target"),
+            )
+            .envs(env.iter().copied());
+        if self.backend != Backend::Toml {
+            command.env("UNIQUE_UUID_REGISTRY", self.backend.file_name());
+        }
+        for (name, _) in std::env::vars() {
+            if name.starts_with("UNIQUE_UUID_") && !env.iter().any(|(set, _)| *set == name) {
+                command.env_remove(name);
+            }
+        }
+        command.output().unwrap()
+    }
+
+    /// Returns the content of the registry of the crate.
+    pub fn registry(&self) -> String {
+        fs::read_to_string(self.directory.join(self.backend.file_name())).unwrap()
+    }
+
+    /// Loads the registry of the crate, with its journal.
+    pub fn load(&self) -> Registry {
+        Registry::load(self.directory.join(self.backend.file_name())).unwrap()
+    }
+
+    /// Returns the canonical serialization of `registry` in the format of the backend.
+    pub fn canonical(&self, registry: &Registry) -> String {
+        registry.to_format_string(self.backend.format())
+    }
+
+    /// Appends `contents` to the manifest of the crate.
+    pub fn extend_manifest(&self, contents: &str) {
+        let manifest = fs::read_to_string(self.path("Cargo.toml")).unwrap();
+        fs::write(self.path("Cargo.toml"), manifest + contents).unwrap();
+    }
+
+    /// Returns the path of the file `name` next to the registry of the crate.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.directory.join(name)
+    }
+
+    /// Returns the content of the journal of the registry, empty if there is none.
+    pub fn journal(&self) -> String {
+        fs::read_to_string(self.sidecar("journal")).unwrap_or_default()
+    }
+
+    /// Replaces the journal of the registry with `contents`.
+    pub fn set_journal(&self, contents: &str) {
+        fs::write(self.sidecar("journal"), contents).unwrap();
+    }
+
+    /// Returns the path of the file kept next to the registry with the `extension`
+    /// appended to its name, e.g. `types.toml.journal`.
+    pub fn sidecar(&self, extension: &str) -> PathBuf {
+        self.directory
+            .join(format!("{}.{}", self.backend.file_name(), extension))
+    }
+}
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Returns the content of `tests/fixtures/<fixture>`.
+pub fn fixture(fixture: &str) -> String {
+    fs::read_to_string(fixture_path(fixture)).unwrap()
+}
+
+/// Returns the path of `tests/fixtures/<fixture>`.
+pub fn fixture_path(fixture: &str) -> PathBuf {
+    manifest_dir().join("tests/fixtures").join(fixture)
+}
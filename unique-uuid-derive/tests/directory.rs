@@ -0,0 +1,70 @@
+//! Registries stored as a directory holding one file per crate, e.g.
+//! `UNIQUE_UUID_REGISTRY=types.d`.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+use unique_uuid::registry::Registry;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+const SHIPPING: &str = "[unique_tags]\n\
+                        \"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n";
+
+#[test]
+fn crates_only_write_their_own_file() {
+    let scratch = Scratch::new("directory-entries", "canonical.toml");
+    fs::create_dir_all(scratch.path("types.d")).unwrap();
+    fs::write(scratch.path("types.d/shipping.toml"), SHIPPING).unwrap();
+
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_REGISTRY", "types.d")]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The existing tag is read from the file of the other crate, which is untouched
+    assert_eq!(
+        fs::read_to_string(scratch.path("types.d/shipping.toml")).unwrap(),
+        SHIPPING
+    );
+    let own = Registry::load(scratch.path("types.d/directory-entries.toml")).unwrap();
+    assert!(own.get("orders.created").is_none());
+    assert!(own.get("orders.paid").is_some());
+    assert!(own.get("::Invoice").is_some());
+
+    let registry = Registry::load(scratch.path("types.d")).unwrap();
+    assert_eq!(
+        registry.get("orders.created").unwrap().tag.0.to_string(),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+    assert!(registry.get("orders.paid").is_some());
+}
+
+#[test]
+fn keys_stored_in_two_files_are_reported() {
+    let scratch = Scratch::new("directory-collision", "canonical.toml");
+    fs::create_dir_all(scratch.path("types.d")).unwrap();
+    fs::write(scratch.path("types.d/shipping.toml"), SHIPPING).unwrap();
+    fs::write(
+        scratch.path("types.d/support.toml"),
+        "[unique_tags]\n\"orders.created\" = \"ce4baeef-bd9c-4932-88be-24eb1033f695\"\n",
+    )
+    .unwrap();
+
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_REGISTRY", "types.d")]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("`orders.created` in [unique_tags] is stored in both"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("shipping.toml"), "{}", stderr);
+    assert!(stderr.contains("support.toml"), "{}", stderr);
+}
@@ -0,0 +1,137 @@
+//! The constructors registered by `#[unique_type_tag(factory)]`.
+mod common;
+
+use common::Scratch;
+
+const SOURCE: &str = r#"use std::any::Any;
+
+use unique_uuid::{
+    registry::{construct, construct_as},
+    unique_tag, UniqueTag, UniqueTypeTag,
+};
+
+#[derive(UniqueTypeTag, Debug, PartialEq)]
+#[unique_type_tag(factory)]
+struct Parcel {
+    weight: u32,
+}
+
+impl Default for Parcel {
+    fn default() -> Self {
+        Parcel { weight: 250 }
+    }
+}
+
+#[derive(UniqueTypeTag, Default, Debug, PartialEq)]
+#[unique_type_tag(factory)]
+struct Letter(String);
+
+#[derive(UniqueTypeTag, Default)]
+struct Postcard;
+
+/// Builds the value of the type denoted by `tag`, as a plugin loader would.
+fn describe(tag: UniqueTag) -> String {
+    let value: Box<dyn Any> = construct(tag).unwrap();
+    if let Some(parcel) = value.downcast_ref::<Parcel>() {
+        format!("parcel of {}g", parcel.weight)
+    } else if let Some(letter) = value.downcast_ref::<Letter>() {
+        format!("letter {:?}", letter.0)
+    } else {
+        unreachable!()
+    }
+}
+
+fn main() {
+    assert_eq!(describe(Parcel::TYPE_TAG), "parcel of 250g");
+    assert_eq!(describe(Letter::TYPE_TAG), "letter \"\"");
+
+    // Typed construction checks the type
+    assert_eq!(
+        construct_as::<Parcel>(Parcel::TYPE_TAG),
+        Some(Parcel { weight: 250 })
+    );
+    assert_eq!(
+        construct_as::<Letter>(Letter::TYPE_TAG),
+        Some(Letter(String::new()))
+    );
+    assert_eq!(construct_as::<Letter>(Parcel::TYPE_TAG), None);
+
+    // Unregistered tags build nothing
+    assert!(construct(Postcard::TYPE_TAG).is_none());
+    assert!(construct(unique_tag!("parcels.lost")).is_none());
+}
+
+#[test]
+fn values_are_built_from_their_tags() {
+    main();
+}
+"#;
+
+const CONFLICT: &str = r#"use unique_uuid::{registry::construct, UniqueTypeTag};
+
+mod inbound {
+    #[derive(unique_uuid::UniqueTypeTag, Default)]
+    #[unique_type_tag(factory)]
+    pub struct Crate;
+}
+
+mod outbound {
+    // The key of a type is its name, so both types share the entry `::Crate`
+    #[derive(unique_uuid::UniqueTypeTag, Default)]
+    #[unique_type_tag(factory)]
+    pub struct Crate;
+}
+
+fn main() {}
+
+#[test]
+#[should_panic(expected = "are both registered as the factory of the tag")]
+fn shared_tags_panic_on_the_first_lookup() {
+    assert_eq!(inbound::Crate::TYPE_TAG, outbound::Crate::TYPE_TAG);
+    construct(inbound::Crate::TYPE_TAG);
+}
+"#;
+
+fn scratch(name: &str) -> Scratch {
+    let scratch = Scratch::new(name, "canonical.toml");
+    scratch.extend_manifest(
+        "\n[features]\ndefault = [\"unique-uuid/registry-file\", \"unique-uuid/linkme\"]\n",
+    );
+    scratch
+}
+
+#[test]
+fn values_are_built_from_their_tags() {
+    let scratch = scratch("factory");
+    let output = scratch.test(SOURCE, &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("values_are_built_from_their_tags ... ok"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn shared_tags_panic_on_the_first_lookup() {
+    let scratch = scratch("factory-conflict");
+    let output = scratch.test(CONFLICT, &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("shared_tags_panic_on_the_first_lookup - should panic ... ok"),
+        "{}",
+        stdout
+    );
+}
@@ -0,0 +1,55 @@
+//! The fingerprints `registry_fingerprint!` declares in crates built against a registry and
+//! against a fork of it.
+mod common;
+
+use common::Scratch;
+use unique_uuid::{check_fingerprint, FingerprintMismatch, UniqueTag};
+use uuid::Uuid;
+
+const SOURCE: &str = r#"unique_uuid::registry_fingerprint!();
+
+fn main() {
+    println!("{}", REGISTRY_FINGERPRINT.0);
+}
+"#;
+
+/// Builds the crate `name` against `registry` and returns the fingerprint it declares.
+fn fingerprint(name: &str, registry: impl FnOnce(String) -> String) -> UniqueTag {
+    let scratch = Scratch::new(name, "canonical.toml");
+    scratch.extend_manifest("\n[features]\ndefault = [\"unique-uuid/fingerprint\"]\n");
+    std::fs::write(scratch.path("types.toml"), registry(scratch.registry())).unwrap();
+
+    let output = scratch.run(SOURCE, &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    // The constant matches the fingerprint computed at runtime
+    let declared = UniqueTag(Uuid::parse_str(stdout.trim()).unwrap());
+    assert_eq!(declared, scratch.load().fingerprint(None));
+    declared
+}
+
+#[test]
+fn crates_built_against_a_forked_file_disagree() {
+    let ours = fingerprint("fingerprint", |registry| registry);
+    let theirs = fingerprint("fingerprint-fork", |registry| {
+        // The fork gives `::Order` another UUID
+        let forked = registry.replace(
+            "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db",
+            "9d0c1f53-52a4-4f4e-8d5a-14b1b2a1f0de",
+        );
+        assert_ne!(forked, registry);
+        forked
+    });
+
+    assert_ne!(ours, theirs);
+    assert_eq!(
+        check_fingerprint(ours, theirs),
+        Err(FingerprintMismatch { ours, theirs })
+    );
+    assert_eq!(check_fingerprint(ours, ours), Ok(()));
+}
@@ -0,0 +1,42 @@
+//! Groups recorded in the `groups` array of the entries.
+mod common;
+
+use common::Scratch;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\n\
+                      #[unique_type_tag(groups(\"billing\"))]\n\
+                      struct Invoice;\n\n\
+                      fn main() {\n    \
+                          let _ = unique_uuid::unique_tag!(\"invoice.sent\", groups = [\"billing\", \"external\"]);\n    \
+                          let _ = unique_uuid::unique_tag!(\"invoice.voided\", groups = [\"billing\"]);\n\
+                      }\n";
+
+#[test]
+fn groups_are_recorded_and_queried() {
+    let scratch = Scratch::new("groups", "canonical.toml");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let registry = scratch.load();
+    let billing: Vec<_> = registry.tags_in_group("billing").collect();
+    assert_eq!(billing, ["invoice.sent", "invoice.voided", "::Invoice"]);
+    let groups: Vec<_> = registry.groups_of("invoice.sent").collect();
+    assert_eq!(groups, ["billing", "external"]);
+    assert_eq!(registry.tags_in_group("retention").count(), 0);
+}
+
+#[test]
+fn duplicate_groups_are_rejected() {
+    let scratch = Scratch::new("groups-duplicate", "canonical.toml");
+    let source = "fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"invoice.sent\", groups = [\"billing\", \"billing\"]);\n\
+                  }\n";
+    let output = scratch.check(source, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("duplicate group"), "{}", stderr);
+}
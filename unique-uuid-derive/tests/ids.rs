@@ -0,0 +1,46 @@
+//! Small integer ids, allocated alongside the UUIDs with `ids = true`.
+mod common;
+
+use common::Scratch;
+use unique_uuid::registry::Section;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Order;\n\n\
+                      #[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      const CREATED: u32 = unique_uuid::unique_tag_id!(\"orders.created\");\n\n\
+                      const _: () = assert!(<Order as unique_uuid::UniqueTypeId>::TYPE_ID == 1);\n\
+                      const _: () = assert!(<Invoice as unique_uuid::UniqueTypeId>::TYPE_ID == 5);\n\
+                      const _: () = assert!(CREATED == 2);\n\n\
+                      fn main() {}\n";
+
+#[test]
+fn ids_follow_the_last_one_allocated() {
+    let scratch = Scratch::new("ids", "ids.toml");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let registry = scratch.load();
+    let invoice = registry
+        .entry(Section::UniqueTypeTags, "::Invoice")
+        .unwrap();
+    assert_eq!(invoice.id(), Some(5));
+    assert!(scratch.registry().contains("last = 5"));
+}
+
+#[test]
+fn ids_must_be_enabled() {
+    let scratch = Scratch::new("ids-disabled", "canonical.toml");
+    let source = "const CREATED: u32 = unique_uuid::unique_tag_id!(\"orders.created\");\n\n\
+                  fn main() {}\n";
+    let output = scratch.check(source, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("`orders.created` has no id: set `ids = true`"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,77 @@
+//! The in-memory registry selected with `UNIQUE_UUID_IN_MEMORY`.
+mod common;
+
+use std::{collections::BTreeSet, fs};
+
+use common::{fixture, Scratch};
+
+const SOURCE: &str = "use unique_uuid::{unique_tag, uuid::uuid, UniqueTag, UniqueTypeTag};\n\n\
+                      #[derive(UniqueTypeTag)]\n\
+                      #[unique_type_tag(selftest)]\n\
+                      struct Order;\n\n\
+                      fn main() {}\n\n\
+                      #[test]\n\
+                      fn tags_are_consistent() {\n    \
+                      assert_eq!(unique_tag!(\"orders.paid\"), unique_tag!(\"orders.paid\"));\n    \
+                      assert_ne!(unique_tag!(\"orders.paid\"), unique_tag!(\"orders.refunded\"));\n    \
+                      assert_ne!(unique_tag!(\"orders.paid\"), Order::TYPE_TAG);\n    \
+                      // The registry file is never read\n    \
+                      assert_ne!(\n        \
+                      unique_tag!(\"orders.created\"),\n        \
+                      UniqueTag(uuid!(\"2af1216e-7042-406a-a577-c39a7e7c3faa\"))\n    \
+                      );\n\
+                      }\n";
+
+fn scratch(name: &str) -> Scratch {
+    let scratch = Scratch::new(name, "canonical.toml");
+    scratch.extend_manifest("\n[features]\ndefault = [\"unique-uuid/registry-file\"]\n");
+    scratch
+}
+
+/// Returns the names of the files and directories of the crate.
+fn listing(scratch: &Scratch) -> BTreeSet<String> {
+    fs::read_dir(scratch.path(""))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name != "Cargo.lock")
+        .collect()
+}
+
+#[test]
+fn no_file_is_created() {
+    let scratch = scratch("in-memory");
+    fs::remove_file(scratch.path("types.toml")).unwrap();
+    let before = listing(&scratch);
+
+    // The flag overrides the configured registry
+    let output = scratch.test(
+        SOURCE,
+        &[
+            ("UNIQUE_UUID_IN_MEMORY", "1"),
+            ("UNIQUE_UUID_REGISTRY", "registry/types.json"),
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("tags_are_consistent ... ok"), "{}", stdout);
+    assert!(!stdout.contains("selftest"), "{}", stdout);
+    assert_eq!(listing(&scratch), before);
+}
+
+#[test]
+fn the_registry_file_is_left_untouched() {
+    let scratch = scratch("in-memory-untouched");
+    let output = scratch.test(SOURCE, &[("UNIQUE_UUID_IN_MEMORY", "1")]);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(scratch.registry(), fixture("canonical.toml"));
+}
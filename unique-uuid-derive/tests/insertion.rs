@@ -0,0 +1,46 @@
+//! Adds entries to a canonical registry, which must only gain their lines and stay
+//! canonical.
+mod common;
+
+use common::{fixture, Scratch};
+use unique_uuid::registry::Registry;
+
+#[test]
+fn new_entries_only_add_their_lines() {
+    let scratch = Scratch::new("insertion", "canonical.toml");
+    let output = scratch.check(
+        "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+         fn main() {\n    \
+         let _ = unique_uuid::unique_tag!(\"analytics.viewed\");\n    \
+         let _ = unique_uuid::unique_tag!(\"orders.paid\");\n    \
+         let _ = unique_uuid::unique_tag!(\"shipping.delayed\");\n}\n",
+        &[],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let registry = scratch.registry();
+    assert_eq!(
+        Registry::parse(&registry).unwrap().to_toml_string(),
+        registry,
+        "the registry is not canonical anymore"
+    );
+
+    let new_keys = [
+        "\"analytics.viewed\"",
+        "\"orders.paid\"",
+        "\"shipping.delayed\"",
+        "\"::Invoice\"",
+    ];
+    let untouched: Vec<&str> = registry
+        .lines()
+        .filter(|line| !new_keys.iter().any(|key| line.starts_with(key)))
+        .collect();
+    assert_eq!(
+        untouched,
+        fixture("canonical.toml").lines().collect::<Vec<_>>()
+    );
+}
@@ -0,0 +1,68 @@
+//! Records new entries in the journal of a registry configured with `journal = true`.
+mod common;
+
+use common::{fixture, Scratch};
+use unique_uuid::registry::Registry;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.shipped\");\n}\n";
+
+#[test]
+fn new_entries_are_appended_to_the_journal() {
+    let scratch = Scratch::new("journal", "journal.toml");
+    // A line torn by an interrupted write is ignored, and does not swallow new entries
+    scratch.set_journal("unique_tags.\"orders.refu");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(scratch.registry(), fixture("journal.toml"));
+    let journal = scratch.journal();
+    let lines: Vec<&str> = journal.lines().collect();
+    assert_eq!(lines.len(), 4, "{}", journal);
+    assert_eq!(lines[0], "unique_tags.\"orders.refu");
+    assert!(lines[1..]
+        .iter()
+        .any(|line| line.starts_with("unique_tags.\"orders.paid\" = ")));
+    assert!(lines[1..]
+        .iter()
+        .any(|line| line.starts_with("unique_type_tags.\"::Invoice\" = ")));
+    assert!(!journal.contains("orders.created"));
+
+    // Expanding again reuses the journal entries
+    let output = scratch.check(SOURCE, &[]);
+    assert!(output.status.success());
+    assert_eq!(scratch.journal(), journal);
+}
+
+#[test]
+fn journal_is_compacted_past_its_limit() {
+    let scratch = Scratch::new("journal-limit", "journal_limit.toml");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(scratch.journal(), "");
+    let registry = scratch.registry();
+    assert_eq!(
+        Registry::parse(&registry).unwrap().to_toml_string(),
+        registry
+    );
+    for key in ["\"orders.paid\"", "\"orders.shipped\"", "\"::Invoice\""] {
+        assert!(
+            registry.contains(key),
+            "{} is missing from\n{}",
+            key,
+            registry
+        );
+    }
+}
@@ -0,0 +1,44 @@
+//! Adds entries to a registry saved with a byte order mark and `\r\n` line endings, which
+//! must both be preserved.
+mod common;
+
+use common::{fixture, Scratch};
+use unique_uuid::registry::Registry;
+
+#[test]
+fn byte_order_mark_and_line_endings_are_preserved() {
+    let scratch = Scratch::new("layout", "windows.toml");
+    let output = scratch.check(
+        "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+         fn main() {\n    \
+         let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+         let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n",
+        &[],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let registry = scratch.registry();
+    let body = registry
+        .strip_prefix('\u{feff}')
+        .expect("the byte order mark was dropped");
+    assert!(
+        !body.replace("\r\n", "").contains('\n'),
+        "a line does not end with \\r\\n:\n{:?}",
+        body
+    );
+    assert!(body.contains("\"orders.paid\" = "));
+    assert!(body.contains("\"::Invoice\" = "));
+
+    let parsed = Registry::parse(&registry).unwrap();
+    let original = Registry::parse(&fixture("windows.toml")).unwrap();
+    assert_eq!(parsed.get("orders.created"), original.get("orders.created"));
+    assert_eq!(
+        parsed.to_toml_string(),
+        body.replace("\r\n", "\n"),
+        "the registry is not canonical anymore"
+    );
+}
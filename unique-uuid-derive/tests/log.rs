@@ -0,0 +1,98 @@
+//! The log of the registry operations enabled with `UNIQUE_UUID_LOG`.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+
+const SOURCE: &str = "fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+/// Checks `scratch` with the log enabled, returning the `event key` pairs logged for
+/// `crate_name`, after checking the fields of each line.
+fn events(scratch: &Scratch, crate_name: &str) -> Vec<String> {
+    let path = scratch.path("log.txt");
+    let _ = fs::remove_file(&path);
+    let output = scratch.check(
+        SOURCE,
+        &[
+            ("UNIQUE_UUID_LOG", "debug"),
+            ("UNIQUE_UUID_LOG_FILE", path.to_str().unwrap()),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let registry = format!(
+        " path={} ",
+        serde_json::Value::String(scratch.path("types.toml").display().to_string())
+    );
+    let contents = fs::read_to_string(&path).unwrap();
+    contents
+        .lines()
+        .filter(|line| line.contains(&format!(" crate=\"{}\" ", crate_name)))
+        .map(|line| {
+            assert!(line.starts_with("unique-uuid: event="), "{}", line);
+            assert!(line.contains(&registry), "{}", line);
+            let (_, elapsed) = line.rsplit_once(" elapsed_us=").unwrap();
+            elapsed.parse::<u64>().unwrap();
+
+            let event = line["unique-uuid: event=".len()..].split(' ').next().unwrap();
+            let (_, key) = line.split_once(" key=").unwrap();
+            let key = key.split(' ').next().unwrap();
+            format!("{} {}", event, key)
+        })
+        .collect()
+}
+
+#[test]
+fn expansions_log_their_operations() {
+    let scratch = Scratch::new("log", "canonical.toml");
+    assert_eq!(
+        events(&scratch, "log"),
+        [
+            "open \"orders.created\"",
+            "parse \"orders.created\"",
+            "open \"orders.paid\"",
+            "parse \"orders.paid\"",
+            "insert \"orders.paid\"",
+            "write \"orders.paid\"",
+            "open \"orders.paid\"",
+            "parse \"orders.paid\"",
+        ]
+    );
+}
+
+#[test]
+fn cached_parses_are_logged() {
+    let scratch = Scratch::new("log-cache", "cache.toml");
+    let _ = fs::remove_file(scratch.path("types.toml.cache"));
+    events(&scratch, "log-cache");
+
+    let events = events(&scratch, "log-cache");
+    assert_eq!(events.len(), 6, "{:?}", events);
+    assert!(
+        events
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .all(|event| event.starts_with("cache_hit ")),
+        "{:?}",
+        events
+    );
+}
+
+#[test]
+fn nothing_is_logged_unless_enabled() {
+    let scratch = Scratch::new("log-disabled", "canonical.toml");
+    let path = scratch.path("log.txt");
+    let _ = fs::remove_file(&path);
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_LOG_FILE", path.to_str().unwrap())]);
+    assert!(output.status.success());
+    assert!(!path.exists());
+}
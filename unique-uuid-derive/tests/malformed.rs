@@ -0,0 +1,69 @@
+//! Expands `unique_tag!` against a registry holding one malformed entry among valid
+//! ones, or bytes that are not UTF-8.
+mod common;
+
+use common::{fixture, Scratch};
+
+const SOURCE: &str = "fn main() {\n    let _ = unique_uuid::unique_tag!(\"orders.created\");\n}\n";
+
+#[test]
+fn malformed_entries_are_named_and_can_be_ignored() {
+    let scratch = Scratch::new("malformed", "one_malformed.toml");
+    let entry = "`orders.refunded` in [unique_tags] is malformed: `not-a-uuid` is not a valid UUID";
+
+    let output = scratch.check(SOURCE, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(stderr.contains(entry), "{}", stderr);
+    assert!(stderr.contains("set `UNIQUE_UUID_LENIENT=1`"), "{}", stderr);
+
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_LENIENT", "1")]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains(&format!("Ignoring a malformed entry: {}", entry)),
+        "{}",
+        stderr
+    );
+
+    // The malformed entry is left untouched
+    assert_eq!(scratch.registry(), fixture("one_malformed.toml"));
+}
+
+#[test]
+fn invalid_utf8_is_located_and_a_valid_backup_offered() {
+    let scratch = Scratch::new("malformed-utf8", "canonical.toml");
+    let mut contents = fixture("canonical.toml").into_bytes();
+    contents[15] = 0xff;
+    std::fs::write(scratch.path("types.toml"), &contents).unwrap();
+    let _ = std::fs::remove_file(scratch.path("types.toml.bak"));
+
+    let output = scratch.check(SOURCE, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains(
+            "invalid UTF-8 at byte 15 (`5f 74 61 67 73 5d 0a 22 [ff] 72 64 65 72 73 2e 63`)"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("git checkout -- types.toml"), "{}", stderr);
+    assert!(!stderr.contains(".bak"), "{}", stderr);
+
+    std::fs::copy(
+        common::fixture_path("canonical.toml"),
+        scratch.path("types.toml.bak"),
+    )
+    .unwrap();
+    let output = scratch.check(SOURCE, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("types.toml.bak` is a valid registry"),
+        "{}",
+        stderr
+    );
+
+    // The registry is left untouched
+    assert_eq!(std::fs::read(scratch.path("types.toml")).unwrap(), contents);
+}
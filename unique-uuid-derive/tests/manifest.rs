@@ -0,0 +1,99 @@
+//! Registries stored in the `[package.metadata.unique-uuid]` table of the manifest.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+use unique_uuid::registry::Registry;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+const METADATA: &str = "\n# The tags of the crate\n\
+                        [package.metadata.unique-uuid.config]\n\
+                        registry = \"cargo-metadata\"\n\n\
+                        [package.metadata.unique-uuid.unique_tags]\n\
+                        # Kept by hand\n\
+                        'orders.created' = \"2af1216e-7042-406a-a577-c39a7e7c3faa\" # first\n\
+                        \"orders.refunded\" = \"ce4baeef-bd9c-4932-88be-24eb1033f695\"\n\n\
+                        [features]\ndefault = []\n";
+
+#[test]
+fn entries_are_stored_in_the_manifest() {
+    let scratch = Scratch::new("manifest-entries", "canonical.toml");
+    fs::remove_file(scratch.path("types.toml")).unwrap();
+    scratch.extend_manifest(METADATA);
+    let before = fs::read_to_string(scratch.path("Cargo.toml")).unwrap();
+
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!scratch.path("types.toml").exists());
+
+    // Only the new entries are written, in order, the rest of the manifest as it was
+    let manifest = fs::read_to_string(scratch.path("Cargo.toml")).unwrap();
+    let line = |key: &str| {
+        manifest
+            .lines()
+            .find(|line| line.starts_with(&format!("\"{}\" = ", key)))
+            .unwrap()
+    };
+    let expected = before
+        .replace(
+            "\"orders.refunded\"",
+            &format!("{}\n\"orders.refunded\"", line("orders.paid")),
+        )
+        .replace(
+            "\n[features]",
+            &format!(
+                "\n[package.metadata.unique-uuid.unique_type_tags]\n{}\n\n[features]",
+                line("::Invoice")
+            ),
+        );
+    assert_eq!(manifest, expected);
+
+    let registry = Registry::load(scratch.path("Cargo.toml")).unwrap();
+    let paid = registry.get("orders.paid").unwrap().tag.0.to_string();
+    assert!(line("orders.paid").contains(&paid));
+    assert_eq!(
+        registry.get("orders.created").unwrap().tag.0.to_string(),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+
+    // Expanding again leaves the manifest untouched
+    let output = scratch.check(SOURCE, &[]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(scratch.path("Cargo.toml")).unwrap(),
+        manifest
+    );
+}
+
+#[test]
+fn virtual_manifests_are_rejected() {
+    let scratch = Scratch::new("manifest-virtual", "canonical.toml");
+    fs::create_dir_all(scratch.path("root")).unwrap();
+    fs::write(
+        scratch.path("root/Cargo.toml"),
+        "[workspace]\nmembers = []\n",
+    )
+    .unwrap();
+
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_REGISTRY", "root/Cargo.toml")]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("a virtual workspace manifest cannot hold the registry"),
+        "{}",
+        stderr
+    );
+    assert_eq!(
+        fs::read_to_string(scratch.path("root/Cargo.toml")).unwrap(),
+        "[workspace]\nmembers = []\n"
+    );
+}
@@ -0,0 +1,68 @@
+//! Named scalar values recorded in the `meta` table of the entries.
+mod common;
+
+use common::Scratch;
+use unique_uuid::registry::{MetaValue, Section};
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\n\
+                      #[unique_type_tag(meta(code = 7, legacy = false, topic = \"orders\"))]\n\
+                      struct Order;\n\n\
+                      const _: () = assert!(Order::TYPE_TAG_CODE == 7);\n\
+                      const _: () = assert!(!Order::TYPE_TAG_LEGACY);\n\
+                      const _: &str = Order::TYPE_TAG_TOPIC;\n\n\
+                      fn main() {\n    \
+                          let _ = unique_uuid::unique_tag!(\"orders.created\", meta(code = 8));\n\
+                      }\n";
+
+#[test]
+fn meta_values_are_recorded_and_emitted() {
+    let scratch = Scratch::new("meta", "canonical.toml");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let registry = scratch.load();
+    let order = registry.entry(Section::UniqueTypeTags, "::Order").unwrap();
+    assert_eq!(order.meta("code"), Some(MetaValue::Integer(7)));
+    assert_eq!(
+        order.meta("topic"),
+        Some(MetaValue::String("orders".to_string()))
+    );
+    let created = registry
+        .entry(Section::UniqueTags, "orders.created")
+        .unwrap();
+    assert_eq!(created.meta("code"), Some(MetaValue::Integer(8)));
+
+    // Without the attribute, the constants still come from the registry
+    let contents = scratch.registry();
+    let source = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Order;\n\n\
+                  const _: () = assert!(Order::TYPE_TAG_CODE == 7);\n\n\
+                  fn main() {}\n";
+    let output = scratch.check(source, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(scratch.registry(), contents);
+}
+
+#[test]
+fn unique_meta_values_are_distinct() {
+    let scratch = Scratch::new("meta-unique", "unique_meta.toml");
+    let source = "#[derive(unique_uuid::UniqueTypeTag)]\n\
+                  #[unique_type_tag(meta(code = 7))]\n\
+                  struct Order;\n\n\
+                  fn main() {}\n";
+    let output = scratch.check(source, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("`orders.created` in [unique_tags] already has it"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,87 @@
+//! Notices of the entries minted during a build, and `UNIQUE_UUID_DENY_NEW`.
+mod common;
+
+use common::Scratch;
+use unique_uuid::registry::Section;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+#[test]
+fn new_entries_are_reported() {
+    let scratch = Scratch::new("minted-notice", "canonical.toml");
+    let output = scratch.check(SOURCE, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+
+    let paid = scratch.load().get("orders.paid").unwrap().tag.0;
+    assert!(
+        stderr.contains(&format!("unique-uuid: minted `orders.paid` = {}", paid)),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("unique-uuid: minted `::Invoice`"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("2 new entries in `"), "{}", stderr);
+    assert!(!stderr.contains("minted `orders.created`"), "{}", stderr);
+}
+
+#[test]
+fn new_entries_record_their_source() {
+    let scratch = Scratch::new("minted-source", "canonical.toml");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The location is left out when the compiler does not provide it
+    let registry = scratch.load();
+    let source = |section, key| registry.entry(section, key).unwrap().source();
+    assert!(matches!(
+        source(Section::UniqueTags, "orders.paid"),
+        None | Some("src/main.rs:6")
+    ));
+    assert!(matches!(
+        source(Section::UniqueTypeTags, "::Invoice"),
+        None | Some("src/main.rs:2")
+    ));
+    assert_eq!(source(Section::UniqueTags, "orders.created"), None);
+}
+
+#[test]
+fn new_entries_can_be_denied() {
+    let scratch = Scratch::new("minted-denied", "canonical.toml");
+    let before = scratch.registry();
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_DENY_NEW", "1")]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("`orders.paid` is not in [unique_tags] of `"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("`UNIQUE_UUID_DENY_NEW` forbids minting new entries"),
+        "{}",
+        stderr
+    );
+    assert_eq!(scratch.registry(), before);
+
+    // Existing entries still resolve
+    let output = scratch.check(
+        "fn main() {\n    let _ = unique_uuid::unique_tag!(\"orders.created\");\n}\n",
+        &[("UNIQUE_UUID_DENY_NEW", "1")],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,52 @@
+//! Keys of types named with raw identifiers, which never keep their `r#`.
+mod common;
+
+use common::Scratch;
+
+const SOURCE: &str = "#![allow(non_camel_case_types)]\n\n\
+                      #[derive(unique_uuid::UniqueTypeTag)]\nstruct r#type;\n\n\
+                      #[derive(unique_uuid::UniqueTypeTag)]\nstruct r#async;\n\n\
+                      mod raw {\n    \
+                      #[derive(unique_uuid::UniqueTypeTag)]\n    pub struct r#Order;\n}\n\n\
+                      fn main() {}\n";
+
+#[test]
+fn raw_identifiers_are_keyed_without_their_prefix() {
+    let scratch = Scratch::new("raw-identifiers", "canonical.toml");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let registry = scratch.load();
+    assert!(registry.get("::type").is_some());
+    assert!(registry.get("::async").is_some());
+    assert!(!scratch.registry().contains("r#"));
+
+    // `r#Order` is `Order`, and keeps its UUID
+    assert_eq!(
+        registry.get("::Order").unwrap().tag.0.to_string(),
+        "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db"
+    );
+}
+
+#[test]
+fn raw_keys_are_migrated() {
+    let scratch = Scratch::new("raw-identifiers-migrated", "raw_keys.toml");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let contents = scratch.registry();
+    assert!(
+        contents.contains("\"::type\" = \"6aac8026-6ca8-4c82-ac39-d74bedf1ac50\""),
+        "{}",
+        contents
+    );
+    assert!(!contents.contains("r#"), "{}", contents);
+}
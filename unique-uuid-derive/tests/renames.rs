@@ -0,0 +1,72 @@
+//! The warnings suggesting `rename` when a new type key looks like an older one.
+mod common;
+
+use common::Scratch;
+
+/// Checks a crate deriving `UniqueTypeTag` for `types` against the `renames.toml`
+/// fixture, returning the standard error.
+fn check(name: &str, types: &str) -> String {
+    let scratch = Scratch::new(name, "renames.toml");
+    let output = scratch.check(
+        &format!(
+            "use unique_uuid::UniqueTypeTag;\n\n{}\nfn main() {{}}\n",
+            types
+        ),
+        &[],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}", stderr);
+    stderr
+}
+
+#[test]
+fn a_changed_prefix_is_reported() {
+    let stderr = check(
+        "renames-prefix",
+        "#[derive(UniqueTypeTag)]\nstruct Invoice;\n",
+    );
+    assert!(
+        stderr.contains(
+            "`::Invoice` gets a new UUID, but the registry holds a similar entry \
+             `::billing::Invoice` that is not used anymore. If the type was renamed, add \
+             `#[unique_type_tag(rename = \"::billing::Invoice\")]` to keep its UUID."
+        ),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn a_close_identifier_is_reported() {
+    let stderr = check(
+        "renames-distance",
+        "#[derive(UniqueTypeTag)]\nstruct Shipmnts;\n",
+    );
+    assert!(
+        stderr.contains(
+            "`::Shipmnts` gets a new UUID, but the registry holds a similar entry `::Shipment`"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("`::Order`"), "{}", stderr);
+}
+
+#[test]
+fn unrelated_keys_are_not_reported() {
+    let stderr = check(
+        "renames-unrelated",
+        "#[derive(UniqueTypeTag)]\nstruct Warehouse;\n",
+    );
+    assert!(!stderr.contains("similar entry"), "{}", stderr);
+}
+
+#[test]
+fn entries_still_in_use_are_not_reported() {
+    let stderr = check(
+        "renames-in-use",
+        "#[derive(UniqueTypeTag)]\nstruct Shipment;\n\n\
+         #[derive(UniqueTypeTag)]\nstruct Shipments;\n",
+    );
+    assert!(!stderr.contains("similar entry `::Shipment`"), "{}", stderr);
+}
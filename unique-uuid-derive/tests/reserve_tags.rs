@@ -0,0 +1,138 @@
+//! Blocks of tags reserved at once with `reserve_tags!`.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+use unique_uuid::UniqueTag;
+
+/// Returns a crate reserving `range` of the `handshake.opcode` block.
+fn source(count: usize, range: &str) -> String {
+    format!(
+        "const OPCODES: [unique_uuid::UniqueTag; {}] =\n    \
+         unique_uuid::reserve_tags!(\"handshake.opcode\", {});\n\n\
+         fn main() {{\n    \
+         let _ = OPCODES;\n}}\n",
+        count, range
+    )
+}
+
+/// Checks `source` in `scratch`, the crate `crate_name`, with the counters enabled,
+/// returning the numbers of entries minted and of files written.
+fn reserve(scratch: &Scratch, crate_name: &str, source: &str) -> (u64, u64) {
+    let path = scratch.path("stats.jsonl");
+    let _ = fs::remove_file(&path);
+    let output = scratch.check(source, &[("UNIQUE_UUID_STATS", path.to_str().unwrap())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let line: serde_json::Value = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .find(|line: &serde_json::Value| line["crate"] == crate_name)
+        .unwrap();
+    (
+        line["minted"].as_u64().unwrap(),
+        line["file_writes"].as_u64().unwrap(),
+    )
+}
+
+/// Returns the tags of the `handshake.opcode` block in the registry, by opcode.
+fn opcodes(scratch: &Scratch) -> Vec<UniqueTag> {
+    let registry = scratch.load();
+    (0..)
+        .map_while(|opcode| registry.get(&format!("handshake.opcode.{}", opcode)))
+        .map(|info| info.tag)
+        .collect()
+}
+
+#[test]
+fn the_block_is_written_at_once() {
+    let scratch = Scratch::new("reserve-tags", "canonical.toml");
+    assert_eq!(
+        reserve(&scratch, "reserve-tags", &source(16, "0..16")),
+        (16, 1)
+    );
+
+    let opcodes = opcodes(&scratch);
+    assert_eq!(opcodes.len(), 16);
+    let mut distinct = opcodes.clone();
+    distinct.sort();
+    distinct.dedup();
+    assert_eq!(distinct.len(), 16);
+    assert!(scratch
+        .registry()
+        .contains("\"orders.created\" = { crate = \"billing\""));
+}
+
+#[test]
+fn reexpanding_writes_nothing() {
+    let scratch = Scratch::new("reserve-tags-again", "canonical.toml");
+    reserve(&scratch, "reserve-tags-again", &source(16, "0..16"));
+    let registry = scratch.registry();
+
+    assert_eq!(
+        reserve(&scratch, "reserve-tags-again", &source(16, "0..16")),
+        (0, 0)
+    );
+    assert_eq!(
+        reserve(&scratch, "reserve-tags-again", &source(4, "2..=5")),
+        (0, 0)
+    );
+    assert_eq!(scratch.registry(), registry);
+}
+
+#[test]
+fn widening_the_range_keeps_the_existing_entries() {
+    let scratch = Scratch::new("reserve-tags-widen", "canonical.toml");
+    reserve(&scratch, "reserve-tags-widen", &source(4, "0..4"));
+    let before = opcodes(&scratch);
+    assert_eq!(before.len(), 4);
+
+    assert_eq!(
+        reserve(&scratch, "reserve-tags-widen", &source(8, "0..8")),
+        (4, 1)
+    );
+    let after = opcodes(&scratch);
+    assert_eq!(after.len(), 8);
+    assert_eq!(after[..4], before[..]);
+
+    // Narrowing the range deletes nothing
+    assert_eq!(
+        reserve(&scratch, "reserve-tags-widen", &source(2, "0..2")),
+        (0, 0)
+    );
+    assert_eq!(opcodes(&scratch), after);
+}
+
+#[test]
+fn empty_ranges_are_rejected() {
+    let scratch = Scratch::new("reserve-tags-empty", "canonical.toml");
+    let output = scratch.check(&source(0, "4..4"), &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("the range is empty"));
+    assert!(!scratch.registry().contains("handshake"));
+}
+
+#[test]
+fn test_blocks_stay_out_of_the_registry() {
+    let scratch = Scratch::new("reserve-tags-test", "canonical.toml");
+    let test_registry = scratch.path("types.test.toml");
+    let _ = fs::remove_file(&test_registry);
+    let contents = scratch.registry();
+    let source = source(4, "0..4").replace("reserve_tags!(", "reserve_tags!(test, ");
+    let output = scratch.check(&source, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(scratch.registry(), contents);
+    let test_tags = fs::read_to_string(&test_registry).unwrap();
+    assert!(test_tags.contains("\"handshake.opcode.3\""), "{}", test_tags);
+}
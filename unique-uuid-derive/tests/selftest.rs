@@ -0,0 +1,100 @@
+//! Tests generated by `#[unique_type_tag(selftest)]`, checking the registry.
+mod common;
+
+use common::{fixture, Scratch};
+
+const SOURCE: &str = "use unique_uuid::UniqueTypeTag;\n\n\
+                      #[derive(UniqueTypeTag)]\n\
+                      #[unique_type_tag(selftest)]\n\
+                      struct Order;\n\n\
+                      #[derive(UniqueTypeTag)]\n\
+                      #[unique_type_tag(selftest)]\n\
+                      struct Invoice;\n\n\
+                      fn main() {}\n";
+
+fn scratch(name: &str) -> Scratch {
+    let scratch = Scratch::new(name, "canonical.toml");
+    scratch.extend_manifest("\n[features]\ndefault = [\"unique-uuid/registry-file\"]\n");
+    scratch
+}
+
+/// Expands `SOURCE` against the fresh registry of `scratch`, whatever a previous run left.
+fn expand(scratch: &Scratch) {
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn an_edited_uuid_fails_the_generated_test() {
+    let scratch = scratch("selftest");
+    expand(&scratch);
+    let output = scratch.test(SOURCE, &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("unique_type_tag_selftest_Order ... ok"), "{}", stdout);
+    assert!(stdout.contains("unique_type_tag_selftest_Invoice ... ok"), "{}", stdout);
+
+    // A hand-edit of the registry, after the types were compiled
+    let edited = scratch.registry().replace(
+        "\"::Order\" = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\"",
+        "\"::Order\" = \"0b6ad2b4-1f1b-4c5e-8d5e-1b2f3c4d5e6f\"",
+    );
+    assert_ne!(edited, scratch.registry());
+    std::fs::write(scratch.path("types.toml"), edited).unwrap();
+
+    let output = scratch.test(SOURCE, &[]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("unique_type_tag_selftest_Order ... FAILED"), "{}", stdout);
+    assert!(stdout.contains("unique_type_tag_selftest_Invoice ... ok"), "{}", stdout);
+    let message = format!(
+        "the registry `{}` stores 0b6ad2b4-1f1b-4c5e-8d5e-1b2f3c4d5e6f under `::Order`, \
+         but `selftest::Order::TYPE_TAG` was compiled as a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db",
+        scratch.path("types.toml").display()
+    );
+    assert!(stdout.contains(&message), "{}", stdout);
+}
+
+#[test]
+fn a_removed_entry_fails_the_generated_test() {
+    let scratch = scratch("selftest-removed");
+    expand(&scratch);
+    let output = scratch.test(SOURCE, &[]);
+    assert!(output.status.success());
+
+    std::fs::write(scratch.path("types.toml"), fixture("canonical.toml")).unwrap();
+    let output = scratch.test(SOURCE, &[]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let message = format!(
+        "the registry `{}` does not store `::Invoice`",
+        scratch.path("types.toml").display()
+    );
+    assert!(stdout.contains(&message), "{}", stdout);
+}
+
+#[test]
+fn generic_types_are_rejected() {
+    let scratch = scratch("selftest-generic");
+    let source = "#[derive(unique_uuid::UniqueTypeTag)]\n\
+                  #[unique_type_tag(selftest)]\n\
+                  struct Envelope<T>(T);\n\n\
+                  fn main() {}\n";
+    let output = scratch.check(source, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("`selftest` is not supported on generic types"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,78 @@
+//! Registries storing a section in a file of its own, named by `tags_file` or
+//! `type_tags_file` in their `[config]` table.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+const REGISTRY: &str = "[unique_tags]\n\n\
+                        [unique_type_tags]\n\
+                        \"::Order\" = \"a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db\"\n\n\
+                        [config]\ntags_file = \"protocol/tags.toml\"\n";
+
+const TAGS: &str = "[unique_tags]\n\
+                    \"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n";
+
+#[test]
+fn new_entries_are_written_to_their_section_file() {
+    let scratch = Scratch::new("split-entries", "canonical.toml");
+    fs::write(scratch.path("types.toml"), REGISTRY).unwrap();
+    fs::create_dir_all(scratch.path("protocol")).unwrap();
+    fs::write(scratch.path("protocol/tags.toml"), TAGS).unwrap();
+
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The tag is added to its own file, which holds nothing else
+    let tags = fs::read_to_string(scratch.path("protocol/tags.toml")).unwrap();
+    let registry = scratch.load();
+    let paid = registry.get("orders.paid").unwrap().tag.0;
+    assert!(tags.starts_with(TAGS), "{}", tags);
+    assert_eq!(tags.lines().count(), 3, "{}", tags);
+    assert!(
+        tags.ends_with(&format!("uuid = \"{}\" }}\n", paid)),
+        "{}",
+        tags
+    );
+    assert_eq!(
+        registry.get("orders.created").unwrap().tag.0.to_string(),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+
+    // The type tag goes to the registry file, without the tags
+    let contents = scratch.registry();
+    assert!(contents.contains("\"::Invoice\" = "), "{}", contents);
+    assert!(!contents.contains("orders."), "{}", contents);
+    assert!(registry.validate().is_empty());
+}
+
+#[test]
+fn entries_left_in_the_registry_file_are_rejected() {
+    let scratch = Scratch::new("split-leftover", "canonical.toml");
+    let contents = format!(
+        "{}[config]\ntags_file = \"tags.toml\"\n",
+        scratch.registry()
+    );
+    fs::write(scratch.path("types.toml"), &contents).unwrap();
+
+    let output = scratch.check(SOURCE, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("the registry holds [unique_tags] entries, but the section is stored in"),
+        "{}",
+        stderr
+    );
+    assert_eq!(scratch.registry(), contents);
+    assert!(!scratch.path("tags.toml").exists());
+}
@@ -0,0 +1,61 @@
+//! The counters reported with `UNIQUE_UUID_STATS`.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+
+const SOURCE: &str = "fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.paid\");\n}\n";
+
+/// Checks `scratch` with the counters enabled, returning the reported line.
+fn report(scratch: &Scratch, crate_name: &str) -> serde_json::Value {
+    let path = scratch.path("stats.jsonl");
+    let _ = fs::remove_file(&path);
+    let output = scratch.check(SOURCE, &[("UNIQUE_UUID_STATS", path.to_str().unwrap())]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let contents = fs::read_to_string(&path).unwrap();
+    let lines: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .filter(|line: &serde_json::Value| line["crate"] == crate_name)
+        .collect();
+    assert_eq!(lines.len(), 1, "{}", contents);
+    lines.into_iter().next().unwrap()
+}
+
+#[test]
+fn counters_follow_the_expansions() {
+    let scratch = Scratch::new("stats", "canonical.toml");
+    let line = report(&scratch, "stats");
+    assert_eq!(line["expansions"], 3);
+    assert_eq!(line["minted"], 1);
+    assert_eq!(line["cache_hits"], 0);
+    assert!(line["file_reads"].as_u64().unwrap() >= 3, "{}", line);
+    assert!(line["file_writes"].as_u64().unwrap() >= 1, "{}", line);
+    assert!(line["io_micros"].is_u64(), "{}", line);
+
+    // Every tag is in the registry now, nothing is written
+    let line = report(&scratch, "stats");
+    assert_eq!(line["expansions"], 3);
+    assert_eq!(line["minted"], 0);
+    assert_eq!(line["file_writes"], 0);
+}
+
+#[test]
+fn cached_expansions_are_counted() {
+    let scratch = Scratch::new("stats-cache", "cache.toml");
+    let _ = fs::remove_file(scratch.path("types.toml.cache"));
+    report(&scratch, "stats-cache");
+
+    let line = report(&scratch, "stats-cache");
+    assert_eq!(line["expansions"], 3);
+    assert_eq!(line["cache_hits"], 3);
+}
@@ -0,0 +1,46 @@
+//! Keys looked up by `tag_name_of!`, from a key or from a UUID.
+mod common;
+
+use common::Scratch;
+
+#[test]
+fn keys_are_found_by_uuid_and_alias() {
+    let scratch = Scratch::new("tag-name", "tag_names.toml");
+    let source = "const CREATED: &str = \
+                  unique_uuid::tag_name_of!(uuid = \"2af1216e-7042-406a-a577-c39a7e7c3faa\");\n\
+                  const ORDER: &str = unique_uuid::tag_name_of!(\"::OrderRecord\");\n\n\
+                  fn main() {\n    \
+                      assert_eq!(CREATED, \"orders.created\");\n    \
+                      assert_eq!(ORDER, \"::Order\");\n\
+                  }\n";
+    let contents = scratch.registry();
+    let output = scratch.check(source, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(scratch.registry(), contents);
+}
+
+#[test]
+fn unknown_and_ambiguous_uuids_are_rejected() {
+    let scratch = Scratch::new("tag-name-errors", "tag_names.toml");
+    let source = "const PLACED: &str = \
+                  unique_uuid::tag_name_of!(uuid = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\");\n\
+                  const UNKNOWN: &str = \
+                  unique_uuid::tag_name_of!(uuid = \"6aac8026-6ca8-4c82-ac39-d74bedf1ac50\");\n\
+                  const MISSING: &str = unique_uuid::tag_name_of!(\"orders.paid\");\n\n\
+                  fn main() {}\n";
+    let output = scratch.check(source, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for expected in [
+        "several entries have it: `orders.placed` in [unique_tags], `::PlacedOrder` in \
+         [unique_type_tags]",
+        "no entry of the registry has the UUID 6aac8026-6ca8-4c82-ac39-d74bedf1ac50",
+        "`orders.paid` is not an entry of the registry",
+    ] {
+        assert!(stderr.contains(expected), "{}", stderr);
+    }
+}
@@ -0,0 +1,49 @@
+//! Expands `unique_tag!` against registries configuring a `tag_style`.
+mod common;
+
+use common::{fixture, Scratch};
+
+#[test]
+fn new_tags_breaking_the_style_are_reported() {
+    let scratch = Scratch::new("tag-style", "tag_style.toml");
+    let output = scratch.check(
+        "fn main() {\n    \
+         let _ = unique_uuid::unique_tag!(\"legacy.UserCreated\");\n    \
+         let _ = unique_uuid::unique_tag!(\"orders.OrderShipped\");\n}\n",
+        &[],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains(
+            "`orders.OrderShipped` does not follow the snake_case convention of the \
+             registry, consider `orders.order_shipped`."
+        ),
+        "{}",
+        stderr
+    );
+
+    // Existing entries are not checked
+    assert!(!stderr.contains("`legacy.UserCreated`"), "{}", stderr);
+    assert!(scratch.registry().contains("\"orders.OrderShipped\" = {"));
+}
+
+#[test]
+fn strict_style_fails_the_expansion() {
+    let scratch = Scratch::new("tag-style-strict", "tag_style_strict.toml");
+    let output = scratch.check(
+        "fn main() {\n    let _ = unique_uuid::unique_tag!(\"orders.order_shipped\");\n}\n",
+        &[],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains(
+            "`orders.order_shipped` does not follow the kebab-case convention of the \
+             registry, consider `orders.order-shipped`."
+        ),
+        "{}",
+        stderr
+    );
+    assert_eq!(scratch.registry(), fixture("tag_style_strict.toml"));
+}
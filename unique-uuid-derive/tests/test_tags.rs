@@ -0,0 +1,70 @@
+//! Tags of `unique_tag!(test, ...)`, written to the test registry.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+
+const SOURCE: &str = "fn main() {\n    \
+                          let fixture = unique_uuid::unique_tag!(test, \"fixture.order\");\n    \
+                          let created = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                          assert_ne!(fixture, created);\n\
+                      }\n";
+
+#[test]
+fn test_tags_stay_out_of_the_registry() {
+    let scratch = Scratch::new("test-tags", "canonical.toml");
+    let test_registry = scratch.path("types.test.toml");
+    let _ = fs::remove_file(&test_registry);
+    let contents = scratch.registry();
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert_eq!(scratch.registry(), contents);
+    let test_tags = fs::read_to_string(&test_registry).unwrap();
+    assert!(test_tags.contains("\"fixture.order\""), "{}", test_tags);
+    assert!(!test_tags.contains("orders.created"), "{}", test_tags);
+}
+
+#[test]
+fn test_tags_are_frozen_independently() {
+    let scratch = Scratch::new("test-tags-frozen", "canonical.toml");
+    fs::write(
+        scratch.path("types.toml"),
+        format!(
+            "{}\n[config]\nfrozen_sections = [\"unique_tags\"]\n",
+            scratch.registry()
+        ),
+    )
+    .unwrap();
+    let test_registry = scratch.path("types.test.toml");
+    let _ = fs::remove_file(&test_registry);
+
+    // Frozen registry, writable test registry
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Writable registry, frozen test registry
+    let source =
+        "fn main() {\n    let _ = unique_uuid::unique_tag!(test, \"fixture.invoice\");\n}\n";
+    fs::write(
+        &test_registry,
+        format!(
+            "{}\n[config]\nfrozen_sections = [\"unique_tags\"]\n",
+            fs::read_to_string(&test_registry).unwrap()
+        ),
+    )
+    .unwrap();
+    let output = scratch.check(source, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("fixture.invoice"), "{}", stderr);
+}
@@ -0,0 +1,24 @@
+//! `UniqueTag::for_test`, only available to crates enabling the `test-util` feature.
+mod common;
+
+use common::Scratch;
+
+const SOURCE: &str = "fn main() {\n    \
+                      let _ = unique_uuid::UniqueTag::for_test(\"orders\");\n}\n";
+
+#[test]
+fn for_test_requires_the_test_util_feature() {
+    let scratch = Scratch::new("test-util-disabled", "canonical.toml");
+    let output = scratch.check(SOURCE, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{}", stderr);
+    assert!(stderr.contains("for_test"), "{}", stderr);
+
+    scratch.extend_manifest("\n[features]\ndefault = [\"unique-uuid/test-util\"]\n");
+    let output = scratch.check(SOURCE, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
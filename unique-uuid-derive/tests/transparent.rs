@@ -0,0 +1,48 @@
+//! Wrappers taking the tag of the type they wrap.
+mod common;
+
+use common::{fixture, Scratch};
+
+#[test]
+fn wrappers_share_the_tag_of_the_inner_type() {
+    let scratch = Scratch::new("transparent", "canonical.toml");
+    let source = "use unique_uuid::UniqueTypeTag;\n\n\
+                  #[derive(UniqueTypeTag)]\n\
+                  struct Order;\n\n\
+                  #[derive(UniqueTypeTag)]\n\
+                  #[unique_type_tag(transparent)]\n\
+                  struct Checked(Order);\n\n\
+                  #[derive(UniqueTypeTag)]\n\
+                  #[unique_type_tag(transparent)]\n\
+                  struct Logged<T> {\n    \
+                      inner: T,\n\
+                  }\n\n\
+                  const _: () = assert!(Checked::TYPE_TAG.0.as_u128() == Order::TYPE_TAG.0.as_u128());\n\
+                  const _: () = assert!(\n    \
+                      Logged::<Checked>::TYPE_TAG.0.as_u128() == Order::TYPE_TAG.0.as_u128()\n\
+                  );\n\n\
+                  fn main() {}\n";
+    let output = scratch.check(source, &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // Neither wrapper gets an entry
+    assert_eq!(scratch.registry(), fixture("canonical.toml"));
+}
+
+#[test]
+fn the_inner_type_must_have_a_tag() {
+    let scratch = Scratch::new("transparent-untagged", "canonical.toml");
+    let source = "struct Order;\n\n\
+                  #[derive(unique_uuid::UniqueTypeTag)]\n\
+                  #[unique_type_tag(transparent)]\n\
+                  struct Checked(Order);\n\n\
+                  fn main() {}\n";
+    let output = scratch.check(source, &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("UniqueTypeTag"), "{}", stderr);
+}
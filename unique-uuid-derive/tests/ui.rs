@@ -0,0 +1,7 @@
+//! Checks that macro errors point at the offending token.
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,7 @@
+// The guard of a derive release written for an interface `unique-uuid` does not support
+unique_uuid::__compat_guard!(99);
+
+// The guard of this release passes
+unique_uuid::__compat_guard!();
+
+fn main() {}
@@ -0,0 +1,11 @@
+use unique_uuid::{match_tag, UniqueTag};
+
+fn describe(incoming: UniqueTag) -> u8 {
+    match_tag!(incoming, {
+        "plugins.audio" => 1,
+        "plugins.video" | "plugins.audio" => 2,
+        _ => 0,
+    })
+}
+
+fn main() {}
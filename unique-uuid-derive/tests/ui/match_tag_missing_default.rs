@@ -0,0 +1,10 @@
+use unique_uuid::{match_tag, UniqueTag};
+
+fn describe(incoming: UniqueTag) -> u8 {
+    match_tag!(incoming, {
+        "plugins.audio" => 1,
+        "plugins.video" => 2,
+    })
+}
+
+fn main() {}
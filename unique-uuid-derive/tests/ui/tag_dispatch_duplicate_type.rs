@@ -0,0 +1,9 @@
+use unique_uuid::TagDispatch;
+
+#[derive(TagDispatch)]
+enum Message {
+    Created(u32),
+    Updated(u32),
+}
+
+fn main() {}
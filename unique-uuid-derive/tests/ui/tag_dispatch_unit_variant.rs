@@ -0,0 +1,8 @@
+use unique_uuid::TagDispatch;
+
+#[derive(TagDispatch)]
+enum Message {
+    Empty,
+}
+
+fn main() {}
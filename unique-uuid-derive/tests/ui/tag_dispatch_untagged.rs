@@ -0,0 +1,10 @@
+use unique_uuid::TagDispatch;
+
+struct Untagged;
+
+#[derive(TagDispatch)]
+enum Message {
+    Untagged(Untagged),
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+use unique_uuid::tags_enum;
+
+tags_enum!(pub enum Plugin, namespaces = "plugins");
+
+fn main() {}
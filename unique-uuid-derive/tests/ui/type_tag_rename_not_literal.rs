@@ -0,0 +1,7 @@
+use unique_uuid::UniqueTypeTag;
+
+#[derive(UniqueTypeTag)]
+#[unique_type_tag(rename = OldName)]
+struct MyType;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use unique_uuid::UniqueTypeTag;
+
+struct Order;
+
+#[derive(UniqueTypeTag)]
+#[unique_type_tag(transparent)]
+enum Checked {
+    Order(Order),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use unique_uuid::UniqueTypeTag;
+
+struct Order;
+
+#[derive(UniqueTypeTag)]
+#[unique_type_tag(transparent)]
+struct Checked(Order, u32);
+
+fn main() {}
@@ -0,0 +1,6 @@
+use unique_uuid::unique_type_tags;
+
+#[unique_type_tags]
+mod events;
+
+fn main() {}
@@ -0,0 +1,12 @@
+use unique_uuid::unique_type_tags;
+
+#[unique_type_tags]
+mod events {
+    use unique_uuid::UniqueTypeTag;
+
+    #[derive(UniqueTypeTag)]
+    #[unique_type_tag(skip)]
+    pub struct OrderCreated;
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+use unique_uuid::unique_tag;
+
+fn main() {
+    let _ = unique_tag!(my_tag);
+}
@@ -0,0 +1,78 @@
+//! The usage log recorded with `UNIQUE_UUID_TRACK_USAGE`.
+mod common;
+
+use std::fs;
+
+use common::Scratch;
+
+const SOURCE: &str = "#[derive(unique_uuid::UniqueTypeTag)]\nstruct Invoice;\n\n\
+                      fn main() {\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n    \
+                      let _ = unique_uuid::unique_tag!(\"orders.created\");\n}\n";
+
+#[test]
+fn expansions_record_their_keys_once() {
+    let scratch = Scratch::new("usage", "canonical.toml");
+    let path = scratch.path("usage.log");
+    let _ = fs::remove_file(&path);
+    let output = scratch.check(
+        SOURCE,
+        &[("UNIQUE_UUID_TRACK_USAGE", path.to_str().unwrap())],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut lines: Vec<String> = fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect();
+    lines.sort();
+    assert_eq!(
+        lines,
+        ["unique_tags\torders.created", "unique_type_tags\t::Invoice"]
+    );
+}
+
+#[test]
+fn an_unwritable_log_is_a_warning() {
+    let scratch = Scratch::new("usage-unwritable", "canonical.toml");
+    // A directory cannot be opened as the log
+    let path = scratch.path("usage.d");
+    fs::create_dir_all(&path).unwrap();
+    let output = scratch.check(
+        SOURCE,
+        &[("UNIQUE_UUID_TRACK_USAGE", path.to_str().unwrap())],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert!(
+        stderr.contains("Cannot record the use of `orders.created` in the usage log"),
+        "{}",
+        stderr
+    );
+    assert!(scratch.registry().contains("\"::Invoice\""));
+}
+
+#[test]
+fn the_in_memory_registry_records_nothing() {
+    let scratch = Scratch::new("usage-in-memory", "canonical.toml");
+    let path = scratch.path("usage.log");
+    let _ = fs::remove_file(&path);
+    let output = scratch.check(
+        SOURCE,
+        &[
+            ("UNIQUE_UUID_TRACK_USAGE", path.to_str().unwrap()),
+            ("UNIQUE_UUID_IN_MEMORY", "1"),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!path.exists());
+}
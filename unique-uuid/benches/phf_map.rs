@@ -0,0 +1,57 @@
+//! Measures the cost of looking up the tags of a map built by `tag_phf_map!`, against a
+//! `HashMap<UniqueTag, u16>` holding the same entries.
+//!
+//! Run with `cargo bench -p unique-uuid --features phf --bench phf_map`.
+use std::{collections::HashMap, hint::black_box, time::Instant};
+
+use unique_uuid::{phf_map::TagPhfMap, tag_phf_map, UniqueTag};
+
+const ITERATIONS: u32 = 1_000_000;
+
+static MAP: TagPhfMap<u16> = tag_phf_map! {
+    "build.greeting" => 0,
+    "build.messages.goodbye" => 1,
+    "build.messages.hello" => 2,
+    "legacy.order_placed" => 3,
+    "my_custom_tag" => 4,
+    "parcels.sent" => 5,
+    "tenants" => 6,
+    "test1" => 7,
+    "test2" => 8,
+};
+
+fn measure(name: &str, tags: &[UniqueTag], mut f: impl FnMut(&UniqueTag) -> u16) -> f64 {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for tag in tags {
+            black_box(f(black_box(tag)));
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64() * 1e9 / (ITERATIONS as usize * tags.len()) as f64;
+    println!("{:<24} {:>10.2} ns", name, elapsed);
+    elapsed
+}
+
+fn main() {
+    let map: HashMap<UniqueTag, u16> = MAP.iter().map(|(tag, value)| (tag, *value)).collect();
+    let mut tags: Vec<UniqueTag> = map.keys().copied().collect();
+    // Half of the lookups miss
+    tags.extend((0..tags.len() as u128).map(|i| UniqueTag(uuid::Uuid::from_u128(i))));
+
+    println!("looking up {} tags in {} entries:", tags.len(), MAP.len());
+    let hash_map = measure("HashMap", &tags, |tag| {
+        map.get(tag).copied().unwrap_or(u16::MAX)
+    });
+    let phf = measure("TagPhfMap", &tags, |tag| {
+        MAP.get(tag).copied().unwrap_or(u16::MAX)
+    });
+
+    for tag in &tags {
+        assert_eq!(
+            map.get(tag),
+            MAP.get(tag),
+            "both maps must hold the same entries"
+        );
+    }
+    println!("TagPhfMap is {:.1}x the speed of HashMap", hash_map / phf);
+}
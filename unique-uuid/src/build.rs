@@ -0,0 +1,531 @@
+//! Helpers for build scripts resolving the registry before the crate is compiled.
+//!
+//! Calling [`ensure_tags`] and [`ensure_type_tags`] from `build.rs` mints the entries a
+//! crate uses before `rustc` runs, so that the macros only ever read the registry. The
+//! entries are minted by the resolution of the macros themselves, with the settings of
+//! the registry, its lock and its journal, so the macros and the build script never
+//! churn each other's writes.
+//!
+//! Code generators needing the UUIDs themselves, or pinning and reserving entries, use
+//! a [`RegistryBuilder`] instead, which writes all its changes at once.
+//!
+//! # Example
+//!
+//! In the `main` function of `build.rs`:
+//!
+//! ```rust,no_run
+//! let registry = concat!(env!("CARGO_MANIFEST_DIR"), "/../types.toml");
+//! unique_uuid::build::ensure_tags(registry, &["orders.created", "orders.paid"]).unwrap();
+//! unique_uuid::build::ensure_type_tags(registry, &["Invoice"]).unwrap();
+//! ```
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use unique_uuid_core::{new_uuid, Config, PinConflict, RegistryHandle, TagOptions};
+use uuid::Uuid;
+
+use crate::{
+    registry::{Entry, Error, Registry, Section},
+    UniqueTag,
+};
+
+/// Mints the entries of `tags` missing from the `[unique_tags]` section of the registry
+/// stored at `path`, as `unique_tag!` would, and asks Cargo to rerun the build script
+/// when the registry changes.
+///
+/// As for the macros, a path with a `.d` extension is a registry directory, of which
+/// only the file of the package is written, and the warnings of the macros are printed
+/// as warnings of the build script.
+///
+/// # Errors
+/// Returns an error if the registry cannot be read or written, or refuses to mint one
+/// of the entries, e.g. for a key breaking the `tag_style` of a `strict` registry.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::registry::Registry;
+///
+/// let path = std::env::temp_dir().join("unique-uuid-doc-ensure-tags.toml");
+/// std::fs::write(
+///     &path,
+///     "[unique_tags]\n\"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n",
+/// )
+/// .unwrap();
+///
+/// unique_uuid::build::ensure_tags(&path, &["orders.created", "orders.paid"]).unwrap();
+/// let registry = Registry::load(&path).unwrap();
+/// assert_eq!(
+///     registry.get("orders.created").unwrap().tag.0.to_string(),
+///     "2af1216e-7042-406a-a577-c39a7e7c3faa"
+/// );
+/// assert!(registry.get("orders.paid").is_some());
+/// ```
+pub fn ensure_tags(path: impl AsRef<Path>, tags: &[&str]) -> Result<(), Error> {
+    let keys: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+    ensure(path.as_ref(), Section::UniqueTags, &keys)
+}
+
+/// Mints the entries of the types named `types` missing from the `[unique_type_tags]`
+/// section of the registry stored at `path`, as `#[derive(UniqueTypeTag)]` would, and
+/// asks Cargo to rerun the build script when the registry changes.
+///
+/// Each type is given by its name, e.g. `Invoice` for the key `::Invoice`; a name
+/// already starting with `::` is used as the key itself.
+///
+/// # Errors
+/// Returns an error as [`ensure_tags`] does.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::registry::Registry;
+///
+/// let path = std::env::temp_dir().join("unique-uuid-doc-ensure-type-tags.toml");
+/// let _ = std::fs::remove_file(&path);
+///
+/// unique_uuid::build::ensure_type_tags(&path, &["Invoice", "::Order"]).unwrap();
+/// let registry = Registry::load(&path).unwrap();
+/// assert!(registry.get("::Invoice").is_some());
+/// assert!(registry.get("::Order").is_some());
+/// ```
+pub fn ensure_type_tags(path: impl AsRef<Path>, types: &[&str]) -> Result<(), Error> {
+    let keys: Vec<String> = types.iter().map(|name| type_key(name)).collect();
+    ensure(path.as_ref(), Section::UniqueTypeTags, &keys)
+}
+
+/// Returns the key of the type named `name`: `::<name>`, unless it already starts with
+/// `::`.
+fn type_key(name: &str) -> String {
+    if name.starts_with("::") {
+        name.to_string()
+    } else {
+        format!("::{}", name)
+    }
+}
+
+/// Resolves `keys` in `section` of the registry at `path` as the macros do, minting the
+/// missing entries.
+fn ensure(path: &Path, section: Section, keys: &[String]) -> Result<(), Error> {
+    println!("cargo:rerun-if-changed={}", path.display());
+    let handle = RegistryHandle::open(path);
+    let mut warnings = Vec::new();
+    for key in keys {
+        let resolved = handle
+            .get_uuid_from_tag(key, section, &TagOptions::default())
+            .map_err(|message| Error::Rejected {
+                path: path.to_path_buf(),
+                message,
+            })?;
+        warnings.extend(resolved.warnings);
+    }
+    for warning in warnings {
+        println!("cargo:warning={}", warning.replace('\n', " "));
+    }
+    Ok(())
+}
+
+/// An error of a [`RegistryBuilder`].
+#[derive(Debug)]
+pub enum BuilderError {
+    /// The registry could not be read or written.
+    Registry(Error),
+
+    /// [`RegistryBuilder::pin_tag`] was given a UUID, but the registry assigns another
+    /// one to the key, and its `pin_conflict` setting is `"error"` (the default).
+    PinConflict {
+        /// The key pinned.
+        key: String,
+        /// The UUID requested.
+        pinned: Uuid,
+        /// The UUID the registry assigns to the key.
+        existing: Uuid,
+    },
+
+    /// [`RegistryBuilder::pin_tag`] was given a UUID that the registry assigns to another
+    /// entry.
+    UuidInUse {
+        /// The key pinned.
+        key: String,
+        /// The UUID requested.
+        uuid: Uuid,
+        /// The section of the entry using the UUID.
+        section: Section,
+        /// The key of the entry using the UUID.
+        owner: String,
+    },
+
+    /// A key was resolved, but its entry is reserved (`reserved = true`) and cannot be
+    /// claimed.
+    Reserved {
+        /// The section of the reserved entry.
+        section: Section,
+        /// The key of the reserved entry.
+        key: String,
+    },
+
+    /// [`RegistryBuilder::reserve`] was given a key whose entry exists and is not
+    /// reserved, i.e. is already in use.
+    InUse {
+        /// The key to reserve.
+        key: String,
+    },
+
+    /// Another process wrote an entry for a key between the moment the builder resolved
+    /// it and [`RegistryBuilder::commit`], with another UUID.
+    Concurrent {
+        /// The section of the entry.
+        section: Section,
+        /// The key of the entry.
+        key: String,
+    },
+}
+
+impl fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuilderError::Registry(err) => err.fmt(f),
+            BuilderError::PinConflict {
+                key,
+                pinned,
+                existing,
+            } => write!(
+                f,
+                "`{}` is pinned to {}, but the registry assigns it {}: set `pin_conflict` \
+                 in the [config] table of the registry to choose one",
+                key, pinned, existing
+            ),
+            BuilderError::UuidInUse {
+                key,
+                uuid,
+                section,
+                owner,
+            } => write!(
+                f,
+                "`{}` cannot be pinned to {}: `{}` in [{}] already has it",
+                key, uuid, owner, section
+            ),
+            BuilderError::Reserved { section, key } => write!(
+                f,
+                "`{}` in [{}] is reserved and cannot be claimed",
+                key, section
+            ),
+            BuilderError::InUse { key } => write!(
+                f,
+                "`{}` cannot be reserved: the registry already has an entry for it",
+                key
+            ),
+            BuilderError::Concurrent { section, key } => write!(
+                f,
+                "`{}` in [{}] was written with another UUID while the builder was open",
+                key, section
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuilderError::Registry(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for BuilderError {
+    fn from(err: Error) -> Self {
+        BuilderError::Registry(err)
+    }
+}
+
+/// A pending change of a [`RegistryBuilder`]: the entry to write, and the UUID of the
+/// entry it replaces, if any.
+struct Change {
+    section: Section,
+    key: String,
+    entry: Entry,
+    replaces: Option<Uuid>,
+}
+
+/// Mints, pins and reserves entries of a registry from a build script, and writes them
+/// all at once with [`RegistryBuilder::commit`].
+///
+/// The builder resolves keys against the registry as it was when opened, with the
+/// changes made so far; nothing is written until [`RegistryBuilder::commit`]. The
+/// entries are minted as by the macros, with a random UUID starting with the prefix of
+/// the package, the time, and the name of the package, and written in the same
+/// canonical form. As for [`ensure_tags`], a path with a `.d` extension is a registry
+/// directory, of which only the file of the package is written.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{build::RegistryBuilder, registry::{Registry, Section}};
+///
+/// let path = std::env::temp_dir().join("unique-uuid-doc-registry-builder.toml");
+/// let _ = std::fs::remove_file(&path);
+///
+/// let mut builder = RegistryBuilder::open(&path).unwrap();
+/// let created = builder.get_or_insert_tag("orders.created").unwrap();
+/// let legacy = "ff187986-b4ae-44dc-ac88-cccaa1228e07".parse().unwrap();
+/// builder.pin_tag("orders.placed", legacy).unwrap();
+/// builder.reserve("orders.v2").unwrap();
+/// builder.reserve_with_owner("orders.v3", "billing-team").unwrap();
+/// builder.get_or_insert_type("Invoice").unwrap();
+/// assert_eq!(builder.get_or_insert_tag("orders.created").unwrap(), created);
+/// builder.commit().unwrap();
+///
+/// let registry = Registry::load(&path).unwrap();
+/// assert_eq!(registry.get("orders.created").unwrap().tag, created);
+/// assert!(registry.entry(Section::UniqueTags, "orders.placed").unwrap().is_pinned());
+/// assert!(registry.entry(Section::UniqueTags, "orders.v2").unwrap().is_reserved());
+/// assert_eq!(registry.entry(Section::UniqueTags, "orders.v3").unwrap().owner(), Some("billing-team"));
+/// assert!(registry.get("::Invoice").is_some());
+/// ```
+pub struct RegistryBuilder {
+    path: PathBuf,
+    registry: Registry,
+    config: Config,
+    changes: Vec<Change>,
+}
+
+impl RegistryBuilder {
+    /// Opens the registry stored at `path`, a missing file having no entries, and asks
+    /// Cargo to rerun the build script when it changes.
+    ///
+    /// # Errors
+    /// Returns an error if the registry cannot be read.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        println!("cargo:rerun-if-changed={}", path.display());
+        let registry = if path.exists() {
+            Registry::load(path)?
+        } else {
+            Registry::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            config: registry.config(),
+            registry,
+            changes: Vec::new(),
+        })
+    }
+
+    /// Returns the tag of `key` in `[unique_tags]`, minting a new entry if needed, as
+    /// `unique_tag!` would.
+    ///
+    /// # Errors
+    /// Returns [`BuilderError::Reserved`] if the entry of `key` is reserved.
+    pub fn get_or_insert_tag(&mut self, key: &str) -> Result<UniqueTag, BuilderError> {
+        self.get_or_insert(Section::UniqueTags, key.to_string())
+    }
+
+    /// Returns the tag of the type named `name` in `[unique_type_tags]`, minting a new
+    /// entry if needed, as `#[derive(UniqueTypeTag)]` would. The key is `::<name>`, as
+    /// for [`ensure_type_tags`].
+    ///
+    /// # Errors
+    /// Returns [`BuilderError::Reserved`] if the entry of the type is reserved.
+    pub fn get_or_insert_type(&mut self, name: &str) -> Result<UniqueTag, BuilderError> {
+        self.get_or_insert(Section::UniqueTypeTags, type_key(name))
+    }
+
+    /// Pins `key` in `[unique_tags]` to `uuid`, as `unique_tag!(key, uuid = "...")` would:
+    /// a missing entry is minted with `uuid` and `pinned = true`.
+    ///
+    /// If the registry assigns another UUID to `key`, its `pin_conflict` setting decides:
+    /// `"error"` (the default) fails, `"prefer-pinned"` rewrites the entry with `uuid`,
+    /// and `"prefer-registry"` keeps the UUID of the registry, which is returned.
+    ///
+    /// # Errors
+    /// Returns [`BuilderError::UuidInUse`] if another entry has `uuid`, and
+    /// [`BuilderError::PinConflict`] as described above.
+    pub fn pin_tag(&mut self, key: &str, uuid: Uuid) -> Result<UniqueTag, BuilderError> {
+        if let Some((section, owner)) = self
+            .owner_of(uuid)
+            .filter(|(section, owner)| *section != Section::UniqueTags || owner.as_str() != key)
+        {
+            return Err(BuilderError::UuidInUse {
+                key: key.to_string(),
+                uuid,
+                section,
+                owner,
+            });
+        }
+        let Some(existing) = self.entry(Section::UniqueTags, key).cloned() else {
+            let entry = Entry::minted(uuid).flagged("pinned");
+            return Ok(self.push(Section::UniqueTags, key.to_string(), entry, None));
+        };
+        if existing.uuid() == uuid {
+            return Ok(existing.tag());
+        }
+        match self.config.pin_conflict() {
+            PinConflict::PreferRegistry => Ok(existing.tag()),
+            PinConflict::PreferPinned => {
+                let replaces = Some(existing.uuid());
+                let entry = existing.with_uuid(uuid).flagged("pinned");
+                Ok(self.push(Section::UniqueTags, key.to_string(), entry, replaces))
+            }
+            PinConflict::Error => Err(BuilderError::PinConflict {
+                key: key.to_string(),
+                pinned: uuid,
+                existing: existing.uuid(),
+            }),
+        }
+    }
+
+    /// Reserves `key` in `[unique_tags]`: a new entry is minted with `reserved = true`,
+    /// pre-allocating its UUID for future use. Reserving a reserved key returns its tag.
+    ///
+    /// # Errors
+    /// Returns [`BuilderError::InUse`] if `key` has an entry that is not reserved.
+    pub fn reserve(&mut self, key: &str) -> Result<UniqueTag, BuilderError> {
+        match self.entry(Section::UniqueTags, key) {
+            Some(entry) if entry.is_reserved() => Ok(entry.tag()),
+            Some(_) => Err(BuilderError::InUse {
+                key: key.to_string(),
+            }),
+            None => {
+                let entry = Entry::minted(new_uuid(&self.config)).flagged("reserved");
+                Ok(self.push(Section::UniqueTags, key.to_string(), entry, None))
+            }
+        }
+    }
+
+    /// Reserves `key` in `[unique_tags]` as [`RegistryBuilder::reserve`] does, recording
+    /// `owner` as the person or team to contact to lift the reservation. The owner of a
+    /// reserved key is updated.
+    ///
+    /// # Errors
+    /// Returns [`BuilderError::InUse`] if `key` has an entry that is not reserved.
+    pub fn reserve_with_owner(
+        &mut self,
+        key: &str,
+        owner: &str,
+    ) -> Result<UniqueTag, BuilderError> {
+        self.reserve(key)?;
+        let current = self
+            .entry(Section::UniqueTags, key)
+            .cloned()
+            .expect("the key was reserved above");
+        if current.owner() == Some(owner) {
+            return Ok(current.tag());
+        }
+        let replaces = self
+            .registry
+            .entry(Section::UniqueTags, key)
+            .map(Entry::uuid);
+        let entry = current.with_field("owner", toml::Value::String(owner.to_string()));
+        Ok(self.push(Section::UniqueTags, key.to_string(), entry, replaces))
+    }
+
+    /// Writes the changes to the registry, in its format and canonical form; nothing is
+    /// written if there is none. Returns the number of entries written.
+    ///
+    /// The registry is read again first, so that the entries written by the macros in the
+    /// meantime are kept. The registry lock the macros write under is held while the
+    /// registry is read and written, which makes the macros wait; with `journal = true`
+    /// in the `[config]` table, the journal is locked too, as for a compaction, and its
+    /// entries are moved into the registry file. The file is replaced atomically, so that
+    /// concurrent readers never observe a partial write.
+    ///
+    /// # Errors
+    /// Returns [`BuilderError::Concurrent`] if another process wrote an entry for one of
+    /// the keys with another UUID meanwhile, and [`BuilderError::Registry`] if the
+    /// registry cannot be read or written.
+    pub fn commit(self) -> Result<usize, BuilderError> {
+        if self.changes.is_empty() {
+            return Ok(0);
+        }
+        let changes = self.changes;
+        Registry::update(&self.path, |registry| {
+            for change in &changes {
+                let current = registry.entry(change.section, &change.key).map(Entry::uuid);
+                if current.is_some() && current != change.replaces {
+                    return Err(BuilderError::Concurrent {
+                        section: change.section,
+                        key: change.key.clone(),
+                    });
+                }
+            }
+            let written = changes.len();
+            for change in changes {
+                registry.insert(change.section, change.key, change.entry);
+            }
+            Ok(written)
+        })
+    }
+
+    /// Resolves `key` in `section`, minting a new entry if needed.
+    fn get_or_insert(&mut self, section: Section, key: String) -> Result<UniqueTag, BuilderError> {
+        match self.entry(section, &key) {
+            Some(entry) if entry.is_reserved() => Err(BuilderError::Reserved { section, key }),
+            Some(entry) => Ok(entry.tag()),
+            None => {
+                let entry = Entry::minted(new_uuid(&self.config));
+                Ok(self.push(section, key, entry, None))
+            }
+        }
+    }
+
+    /// Returns the entry of `key` in `section`, with the changes made so far.
+    fn entry(&self, section: Section, key: &str) -> Option<&Entry> {
+        self.changes
+            .iter()
+            .rev()
+            .find(|change| change.section == section && change.key == key)
+            .map(|change| &change.entry)
+            .or_else(|| self.registry.entry(section, key))
+    }
+
+    /// Returns the section and key of the entry with the UUID `uuid`, with the changes
+    /// made so far.
+    fn owner_of(&self, uuid: Uuid) -> Option<(Section, String)> {
+        self.changes
+            .iter()
+            .find(|change| change.entry.uuid() == uuid)
+            .map(|change| (change.section, change.key.clone()))
+            .or_else(|| {
+                Section::ALL.into_iter().find_map(|section| {
+                    self.registry
+                        .entries(section)
+                        .find(|(key, entry)| {
+                            entry.uuid() == uuid
+                                && self
+                                    .entry(section, key)
+                                    .is_some_and(|entry| entry.uuid() == uuid)
+                        })
+                        .map(|(key, _)| (section, key.to_string()))
+                })
+            })
+    }
+
+    /// Records the change of `key` in `section` to `entry`, returning the tag of `entry`.
+    fn push(
+        &mut self,
+        section: Section,
+        key: String,
+        entry: Entry,
+        replaces: Option<Uuid>,
+    ) -> UniqueTag {
+        let tag = entry.tag();
+        // The entry of the file is the one replaced, whatever the changes in between
+        let replaces = self
+            .changes
+            .iter()
+            .find(|change| change.section == section && change.key == key)
+            .map_or(replaces, |change| change.replaces);
+        self.changes
+            .retain(|change| change.section != section || change.key != key);
+        self.changes.push(Change {
+            section,
+            key,
+            entry,
+            replaces,
+        });
+        tag
+    }
+}
@@ -0,0 +1,649 @@
+//! Sets and maps of [`UniqueTag`]s, stored as sorted slices.
+use std::{
+    cmp::Ordering,
+    iter::{FusedIterator, Peekable},
+    ops::{BitAnd, BitOr, BitXor, Sub},
+    slice,
+};
+
+use crate::UniqueTag;
+
+/// A set of tags, stored as a sorted slice.
+///
+/// Membership is checked by a binary search, and iteration follows the order of
+/// [`UniqueTag`]. Inserting and removing tags shifts the tags that follow, so a set suits
+/// groups of tags built once and then queried, e.g. the tags routed to a handler.
+///
+/// With the `serde` feature, a set is serialized as a sequence of tags, and deserializing
+/// a sequence holding a tag twice fails.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{unique_tag, TagSet};
+///
+/// let mut plugins = TagSet::new();
+/// assert!(plugins.insert(unique_tag!(test, "plugins.audio")));
+/// assert!(plugins.insert(unique_tag!(test, "plugins.video")));
+/// assert!(!plugins.insert(unique_tag!(test, "plugins.audio")));
+///
+/// assert_eq!(plugins.len(), 2);
+/// assert!(plugins.contains(&unique_tag!(test, "plugins.video")));
+/// assert!(!plugins.contains(&unique_tag!(test, "plugins")));
+/// assert!(plugins.as_slice().is_sorted());
+/// ```
+///
+/// Sets combine with [`TagSet::union`], [`TagSet::intersection`],
+/// [`TagSet::difference`] and [`TagSet::symmetric_difference`], which merge the sorted
+/// slices lazily, or with the `|`, `&`, `-` and `^` operators, which collect the result
+/// into a new set:
+///
+/// ```rust
+/// use unique_uuid::{unique_tag, TagSet};
+///
+/// let billing: TagSet = [unique_tag!("billing.invoice"), unique_tag!("billing.refund")]
+///     .into_iter()
+///     .collect();
+/// let payments: TagSet = [unique_tag!("payments.card")].into_iter().collect();
+/// let deprecated: TagSet = [unique_tag!("billing.refund")].into_iter().collect();
+///
+/// // The tags handled here: billing ∪ payments ∖ deprecated
+/// let handled = &(&billing | &payments) - &deprecated;
+/// assert_eq!(handled.len(), 2);
+/// assert!(handled.contains(&unique_tag!("payments.card")));
+/// assert!(!handled.contains(&unique_tag!("billing.refund")));
+/// assert!(handled.is_disjoint(&deprecated));
+/// assert!(billing.is_superset(&deprecated));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TagSet {
+    tags: Vec<UniqueTag>,
+}
+
+impl TagSet {
+    /// Creates an empty set.
+    pub const fn new() -> Self {
+        Self { tags: Vec::new() }
+    }
+
+    /// Creates an empty set with room for `capacity` tags.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            tags: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Adds `tag` to the set, returning `false` if it was already in it.
+    pub fn insert(&mut self, tag: UniqueTag) -> bool {
+        match self.tags.binary_search(&tag) {
+            Ok(_) => false,
+            Err(index) => {
+                self.tags.insert(index, tag);
+                true
+            }
+        }
+    }
+
+    /// Removes `tag` from the set, returning `false` if it was not in it.
+    pub fn remove(&mut self, tag: &UniqueTag) -> bool {
+        match self.tags.binary_search(tag) {
+            Ok(index) => {
+                self.tags.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if `tag` is in the set.
+    pub fn contains(&self, tag: &UniqueTag) -> bool {
+        self.tags.binary_search(tag).is_ok()
+    }
+
+    /// Returns the number of tags in the set.
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Returns `true` if the set holds no tag.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Iterates over the tags of the set, in order.
+    pub fn iter(&self) -> slice::Iter<'_, UniqueTag> {
+        self.tags.iter()
+    }
+
+    /// Returns the tags of the set, sorted.
+    pub fn as_slice(&self) -> &[UniqueTag] {
+        &self.tags
+    }
+
+    /// Iterates over the tags of `self` or `other`, in order.
+    pub fn union<'a>(&'a self, other: &'a TagSet) -> TagSetUnion<'a> {
+        TagSetUnion(Merge::new(self, other))
+    }
+
+    /// Iterates over the tags of both `self` and `other`, in order.
+    pub fn intersection<'a>(&'a self, other: &'a TagSet) -> TagSetIntersection<'a> {
+        TagSetIntersection(Merge::new(self, other))
+    }
+
+    /// Iterates over the tags of `self` that are not in `other`, in order.
+    pub fn difference<'a>(&'a self, other: &'a TagSet) -> TagSetDifference<'a> {
+        TagSetDifference(Merge::new(self, other))
+    }
+
+    /// Iterates over the tags of either `self` or `other` but not both, in order.
+    pub fn symmetric_difference<'a>(&'a self, other: &'a TagSet) -> TagSetSymmetricDifference<'a> {
+        TagSetSymmetricDifference(Merge::new(self, other))
+    }
+
+    /// Returns `true` if `self` and `other` have no tag in common.
+    pub fn is_disjoint(&self, other: &TagSet) -> bool {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Returns `true` if every tag of `self` is in `other`.
+    pub fn is_subset(&self, other: &TagSet) -> bool {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+
+    /// Returns `true` if every tag of `other` is in `self`.
+    pub fn is_superset(&self, other: &TagSet) -> bool {
+        other.is_subset(self)
+    }
+}
+
+/// Collects tags into a set, sorting them once rather than inserting them one by one.
+impl FromIterator<UniqueTag> for TagSet {
+    fn from_iter<I: IntoIterator<Item = UniqueTag>>(tags: I) -> Self {
+        let mut tags: Vec<_> = tags.into_iter().collect();
+        tags.sort_unstable();
+        tags.dedup();
+        Self { tags }
+    }
+}
+
+impl<'a> FromIterator<&'a UniqueTag> for TagSet {
+    fn from_iter<I: IntoIterator<Item = &'a UniqueTag>>(tags: I) -> Self {
+        tags.into_iter().copied().collect()
+    }
+}
+
+/// Adds tags to the set, sorting the set once rather than inserting them one by one.
+impl Extend<UniqueTag> for TagSet {
+    fn extend<I: IntoIterator<Item = UniqueTag>>(&mut self, tags: I) {
+        self.tags.extend(tags);
+        self.tags.sort_unstable();
+        self.tags.dedup();
+    }
+}
+
+impl<'a> Extend<&'a UniqueTag> for TagSet {
+    fn extend<I: IntoIterator<Item = &'a UniqueTag>>(&mut self, tags: I) {
+        self.extend(tags.into_iter().copied());
+    }
+}
+
+impl BitOr for &TagSet {
+    type Output = TagSet;
+
+    /// Returns the union of the sets, see [`TagSet::union`].
+    fn bitor(self, other: &TagSet) -> TagSet {
+        TagSet {
+            tags: self.union(other).copied().collect(),
+        }
+    }
+}
+
+impl BitAnd for &TagSet {
+    type Output = TagSet;
+
+    /// Returns the intersection of the sets, see [`TagSet::intersection`].
+    fn bitand(self, other: &TagSet) -> TagSet {
+        TagSet {
+            tags: self.intersection(other).copied().collect(),
+        }
+    }
+}
+
+impl Sub for &TagSet {
+    type Output = TagSet;
+
+    /// Returns the difference of the sets, see [`TagSet::difference`].
+    fn sub(self, other: &TagSet) -> TagSet {
+        TagSet {
+            tags: self.difference(other).copied().collect(),
+        }
+    }
+}
+
+impl BitXor for &TagSet {
+    type Output = TagSet;
+
+    /// Returns the symmetric difference of the sets, see
+    /// [`TagSet::symmetric_difference`].
+    fn bitxor(self, other: &TagSet) -> TagSet {
+        TagSet {
+            tags: self.symmetric_difference(other).copied().collect(),
+        }
+    }
+}
+
+/// The tags of two sets, merged in order.
+#[derive(Debug, Clone)]
+struct Merge<'a> {
+    left: Peekable<slice::Iter<'a, UniqueTag>>,
+    right: Peekable<slice::Iter<'a, UniqueTag>>,
+}
+
+/// The sets holding a tag yielded by [`Merge`].
+enum Side<'a> {
+    Left(&'a UniqueTag),
+    Right(&'a UniqueTag),
+    Both(&'a UniqueTag),
+}
+
+impl<'a> Merge<'a> {
+    fn new(left: &'a TagSet, right: &'a TagSet) -> Self {
+        Self {
+            left: left.iter().peekable(),
+            right: right.iter().peekable(),
+        }
+    }
+
+    /// Returns the next tag of either set, and the sets holding it.
+    fn next(&mut self) -> Option<Side<'a>> {
+        let order = match (self.left.peek(), self.right.peek()) {
+            (Some(left), Some(right)) => left.cmp(right),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+        Some(match order {
+            Ordering::Less => Side::Left(self.left.next()?),
+            Ordering::Greater => Side::Right(self.right.next()?),
+            Ordering::Equal => {
+                self.right.next();
+                Side::Both(self.left.next()?)
+            }
+        })
+    }
+
+    /// Returns the number of tags left in each set.
+    fn lens(&self) -> (usize, usize) {
+        (self.left.len(), self.right.len())
+    }
+}
+
+/// The lazy union of two [`TagSet`]s, see [`TagSet::union`].
+#[derive(Debug, Clone)]
+pub struct TagSetUnion<'a>(Merge<'a>);
+
+impl<'a> Iterator for TagSetUnion<'a> {
+    type Item = &'a UniqueTag;
+
+    fn next(&mut self) -> Option<&'a UniqueTag> {
+        match self.0.next()? {
+            Side::Left(tag) | Side::Right(tag) | Side::Both(tag) => Some(tag),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left, right) = self.0.lens();
+        (left.max(right), Some(left + right))
+    }
+}
+
+impl FusedIterator for TagSetUnion<'_> {}
+
+/// The lazy intersection of two [`TagSet`]s, see [`TagSet::intersection`].
+#[derive(Debug, Clone)]
+pub struct TagSetIntersection<'a>(Merge<'a>);
+
+impl<'a> Iterator for TagSetIntersection<'a> {
+    type Item = &'a UniqueTag;
+
+    fn next(&mut self) -> Option<&'a UniqueTag> {
+        loop {
+            if let Side::Both(tag) = self.0.next()? {
+                return Some(tag);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left, right) = self.0.lens();
+        (0, Some(left.min(right)))
+    }
+}
+
+impl FusedIterator for TagSetIntersection<'_> {}
+
+/// The lazy difference of two [`TagSet`]s, see [`TagSet::difference`].
+#[derive(Debug, Clone)]
+pub struct TagSetDifference<'a>(Merge<'a>);
+
+impl<'a> Iterator for TagSetDifference<'a> {
+    type Item = &'a UniqueTag;
+
+    fn next(&mut self) -> Option<&'a UniqueTag> {
+        loop {
+            match self.0.next()? {
+                Side::Left(tag) => return Some(tag),
+                // The tags of the other set past the end of this one are of no interest
+                Side::Right(_) if self.0.left.peek().is_none() => return None,
+                Side::Right(_) | Side::Both(_) => {}
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left, right) = self.0.lens();
+        (left.saturating_sub(right), Some(left))
+    }
+}
+
+impl FusedIterator for TagSetDifference<'_> {}
+
+/// The lazy symmetric difference of two [`TagSet`]s, see
+/// [`TagSet::symmetric_difference`].
+#[derive(Debug, Clone)]
+pub struct TagSetSymmetricDifference<'a>(Merge<'a>);
+
+impl<'a> Iterator for TagSetSymmetricDifference<'a> {
+    type Item = &'a UniqueTag;
+
+    fn next(&mut self) -> Option<&'a UniqueTag> {
+        loop {
+            match self.0.next()? {
+                Side::Left(tag) | Side::Right(tag) => return Some(tag),
+                Side::Both(_) => {}
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left, right) = self.0.lens();
+        (0, Some(left + right))
+    }
+}
+
+impl FusedIterator for TagSetSymmetricDifference<'_> {}
+
+impl<'a> IntoIterator for &'a TagSet {
+    type Item = &'a UniqueTag;
+    type IntoIter = slice::Iter<'a, UniqueTag>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for TagSet {
+    type Item = UniqueTag;
+    type IntoIter = std::vec::IntoIter<UniqueTag>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.tags.into_iter()
+    }
+}
+
+/// A map keyed by tags, stored as a slice of entries sorted by tag.
+///
+/// Lookups are binary searches, and iteration follows the order of [`UniqueTag`].
+/// Inserting and removing entries shifts the entries that follow, so a map suits tables
+/// built once and then queried, e.g. the settings of each tag in a configuration.
+///
+/// With the `serde` feature, a map is serialized as a map from UUID strings to values in
+/// human-readable formats (e.g. JSON), and as a sequence of `(tag, value)` entries in
+/// binary formats (e.g. bincode), whose tags are 16 bytes. Deserializing a map holding a
+/// tag twice fails.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{unique_tag, TagMap};
+///
+/// let mut priorities = TagMap::new();
+/// priorities.insert(unique_tag!(test, "plugins.audio"), 10);
+/// priorities.insert(unique_tag!(test, "plugins.video"), 20);
+/// assert_eq!(priorities.insert(unique_tag!(test, "plugins.audio"), 15), Some(10));
+///
+/// assert_eq!(priorities.get(&unique_tag!(test, "plugins.audio")), Some(&15));
+/// assert_eq!(priorities.get(&unique_tag!(test, "plugins")), None);
+/// assert_eq!(priorities.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TagMap<V> {
+    entries: Vec<(UniqueTag, V)>,
+}
+
+impl<V> TagMap<V> {
+    /// Creates an empty map.
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Creates an empty map with room for `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the index of the entry of `tag`, or where it would be inserted.
+    fn search(&self, tag: &UniqueTag) -> Result<usize, usize> {
+        self.entries.binary_search_by(|(key, _)| key.cmp(tag))
+    }
+
+    /// Maps `tag` to `value`, returning the previous value of `tag`, if any.
+    pub fn insert(&mut self, tag: UniqueTag, value: V) -> Option<V> {
+        match self.search(&tag) {
+            Ok(index) => Some(std::mem::replace(&mut self.entries[index].1, value)),
+            Err(index) => {
+                self.entries.insert(index, (tag, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the entry of `tag`, returning its value, if any.
+    pub fn remove(&mut self, tag: &UniqueTag) -> Option<V> {
+        let index = self.search(tag).ok()?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Returns the value of `tag`, if any.
+    pub fn get(&self, tag: &UniqueTag) -> Option<&V> {
+        let index = self.search(tag).ok()?;
+        Some(&self.entries[index].1)
+    }
+
+    /// Returns the value of `tag` mutably, if any.
+    pub fn get_mut(&mut self, tag: &UniqueTag) -> Option<&mut V> {
+        let index = self.search(tag).ok()?;
+        Some(&mut self.entries[index].1)
+    }
+
+    /// Returns `true` if the map has an entry for `tag`.
+    pub fn contains_key(&self, tag: &UniqueTag) -> bool {
+        self.search(tag).is_ok()
+    }
+
+    /// Returns the number of entries of the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map has no entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries of the map, in tag order.
+    pub fn iter(&self) -> impl Iterator<Item = (UniqueTag, &V)> + '_ {
+        self.entries.iter().map(|(tag, value)| (*tag, value))
+    }
+
+    /// Iterates over the tags of the map, in order.
+    pub fn keys(&self) -> impl Iterator<Item = UniqueTag> + '_ {
+        self.entries.iter().map(|(tag, _)| *tag)
+    }
+
+    /// Iterates over the values of the map, in tag order.
+    pub fn values(&self) -> impl Iterator<Item = &V> + '_ {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+impl<V> Default for TagMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Collects entries into a map, later entries replacing earlier ones for the same tag.
+impl<V> FromIterator<(UniqueTag, V)> for TagMap<V> {
+    fn from_iter<I: IntoIterator<Item = (UniqueTag, V)>>(entries: I) -> Self {
+        let entries = entries.into_iter();
+        let mut map = Self::with_capacity(entries.size_hint().0);
+        for (tag, value) in entries {
+            map.insert(tag, value);
+        }
+        map
+    }
+}
+
+impl<V> IntoIterator for TagMap<V> {
+    type Item = (UniqueTag, V);
+    type IntoIter = std::vec::IntoIter<(UniqueTag, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use std::{fmt, marker::PhantomData};
+
+    use serde::{
+        de::{self, MapAccess, SeqAccess, Visitor},
+        ser::{SerializeMap, SerializeSeq},
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+
+    use super::{TagMap, TagSet};
+    use crate::UniqueTag;
+
+    /// Sorts `entries` by tag, failing on the first tag found twice.
+    fn sorted<T, E: de::Error>(
+        mut entries: Vec<T>,
+        tag: impl Fn(&T) -> UniqueTag,
+    ) -> Result<Vec<T>, E> {
+        entries.sort_by_key(|entry| tag(entry));
+        match entries
+            .windows(2)
+            .find(|pair| tag(&pair[0]) == tag(&pair[1]))
+        {
+            Some(pair) => Err(E::custom(format_args!("duplicate tag {}", tag(&pair[0]).0))),
+            None => Ok(entries),
+        }
+    }
+
+    impl Serialize for TagSet {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.iter())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for TagSet {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct TagSetVisitor;
+
+            impl<'de> Visitor<'de> for TagSetVisitor {
+                type Value = TagSet;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a sequence of distinct tags")
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TagSet, A::Error> {
+                    let mut tags = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                    while let Some(tag) = seq.next_element()? {
+                        tags.push(tag);
+                    }
+                    Ok(TagSet {
+                        tags: sorted(tags, |tag| *tag)?,
+                    })
+                }
+            }
+
+            deserializer.deserialize_seq(TagSetVisitor)
+        }
+    }
+
+    impl<V: Serialize> Serialize for TagMap<V> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                let mut map = serializer.serialize_map(Some(self.len()))?;
+                for (tag, value) in &self.entries {
+                    map.serialize_entry(tag, value)?;
+                }
+                map.end()
+            } else {
+                let mut seq = serializer.serialize_seq(Some(self.len()))?;
+                for entry in &self.entries {
+                    seq.serialize_element(entry)?;
+                }
+                seq.end()
+            }
+        }
+    }
+
+    impl<'de, V: Deserialize<'de>> Deserialize<'de> for TagMap<V> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct TagMapVisitor<V>(PhantomData<V>);
+
+            impl<'de, V: Deserialize<'de>> Visitor<'de> for TagMapVisitor<V> {
+                type Value = TagMap<V>;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a map keyed by distinct tags")
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TagMap<V>, A::Error> {
+                    let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+                    while let Some(entry) = map.next_entry::<UniqueTag, V>()? {
+                        entries.push(entry);
+                    }
+                    Ok(TagMap {
+                        entries: sorted(entries, |(tag, _)| *tag)?,
+                    })
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TagMap<V>, A::Error> {
+                    let mut entries = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                    while let Some(entry) = seq.next_element::<(UniqueTag, V)>()? {
+                        entries.push(entry);
+                    }
+                    Ok(TagMap {
+                        entries: sorted(entries, |(tag, _)| *tag)?,
+                    })
+                }
+            }
+
+            let visitor = TagMapVisitor(PhantomData);
+            if deserializer.is_human_readable() {
+                deserializer.deserialize_map(visitor)
+            } else {
+                deserializer.deserialize_seq(visitor)
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! The guard checking that the expansions of `unique-uuid-derive` match this crate.
+//!
+//! The expansions of the derive macros name items of this crate, which may change
+//! between releases. Each shape of these items is a version of the interface between
+//! both crates, and this module exports a marker `V<n>` for every version it supports.
+//! Every expansion calls the marker of the version it was written for, through a glob
+//! import of this module that shadows a fallback defined by the expansion itself:
+//!
+//! ```rust,ignore
+//! const _: () = {
+//!     const fn V1() {
+//!         unique_uuid::__compat::incompatible("0.1.0", 1)
+//!     }
+//!     {
+//!         use unique_uuid::__compat::*;
+//!         V1()
+//!     }
+//! };
+//! ```
+//!
+//! When this crate does not support the version, the fallback is called instead, and its
+//! evaluation fails the build with a message naming both releases.
+//!
+//! A release keeps the marker of every version it still supports, so that older derive
+//! releases keep compiling against it, and only a derive release whose expansions need
+//! new items moves to a new version. Markers are only removed by incompatible releases.
+
+/// The version of this crate, as named by the errors of the guard.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The marker of version 1 of the interface, the first one.
+#[allow(non_snake_case)]
+pub const fn V1() {}
+
+/// Fails the evaluation of the guard of an expansion by `unique-uuid-derive` at version
+/// `derive_version`, written for version `interface` of the interface, which this crate
+/// does not support.
+pub const fn incompatible(derive_version: &str, interface: u32) {
+    let mut message = Message::new();
+    message.push("unique-uuid-derive ");
+    message.push(derive_version);
+    message.push(" expands to code for version ");
+    message.push_number(interface);
+    message.push(" of the interface of unique-uuid, which unique-uuid ");
+    message.push(VERSION);
+    message.push(
+        " does not support. Use releases of `unique-uuid` and `unique-uuid-derive` that \
+         belong together, e.g. by only depending on `unique-uuid`.",
+    );
+    panic!("{}", message.as_str());
+}
+
+/// A message assembled at compile time, where `format!` is not available.
+struct Message {
+    bytes: [u8; 512],
+    len: usize,
+}
+
+impl Message {
+    const fn new() -> Self {
+        Self {
+            bytes: [0; 512],
+            len: 0,
+        }
+    }
+
+    /// Appends `value`, truncated if the message is full.
+    const fn push(&mut self, value: &str) {
+        let value = value.as_bytes();
+        let mut i = 0;
+        while i < value.len() && self.len < self.bytes.len() {
+            self.bytes[self.len] = value[i];
+            self.len += 1;
+            i += 1;
+        }
+    }
+
+    /// Appends the decimal digits of `value`.
+    const fn push_number(&mut self, value: u32) {
+        let mut digits = [0u8; 10];
+        let mut count = 0;
+        let mut value = value;
+        loop {
+            digits[count] = b'0' + (value % 10) as u8;
+            count += 1;
+            value /= 10;
+            if value == 0 {
+                break;
+            }
+        }
+        while count > 0 && self.len < self.bytes.len() {
+            count -= 1;
+            self.bytes[self.len] = digits[count];
+            self.len += 1;
+        }
+    }
+
+    const fn as_str(&self) -> &str {
+        let (bytes, _) = self.bytes.split_at(self.len);
+        match std::str::from_utf8(bytes) {
+            Ok(message) => message,
+            // Only when a version was truncated in the middle of a character
+            Err(_) => "unique-uuid and unique-uuid-derive are incompatible",
+        }
+    }
+}
@@ -0,0 +1,218 @@
+//! Deserialization of values whose type is only known from their [`UniqueTag`].
+//!
+//! This module is only available with the `erased-serde` feature. A
+//! [`DeserializerRegistry`] maps the `TYPE_TAG` of each registered type to a function
+//! deserializing it, so that `(tag, payload)` pairs, e.g. read from a message bus, can
+//! be decoded without matching on the tags by hand. Any self-describing format works,
+//! through [`erased_serde`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use unique_uuid::{deserializers::DeserializerRegistry, erased_serde, UniqueTypeTag};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize, UniqueTypeTag)]
+//! struct OrderCreated {
+//!     id: u64,
+//! }
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize, UniqueTypeTag)]
+//! struct OrderPaid {
+//!     id: u64,
+//!     amount: u32,
+//! }
+//!
+//! let mut registry = DeserializerRegistry::new();
+//! registry.register::<OrderCreated>().register::<OrderPaid>();
+//!
+//! // The messages only carry the tag of their payload
+//! let messages = [
+//!     (OrderCreated::TYPE_TAG, serde_json::to_string(&OrderCreated { id: 7 }).unwrap()),
+//!     (OrderPaid::TYPE_TAG, serde_json::to_string(&OrderPaid { id: 7, amount: 12 }).unwrap()),
+//! ];
+//! let mut values = Vec::new();
+//! for (tag, payload) in &messages {
+//!     let mut json = serde_json::Deserializer::from_str(payload);
+//!     let mut json = <dyn erased_serde::Deserializer>::erase(&mut json);
+//!     values.push(registry.deserialize(*tag, &mut json).unwrap());
+//! }
+//! assert_eq!(values[0].downcast_ref(), Some(&OrderCreated { id: 7 }));
+//! assert_eq!(values[1].downcast_ref(), Some(&OrderPaid { id: 7, amount: 12 }));
+//! ```
+use std::{any::Any, collections::HashMap, fmt};
+
+use serde::de::DeserializeOwned;
+
+use crate::{UniqueTag, UniqueTypeTag};
+
+/// The function deserializing a registered type.
+type DeserializeFn<O> = Box<
+    dyn Fn(&mut dyn erased_serde::Deserializer<'_>) -> Result<Box<O>, erased_serde::Error>
+        + Send
+        + Sync,
+>;
+
+/// A table of deserializers, indexed by the `TYPE_TAG` of the type they produce.
+///
+/// The values are returned as `Box<O>`: `Box<dyn Any>` by default, to be downcast by the
+/// caller, or any other type given to [`DeserializerRegistry::register_with`], e.g. a
+/// `Box<dyn Event>` of a trait of the application.
+pub struct DeserializerRegistry<O: ?Sized + 'static = dyn Any> {
+    deserializers: HashMap<UniqueTag, DeserializeFn<O>>,
+}
+
+impl DeserializerRegistry {
+    /// Creates an empty registry, returning the values as `Box<dyn Any>`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `T`, deserialized as a `Box<dyn Any>` holding a `T`.
+    ///
+    /// Registering another type with the same tag replaces its deserializer.
+    pub fn register<T: DeserializeOwned + UniqueTypeTag + 'static>(&mut self) -> &mut Self {
+        self.register_with(|value: T| Box::new(value) as Box<dyn Any>)
+    }
+}
+
+impl<O: ?Sized + 'static> DeserializerRegistry<O> {
+    /// Registers `T`, converted to the output of the registry with `into` once
+    /// deserialized.
+    ///
+    /// Registering another type with the same tag replaces its deserializer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use serde::Deserialize;
+    /// use unique_uuid::{deserializers::DeserializerRegistry, erased_serde, UniqueTypeTag};
+    ///
+    /// trait Event {
+    ///     fn describe(&self) -> String;
+    /// }
+    ///
+    /// #[derive(Deserialize, UniqueTypeTag)]
+    /// struct OrderPaid {
+    ///     amount: u32,
+    /// }
+    ///
+    /// impl Event for OrderPaid {
+    ///     fn describe(&self) -> String {
+    ///         format!("paid {}", self.amount)
+    ///     }
+    /// }
+    ///
+    /// let mut registry = DeserializerRegistry::<dyn Event>::default();
+    /// registry.register_with(|event: OrderPaid| Box::new(event) as Box<dyn Event>);
+    ///
+    /// let mut json = serde_json::Deserializer::from_str(r#"{ "amount": 12 }"#);
+    /// let event = registry
+    ///     .deserialize(OrderPaid::TYPE_TAG, &mut <dyn erased_serde::Deserializer>::erase(&mut json))
+    ///     .unwrap();
+    /// assert_eq!(event.describe(), "paid 12");
+    /// ```
+    pub fn register_with<T: DeserializeOwned + UniqueTypeTag + 'static>(
+        &mut self,
+        into: impl Fn(T) -> Box<O> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.deserializers.insert(
+            T::TYPE_TAG,
+            Box::new(move |deserializer| erased_serde::deserialize::<T>(deserializer).map(&into)),
+        );
+        self
+    }
+
+    /// Returns `true` if a type is registered for `tag`.
+    pub fn contains(&self, tag: UniqueTag) -> bool {
+        self.deserializers.contains_key(&tag)
+    }
+
+    /// Returns the tags of the registered types, in no particular order.
+    pub fn tags(&self) -> impl Iterator<Item = UniqueTag> + '_ {
+        self.deserializers.keys().copied()
+    }
+
+    /// Deserializes a value of the type registered for `tag` from `deserializer`.
+    ///
+    /// # Errors
+    /// Returns [`Error::UnknownTag`] if no type is registered for `tag`, and
+    /// [`Error::Deserialize`] if the value cannot be deserialized.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::{
+    ///     deserializers::{DeserializerRegistry, Error},
+    ///     erased_serde,
+    ///     uuid::uuid,
+    ///     UniqueTag,
+    /// };
+    ///
+    /// let registry = DeserializerRegistry::new();
+    /// let tag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    ///
+    /// let mut json = serde_json::Deserializer::from_str("{}");
+    /// let err = registry
+    ///     .deserialize(tag, &mut <dyn erased_serde::Deserializer>::erase(&mut json))
+    ///     .unwrap_err();
+    /// assert!(matches!(err, Error::UnknownTag(unknown) if unknown == tag));
+    /// ```
+    pub fn deserialize(
+        &self,
+        tag: UniqueTag,
+        deserializer: &mut dyn erased_serde::Deserializer<'_>,
+    ) -> Result<Box<O>, Error> {
+        let deserialize = self.deserializers.get(&tag).ok_or(Error::UnknownTag(tag))?;
+        deserialize(deserializer).map_err(|source| Error::Deserialize { tag, source })
+    }
+}
+
+impl<O: ?Sized + 'static> Default for DeserializerRegistry<O> {
+    fn default() -> Self {
+        Self {
+            deserializers: HashMap::new(),
+        }
+    }
+}
+
+impl<O: ?Sized + 'static> fmt::Debug for DeserializerRegistry<O> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.deserializers.keys()).finish()
+    }
+}
+
+/// The errors of [`DeserializerRegistry::deserialize`].
+#[derive(Debug)]
+pub enum Error {
+    /// No type is registered for the tag.
+    UnknownTag(UniqueTag),
+
+    /// The value could not be deserialized as the type registered for the tag.
+    Deserialize {
+        /// The tag of the value.
+        tag: UniqueTag,
+        /// The underlying deserialization error.
+        source: erased_serde::Error,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownTag(tag) => write!(f, "no deserializer is registered for tag {}", tag.0),
+            Error::Deserialize { tag, source } => {
+                write!(f, "cannot deserialize tag {}: {}", tag.0, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::UnknownTag(_) => None,
+            Error::Deserialize { source, .. } => Some(source),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Routing of messages to handlers by their [`UniqueTag`].
+use std::{collections::HashMap, fmt};
+
+use crate::{UniqueTag, UniqueTypeTag};
+
+/// A handler of a [`DispatchTable`].
+type Handler<Ctx, Out> = Box<dyn Fn(Ctx) -> Out + Send + Sync>;
+
+/// The handler of the tags without a handler of their own.
+type Fallback<Ctx, Out> = Box<dyn Fn(UniqueTag, Ctx) -> Out + Send + Sync>;
+
+/// A table of handlers, indexed by the tag of the messages they handle.
+///
+/// Each handler receives a context of type `Ctx`, e.g. the payload of the message, and
+/// returns an `Out`. A tag has at most one handler: registering a second one fails with
+/// [`DuplicateHandler`]. Tags without a handler go to the fallback, if any.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{DispatchTable, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// struct OrderCreated;
+///
+/// #[derive(UniqueTypeTag)]
+/// struct OrderPaid;
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Shipment;
+///
+/// let mut table = DispatchTable::new();
+/// table
+///     .on::<OrderCreated>(|id: u32| format!("created #{}", id))
+///     .unwrap()
+///     .on::<OrderPaid>(|id| format!("paid #{}", id))
+///     .unwrap();
+///
+/// assert_eq!(table.dispatch(OrderCreated::TYPE_TAG, 7), Some("created #7".to_string()));
+/// assert_eq!(table.dispatch(Shipment::TYPE_TAG, 7), None);
+///
+/// // Tags without a handler go to the fallback
+/// table.fallback(|tag, id| format!("unhandled {} #{}", tag.0, id));
+/// assert_eq!(
+///     table.dispatch(Shipment::TYPE_TAG, 7),
+///     Some(format!("unhandled {} #7", Shipment::TYPE_TAG.0))
+/// );
+///
+/// // A type is only handled once
+/// assert!(table.on::<OrderPaid>(|_| String::new()).is_err());
+/// assert_eq!(table.dispatch(OrderPaid::TYPE_TAG, 7), Some("paid #7".to_string()));
+/// ```
+pub struct DispatchTable<Ctx, Out> {
+    handlers: HashMap<UniqueTag, Handler<Ctx, Out>>,
+    fallback: Option<Fallback<Ctx, Out>>,
+}
+
+impl<Ctx, Out> DispatchTable<Ctx, Out> {
+    /// Creates a table without any handler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a handler for the messages tagged with the `TYPE_TAG` of `T`.
+    ///
+    /// # Errors
+    /// Fails if the tag already has a handler, which is kept.
+    pub fn on<T: UniqueTypeTag>(
+        &mut self,
+        handler: impl Fn(Ctx) -> Out + Send + Sync + 'static,
+    ) -> Result<&mut Self, DuplicateHandler> {
+        self.on_tag(T::TYPE_TAG, handler)
+    }
+
+    /// Adds a handler for the messages tagged with `tag`, e.g. a tag of [`unique_tag!`].
+    ///
+    /// # Errors
+    /// Fails if the tag already has a handler, which is kept.
+    ///
+    /// [`unique_tag!`]: crate::unique_tag
+    pub fn on_tag(
+        &mut self,
+        tag: UniqueTag,
+        handler: impl Fn(Ctx) -> Out + Send + Sync + 'static,
+    ) -> Result<&mut Self, DuplicateHandler> {
+        if self.handlers.contains_key(&tag) {
+            return Err(DuplicateHandler(tag));
+        }
+        self.handlers.insert(tag, Box::new(handler));
+        Ok(self)
+    }
+
+    /// Sets the handler of the tags without a handler of their own, replacing the
+    /// previous one.
+    pub fn fallback(
+        &mut self,
+        handler: impl Fn(UniqueTag, Ctx) -> Out + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Calls the handler of `tag` with `ctx`, or the fallback if the tag has no handler.
+    ///
+    /// Returns `None` if neither exists.
+    pub fn dispatch(&self, tag: UniqueTag, ctx: Ctx) -> Option<Out> {
+        match self.handlers.get(&tag) {
+            Some(handler) => Some(handler(ctx)),
+            None => self.fallback.as_ref().map(|fallback| fallback(tag, ctx)),
+        }
+    }
+
+    /// Returns `true` if `tag` has a handler of its own.
+    pub fn handles(&self, tag: UniqueTag) -> bool {
+        self.handlers.contains_key(&tag)
+    }
+
+    /// Returns the number of tags with a handler of their own.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if no tag has a handler of its own, whether or not there is a
+    /// fallback.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl<Ctx, Out> Default for DispatchTable<Ctx, Out> {
+    fn default() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            fallback: None,
+        }
+    }
+}
+
+impl<Ctx, Out> fmt::Debug for DispatchTable<Ctx, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DispatchTable")
+            .field("tags", &self.handlers.keys().collect::<Vec<_>>())
+            .field("fallback", &self.fallback.is_some())
+            .finish()
+    }
+}
+
+/// The error returned when adding a handler to a [`DispatchTable`] for a tag that
+/// already has one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DuplicateHandler(pub UniqueTag);
+
+impl fmt::Display for DuplicateHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the tag {} already has a handler", self.0 .0)
+    }
+}
+
+impl std::error::Error for DuplicateHandler {}
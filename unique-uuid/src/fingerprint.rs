@@ -0,0 +1,73 @@
+//! Fingerprints of the registry, for services to check that they were built against the
+//! same entries.
+//!
+//! A binary whose tags come from a copy of the registry that was not shared, or that was
+//! forked, may give a UUID another meaning than its peers do, and nothing fails. The
+//! `registry_fingerprint!` macro declares a `REGISTRY_FINGERPRINT` constant summarizing
+//! the keys and UUIDs of the registry at compile time; services exchange it during their
+//! handshake and call [`check_fingerprint`] on the one of their peer.
+//!
+//! # Algorithm
+//!
+//! The fingerprint is the version 5 UUID (see [`UniqueTag::new_v5`]) whose namespace is
+//! [`NAMESPACE`] and whose name is a line `<section>\t<key>\t<uuid>\n` for every entry,
+//! e.g. `unique_tags\torders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa\n`. The
+//! entries of `[unique_tags]` come first, each section is sorted by key, and the UUIDs
+//! are hyphenated and lowercase. Only the keys and UUIDs count: the other fields of the
+//! entries, the layout and the format of the file never change the fingerprint.
+//! `unique_uuid::registry::Registry::fingerprint` computes the same value at runtime.
+use crate::UniqueTag;
+
+/// The namespace of the fingerprints. Changing it changes every fingerprint.
+pub const NAMESPACE: UniqueTag = UniqueTag(uuid::uuid!("3c9e7f52-81d4-5a6b-b0e3-9f24c6d18a75"));
+
+/// The error returned by [`check_fingerprint`] when two registries differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FingerprintMismatch {
+    /// The fingerprint of the registry of this binary.
+    pub ours: UniqueTag,
+
+    /// The fingerprint received from the peer.
+    pub theirs: UniqueTag,
+}
+
+impl std::fmt::Display for FingerprintMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the registry fingerprint {} differs from ours, {}: the peer was built against \
+             another registry",
+            self.theirs.0, self.ours.0
+        )
+    }
+}
+
+impl std::error::Error for FingerprintMismatch {}
+
+/// Checks that `theirs`, the fingerprint received from a peer, is `ours`, the
+/// `REGISTRY_FINGERPRINT` declared by `registry_fingerprint!`.
+///
+/// # Errors
+/// Returns a [`FingerprintMismatch`] holding both fingerprints if they differ.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{check_fingerprint, uuid::uuid, FingerprintMismatch, UniqueTag};
+///
+/// const OURS: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+/// const THEIRS: UniqueTag = UniqueTag(uuid!("d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"));
+///
+/// assert_eq!(check_fingerprint(OURS, OURS), Ok(()));
+/// assert_eq!(
+///     check_fingerprint(OURS, THEIRS),
+///     Err(FingerprintMismatch { ours: OURS, theirs: THEIRS })
+/// );
+/// ```
+pub fn check_fingerprint(ours: UniqueTag, theirs: UniqueTag) -> Result<(), FingerprintMismatch> {
+    if ours == theirs {
+        Ok(())
+    } else {
+        Err(FingerprintMismatch { ours, theirs })
+    }
+}
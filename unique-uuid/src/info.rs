@@ -0,0 +1,39 @@
+//! Metadata of a tag, the same whichever lookup found it.
+use crate::UniqueTag;
+
+/// What a tag identifies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TagKind {
+    /// A string tag of `unique_tag!`, stored in `[unique_tags]`.
+    Tag,
+
+    /// A type tag of `#[derive(UniqueTypeTag)]`, stored in `[unique_type_tags]`.
+    TypeTag,
+}
+
+/// A tag with its key and metadata, as returned by the lookups of the `registry` module:
+/// `Registry::get`, which reads the registry file, and `find_by_tag`, which reads the
+/// registrations of the running binary.
+///
+/// The same entry yields the same `TagInfo` through every lookup, as far as they know
+/// its metadata: the registrations of the binary carry no description.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TagInfo<'a> {
+    /// The tag.
+    pub tag: UniqueTag,
+
+    /// The key of the tag in the registry, e.g. `orders.created` or `::Order`.
+    pub key: &'a str,
+
+    /// What the tag identifies.
+    pub kind: TagKind,
+
+    /// The namespace of the key, e.g. `orders` for `orders.created`, if it has one.
+    pub namespace: Option<&'a str>,
+
+    /// The human-readable description of the tag, if any.
+    pub description: Option<&'a str>,
+
+    /// The name of the crate that minted or registered the tag, if known.
+    pub source_crate: Option<&'a str>,
+}
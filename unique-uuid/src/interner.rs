@@ -0,0 +1,240 @@
+//! Interning of [`UniqueTag`]s into dense `u32` handles.
+use std::{collections::HashMap, fmt};
+
+use crate::UniqueTag;
+
+/// The dense handle of a tag interned by a [`TagInterner`].
+///
+/// Handles are allocated in interning order, from `0`, so that they can index a `Vec`
+/// directly. A handle is only meaningful to the interner that allocated it, or to the
+/// [`FrozenTagInterner`] it was frozen into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InternedTag(u32);
+
+impl InternedTag {
+    /// Returns the handle as an index, `0` for the first tag interned.
+    pub const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the handle as a `u32`.
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for InternedTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Maps tags to dense `u32` handles, for hot loops where 16-byte tags are too costly to
+/// carry and compare.
+///
+/// Tags are interned once, e.g. at startup, and the interner is then frozen into a
+/// [`FrozenTagInterner`], which is `Sync` and looks tags up without any lock. Interning
+/// the same tag twice returns the same handle.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{unique_tag, TagInterner};
+///
+/// let mut interner = TagInterner::with_capacity(2);
+/// let audio = interner.intern(unique_tag!(test, "plugins.audio"));
+/// let video = interner.intern(unique_tag!(test, "plugins.video"));
+/// assert_eq!(interner.intern(unique_tag!(test, "plugins.audio")), audio);
+/// assert_eq!((audio.index(), video.index()), (0, 1));
+///
+/// let interner = interner.freeze();
+/// assert_eq!(interner.get(unique_tag!(test, "plugins.video")), Some(video));
+/// assert_eq!(interner.get(unique_tag!(test, "plugins")), None);
+/// assert_eq!(interner.resolve(audio), unique_tag!(test, "plugins.audio"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TagInterner {
+    tags: Vec<UniqueTag>,
+    handles: HashMap<UniqueTag, InternedTag>,
+}
+
+impl TagInterner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty interner with room for `capacity` tags.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            tags: Vec::with_capacity(capacity),
+            handles: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Creates an interner holding every entry of `registry`, `[unique_tags]` first and
+    /// each section in key order, so that the handles only depend on the registry.
+    /// Aliases share the handle of their entry.
+    ///
+    /// This function is only available with the `registry-file` feature.
+    ///
+    /// # Panics
+    /// Panics if the registry has more than `u32::MAX` distinct UUIDs.
+    #[cfg(feature = "registry-file")]
+    pub fn from_registry(registry: &crate::registry::Registry) -> Self {
+        let mut interner = Self::with_capacity(registry.len());
+        interner.extend(registry.tags().chain(registry.type_tags()).map(|(_, tag)| tag));
+        interner
+    }
+
+    /// Returns the handle of `tag`, allocating the next one if `tag` is new.
+    ///
+    /// # Panics
+    /// Panics if `u32::MAX` tags are already interned.
+    pub fn intern(&mut self, tag: UniqueTag) -> InternedTag {
+        if let Some(handle) = self.handles.get(&tag) {
+            return *handle;
+        }
+        let handle = u32::try_from(self.tags.len())
+            .ok()
+            .filter(|handle| *handle < u32::MAX)
+            .map(InternedTag)
+            .expect("a TagInterner holds at most u32::MAX tags");
+        self.tags.push(tag);
+        self.handles.insert(tag, handle);
+        handle
+    }
+
+    /// Returns the handle of `tag`, if it is interned.
+    pub fn get(&self, tag: UniqueTag) -> Option<InternedTag> {
+        self.handles.get(&tag).copied()
+    }
+
+    /// Returns the tag of `handle`.
+    ///
+    /// # Panics
+    /// Panics if `handle` was not allocated by this interner.
+    pub fn resolve(&self, handle: InternedTag) -> UniqueTag {
+        resolve(&self.tags, handle)
+    }
+
+    /// Returns the number of interned tags.
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Returns `true` if no tag is interned.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Iterates over the interned tags with their handles, in handle order.
+    pub fn iter(&self) -> impl Iterator<Item = (InternedTag, UniqueTag)> + '_ {
+        iter(&self.tags)
+    }
+
+    /// Freezes the interner into a read-only snapshot, which keeps every handle.
+    pub fn freeze(self) -> FrozenTagInterner {
+        FrozenTagInterner {
+            tags: self.tags.into_boxed_slice(),
+            handles: self.handles,
+        }
+    }
+}
+
+impl Extend<UniqueTag> for TagInterner {
+    fn extend<I: IntoIterator<Item = UniqueTag>>(&mut self, tags: I) {
+        for tag in tags {
+            self.intern(tag);
+        }
+    }
+}
+
+impl FromIterator<UniqueTag> for TagInterner {
+    fn from_iter<I: IntoIterator<Item = UniqueTag>>(tags: I) -> Self {
+        let tags = tags.into_iter();
+        let mut interner = Self::with_capacity(tags.size_hint().0);
+        interner.extend(tags);
+        interner
+    }
+}
+
+/// A read-only snapshot of a [`TagInterner`], see [`TagInterner::freeze`].
+///
+/// The snapshot cannot intern new tags. It is `Send` and `Sync`, and its lookups take no
+/// lock, so that it can be shared by every thread, e.g. in a `static` initialized once.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use unique_uuid::{unique_tag, TagInterner};
+///
+/// let interner: TagInterner = [
+///     unique_tag!(test, "plugins.audio"),
+///     unique_tag!(test, "plugins.video"),
+/// ]
+/// .into_iter()
+/// .collect();
+/// let interner = Arc::new(interner.freeze());
+///
+/// let shared = Arc::clone(&interner);
+/// let handle = std::thread::spawn(move || shared.get(unique_tag!(test, "plugins.video")))
+///     .join()
+///     .unwrap();
+/// assert_eq!(handle.map(|handle| handle.index()), Some(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FrozenTagInterner {
+    tags: Box<[UniqueTag]>,
+    handles: HashMap<UniqueTag, InternedTag>,
+}
+
+impl FrozenTagInterner {
+    /// Returns the handle of `tag`, if it is interned.
+    pub fn get(&self, tag: UniqueTag) -> Option<InternedTag> {
+        self.handles.get(&tag).copied()
+    }
+
+    /// Returns the tag of `handle`.
+    ///
+    /// # Panics
+    /// Panics if `handle` was not allocated by the interner this snapshot was frozen from.
+    pub fn resolve(&self, handle: InternedTag) -> UniqueTag {
+        resolve(&self.tags, handle)
+    }
+
+    /// Returns the number of interned tags.
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    /// Returns `true` if no tag is interned.
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Iterates over the interned tags with their handles, in handle order.
+    pub fn iter(&self) -> impl Iterator<Item = (InternedTag, UniqueTag)> + '_ {
+        iter(&self.tags)
+    }
+}
+
+/// Returns the tag of `handle` among `tags`, in handle order.
+fn resolve(tags: &[UniqueTag], handle: InternedTag) -> UniqueTag {
+    match tags.get(handle.index()) {
+        Some(tag) => *tag,
+        None => panic!(
+            "{} was not allocated by this interner, which holds {} tags",
+            handle,
+            tags.len()
+        ),
+    }
+}
+
+/// Iterates over `tags`, in handle order, with their handles.
+fn iter(tags: &[UniqueTag]) -> impl Iterator<Item = (InternedTag, UniqueTag)> + '_ {
+    tags.iter()
+        .enumerate()
+        .map(|(index, tag)| (InternedTag(index as u32), *tag))
+}
@@ -8,6 +8,26 @@
 //!
 //! - Cross-platform stable type identification
 //! - Serialization support via the `serde` feature
+//! - Runtime access to the `types.toml` registry via the `registry-file` feature, stored as
+//!   TOML, JSON or, with the `ron` and `yaml` features, RON and YAML
+//! - Minting the registry entries from build scripts via the `build` feature
+//! - Human-readable `Debug` output via the `named-debug` feature
+//! - Conversions to and from JavaScript values via the `wasm` feature
+//! - Postgres `uuid` columns support via the `sqlx` and `postgres` features
+//! - Deserializing values by their tag via the `erased-serde` feature
+//! - Bevy `TypeUuid` implementations via the `bevy` feature
+//! - Iterating the type tags of the running binary, and constructing values from their
+//!   tags, via the `linkme` feature
+//! - Static maps keyed by tags, with a perfect hash function, via the `phf` feature
+//! - Viewing bytes as tags without copying them via the `zerocopy` feature
+//! - Listing the type tags of a compiled binary without running it via the `tag-section`
+//!   and `inspect` features
+//! - Conversions between tags and ULIDs via the `ulid` feature
+//! - Deterministic tags for unit tests via the `test-util` feature
+//! - Fingerprints of the registry, to detect peers built against another registry, via
+//!   the `fingerprint` feature
+//! - 64-bit projections of the tags, for headers with room for 8 bytes only, via the
+//!   `tag64` feature
 //! - Type-safe wrapper around UUIDs
 //!
 //! # Example
@@ -21,8 +41,61 @@
 //! let tag = MyType::TYPE_TAG;
 //! ```
 
+#[cfg(feature = "bevy")]
+pub extern crate bevy_reflect;
+#[cfg(feature = "erased-serde")]
+pub extern crate erased_serde;
+#[cfg(feature = "linkme")]
+#[doc(hidden)]
+pub extern crate linkme;
+#[cfg(feature = "phf")]
+#[doc(hidden)]
+pub extern crate phf;
 pub extern crate uuid;
+pub use collections::{
+    TagMap, TagSet, TagSetDifference, TagSetIntersection, TagSetSymmetricDifference, TagSetUnion,
+};
+pub use dispatch::{DispatchTable, DuplicateHandler};
+#[cfg(feature = "fingerprint")]
+pub use fingerprint::{check_fingerprint, FingerprintMismatch};
+pub use info::{TagInfo, TagKind};
+pub use interner::{FrozenTagInterner, InternedTag, TagInterner};
 pub use unique_uuid_derive::*;
+
+#[doc(hidden)]
+#[path = "compat.rs"]
+pub mod __compat;
+#[cfg(feature = "build")]
+pub mod build;
+mod collections;
+#[cfg(feature = "erased-serde")]
+pub mod deserializers;
+mod dispatch;
+#[cfg(feature = "fingerprint")]
+pub mod fingerprint;
+mod info;
+mod interner;
+#[cfg(feature = "named-debug")]
+pub mod names;
+#[cfg(feature = "phf")]
+pub mod phf_map;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "registry-file")]
+pub mod registry;
+mod sha1;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(any(feature = "tag-section", feature = "inspect"))]
+pub mod tag_section;
+#[cfg(feature = "ulid")]
+mod ulid;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "zerocopy")]
+mod zerocopy;
+
+use std::{borrow::Borrow, rc::Rc, sync::Arc};
 use uuid::Uuid;
 
 /// A type-safe wrapper around [`uuid::Uuid`] for storing unique type identifiers.
@@ -40,11 +113,562 @@ use uuid::Uuid;
 ///
 /// When the `serde` feature is enabled, this type implements `Serialize` and
 /// `Deserialize` for compatibility with serde-based serialization formats.
+///
+/// # Matching
+///
+/// `UniqueTag` derives `PartialEq` and `Eq`, as [`Uuid`] does, so tag constants can be
+/// used as `match` patterns. A macro invocation cannot be a pattern itself: bind the tags
+/// to constants first. Raw `u128` values, e.g. read from the wire, are matched against
+/// [`UniqueTag::as_u128`] of the same constants:
+///
+/// ```rust
+/// use unique_uuid::{unique_tag, UniqueTag};
+///
+/// const AUDIO: UniqueTag = unique_tag!(test, "plugins.audio");
+/// const VIDEO: UniqueTag = unique_tag!(test, "plugins.video");
+///
+/// fn mime(tag: UniqueTag) -> &'static str {
+///     match tag {
+///         AUDIO => "audio/ogg",
+///         VIDEO => "video/webm",
+///         _ => "application/octet-stream",
+///     }
+/// }
+/// assert_eq!(mime(unique_tag!(test, "plugins.video")), "video/webm");
+/// assert_eq!(mime(unique_tag!(test, "plugins")), "application/octet-stream");
+///
+/// const AUDIO_U128: u128 = AUDIO.as_u128();
+/// let wire = AUDIO.as_u128();
+/// assert!(matches!(wire, AUDIO_U128));
+/// ```
+///
+/// # Debug output
+///
+/// By default, `UniqueTag` is formatted as `UniqueTag(<uuid>)`. When the `named-debug`
+/// feature is enabled, tags registered in the [`names`] table are formatted as
+/// `<name> (<uuid>)` instead, while unknown tags keep the default format.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(not(feature = "named-debug"), derive(Debug))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[repr(transparent)]
 pub struct UniqueTag(pub Uuid);
 
+#[cfg(feature = "named-debug")]
+impl std::fmt::Debug for UniqueTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match names::name_of(*self) {
+            Some(name) => write!(f, "{} ({})", name, self.0),
+            None => f.debug_tuple("UniqueTag").field(&self.0).finish(),
+        }
+    }
+}
+
+/// The namespace of [`UniqueTag::combine_all`]. Changing it changes every combined tag.
+const COMBINE_NAMESPACE: UniqueTag = UniqueTag(uuid::uuid!("6f1c5a3e-2b7d-5e84-9c0a-4d3f8e21b7c6"));
+
+/// The namespace of [`UniqueTag::for_test`]. Changing it changes every test tag.
+#[cfg(any(test, feature = "test-util"))]
+const TEST_NAMESPACE: UniqueTag = UniqueTag(uuid::uuid!("b1d3c6f0-8e2a-5f47-a9c4-3e6d0b7f2a18"));
+
+impl UniqueTag {
+    /// Derives a tag from a namespace tag and a name, as a version 5 (SHA-1, name-based)
+    /// UUID.
+    ///
+    /// This allows applications to mint tags from runtime data (tenant IDs, plugin
+    /// names, ...) under a registered namespace tag. The derivation is deterministic
+    /// and follows RFC 9562, so the result is identical to
+    /// [`Uuid::new_v5`](uuid::Uuid::new_v5) for the same inputs.
+    ///
+    /// Derived tags are **never** written to `types.toml`: they are fully determined by
+    /// their inputs and can be recomputed at any time.
+    ///
+    /// This function is `const`, so sub-tags of a compile-time tag can be computed at
+    /// compile time as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{unique_tag, uuid::{uuid, Uuid}, UniqueTag};
+    ///
+    /// let tenants = unique_tag!("tenants");
+    /// let acme = UniqueTag::new_v5(tenants, b"acme");
+    ///
+    /// // Deterministic
+    /// assert_eq!(acme, UniqueTag::new_v5(tenants, b"acme"));
+    /// assert_eq!(acme.0.get_version_num(), 5);
+    ///
+    /// // Different names or namespaces give different tags
+    /// assert_ne!(acme, UniqueTag::new_v5(tenants, b"globex"));
+    /// assert_ne!(acme, UniqueTag::new_v5(unique_tag!(test, "plugins"), b"acme"));
+    ///
+    /// // Compatible with the standard version 5 derivation
+    /// const PYTHON: UniqueTag = UniqueTag::new_v5(UniqueTag(Uuid::NAMESPACE_DNS), b"python.org");
+    /// assert_eq!(PYTHON.0, uuid!("886313e1-3b8a-5372-9b90-0c9aee199e5d"));
+    /// ```
+    pub const fn new_v5(namespace: UniqueTag, name: &[u8]) -> UniqueTag {
+        UniqueTag::from_sha1(sha1::digest(&[namespace.0.as_bytes(), name]))
+    }
+
+    /// Derives a stable tag from `name`, for unit tests needing "some tag" without minting
+    /// an entry in the registry.
+    ///
+    /// The tag is the version 5 UUID (see [`UniqueTag::new_v5`]) of `name` under the
+    /// namespace `b1d3c6f0-8e2a-5f47-a9c4-3e6d0b7f2a18`, dedicated to tests. It is never
+    /// written to the registry, and never equal to a tag minted by the macros, which are
+    /// version 4 UUIDs.
+    ///
+    /// This function is only available with the `test-util` feature, which should only be
+    /// enabled for `[dev-dependencies]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{unique_tag, uuid::uuid, UniqueTag};
+    ///
+    /// const ORDERS: UniqueTag = UniqueTag::for_test("orders");
+    ///
+    /// assert_eq!(ORDERS, UniqueTag::for_test("orders"));
+    /// assert_eq!(ORDERS.0, uuid!("e2a6fccb-dd95-56be-885e-97f9d5182d32"));
+    /// assert_ne!(ORDERS, UniqueTag::for_test("invoices"));
+    /// assert_eq!(ORDERS.0.get_version_num(), 5);
+    /// assert_ne!(unique_tag!("tenants").0.get_version_num(), 5);
+    /// ```
+    #[cfg(any(test, feature = "test-util"))]
+    pub const fn for_test(name: &str) -> UniqueTag {
+        UniqueTag::new_v5(TEST_NAMESPACE, name.as_bytes())
+    }
+
+    /// Derives a new tag from `self` and `other`.
+    ///
+    /// This is the same as [`UniqueTag::combine_all`] with `&[self, other]`. The
+    /// combination is order-sensitive: `a.combine(b)` and `b.combine(a)` differ.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const SCHEMA: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    /// const V2: UniqueTag = UniqueTag(uuid!("d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"));
+    /// const SCHEMA_V2: UniqueTag = SCHEMA.combine(V2);
+    ///
+    /// assert_eq!(SCHEMA_V2.0, uuid!("9e051abc-d3c8-5ac0-abd3-769feaa4c057"));
+    /// assert_eq!(V2.combine(SCHEMA).0, uuid!("7344ead6-aac4-520e-aa55-dd8ed7202f5f"));
+    /// assert_eq!(SCHEMA_V2, UniqueTag::combine_all(&[SCHEMA, V2]));
+    /// ```
+    pub const fn combine(self, other: UniqueTag) -> UniqueTag {
+        UniqueTag::combine_all(&[self, other])
+    }
+
+    /// Derives a new tag from `tags`, in order.
+    ///
+    /// # Algorithm
+    ///
+    /// The result is the version 5 UUID (see [`UniqueTag::new_v5`]) whose namespace is
+    /// `6f1c5a3e-2b7d-5e84-9c0a-4d3f8e21b7c6` and whose name is the concatenation of the
+    /// big-endian bytes (i.e. [`Uuid::as_bytes`]) of every tag of `tags`. This is version 1
+    /// of the algorithm: it is also used for the tags of generic types derived with
+    /// `#[unique_type_tag(compose)]`, and will not change in a semver-compatible release.
+    ///
+    /// The result depends on the order of `tags`, and is never equal to one of them except
+    /// by a hash collision. Nesting matters as well: `a.combine(b).combine(c)` differs from
+    /// `combine_all(&[a, b, c])`. An empty slice gives a fixed tag.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const A: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    /// const B: UniqueTag = UniqueTag(uuid!("d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"));
+    /// const C: UniqueTag = UniqueTag(uuid!("83db37ed-2e0c-46ef-9185-991c699f034d"));
+    ///
+    /// assert_eq!(UniqueTag::combine_all(&[A, B, C]).0, uuid!("ee115ebe-49ca-5239-a747-024d1262e0e4"));
+    /// assert_eq!(UniqueTag::combine_all(&[A]).0, uuid!("aac050c7-7679-5c58-aaa6-0e9e941feb4e"));
+    /// assert_eq!(UniqueTag::combine_all(&[]).0, uuid!("991a96e8-ce51-50d2-81cc-4ef55d48787d"));
+    ///
+    /// // The same as a version 5 UUID over the concatenated bytes
+    /// let namespace = UniqueTag(uuid!("6f1c5a3e-2b7d-5e84-9c0a-4d3f8e21b7c6"));
+    /// let name = [*A.0.as_bytes(), *B.0.as_bytes(), *C.0.as_bytes()].concat();
+    /// assert_eq!(UniqueTag::combine_all(&[A, B, C]), UniqueTag::new_v5(namespace, &name));
+    /// ```
+    pub const fn combine_all(tags: &[UniqueTag]) -> UniqueTag {
+        UniqueTag::from_sha1(sha1::digest_with_tags(
+            &[COMBINE_NAMESPACE.0.as_bytes()],
+            tags,
+        ))
+    }
+
+    /// Builds a version 5 tag out of the first 16 bytes of a SHA-1 digest.
+    const fn from_sha1(digest: [u8; 20]) -> UniqueTag {
+        let mut bytes = [0u8; 16];
+        let mut i = 0;
+        while i < bytes.len() {
+            bytes[i] = digest[i];
+            i += 1;
+        }
+        UniqueTag(uuid::Builder::from_sha1_bytes(bytes).into_uuid())
+    }
+
+    /// Returns `true` if both tags are equal, usable in `const` context.
+    ///
+    /// This is equivalent to `==`, which cannot be called in `const` context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const A: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    /// const B: UniqueTag = UniqueTag(uuid!("d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"));
+    ///
+    /// const _: () = assert!(A.const_eq(&A));
+    /// const _: () = assert!(!A.const_eq(&B));
+    /// ```
+    pub const fn const_eq(&self, other: &UniqueTag) -> bool {
+        matches!(self.const_cmp(other), core::cmp::Ordering::Equal)
+    }
+
+    /// Compares two tags, usable in `const` context.
+    ///
+    /// The ordering is the same as the one of [`Ord`], i.e. the lexicographic order of
+    /// the big-endian bytes of the UUIDs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::cmp::Ordering;
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const A: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    /// const B: UniqueTag = UniqueTag(uuid!("d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"));
+    ///
+    /// const _: () = assert!(matches!(A.const_cmp(&B), Ordering::Less));
+    /// assert_eq!(A.const_cmp(&B), A.cmp(&B));
+    /// assert_eq!(B.const_cmp(&A), B.cmp(&A));
+    /// ```
+    pub const fn const_cmp(&self, other: &UniqueTag) -> core::cmp::Ordering {
+        let (lhs, rhs) = (self.0.as_bytes(), other.0.as_bytes());
+        let mut i = 0;
+        while i < lhs.len() {
+            if lhs[i] < rhs[i] {
+                return core::cmp::Ordering::Less;
+            } else if lhs[i] > rhs[i] {
+                return core::cmp::Ordering::Greater;
+            }
+            i += 1;
+        }
+        core::cmp::Ordering::Equal
+    }
+
+    /// Returns the four fields of the UUID of the tag, as [`Uuid::as_fields`]: the first
+    /// three are read in big-endian order, and the last 8 bytes as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// /// The header of a message, as packed on the wire
+    /// #[repr(C)]
+    /// struct Header {
+    ///     time_low: u32,
+    ///     time_mid: u16,
+    ///     time_hi_and_version: u16,
+    ///     clock_seq_and_node: [u8; 8],
+    /// }
+    ///
+    /// const TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    ///
+    /// let (d1, d2, d3, d4) = TAG.as_fields();
+    /// let header = Header {
+    ///     time_low: d1.to_be(),
+    ///     time_mid: d2.to_be(),
+    ///     time_hi_and_version: d3.to_be(),
+    ///     clock_seq_and_node: *d4,
+    /// };
+    /// assert_eq!((d1, d2, d3), (0x2af1216e, 0x7042, 0x406a));
+    ///
+    /// let tag = UniqueTag::from_fields(
+    ///     u32::from_be(header.time_low),
+    ///     u16::from_be(header.time_mid),
+    ///     u16::from_be(header.time_hi_and_version),
+    ///     &header.clock_seq_and_node,
+    /// );
+    /// assert_eq!(tag, TAG);
+    /// ```
+    pub fn as_fields(&self) -> (u32, u16, u16, &[u8; 8]) {
+        self.0.as_fields()
+    }
+
+    /// Returns the four fields of the UUID of the tag, as [`Uuid::to_fields_le`]: the
+    /// first three are read in little-endian order, as in the Microsoft GUID layout, and
+    /// the last 8 bytes as they are.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    ///
+    /// let (d1, d2, d3, d4) = TAG.to_fields_le();
+    /// assert_eq!((d1, d2, d3), (0x6e21f12a, 0x4270, 0x6a40));
+    /// assert_eq!(UniqueTag::from_fields_le(d1, d2, d3, d4), TAG);
+    /// assert_ne!(UniqueTag::from_fields(d1, d2, d3, d4), TAG);
+    /// ```
+    pub fn to_fields_le(&self) -> (u32, u16, u16, &[u8; 8]) {
+        self.0.to_fields_le()
+    }
+
+    /// Builds a tag from the four fields of its UUID, the first three in big-endian
+    /// order, as [`Uuid::from_fields`]. This is the inverse of [`UniqueTag::as_fields`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const TAG: UniqueTag = UniqueTag::from_fields(
+    ///     0x2af1216e,
+    ///     0x7042,
+    ///     0x406a,
+    ///     &[0xa5, 0x77, 0xc3, 0x9a, 0x7e, 0x7c, 0x3f, 0xaa],
+    /// );
+    /// assert_eq!(TAG.0, uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    ///
+    /// let (d1, d2, d3, d4) = TAG.as_fields();
+    /// assert_eq!(UniqueTag::from_fields(d1, d2, d3, d4), TAG);
+    /// ```
+    pub const fn from_fields(d1: u32, d2: u16, d3: u16, d4: &[u8; 8]) -> UniqueTag {
+        UniqueTag(Uuid::from_fields(d1, d2, d3, d4))
+    }
+
+    /// Builds a tag from the four fields of its UUID, the first three in little-endian
+    /// order, as [`Uuid::from_fields_le`]. This is the inverse of
+    /// [`UniqueTag::to_fields_le`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const TAG: UniqueTag = UniqueTag::from_fields_le(
+    ///     0x6e21f12a,
+    ///     0x4270,
+    ///     0x6a40,
+    ///     &[0xa5, 0x77, 0xc3, 0x9a, 0x7e, 0x7c, 0x3f, 0xaa],
+    /// );
+    /// assert_eq!(TAG.0, uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    ///
+    /// let (d1, d2, d3, d4) = TAG.to_fields_le();
+    /// assert_eq!(UniqueTag::from_fields_le(d1, d2, d3, d4), TAG);
+    /// ```
+    pub const fn from_fields_le(d1: u32, d2: u16, d3: u16, d4: &[u8; 8]) -> UniqueTag {
+        UniqueTag(Uuid::from_fields_le(d1, d2, d3, d4))
+    }
+
+    /// Returns the UUID of the tag as a big-endian `u128`, as [`Uuid::as_u128`].
+    ///
+    /// Being `const`, it gives the `u128` constants to match raw values against, see
+    /// [Matching](UniqueTag#matching).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    /// const TAG_U128: u128 = TAG.as_u128();
+    ///
+    /// assert_eq!(TAG_U128, 0x2af1216e_7042_406a_a577_c39a7e7c3faa);
+    /// assert_eq!(UniqueTag::from_u128(TAG_U128), TAG);
+    /// ```
+    pub const fn as_u128(&self) -> u128 {
+        self.0.as_u128()
+    }
+
+    /// Builds a tag from its UUID as a big-endian `u128`, as [`Uuid::from_u128`]. This is
+    /// the inverse of [`UniqueTag::as_u128`].
+    pub const fn from_u128(value: u128) -> UniqueTag {
+        UniqueTag(Uuid::from_u128(value))
+    }
+
+    /// Returns the 64-bit projection of the tag, for formats with room for 8 bytes only.
+    ///
+    /// # Algorithm
+    ///
+    /// The projection is the exclusive or of the two halves of the UUID, each read as a
+    /// big-endian `u64` (i.e. the high and low halves of [`Uuid::as_u128`]). Truncating
+    /// the UUID instead would keep its version bits and the prefix reserved for its crate
+    /// (see the `prefixes` setting of the registry), leaving as few as 28 random bits; the
+    /// folded projection keeps at least 62 of them. This is version 1 of the algorithm,
+    /// which will not change in a semver-compatible release.
+    ///
+    /// Distinct tags may share a projection: `Registry::validate` reports the entries of
+    /// the registry whose projections collide, so that a projection identifies a single
+    /// entry as long as the registry validates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unique_uuid::{uuid::uuid, UniqueTag};
+    ///
+    /// const TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    /// const TAG_64: u64 = TAG.to_u64();
+    ///
+    /// assert_eq!(TAG_64, 0x8f86e2f40e3e7fc0);
+    /// assert_eq!(TAG_64, 0x2af1216e7042406a ^ 0xa577c39a7e7c3faa);
+    /// ```
+    pub const fn to_u64(&self) -> u64 {
+        let value = self.0.as_u128();
+        (value >> 64) as u64 ^ value as u64
+    }
+}
+
+/// A tag borrows as its UUID, so that collections keyed by `UniqueTag` can be queried
+/// with a [`Uuid`].
+///
+/// `UniqueTag` derives `Hash`, `Eq` and `Ord` from its only field, so a tag and its UUID
+/// always hash and compare alike, as `Borrow` requires. These implementations must stay
+/// derived for the `Borrow` implementations to hold.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use unique_uuid::{uuid::uuid, UniqueTag};
+///
+/// let uuid = uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa");
+/// let handlers: HashMap<UniqueTag, &str> = HashMap::from([(UniqueTag(uuid), "orders")]);
+/// assert_eq!(handlers.get(&uuid), Some(&"orders"));
+/// ```
+impl Borrow<Uuid> for UniqueTag {
+    fn borrow(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+/// A UUID borrows as a tag, so that collections keyed by [`Uuid`], e.g. written before
+/// the tags were introduced, can be queried with a `UniqueTag`, without unwrapping it.
+///
+/// The hashing and ordering of both types agree, see the implementation of
+/// `Borrow<Uuid>` for `UniqueTag`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::{BTreeMap, HashMap};
+/// use unique_uuid::{unique_tag, UniqueTag};
+///
+/// let plugins = unique_tag!(test, "plugins");
+/// let legacy: HashMap<_, _> = HashMap::from([(plugins.0, "plugins")]);
+/// assert_eq!(legacy.get(&plugins), Some(&"plugins"));
+/// assert_eq!(legacy.get(&unique_tag!("tenants")), None);
+///
+/// let sorted: BTreeMap<_, _> = BTreeMap::from([(plugins.0, 1), (unique_tag!("tenants").0, 2)]);
+/// assert_eq!(sorted.get(&unique_tag!("tenants")), Some(&2));
+/// assert_eq!(sorted.get(&unique_tag!("test1")), None);
+/// ```
+impl Borrow<UniqueTag> for Uuid {
+    fn borrow(&self) -> &UniqueTag {
+        // SAFETY: `UniqueTag` is a `repr(transparent)` wrapper around a `Uuid`
+        unsafe { &*(self as *const Uuid as *const UniqueTag) }
+    }
+}
+
+impl AsRef<Uuid> for UniqueTag {
+    fn as_ref(&self) -> &Uuid {
+        &self.0
+    }
+}
+
+/// The error returned when converting a [`UniqueTag`] that is not one of the expected
+/// tags, e.g. by the `TryFrom<UniqueTag>` implementation generated by [`tags_enum!`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnknownTag(pub UniqueTag);
+
+impl std::fmt::Display for UnknownTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown tag {}", self.0 .0)
+    }
+}
+
+impl std::error::Error for UnknownTag {}
+
+/// Returns a [`UniqueTag`] scoped to the module invoking the macro.
+///
+/// The tag is the version 5 UUID (see [`UniqueTag::new_v5`]) of the path of the module,
+/// as given by [`module_path!`], under the tag of the literal, minted by [`unique_tag!`].
+/// The same literal thus gives a distinct tag in each module, stable across builds, and
+/// the path of the module is never repeated in the literal. The arguments are those of
+/// [`unique_tag!`], which the macro can replace in a `const` context.
+///
+/// Only the literal is in the registry: the scoped tags are computed at compile time and
+/// never written to `types.toml`, and unknown to `registry::Registry`.
+/// Moving the code to another module, or renaming the crate, changes the tag.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{unique_tag, UniqueTag};
+///
+/// mod audio {
+///     use unique_uuid::{module_unique_tag, UniqueTag};
+///
+///     pub const INIT: UniqueTag = module_unique_tag!(test, "plugins");
+///     pub const PATH: &str = module_path!();
+/// }
+///
+/// mod video {
+///     use unique_uuid::{module_unique_tag, UniqueTag};
+///
+///     pub const INIT: UniqueTag = module_unique_tag!(test, "plugins");
+/// }
+///
+/// assert_ne!(audio::INIT, video::INIT);
+/// assert_ne!(audio::INIT, unique_tag!(test, "plugins"));
+/// assert_eq!(
+///     audio::INIT,
+///     UniqueTag::new_v5(unique_tag!(test, "plugins"), audio::PATH.as_bytes())
+/// );
+/// ```
+///
+/// [`unique_tag!`]: crate::unique_tag
+#[macro_export]
+macro_rules! module_unique_tag {
+    ($($input:tt)*) => {
+        $crate::UniqueTag::new_v5(
+            $crate::unique_tag!($($input)*),
+            ::core::module_path!().as_bytes(),
+        )
+    };
+}
+
+/// Returns the 64-bit projection of a tag of the registry, as a `u64`.
+///
+/// The arguments are those of [`unique_tag!`], and the result is the
+/// [`UniqueTag::to_u64`] of the tag, which the macro can replace in a `const` context.
+/// This macro is only available with the `tag64` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{unique_tag, unique_tag_64};
+///
+/// const PLUGINS: u64 = unique_tag_64!(test, "plugins");
+/// assert_eq!(PLUGINS, unique_tag!(test, "plugins").to_u64());
+/// ```
+///
+/// [`unique_tag!`]: crate::unique_tag
+#[cfg(feature = "tag64")]
+#[macro_export]
+macro_rules! unique_tag_64 {
+    ($($input:tt)*) => {
+        $crate::UniqueTag::to_u64(&$crate::unique_tag!($($input)*))
+    };
+}
+
 /// A trait for types that have an associated unique identifier.
 ///
 /// This trait provides a stable way to identify types across different
@@ -66,12 +690,317 @@ pub struct UniqueTag(pub Uuid);
 ///
 /// This trait can be automatically derived using the `#[derive(UniqueTypeTag)]`
 /// macro provided by this crate.
+///
+/// # References and smart pointers
+///
+/// `&T`, `&mut T`, `Box<T>`, `Rc<T>` and `Arc<T>` implement this trait whenever `T`
+/// does, exposing exactly `T::TYPE_TAG`. Functions generic over a tagged type can
+/// therefore accept borrowed or boxed values directly:
+///
+/// ```rust
+/// use std::{borrow::Borrow, rc::Rc, sync::Arc};
+/// use unique_uuid::{UniqueTag, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// struct MyStruct;
+///
+/// fn tag_of<T: UniqueTypeTag>(_: T) -> UniqueTag {
+///     T::TYPE_TAG
+/// }
+///
+/// assert_eq!(tag_of(&MyStruct), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(&mut MyStruct), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(Box::new(MyStruct)), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(Rc::new(MyStruct)), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(Arc::new(MyStruct)), MyStruct::TYPE_TAG);
+/// ```
+///
+/// # Tuples
+///
+/// Tuples of up to 12 elements implement this trait whenever all their elements do. The
+/// tag of a tuple is derived from the tags of its elements, and is never stored in the
+/// registry: it is the [`UniqueTag::combine_all`] of the version 5 tag named `tuple`
+/// under the namespace of [`UniqueTag::combine_all`], followed by the tag of each
+/// element, in order.
+///
+/// ```rust
+/// use unique_uuid::{uuid::uuid, UniqueTag, UniqueTypeTag};
+///
+/// struct Header;
+/// struct Body;
+///
+/// impl UniqueTypeTag for Header {
+///     const TYPE_TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+/// }
+///
+/// impl UniqueTypeTag for Body {
+///     const TYPE_TAG: UniqueTag = UniqueTag(uuid!("d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"));
+/// }
+///
+/// assert_eq!(<(Header, Body)>::TYPE_TAG.0, uuid!("7a39761d-fe45-5321-ab4e-76533ea58fc8"));
+/// assert_eq!(<(Body, Header)>::TYPE_TAG.0, uuid!("58111a35-31f2-5bd8-bf9e-cf20e3393e2e"));
+/// assert_ne!(<(Header,)>::TYPE_TAG, Header::TYPE_TAG);
+///
+/// let namespace = UniqueTag(uuid!("6f1c5a3e-2b7d-5e84-9c0a-4d3f8e21b7c6"));
+/// let tuple = UniqueTag::new_v5(namespace, b"tuple");
+/// assert_eq!(
+///     <(Header, Body)>::TYPE_TAG,
+///     UniqueTag::combine_all(&[tuple, Header::TYPE_TAG, Body::TYPE_TAG]),
+/// );
+/// ```
+///
+/// # Containers
+///
+/// `Option<T>`, `Vec<T>`, slices `[T]` and arrays `[T; N]` implement this trait whenever
+/// `T` does. Like the tags of tuples, their tags are derived and never stored: each is the
+/// [`UniqueTag::combine_all`] of the version 5 tag named `Option`, `Vec`, `slice` or
+/// `array` (under the namespace of [`UniqueTag::combine_all`]) and of `T::TYPE_TAG`.
+/// Arrays are also combined with their length, as the tag whose UUID is
+/// `Uuid::from_u128(N as u128)`, so arrays of different lengths have different tags.
+///
+/// Pointers stay transparent, including for slices: `Box<[T]>` and `&[T]` have the tag of
+/// `[T]`, while `Vec<T>` is a distinct container with a tag of its own.
+///
+/// ```rust
+/// use unique_uuid::{uuid::{uuid, Uuid}, UniqueTag, UniqueTypeTag};
+///
+/// struct Event;
+///
+/// impl UniqueTypeTag for Event {
+///     const TYPE_TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+/// }
+///
+/// assert_eq!(<Option<Event>>::TYPE_TAG.0, uuid!("2be5d5cd-550e-5d3f-93f1-165f3aa1c922"));
+/// assert_eq!(<Vec<Event>>::TYPE_TAG.0, uuid!("4773e2aa-1678-5787-90ac-89d76d7687d9"));
+/// assert_eq!(<[Event]>::TYPE_TAG.0, uuid!("5e249199-e3c0-5b4f-ba9d-497012f22cae"));
+/// assert_eq!(<[Event; 4]>::TYPE_TAG.0, uuid!("e63f081b-0d55-5d89-971c-61cbee3fb596"));
+///
+/// assert_ne!(<[Event; 4]>::TYPE_TAG, <[Event; 5]>::TYPE_TAG);
+/// assert_ne!(<Option<Option<Event>>>::TYPE_TAG, <Option<Event>>::TYPE_TAG);
+/// assert_eq!(<Box<Event>>::TYPE_TAG, Event::TYPE_TAG);
+/// assert_eq!(<&[Event]>::TYPE_TAG, <[Event]>::TYPE_TAG);
+/// assert_eq!(<Box<[Event]>>::TYPE_TAG, <[Event]>::TYPE_TAG);
+///
+/// let namespace = UniqueTag(uuid!("6f1c5a3e-2b7d-5e84-9c0a-4d3f8e21b7c6"));
+/// let array = UniqueTag::new_v5(namespace, b"array");
+/// assert_eq!(
+///     <[Event; 4]>::TYPE_TAG,
+///     UniqueTag::combine_all(&[array, Event::TYPE_TAG, UniqueTag(Uuid::from_u128(4))]),
+/// );
+/// ```
 pub trait UniqueTypeTag {
     /// The unique tag associated with the type.
     const TYPE_TAG: UniqueTag;
 
+    /// The alternative keys of the type in the registry, given by
+    /// `#[unique_type_tag(alias = "...")]`, which share its tag.
+    const TYPE_TAG_ALIASES: &'static [&'static str] = &[];
+
+    /// The 64-bit projection of [`TYPE_TAG`](UniqueTypeTag::TYPE_TAG), see
+    /// [`UniqueTag::to_u64`], for headers with room for 8 bytes only.
+    ///
+    /// This constant is only available with the `tag64` feature.
+    ///
+    /// ```rust
+    /// use unique_uuid::UniqueTypeTag;
+    ///
+    /// #[derive(UniqueTypeTag)]
+    /// struct MyStruct;
+    ///
+    /// assert_eq!(MyStruct::TYPE_TAG_64, MyStruct::TYPE_TAG.to_u64());
+    /// ```
+    #[cfg(feature = "tag64")]
+    const TYPE_TAG_64: u64 = Self::TYPE_TAG.to_u64();
+
     /// Returns the type id number.
     fn type_id(&self) -> UniqueTag {
         Self::TYPE_TAG
     }
 }
+
+/// The small integer id of a type, allocated alongside its tag.
+///
+/// With `ids = true` in the `[config]` table of the registry, every entry resolved by the
+/// macros gets an `id` field, one above the last id ever allocated: ids are unique across
+/// both sections and never reused, even once an entry is removed. `#[derive(UniqueTypeTag)]`
+/// then implements this trait as well, except for types composing their tag with `compose`.
+/// Ids suit dense tables and compact wire formats, where a 16-byte UUID is too large.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::UniqueTypeId;
+///
+/// fn slot<T: UniqueTypeId>(table: &mut Vec<Option<&'static str>>, name: &'static str) {
+///     let index = T::TYPE_ID as usize;
+///     if table.len() <= index {
+///         table.resize(index + 1, None);
+///     }
+///     table[index] = Some(name);
+/// }
+/// ```
+pub trait UniqueTypeId: UniqueTypeTag {
+    /// The id of the type, from the `id` field of its entry.
+    const TYPE_ID: u32;
+}
+
+/// An object-safe counterpart of [`UniqueTypeTag`].
+///
+/// [`UniqueTypeTag`] exposes its identifier through an associated constant, which
+/// makes it impossible to use behind `dyn`. `HasTypeTag` exposes the same identifier
+/// through a method instead, so values of different types can be stored together
+/// (e.g. `Vec<Box<dyn HasTypeTag>>`) while still reporting the tag of their concrete
+/// type.
+///
+/// Every [`UniqueTypeTag`] type implements this trait through a blanket implementation,
+/// it should therefore never be implemented manually.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use unique_uuid::{HasTypeTag, UniqueTag, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// struct MyStruct;
+///
+/// fn tag_of<T: HasTypeTag>(value: T) -> UniqueTag {
+///     value.type_tag()
+/// }
+///
+/// let boxed: Box<dyn HasTypeTag> = Box::new(MyStruct);
+/// let shared: Arc<dyn HasTypeTag + Send + Sync> = Arc::new(MyStruct);
+///
+/// assert_eq!(boxed.type_tag(), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(&*boxed), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(boxed), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(shared), MyStruct::TYPE_TAG);
+/// assert_eq!(tag_of(&MyStruct), MyStruct::TYPE_TAG);
+/// ```
+///
+/// # Coherence
+///
+/// Pointers to concrete types (`&T`, `Box<T>`, `Rc<T>`, `Arc<T>`, ...) implement
+/// [`UniqueTypeTag`] by forwarding to `T`, and obtain this trait through the blanket
+/// implementation. A generic `impl<T: HasTypeTag + ?Sized> HasTypeTag for Box<T>` would
+/// overlap with that blanket implementation, so pointers to trait objects are instead
+/// covered by dedicated implementations for `dyn HasTypeTag`, `dyn HasTypeTag + Send`
+/// and `dyn HasTypeTag + Send + Sync`. Such pointers do not expose a `TYPE_TAG`
+/// constant since their tag is only known at runtime.
+pub trait HasTypeTag {
+    /// Returns the unique tag of the concrete type of `self`.
+    fn type_tag(&self) -> UniqueTag;
+}
+
+impl<T: UniqueTypeTag + ?Sized> HasTypeTag for T {
+    fn type_tag(&self) -> UniqueTag {
+        T::TYPE_TAG
+    }
+}
+
+// References and smart pointers are transparent with respect to type tags.
+macro_rules! forward_unique_type_tag {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<T: UniqueTypeTag + ?Sized> UniqueTypeTag for $ty {
+                const TYPE_TAG: UniqueTag = T::TYPE_TAG;
+            }
+        )*
+    };
+}
+
+forward_unique_type_tag!(&T, &mut T, Box<T>, Rc<T>, Arc<T>);
+
+/// Returns the tag marking the combined tags of a family of types, e.g. tuples.
+const fn marker(name: &[u8]) -> UniqueTag {
+    UniqueTag::new_v5(COMBINE_NAMESPACE, name)
+}
+
+// Tuples combine the tags of their elements, in order.
+macro_rules! tuple_unique_type_tag {
+    ($(($($name:ident),+)),* $(,)?) => {
+        $(
+            impl<$($name: UniqueTypeTag),+> UniqueTypeTag for ($($name,)+) {
+                const TYPE_TAG: UniqueTag =
+                    UniqueTag::combine_all(&[marker(b"tuple"), $($name::TYPE_TAG),+]);
+            }
+        )*
+    };
+}
+
+impl<T: UniqueTypeTag> UniqueTypeTag for Option<T> {
+    const TYPE_TAG: UniqueTag = UniqueTag::combine_all(&[marker(b"Option"), T::TYPE_TAG]);
+}
+
+impl<T: UniqueTypeTag> UniqueTypeTag for Vec<T> {
+    const TYPE_TAG: UniqueTag = UniqueTag::combine_all(&[marker(b"Vec"), T::TYPE_TAG]);
+}
+
+impl<T: UniqueTypeTag> UniqueTypeTag for [T] {
+    const TYPE_TAG: UniqueTag = UniqueTag::combine_all(&[marker(b"slice"), T::TYPE_TAG]);
+}
+
+impl<T: UniqueTypeTag, const N: usize> UniqueTypeTag for [T; N] {
+    const TYPE_TAG: UniqueTag = UniqueTag::combine_all(&[
+        marker(b"array"),
+        T::TYPE_TAG,
+        UniqueTag(Uuid::from_u128(N as u128)),
+    ]);
+}
+
+tuple_unique_type_tag!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H),
+    (A, B, C, D, E, F, G, H, I),
+    (A, B, C, D, E, F, G, H, I, J),
+    (A, B, C, D, E, F, G, H, I, J, K),
+    (A, B, C, D, E, F, G, H, I, J, K, L),
+);
+
+macro_rules! forward_has_type_tag {
+    ($($dyn:ty),* $(,)?) => {
+        $(
+            impl HasTypeTag for &$dyn {
+                fn type_tag(&self) -> UniqueTag {
+                    (**self).type_tag()
+                }
+            }
+
+            impl HasTypeTag for &mut $dyn {
+                fn type_tag(&self) -> UniqueTag {
+                    (**self).type_tag()
+                }
+            }
+
+            impl HasTypeTag for Box<$dyn> {
+                fn type_tag(&self) -> UniqueTag {
+                    (**self).type_tag()
+                }
+            }
+
+            impl HasTypeTag for Rc<$dyn> {
+                fn type_tag(&self) -> UniqueTag {
+                    (**self).type_tag()
+                }
+            }
+
+            impl HasTypeTag for Arc<$dyn> {
+                fn type_tag(&self) -> UniqueTag {
+                    (**self).type_tag()
+                }
+            }
+        )*
+    };
+}
+
+forward_has_type_tag!(
+    dyn HasTypeTag,
+    dyn HasTypeTag + Send,
+    dyn HasTypeTag + Send + Sync,
+);
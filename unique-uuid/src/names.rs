@@ -0,0 +1,53 @@
+//! A process-wide table of human-readable tag names.
+//!
+//! When the `named-debug` feature is enabled, the [`Debug`] implementation of
+//! [`UniqueTag`] looks tags up in this table, so that logs show
+//! `payments.captured (2af1216e-…)` rather than a bare UUID. The table is empty until
+//! names are registered, either one by one with [`register_name`] or all at once from
+//! the registry file with `Registry::register_names` (requires the `registry-file`
+//! feature).
+//!
+//! # Example
+//!
+//! ```rust
+//! use unique_uuid::{names, uuid::uuid, UniqueTag};
+//!
+//! let captured = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+//! let unknown = UniqueTag(uuid!("d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"));
+//! names::register_name(captured, "payments.captured");
+//!
+//! assert_eq!(
+//!     format!("{:?}", captured),
+//!     "payments.captured (2af1216e-7042-406a-a577-c39a7e7c3faa)"
+//! );
+//! assert_eq!(
+//!     format!("{:?}", unknown),
+//!     "UniqueTag(d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24)"
+//! );
+//! ```
+use std::{
+    collections::BTreeMap,
+    sync::{PoisonError, RwLock},
+};
+
+use crate::UniqueTag;
+
+static NAMES: RwLock<BTreeMap<UniqueTag, String>> = RwLock::new(BTreeMap::new());
+
+/// Registers `name` as the human-readable name of `tag`, returning the name previously
+/// registered for it.
+pub fn register_name(tag: UniqueTag, name: impl Into<String>) -> Option<String> {
+    NAMES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(tag, name.into())
+}
+
+/// Returns the name registered for `tag`, if any.
+pub fn name_of(tag: UniqueTag) -> Option<String> {
+    NAMES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&tag)
+        .cloned()
+}
@@ -0,0 +1,156 @@
+//! Static maps keyed by tags, built at compile time with a perfect hash function.
+//!
+//! This module is only available with the `phf` feature. [`tag_phf_map!`] resolves its
+//! keys through the registry, as [`unique_tag!`] does, and hashes their UUIDs while the
+//! crate compiles, so that a [`TagPhfMap`] needs no initialization and can be stored in
+//! a `static`. A lookup hashes the 16 bytes of the tag once and compares a single entry,
+//! whatever the number of entries, and can be evaluated in `const` context.
+//!
+//! # Example
+//!
+//! ```rust
+//! use unique_uuid::{phf_map::TagPhfMap, tag_phf_map, unique_tag};
+//!
+//! static CODES: TagPhfMap<u16> = tag_phf_map! {
+//!     "build.messages.hello" => 1,
+//!     "build.messages.goodbye" => 2,
+//! };
+//!
+//! assert_eq!(CODES.get(&unique_tag!("build.messages.goodbye")), Some(&2));
+//! assert_eq!(CODES.get(&unique_tag!("my_custom_tag")), None);
+//! assert_eq!(CODES.len(), 2);
+//! ```
+//!
+//! ```rust
+//! use unique_uuid::{phf_map::TagPhfMap, tag_phf_map, unique_tag, uuid::Uuid, UniqueTag};
+//!
+//! const CODES: TagPhfMap<u16> = tag_phf_map! {
+//!     "build.messages.hello" => 1,
+//!     "build.messages.goodbye" => 2,
+//! };
+//! const GOODBYE: UniqueTag = unique_tag!("build.messages.goodbye");
+//!
+//! const _: () = assert!(CODES.get(&GOODBYE).is_some());
+//! const _: () = assert!(!CODES.contains(&UniqueTag(Uuid::nil())));
+//! ```
+//!
+//! [`tag_phf_map!`]: crate::tag_phf_map
+//! [`unique_tag!`]: crate::unique_tag
+use std::fmt;
+
+use uuid::Uuid;
+
+use crate::UniqueTag;
+
+/// A map from tags to values of type `V`, built by [`tag_phf_map!`](crate::tag_phf_map).
+///
+/// The entries are stored in the order of the perfect hash function, which is not the
+/// order of the macro input.
+pub struct TagPhfMap<V: 'static> {
+    map: phf::Map<[u8; 16], V>,
+}
+
+impl<V: 'static> TagPhfMap<V> {
+    /// Wraps a map generated by `tag_phf_map!`, keyed by the bytes of the UUIDs.
+    #[doc(hidden)]
+    pub const fn from_phf(map: phf::Map<[u8; 16], V>) -> Self {
+        Self { map }
+    }
+
+    /// Returns the value of `tag`, if it is a key of the map.
+    pub const fn get(&self, tag: &UniqueTag) -> Option<&V> {
+        if self.map.disps.is_empty() {
+            return None;
+        }
+        let (g, f1, f2) = hash(tag.0.as_bytes(), self.map.key);
+        let (d1, d2) = self.map.disps[(g % self.map.disps.len() as u32) as usize];
+        let index =
+            d2.wrapping_add(f1.wrapping_mul(d1)).wrapping_add(f2) % self.map.entries.len() as u32;
+        let (key, value) = &self.map.entries[index as usize];
+        if UniqueTag(Uuid::from_bytes(*key)).const_eq(tag) {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if `tag` is a key of the map.
+    pub const fn contains(&self, tag: &UniqueTag) -> bool {
+        self.get(tag).is_some()
+    }
+
+    /// Returns the number of entries of the map.
+    pub const fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map has no entry.
+    pub const fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the entries of the map, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (UniqueTag, &V)> + '_ {
+        self.map
+            .entries()
+            .map(|(bytes, value)| (UniqueTag(Uuid::from_bytes(*bytes)), value))
+    }
+}
+
+/// Hashes the bytes of a UUID with SipHash-1-3 keyed by `(0, key)`, as `phf` hashes the
+/// keys of its maps, returning the bucket and both displacement factors.
+const fn hash(bytes: &[u8; 16], key: u64) -> (u32, u32, u32) {
+    let mut v = [
+        0x736f6d6570736575,
+        key ^ 0x646f72616e646f83,
+        0x6c7967656e657261,
+        key ^ 0x7465646279746573,
+    ];
+    let mut word = 0;
+    while word < 2 {
+        let mut m = 0;
+        let mut i = 0;
+        while i < 8 {
+            m |= (bytes[word * 8 + i] as u64) << (8 * i);
+            i += 1;
+        }
+        v[3] ^= m;
+        v = round(v);
+        v[0] ^= m;
+        word += 1;
+    }
+    // The last block only holds the length of the message
+    let last = 16 << 56;
+    v[3] ^= last;
+    v = round(v);
+    v[0] ^= last;
+
+    v[2] ^= 0xee;
+    v = round(round(round(v)));
+    let h1 = v[0] ^ v[1] ^ v[2] ^ v[3];
+    v[1] ^= 0xdd;
+    v = round(round(round(v)));
+    let h2 = v[0] ^ v[1] ^ v[2] ^ v[3];
+    ((h1 >> 32) as u32, h1 as u32, h2 as u32)
+}
+
+/// A SipHash round over the state `v`.
+const fn round(mut v: [u64; 4]) -> [u64; 4] {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+    v
+}
+
+impl<V: fmt::Debug + 'static> fmt::Debug for TagPhfMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
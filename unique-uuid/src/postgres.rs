@@ -0,0 +1,55 @@
+//! [`postgres_types`] support for storing [`UniqueTag`] in Postgres `uuid` columns, as
+//! used by the `postgres` and `tokio-postgres` crates.
+//!
+//! This module is only available with the `postgres` feature. Every implementation
+//! delegates to the one of [`Uuid`], so a tag is stored exactly like its UUID.
+//!
+//! # Example
+//!
+//! ```rust
+//! use bytes::BytesMut;
+//! use postgres_types::{FromSql, ToSql, Type};
+//! use unique_uuid::{uuid::uuid, UniqueTag};
+//!
+//! let tag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+//!
+//! let mut buffer = BytesMut::new();
+//! tag.to_sql_checked(&Type::UUID, &mut buffer).unwrap();
+//! assert_eq!(&buffer[..], tag.0.as_bytes());
+//! assert_eq!(UniqueTag::from_sql(&Type::UUID, &buffer).unwrap(), tag);
+//!
+//! assert!(tag.to_sql_checked(&Type::TEXT, &mut buffer).is_err());
+//! ```
+use std::error::Error;
+
+use bytes::BytesMut;
+use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+use uuid::Uuid;
+
+use crate::UniqueTag;
+
+impl ToSql for UniqueTag {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.0.to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <Uuid as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for UniqueTag {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Uuid::from_sql(ty, raw).map(UniqueTag)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <Uuid as FromSql>::accepts(ty)
+    }
+}
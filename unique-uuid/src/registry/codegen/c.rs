@@ -0,0 +1,144 @@
+//! Generation of a C header.
+use std::path::Path;
+
+use super::{constant_name, write_generated, CodegenError};
+use crate::registry::{in_namespace, Registry, Section};
+
+/// Options of [`Registry::to_c_header`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CHeaderOptions {
+    /// The prefix of every identifier of the header, `UU_` by default.
+    pub prefix: String,
+
+    /// Only declare the entries of this namespace (see [`in_namespace`]).
+    pub namespace: Option<String>,
+}
+
+impl Default for CHeaderOptions {
+    fn default() -> Self {
+        Self {
+            prefix: "UU_".to_string(),
+            namespace: None,
+        }
+    }
+}
+
+impl Registry {
+    /// Returns the source of a C header declaring a `static const uint8_t[16]` array
+    /// for every entry.
+    ///
+    /// The arrays hold the 16 bytes of the UUID in big-endian (network) order, which is
+    /// the order of its hyphenated representation and of [`uuid::Uuid::as_bytes`], so
+    /// they can be compared with `memcmp` against tags framed by the Rust side.
+    ///
+    /// The identifier of an entry is made of the prefix, `TAG_` for `[unique_tags]` or
+    /// `TYPE_` for `[unique_type_tags]`, and the key turned into `SCREAMING_SNAKE_CASE`
+    /// as by [`constant_name`] (without its leading `_`): with the default options,
+    /// `orders.created` is declared as `UU_TAG_ORDERS_CREATED` and `::shop::Invoice` as
+    /// `UU_TYPE_SHOP_INVOICE`.
+    ///
+    /// [`constant_name`]: crate::registry::constant_name
+    ///
+    /// # Errors
+    /// Returns [`CodegenError::EmptyIdentifier`] if a key has no usable character, and
+    /// [`CodegenError::ConflictingIdentifiers`] if several keys of a section map to the
+    /// same identifier.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{CHeaderOptions, Registry};
+    ///
+    /// # let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/codegen/types.toml");
+    /// # let golden = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/codegen/tags.h"));
+    /// let registry = Registry::load(path).unwrap();
+    /// let header = registry.to_c_header(&CHeaderOptions::default()).unwrap();
+    /// assert_eq!(header, golden);
+    ///
+    /// let options = CHeaderOptions {
+    ///     namespace: Some("orders".to_string()),
+    ///     ..CHeaderOptions::default()
+    /// };
+    /// let header = registry.to_c_header(&options).unwrap();
+    /// assert!(header.contains("static const uint8_t UU_TAG_ORDERS_CREATED[16] = {0x2a, 0xf1,"));
+    /// assert!(!header.contains("PAYMENTS_REFUNDED"));
+    /// ```
+    pub fn to_c_header(&self, options: &CHeaderOptions) -> Result<String, CodegenError> {
+        let guard = format!("{}TAGS_H", options.prefix);
+        let mut header = format!(
+            "/* @generated by unique-uuid from the registry file, do not edit.\n \
+             * Regenerate it with `unique-uuid-admin c-header`.\n \
+             *\n \
+             * Every tag is the 16 bytes of its UUID in big-endian (network) order.\n \
+             */\n\
+             #ifndef {guard}\n\
+             #define {guard}\n\
+             \n\
+             #include <stdint.h>\n",
+            guard = guard
+        );
+
+        for section in Section::ALL {
+            let kind = match section {
+                Section::UniqueTags => "TAG_",
+                Section::UniqueTypeTags => "TYPE_",
+            };
+            let constants = self.constants(
+                section,
+                |key| {
+                    options
+                        .namespace
+                        .as_ref()
+                        .is_none_or(|namespace| in_namespace(key, namespace))
+                },
+                |key| {
+                    let name = constant_name(key)?;
+                    Some(format!(
+                        "{}{}{}",
+                        options.prefix,
+                        kind,
+                        name.trim_start_matches('_')
+                    ))
+                },
+            )?;
+            if constants.is_empty() {
+                continue;
+            }
+
+            header.push_str(&format!("\n/* [{}] */\n", section));
+            for (name, (key, entry)) in &constants {
+                let bytes: Vec<String> = entry
+                    .uuid()
+                    .as_bytes()
+                    .iter()
+                    .map(|byte| format!("{:#04x}", byte))
+                    .collect();
+                header.push_str(&format!(
+                    "\n/* {} ({}) */\nstatic const uint8_t {}[16] = {{{}}};\n",
+                    key.replace("*/", "*\\/"),
+                    entry.uuid(),
+                    name,
+                    bytes.join(", ")
+                ));
+            }
+        }
+
+        header.push_str(&format!("\n#endif /* {} */\n", guard));
+        Ok(header)
+    }
+
+    /// Writes the header returned by [`Registry::to_c_header`] to `path`.
+    ///
+    /// The header is written to a temporary file next to `path` first, then moved in
+    /// place. Nothing is written if the header cannot be generated.
+    ///
+    /// # Errors
+    /// Returns an error if the header cannot be generated or written.
+    pub fn write_c_header(
+        &self,
+        path: impl AsRef<Path>,
+        options: &CHeaderOptions,
+    ) -> Result<(), CodegenError> {
+        write_generated(path.as_ref(), &self.to_c_header(options)?)
+    }
+}
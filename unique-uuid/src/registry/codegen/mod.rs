@@ -0,0 +1,63 @@
+//! Generation of source files declaring a constant for every registry entry.
+use std::{collections::BTreeMap, fs, path::Path};
+
+use super::{Entry, Registry, Section};
+
+mod c;
+mod rust;
+mod typescript;
+
+pub use c::CHeaderOptions;
+pub use rust::RustModuleOptions;
+pub use typescript::TypeScriptOptions;
+
+pub use unique_uuid_core::{constant_name, CodegenError};
+
+impl Registry {
+    /// Returns the entries of `section` accepted by `filter`, keyed by the identifier
+    /// `name` gives them.
+    ///
+    /// # Errors
+    /// Returns an error if `name` gives no identifier for a key, or the same identifier
+    /// for several keys.
+    fn constants(
+        &self,
+        section: Section,
+        filter: impl Fn(&str) -> bool,
+        name: impl Fn(&str) -> Option<String>,
+    ) -> Result<BTreeMap<String, (&str, &Entry)>, CodegenError> {
+        let mut constants: BTreeMap<String, Vec<(&str, &Entry)>> = BTreeMap::new();
+        for (key, entry) in self.entries(section).filter(|(key, _)| filter(key)) {
+            let name = name(key).ok_or_else(|| CodegenError::EmptyIdentifier {
+                section,
+                key: key.to_string(),
+            })?;
+            constants.entry(name).or_default().push((key, entry));
+        }
+
+        if let Some((identifier, entries)) = constants.iter().find(|(_, e)| e.len() > 1) {
+            return Err(CodegenError::ConflictingIdentifiers {
+                section,
+                identifier: identifier.clone(),
+                keys: entries.iter().map(|(key, _)| key.to_string()).collect(),
+            });
+        }
+        Ok(constants
+            .into_iter()
+            .map(|(name, mut entries)| (name, entries.remove(0)))
+            .collect())
+    }
+}
+
+/// Writes a generated file to a temporary file next to `path`, then moves it in place.
+fn write_generated(path: &Path, contents: &str) -> Result<(), CodegenError> {
+    let error = |source| CodegenError::Write {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, contents).map_err(error)?;
+    fs::rename(&temporary, path).map_err(error)
+}
@@ -0,0 +1,107 @@
+//! Generation of a Rust module.
+use std::path::Path;
+
+#[cfg(doc)]
+use super::constant_name;
+use super::{write_generated, CodegenError};
+use crate::registry::Registry;
+
+/// Options of [`Registry::to_rust_module`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustModuleOptions {
+    /// The path under which the generated code refers to this crate, `unique_uuid` by
+    /// default.
+    pub crate_path: String,
+}
+
+impl Default for RustModuleOptions {
+    fn default() -> Self {
+        Self {
+            crate_path: "unique_uuid".to_string(),
+        }
+    }
+}
+
+impl Registry {
+    /// Returns the source of a Rust module declaring a `pub const` [`UniqueTag`] for
+    /// every entry.
+    ///
+    /// The constants are grouped in one `pub mod` per section, named after the section,
+    /// and named with [`constant_name`]. Each constant is documented with its key and
+    /// UUID, and with the entry's description if it has one. The module is meant to be
+    /// included with [`include!`].
+    ///
+    /// [`UniqueTag`]: crate::UniqueTag
+    ///
+    /// # Errors
+    /// Returns [`CodegenError::EmptyIdentifier`] if a key has no usable character, and
+    /// [`CodegenError::ConflictingIdentifiers`] if several keys of a section map to the
+    /// same identifier. Conflicts are never resolved by renaming, since that would make
+    /// the generated names depend on which keys happen to exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{CodegenError, Registry, RustModuleOptions};
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", description = "An order was placed" }
+    ///
+    ///     [unique_type_tags]
+    ///     "::OrderRecord" = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50"
+    /// "#).unwrap();
+    ///
+    /// let module = registry.to_rust_module(&RustModuleOptions::default()).unwrap();
+    /// assert!(module.contains("pub const ORDERS_CREATED: unique_uuid::UniqueTag"));
+    /// assert!(module.contains("    /// An order was placed\n"));
+    /// assert!(module.contains("pub mod unique_type_tags {"));
+    ///
+    /// let conflicting = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "orders-created" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+    /// "#).unwrap();
+    /// assert!(matches!(
+    ///     conflicting.to_rust_module(&RustModuleOptions::default()),
+    ///     Err(CodegenError::ConflictingIdentifiers { .. })
+    /// ));
+    /// ```
+    ///
+    /// The generated module compiles, and its constants match the registry:
+    ///
+    /// ```rust
+    /// # /*
+    /// include!("types_generated.rs");
+    /// # */
+    /// # include!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/codegen/types_generated.rs"));
+    ///
+    /// # fn main() {
+    /// # let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/codegen/types.toml");
+    /// # let golden = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/codegen/types_generated.rs"));
+    /// # let registry = unique_uuid::registry::Registry::load(path).unwrap();
+    /// # let options = unique_uuid::registry::RustModuleOptions::default();
+    /// # assert_eq!(registry.to_rust_module(&options).unwrap(), golden);
+    /// assert_eq!(unique_tags::ORDERS_CREATED, registry.get("orders.created").unwrap().tag);
+    /// assert_eq!(unique_type_tags::SHOP_INVOICE, registry.get("::shop::Invoice").unwrap().tag);
+    /// # }
+    /// ```
+    pub fn to_rust_module(&self, options: &RustModuleOptions) -> Result<String, CodegenError> {
+        unique_uuid_core::rust_module(&self.document(), &options.crate_path)
+    }
+
+    /// Writes the module returned by [`Registry::to_rust_module`] to `path`.
+    ///
+    /// The module is written to a temporary file next to `path` first, then moved in
+    /// place. Nothing is written if the module cannot be generated.
+    ///
+    /// # Errors
+    /// Returns an error if the module cannot be generated or written.
+    pub fn write_rust_module(
+        &self,
+        path: impl AsRef<Path>,
+        options: &RustModuleOptions,
+    ) -> Result<(), CodegenError> {
+        write_generated(path.as_ref(), &self.to_rust_module(options)?)
+    }
+}
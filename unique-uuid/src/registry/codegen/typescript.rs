@@ -0,0 +1,177 @@
+//! Generation of a TypeScript module.
+use std::path::Path;
+
+use super::{constant_name, write_generated, CodegenError};
+use crate::registry::{in_namespace, Registry, Section};
+
+/// Options of [`Registry::to_typescript`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeScriptOptions {
+    /// Only declare the entries of this namespace (see [`in_namespace`]).
+    pub namespace: Option<String>,
+}
+
+impl Registry {
+    /// Returns the source of a TypeScript module exporting a string constant for every
+    /// entry, and a lookup object per section mapping the keys to these constants.
+    ///
+    /// The constants are named with [`constant_name`], with a `TYPE_` prefix for the
+    /// entries of `[unique_type_tags]`: `orders.created` is exported as
+    /// `ORDERS_CREATED` and `::shop::Invoice` as `TYPE_SHOP_INVOICE`. The lookup
+    /// objects are `uniqueTags` and `uniqueTypeTags`, which cannot collide with the
+    /// constants. Every value is declared `as const`, so the UUIDs are literal types.
+    ///
+    /// Entries are sorted by identifier and the module has a fixed layout, so it only
+    /// changes when the registry does and can be committed.
+    ///
+    /// [`constant_name`]: crate::registry::constant_name
+    ///
+    /// # Errors
+    /// Returns [`CodegenError::EmptyIdentifier`] if a key has no usable character,
+    /// [`CodegenError::ConflictingIdentifiers`] if several keys of a section map to the
+    /// same identifier, and [`CodegenError::ConflictingSections`] if a key of each
+    /// section does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{CodegenError, Registry, TypeScriptOptions};
+    ///
+    /// # let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/codegen/types.toml");
+    /// # let golden = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/codegen/tags.ts"));
+    /// let registry = Registry::load(path).unwrap();
+    /// let module = registry.to_typescript(&TypeScriptOptions::default()).unwrap();
+    /// assert_eq!(module, golden);
+    ///
+    /// let options = TypeScriptOptions {
+    ///     namespace: Some("orders".to_string()),
+    /// };
+    /// let module = registry.to_typescript(&options).unwrap();
+    /// assert!(module.contains(
+    ///     "export const ORDERS_CREATED = \"2af1216e-7042-406a-a577-c39a7e7c3faa\" as const;"
+    /// ));
+    /// assert!(!module.contains("PAYMENTS_REFUNDED"));
+    ///
+    /// let conflicting = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "type.invoice" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///
+    ///     [unique_type_tags]
+    ///     "::Invoice" = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb"
+    /// "#).unwrap();
+    /// assert!(matches!(
+    ///     conflicting.to_typescript(&TypeScriptOptions::default()),
+    ///     Err(CodegenError::ConflictingSections { .. })
+    /// ));
+    /// ```
+    pub fn to_typescript(&self, options: &TypeScriptOptions) -> Result<String, CodegenError> {
+        let filter = |key: &str| {
+            options
+                .namespace
+                .as_ref()
+                .is_none_or(|namespace| in_namespace(key, namespace))
+        };
+        let tags = self.constants(Section::UniqueTags, filter, constant_name)?;
+        let type_tags = self.constants(Section::UniqueTypeTags, filter, |key| {
+            constant_name(key).map(|name| format!("TYPE_{}", name.trim_start_matches('_')))
+        })?;
+        if let Some((identifier, (type_tag, _))) = type_tags
+            .iter()
+            .find(|(identifier, _)| tags.contains_key(*identifier))
+        {
+            return Err(CodegenError::ConflictingSections {
+                identifier: identifier.clone(),
+                tag: tags[identifier].0.to_string(),
+                type_tag: type_tag.to_string(),
+            });
+        }
+
+        let mut module = String::from(
+            "// @generated by unique-uuid from the registry file, do not edit.\n\
+             // Regenerate it with `unique-uuid-admin typescript`.\n",
+        );
+
+        let sections = [
+            (Section::UniqueTags, "uniqueTags", &tags),
+            (Section::UniqueTypeTags, "uniqueTypeTags", &type_tags),
+        ];
+        for (section, _, constants) in &sections {
+            if constants.is_empty() {
+                continue;
+            }
+
+            module.push_str(&format!("\n// [{}]\n", section));
+            for (name, (key, entry)) in constants.iter() {
+                module.push_str(&format!(
+                    "\n/**\n * `{}` (`{}`)\n",
+                    key.replace("*/", "*\\/"),
+                    entry.uuid()
+                ));
+                if let Some(description) = entry.description() {
+                    module.push_str(" *\n");
+                    for line in description.lines() {
+                        let line = format!(" * {}\n", line.replace("*/", "*\\/"));
+                        module.push_str(&line.replace(" \n", "\n"));
+                    }
+                }
+                module.push_str(&format!(
+                    " */\nexport const {} = \"{}\" as const;\n",
+                    name,
+                    entry.uuid()
+                ));
+            }
+        }
+
+        for (section, object, constants) in &sections {
+            module.push_str(&format!(
+                "\n/** The entries of the `[{}]` section, by key. */\nexport const {} = {{",
+                section, object
+            ));
+            let mut keys: Vec<_> = constants
+                .iter()
+                .map(|(name, (key, _))| (*key, name))
+                .collect();
+            keys.sort();
+            if !keys.is_empty() {
+                module.push('\n');
+            }
+            for (key, name) in keys {
+                module.push_str(&format!("  {}: {},\n", string_literal(key), name));
+            }
+            module.push_str("} as const;\n");
+        }
+
+        Ok(module)
+    }
+
+    /// Writes the module returned by [`Registry::to_typescript`] to `path`.
+    ///
+    /// The module is written to a temporary file next to `path` first, then moved in
+    /// place. Nothing is written if the module cannot be generated.
+    ///
+    /// # Errors
+    /// Returns an error if the module cannot be generated or written.
+    pub fn write_typescript(
+        &self,
+        path: impl AsRef<Path>,
+        options: &TypeScriptOptions,
+    ) -> Result<(), CodegenError> {
+        write_generated(path.as_ref(), &self.to_typescript(options)?)
+    }
+}
+
+/// Returns `value` as a double-quoted TypeScript string literal.
+fn string_literal(value: &str) -> String {
+    let mut literal = String::with_capacity(value.len() + 2);
+    literal.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            c if c.is_control() => literal.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    literal
+}
@@ -0,0 +1,127 @@
+//! The settings of the `[config]` table of the registry, as the macros read them:
+//!
+//! ```toml
+//! [config]
+//! tag_pattern = '^[a-z0-9_]+(\.[a-z0-9_]+)*$'
+//! case_insensitive_keys = "error"
+//!
+//! [config.prefixes]
+//! billing = "2af1"
+//! shipping = "d7ed"
+//! ```
+//!
+//! Loading or parsing a registry fails if a setting is unknown or invalid, as expanding a
+//! macro would. The macros reject new `[unique_tags]` keys that do not match
+//! `tag_pattern`, and new keys only differing by case from an existing key depending on
+//! `case_insensitive_keys`; existing entries are only reported by [`Registry::validate`].
+//! Each crate named in `[config.prefixes]` is assigned 1 to 4 bytes, written in
+//! hexadecimal, which lead every UUID minted while compiling it, before the version and
+//! variant bits.
+use std::collections::BTreeMap;
+
+use unique_uuid_core::Config;
+
+use super::{CaseInsensitiveKeys, Registry};
+
+impl Registry {
+    /// Returns the settings of the registry.
+    pub(crate) fn config(&self) -> Config {
+        Config::from_items(&self.other).unwrap_or_default()
+    }
+
+    /// Returns the `case_insensitive_keys` setting of the registry.
+    ///
+    /// Loading or parsing a registry fails if the setting is invalid, or if it is
+    /// `"error"` and two keys of the same section only differ by case.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{CaseInsensitiveKeys, Registry};
+    ///
+    /// let registry = Registry::parse("[config]\ncase_insensitive_keys = \"warn\"\n").unwrap();
+    /// assert_eq!(registry.case_insensitive_keys(), CaseInsensitiveKeys::Warn);
+    /// assert_eq!(Registry::new().case_insensitive_keys(), CaseInsensitiveKeys::Off);
+    ///
+    /// let err = Registry::parse(
+    ///     "[unique_tags]\n\
+    ///      OrderCreated = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\
+    ///      ordercreated = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\n\n\
+    ///      [config]\ncase_insensitive_keys = \"error\"\n",
+    /// )
+    /// .unwrap_err();
+    /// assert!(err.to_string().contains("`OrderCreated` and `ordercreated`"));
+    /// ```
+    ///
+    /// ```rust
+    /// # use unique_uuid::registry::Registry;
+    /// # let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/case");
+    /// // ASCII and non-ASCII pairs, e.g. `Straße` and `STRASSE`
+    /// assert!(Registry::load(format!("{}/ascii.toml", fixtures)).is_err());
+    /// assert!(Registry::load(format!("{}/unicode.toml", fixtures)).is_err());
+    /// assert!(Registry::load(format!("{}/distinct.toml", fixtures)).is_ok());
+    /// ```
+    pub fn case_insensitive_keys(&self) -> CaseInsensitiveKeys {
+        self.config().case_insensitive_keys()
+    }
+
+    /// Returns the `tag_pattern` setting of the registry, if any.
+    ///
+    /// Loading or parsing a registry fails if the setting is not a valid regular
+    /// expression.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse("[config]\ntag_pattern = '^[a-z.]+$'\n").unwrap();
+    /// assert_eq!(registry.tag_pattern(), Some("^[a-z.]+$"));
+    ///
+    /// assert!(Registry::parse("[config]\ntag_pattern = '^[a-z'\n").is_err());
+    /// ```
+    pub fn tag_pattern(&self) -> Option<&str> {
+        self.other.get("config")?.get("tag_pattern")?.as_str()
+    }
+
+    /// Returns the prefixes reserved for the UUIDs of each crate, by crate name.
+    ///
+    /// Loading or parsing a registry fails if a prefix is malformed, or if the prefix of
+    /// a crate is a prefix of the one of another crate, as their UUIDs could collide.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse("[config.prefixes]\nbilling = \"2af1\"\n").unwrap();
+    /// assert_eq!(registry.prefixes()["billing"], [0x2a, 0xf1]);
+    ///
+    /// let overlapping = "[config.prefixes]\nbilling = \"2a\"\nshipping = \"2af1\"\n";
+    /// assert!(Registry::parse(overlapping).is_err());
+    /// ```
+    pub fn prefixes(&self) -> BTreeMap<String, Vec<u8>> {
+        self.config().prefixes().iter().cloned().collect()
+    }
+
+    /// Returns the `unique_meta` setting of the registry: the names of the `meta` values
+    /// no two entries may share.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse("[config]\nunique_meta = [\"code\"]\n").unwrap();
+    /// assert_eq!(registry.unique_meta().collect::<Vec<_>>(), ["code"]);
+    /// ```
+    pub fn unique_meta(&self) -> impl Iterator<Item = &str> {
+        self.other
+            .get("config")
+            .and_then(|config| config.get("unique_meta"))
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(toml::Value::as_str)
+    }
+}
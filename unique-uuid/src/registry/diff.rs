@@ -0,0 +1,230 @@
+//! Semantic comparison of two registries.
+use std::{collections::BTreeMap, fmt};
+
+use uuid::Uuid;
+
+use super::{Registry, Section};
+
+/// An entry added to or removed from a registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    /// The section holding the entry.
+    pub section: Section,
+
+    /// The key of the entry.
+    pub key: String,
+
+    /// The UUID of the entry.
+    pub uuid: Uuid,
+}
+
+/// A UUID moved to a new key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenamedEntry {
+    /// The section and key of the entry in the old registry.
+    pub old: (Section, String),
+
+    /// The section and key of the entry in the new registry.
+    pub new: (Section, String),
+
+    /// The UUID of the entry.
+    pub uuid: Uuid,
+}
+
+/// A key whose UUID changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UuidChange {
+    /// The section holding the key.
+    pub section: Section,
+
+    /// The key.
+    pub key: String,
+
+    /// The UUID in the old registry.
+    pub old: Uuid,
+
+    /// The UUID in the new registry.
+    pub new: Uuid,
+}
+
+/// The differences between two registries, returned by [`Registry::diff`].
+///
+/// Only keys and UUIDs are compared: changes to metadata alone (aliases, descriptions,
+/// ...) and to the formatting of the file are not reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryDiff {
+    /// Entries of the new registry whose UUID is not in the old one.
+    pub added: Vec<DiffEntry>,
+
+    /// Entries of the old registry whose UUID is not in the new one.
+    pub removed: Vec<DiffEntry>,
+
+    /// UUIDs stored under a different key in the new registry.
+    pub renamed: Vec<RenamedEntry>,
+
+    /// Keys whose UUID changed. Since a tag must never change once published, this is
+    /// almost always a mistake.
+    pub uuid_changed: Vec<UuidChange>,
+}
+
+impl RegistryDiff {
+    /// Returns `true` if the registries hold the same UUIDs under the same keys.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.uuid_changed.is_empty()
+    }
+}
+
+impl fmt::Display for RegistryDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.uuid_changed {
+            writeln!(
+                f,
+                "! `{}` in [{}] changed UUID from {} to {}",
+                change.key, change.section, change.old, change.new
+            )?;
+        }
+        for entry in &self.renamed {
+            writeln!(
+                f,
+                "~ `{}` in [{}] renamed to `{}` in [{}] ({})",
+                entry.old.1, entry.old.0, entry.new.1, entry.new.0, entry.uuid
+            )?;
+        }
+        for entry in &self.added {
+            writeln!(f, "+ `{}` in [{}] ({})", entry.key, entry.section, entry.uuid)?;
+        }
+        for entry in &self.removed {
+            writeln!(f, "- `{}` in [{}] ({})", entry.key, entry.section, entry.uuid)?;
+        }
+        Ok(())
+    }
+}
+
+impl Registry {
+    /// Compares the entries of `old` and `new`.
+    ///
+    /// Keys present in both registries with different UUIDs are reported as
+    /// [`uuid_changed`](RegistryDiff::uuid_changed). Among the remaining keys, a UUID
+    /// that disappears from a key and appears under another (possibly in the other
+    /// section) is reported as [`renamed`](RegistryDiff::renamed), and the others as
+    /// [`added`](RegistryDiff::added) or [`removed`](RegistryDiff::removed). Every list
+    /// is sorted by section and key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Registry, Section};
+    ///
+    /// let old = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "orders.legacy" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+    ///
+    ///     [unique_type_tags]
+    ///     "::OrderRecord" = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50"
+    /// "#).unwrap();
+    /// let new = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", description = "Metadata is ignored" }
+    ///     "orders.shipped" = "ce4baeef-bd9c-4932-88be-24eb1033f695"
+    ///
+    ///     [unique_type_tags]
+    ///     "::Order" = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50"
+    /// "#).unwrap();
+    ///
+    /// let diff = Registry::diff(&old, &new);
+    /// assert_eq!(diff.added[0].key, "orders.shipped");
+    /// assert_eq!(diff.removed[0].key, "orders.legacy");
+    /// assert_eq!(diff.renamed[0].old, (Section::UniqueTypeTags, "::OrderRecord".to_string()));
+    /// assert_eq!(diff.renamed[0].new, (Section::UniqueTypeTags, "::Order".to_string()));
+    /// assert!(diff.uuid_changed.is_empty());
+    /// ```
+    ///
+    /// A key whose UUID changed is reported on its own:
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let old = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    /// "#).unwrap();
+    /// let new = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+    /// "#).unwrap();
+    ///
+    /// let diff = Registry::diff(&old, &new);
+    /// assert_eq!(diff.uuid_changed.len(), 1);
+    /// assert!(diff.added.is_empty() && diff.removed.is_empty());
+    /// assert_eq!(
+    ///     diff.to_string(),
+    ///     "! `orders.created` in [unique_tags] changed UUID from \
+    ///      2af1216e-7042-406a-a577-c39a7e7c3faa to d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\n"
+    /// );
+    /// ```
+    pub fn diff(old: &Registry, new: &Registry) -> RegistryDiff {
+        let mut diff = RegistryDiff::default();
+        let mut removed: BTreeMap<(Section, &str), Uuid> = BTreeMap::new();
+        let mut added: BTreeMap<(Section, &str), Uuid> = BTreeMap::new();
+
+        for section in Section::ALL {
+            for (key, entry) in old.entries(section) {
+                match new.entry(section, key) {
+                    Some(other) if other.uuid() != entry.uuid() => {
+                        diff.uuid_changed.push(UuidChange {
+                            section,
+                            key: key.to_string(),
+                            old: entry.uuid(),
+                            new: other.uuid(),
+                        })
+                    }
+                    Some(_) => {}
+                    None => {
+                        removed.insert((section, key), entry.uuid());
+                    }
+                }
+            }
+            for (key, entry) in new.entries(section) {
+                if old.entry(section, key).is_none() {
+                    added.insert((section, key), entry.uuid());
+                }
+            }
+        }
+
+        let mut added_by_uuid: BTreeMap<Uuid, (Section, &str)> = added
+            .iter()
+            .map(|(location, uuid)| (*uuid, *location))
+            .collect();
+        for ((section, key), uuid) in removed {
+            match added_by_uuid.remove(&uuid) {
+                Some((new_section, new_key)) => {
+                    added.remove(&(new_section, new_key));
+                    diff.renamed.push(RenamedEntry {
+                        old: (section, key.to_string()),
+                        new: (new_section, new_key.to_string()),
+                        uuid,
+                    });
+                }
+                None => diff.removed.push(DiffEntry {
+                    section,
+                    key: key.to_string(),
+                    uuid,
+                }),
+            }
+        }
+        diff.added = added
+            .into_iter()
+            .map(|((section, key), uuid)| DiffEntry {
+                section,
+                key: key.to_string(),
+                uuid,
+            })
+            .collect();
+
+        diff
+    }
+}
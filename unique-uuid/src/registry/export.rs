@@ -0,0 +1,187 @@
+//! Export of the registry to formats consumed by other tooling.
+//!
+//! Both formats list every valid entry, tags first and then type tags, each sorted by
+//! key, with the following fields:
+//!
+//! | Field         | Content                                                        |
+//! |---------------|----------------------------------------------------------------|
+//! | `key`         | the key of the entry                                           |
+//! | `uuid`        | the UUID, hyphenated and lowercase                             |
+//! | `kind`        | `tag` for `[unique_tags]`, `type` for `[unique_type_tags]`     |
+//! | `namespace`   | the namespace of the key (see [`namespace_of`]), if any        |
+//! | `aliases`     | the alternative keys of the entry                              |
+//! | `description` | the description of the entry, if any                           |
+//! | `groups`      | the groups of the entry (see [`Entry::groups`])                |
+//!
+//! New fields may be appended in later versions, existing fields are never renamed
+//! nor reordered: `groups` was appended to the six fields of the first version.
+use std::io::{self, Write};
+
+use unique_uuid_core::{json_mirror, json_string};
+
+use super::{namespace_of, Entry, Registry, Section};
+
+fn kind(section: Section) -> &'static str {
+    match section {
+        Section::UniqueTags => "tag",
+        Section::UniqueTypeTags => "type",
+    }
+}
+
+impl Registry {
+    /// Writes the registry to `writer` as a JSON array, with one object per entry and
+    /// per line.
+    ///
+    /// Absent namespaces and descriptions are written as `null`, and `aliases` and
+    /// `groups` are always arrays. See the [module documentation](self) for the fields.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `writer` fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// # let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/export/registry.toml");
+    /// # let golden = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/export/registry.json"));
+    /// let registry = Registry::load(path).unwrap();
+    ///
+    /// let mut json = Vec::new();
+    /// registry.export_json(&mut json).unwrap();
+    /// assert_eq!(String::from_utf8(json).unwrap(), golden);
+    /// ```
+    pub fn export_json(&self, mut writer: impl Write) -> io::Result<()> {
+        let optional = |value: Option<&str>| value.map_or_else(|| "null".to_string(), json_string);
+
+        write!(writer, "[")?;
+        for (i, (section, key, entry)) in self.export_entries().enumerate() {
+            let aliases: Vec<String> = entry.aliases().map(json_string).collect();
+            let groups: Vec<String> = entry.groups().map(json_string).collect();
+            write!(
+                writer,
+                "{}\n  {{\"key\": {}, \"uuid\": \"{}\", \"kind\": \"{}\", \"namespace\": {}, \
+                 \"aliases\": [{}], \"description\": {}, \"groups\": [{}]}}",
+                if i == 0 { "" } else { "," },
+                json_string(key),
+                entry.uuid(),
+                kind(section),
+                optional(namespace_of(key)),
+                aliases.join(", "),
+                optional(entry.description()),
+                groups.join(", "),
+            )?;
+        }
+        if !self.is_empty() {
+            writeln!(writer)?;
+        }
+        writeln!(writer, "]")
+    }
+
+    /// Writes the registry to `writer` as CSV (RFC 4180), with a header line.
+    ///
+    /// Absent namespaces and descriptions are written as empty fields, and aliases and
+    /// groups are joined with `;`. See the [module documentation](self) for the fields.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `writer` fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// # let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/export/registry.toml");
+    /// # let golden = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/export/registry.csv"));
+    /// let registry = Registry::load(path).unwrap();
+    ///
+    /// let mut csv = Vec::new();
+    /// registry.export_csv(&mut csv).unwrap();
+    /// assert_eq!(String::from_utf8(csv).unwrap(), golden);
+    /// ```
+    pub fn export_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "key,uuid,kind,namespace,aliases,description,groups\r\n"
+        )?;
+        for (section, key, entry) in self.export_entries() {
+            let aliases: Vec<&str> = entry.aliases().collect();
+            let groups: Vec<&str> = entry.groups().collect();
+            write!(
+                writer,
+                "{},{},{},{},{},{},{}\r\n",
+                csv_field(key),
+                entry.uuid(),
+                kind(section),
+                csv_field(namespace_of(key).unwrap_or_default()),
+                csv_field(&aliases.join(";")),
+                csv_field(entry.description().unwrap_or_default()),
+                csv_field(&groups.join(";")),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns a JSON mirror of the registry, mapping the keys of each section to their
+    /// UUID.
+    ///
+    /// This is the file written by the macros when the `UNIQUE_UUID_EMIT_JSON`
+    /// environment variable is set. Unlike [`Registry::export_json`], it holds no
+    /// metadata, and is meant to be committed next to the registry for consumers written
+    /// in other languages. Its layout is versioned by the `schema` field:
+    ///
+    /// ```json
+    /// {
+    ///   "unique_tags": {
+    ///     "orders.created": "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///   },
+    ///   "unique_type_tags": {},
+    ///   "schema": 1
+    /// }
+    /// ```
+    ///
+    /// Keys are sorted, so the output only changes when an entry does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", crate = "billing" }
+    ///     "orders.cancelled" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+    /// "#).unwrap();
+    ///
+    /// assert_eq!(
+    ///     registry.to_json_mirror(),
+    ///     r#"{
+    ///   "unique_tags": {
+    ///     "orders.cancelled": "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24",
+    ///     "orders.created": "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///   },
+    ///   "unique_type_tags": {},
+    ///   "schema": 1
+    /// }
+    /// "#
+    /// );
+    /// ```
+    pub fn to_json_mirror(&self) -> String {
+        json_mirror(&self.document())
+    }
+
+    fn export_entries(&self) -> impl Iterator<Item = (Section, &str, &Entry)> {
+        Section::ALL.into_iter().flat_map(move |section| {
+            self.entries(section)
+                .map(move |(key, entry)| (section, key, entry))
+        })
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
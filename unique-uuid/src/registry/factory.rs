@@ -0,0 +1,107 @@
+//! The constructors registered in the running binary by `#[unique_type_tag(factory)]`.
+//!
+//! With the `linkme` feature, `#[unique_type_tag(factory)]` registers a constructor of the
+//! type, returning its [`Default`] value, in a [`linkme`] distributed slice, so that a
+//! value of the type can be built from its tag alone, e.g. while deserializing or loading
+//! plugins. As with [`super::iter_type_tags`], the registrations are collected by the
+//! linker, whatever the crate the type is defined in.
+use std::{any::Any, sync::OnceLock};
+
+use crate::UniqueTag;
+
+/// A constructor registered by `#[unique_type_tag(factory)]`, see [`construct`].
+#[derive(Debug, Clone, Copy)]
+pub struct FactoryEntry {
+    /// The tag of the type.
+    pub tag: UniqueTag,
+
+    /// The path of the type, e.g. `shop::orders::Order`.
+    pub type_name: &'static str,
+
+    /// Returns the default value of the type.
+    pub construct: fn() -> Box<dyn Any>,
+}
+
+/// The registrations of `#[unique_type_tag(factory)]`, in link order.
+#[doc(hidden)]
+#[linkme::distributed_slice]
+pub static FACTORIES: [FactoryEntry];
+
+/// Returns the registrations sorted by tag, checking that no two types share a tag.
+///
+/// # Panics
+/// Panics if two types are registered with the same tag, naming both.
+fn index() -> &'static [&'static FactoryEntry] {
+    static INDEX: OnceLock<Vec<&'static FactoryEntry>> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let mut index: Vec<_> = FACTORIES.iter().collect();
+        index.sort_by_key(|entry| (entry.tag, entry.type_name));
+        if let Some(pair) = index.windows(2).find(|pair| pair[0].tag == pair[1].tag) {
+            panic!(
+                "`{}` and `{}` are both registered as the factory of the tag {}: a tag must \
+                 denote a single type",
+                pair[0].type_name, pair[1].type_name, pair[0].tag.0
+            );
+        }
+        index
+    })
+}
+
+/// Returns a new value, boxed, of the type registered with `tag` by
+/// `#[unique_type_tag(factory)]` in the running binary, if any.
+///
+/// The registrations are sorted by tag on the first call, so that lookups take a
+/// logarithmic time.
+///
+/// This function is only available with the `linkme` feature.
+///
+/// # Panics
+/// Panics on the first call if two types are registered with the same tag, e.g. types of
+/// different crates resolved against different registries.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{registry::construct, unique_tag, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag, Default, Debug, PartialEq)]
+/// #[unique_type_tag(factory)]
+/// struct Pallet {
+///     parcels: Vec<String>,
+/// }
+///
+/// let value = construct(Pallet::TYPE_TAG).unwrap();
+/// assert_eq!(value.downcast_ref::<Pallet>(), Some(&Pallet::default()));
+/// assert!(construct(unique_tag!(test, "pallets.unloaded")).is_none());
+/// ```
+pub fn construct(tag: UniqueTag) -> Option<Box<dyn Any>> {
+    let index = index();
+    index
+        .binary_search_by_key(&tag, |entry| entry.tag)
+        .ok()
+        .map(|position| (index[position].construct)())
+}
+
+/// Returns a new value of `T`, if `T` is the type registered with `tag` by
+/// `#[unique_type_tag(factory)]` in the running binary, see [`construct`].
+///
+/// This function is only available with the `linkme` feature.
+///
+/// # Panics
+/// Panics on the first call if two types are registered with the same tag.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{registry::construct_as, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag, Default, Debug, PartialEq)]
+/// #[unique_type_tag(factory)]
+/// struct Pallet(u32);
+///
+/// assert_eq!(construct_as::<Pallet>(Pallet::TYPE_TAG), Some(Pallet(0)));
+/// assert_eq!(construct_as::<String>(Pallet::TYPE_TAG), None);
+/// ```
+pub fn construct_as<T: 'static>(tag: UniqueTag) -> Option<T> {
+    construct(tag)?.downcast().ok().map(|value| *value)
+}
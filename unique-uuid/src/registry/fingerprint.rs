@@ -0,0 +1,42 @@
+//! Fingerprints of the registries, as declared by `registry_fingerprint!`.
+use super::Registry;
+use crate::{fingerprint::NAMESPACE, UniqueTag};
+
+impl Registry {
+    /// Returns the fingerprint of the registry, covering the entries of `namespace` if
+    /// given (see [`in_namespace`](super::in_namespace)) and every entry otherwise. This is the value of the
+    /// `REGISTRY_FINGERPRINT` that `registry_fingerprint!` declares for the same
+    /// registry, see [`crate::fingerprint`] for the algorithm.
+    ///
+    /// Rejected entries are left out, as the macros ignore them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::{fingerprint::NAMESPACE, registry::Registry, UniqueTag};
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "payments.refunded" = { uuid = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24", description = "Ignored" }
+    ///
+    ///     [unique_type_tags]
+    ///     "::Invoice" = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb"
+    /// "#).unwrap();
+    ///
+    /// let name = "unique_tags\torders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa\n\
+    ///             unique_tags\tpayments.refunded\td7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\n\
+    ///             unique_type_tags\t::Invoice\t8701a796-9ffe-4723-89cf-ebcaaafe9ebb\n";
+    /// assert_eq!(registry.fingerprint(None), UniqueTag::new_v5(NAMESPACE, name.as_bytes()));
+    ///
+    /// let orders = "unique_tags\torders.created\t2af1216e-7042-406a-a577-c39a7e7c3faa\n";
+    /// assert_eq!(
+    ///     registry.fingerprint(Some("orders")),
+    ///     UniqueTag::new_v5(NAMESPACE, orders.as_bytes())
+    /// );
+    /// ```
+    pub fn fingerprint(&self, namespace: Option<&str>) -> UniqueTag {
+        let name = self.document().fingerprint_name(namespace);
+        UniqueTag::new_v5(NAMESPACE, name.as_bytes())
+    }
+}
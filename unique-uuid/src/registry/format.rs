@@ -0,0 +1,117 @@
+//! The storage formats of the registry file, see [`Format`].
+use unique_uuid_core::Registry as Document;
+
+use super::{Error, Format, Registry, Section};
+
+impl Registry {
+    /// Parses a registry from the content of a registry file in the given format.
+    ///
+    /// # Errors
+    /// Returns an error if `contents` is not a valid document in that format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Format, Registry};
+    ///
+    /// let contents = r#"{
+    ///   "unique_tags": {
+    ///     "orders.created": "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///   },
+    ///   "unique_type_tags": {}
+    /// }
+    /// "#;
+    ///
+    /// let registry = Registry::parse_format(contents, Format::Json).unwrap();
+    /// assert!(registry.get("orders.created").is_some());
+    /// assert_eq!(registry.to_format_string(Format::Json), contents);
+    /// assert_eq!(Registry::parse(&registry.to_toml_string()).unwrap(), registry);
+    ///
+    /// assert!(Registry::parse_format("[workspace]\n", Format::CargoMetadata).is_err());
+    /// ```
+    ///
+    /// A registry stored in the package metadata of a `Cargo.toml` is saved by editing
+    /// the manifest in place:
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use unique_uuid::registry::{Entry, Registry, Section};
+    ///
+    /// let directory = std::env::temp_dir().join("unique-uuid-doc-cargo-metadata");
+    /// fs::create_dir_all(&directory).unwrap();
+    /// let path = directory.join("Cargo.toml");
+    /// fs::write(
+    ///     &path,
+    ///     "[package]\nname = \"billing\" # the crate\n\n\
+    ///      [package.metadata.unique-uuid.unique_tags]\n\
+    ///      'orders.created' = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n\
+    ///      [dependencies]\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let mut registry = Registry::load(&path).unwrap();
+    /// let uuid = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24".parse().unwrap();
+    /// registry.insert(Section::UniqueTags, "orders.paid", Entry::new(uuid));
+    /// registry.save(&path).unwrap();
+    /// assert_eq!(
+    ///     fs::read_to_string(&path).unwrap(),
+    ///     "[package]\nname = \"billing\" # the crate\n\n\
+    ///      [package.metadata.unique-uuid.unique_tags]\n\
+    ///      'orders.created' = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\
+    ///      \"orders.paid\" = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\n\n\
+    ///      [dependencies]\n"
+    /// );
+    /// ```
+    pub fn parse_format(contents: &str, format: Format) -> Result<Self, Error> {
+        Self::parse_document(contents, format)
+    }
+
+    /// Serializes the registry in the canonical form of the given format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Format, Registry};
+    ///
+    /// let registry = Registry::parse(
+    ///     "[unique_tags]\n\"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// # #[cfg(feature = "ron")]
+    /// # {
+    /// let contents = registry.to_format_string(Format::Ron);
+    /// assert_eq!(
+    ///     contents,
+    ///     "{\n    \"unique_tags\": {\n        \
+    ///      \"orders.created\": \"2af1216e-7042-406a-a577-c39a7e7c3faa\",\n    },\n    \
+    ///      \"unique_type_tags\": {},\n}\n"
+    /// );
+    /// assert_eq!(Registry::parse_format(&contents, Format::Ron).unwrap(), registry);
+    /// # }
+    ///
+    /// # #[cfg(feature = "yaml")]
+    /// # {
+    /// let contents = registry.to_format_string(Format::Yaml);
+    /// assert_eq!(
+    ///     contents,
+    ///     "unique_tags:\n  orders.created: 2af1216e-7042-406a-a577-c39a7e7c3faa\n\
+    ///      unique_type_tags: {}\n"
+    /// );
+    /// assert_eq!(Registry::parse_format(&contents, Format::Yaml).unwrap(), registry);
+    /// # }
+    /// ```
+    pub fn to_format_string(&self, format: Format) -> String {
+        format.serialize(&self.document())
+    }
+
+    /// Returns the document written back for the registry: its sections, malformed
+    /// entries included, and the other top-level items.
+    pub(super) fn document(&self) -> Document {
+        Document {
+            unique_tags: self.section_values(Section::UniqueTags),
+            unique_type_tags: self.section_values(Section::UniqueTypeTags),
+            other: self.other.clone(),
+        }
+    }
+}
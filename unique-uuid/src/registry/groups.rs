@@ -0,0 +1,84 @@
+//! The groups of the entries, labels recorded in their `groups` array:
+//!
+//! ```toml
+//! [unique_tags]
+//! "invoice.sent" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", groups = ["billing", "external"] }
+//! ```
+//!
+//! Unlike namespaces and parents, groups do not depend on the keys, and an entry may
+//! belong to any number of them. The macros add them from `groups = [...]` and
+//! `#[unique_type_tag(groups(...))]`.
+use super::{Entry, Registry, Section};
+
+impl Entry {
+    /// Returns the groups of the entry (the `groups` field).
+    pub fn groups(&self) -> impl Iterator<Item = &str> {
+        unique_uuid_core::groups(&self.extra)
+    }
+}
+
+impl Registry {
+    /// Returns the keys of the entries belonging to `group`, those of `[unique_tags]`
+    /// first and then those of `[unique_type_tags]`, each sorted.
+    ///
+    /// A group no entry belongs to is empty, whether or not it is used anywhere else.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "invoice.sent" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", groups = ["billing", "external"] }
+    ///     "invoice.voided" = { uuid = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24", groups = ["billing"] }
+    ///
+    ///     [unique_type_tags]
+    ///     "::Invoice" = { uuid = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb", groups = ["billing"] }
+    /// "#).unwrap();
+    ///
+    /// let billing: Vec<_> = registry.tags_in_group("billing").collect();
+    /// assert_eq!(billing, ["invoice.sent", "invoice.voided", "::Invoice"]);
+    /// assert_eq!(registry.tags_in_group("external").collect::<Vec<_>>(), ["invoice.sent"]);
+    /// assert_eq!(registry.tags_in_group("retention").count(), 0);
+    /// ```
+    pub fn tags_in_group<'a>(&'a self, group: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        Section::ALL.into_iter().flat_map(move |section| {
+            self.entries(section)
+                .filter(move |(_, entry)| entry.groups().any(|g| g == group))
+                .map(|(key, _)| key)
+        })
+    }
+
+    /// Returns the groups of the entry `key` resolves to, as [`Registry::get`] resolves
+    /// it, in the order of its `groups` array.
+    ///
+    /// A key missing from the registry has no groups.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "invoice.sent" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", groups = ["billing", "external"] }
+    /// "#).unwrap();
+    ///
+    /// assert_eq!(registry.groups_of("invoice.sent").collect::<Vec<_>>(), ["billing", "external"]);
+    /// assert_eq!(registry.groups_of("invoice.voided").count(), 0);
+    /// ```
+    pub fn groups_of(&self, key: &str) -> impl Iterator<Item = &str> {
+        let entry = Section::ALL
+            .into_iter()
+            .find_map(|section| self.entry(section, key))
+            .or_else(|| {
+                Section::ALL.into_iter().find_map(|section| {
+                    self.entries(section)
+                        .find(|(_, entry)| entry.aliases().any(|alias| alias == key))
+                        .map(|(_, entry)| entry)
+                })
+            });
+        entry.into_iter().flat_map(Entry::groups)
+    }
+}
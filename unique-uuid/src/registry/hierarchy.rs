@@ -0,0 +1,112 @@
+//! The tree of `[unique_tags]` entries formed by their `parent` fields.
+use std::collections::BTreeSet;
+
+use crate::UniqueTag;
+
+use super::{Registry, Section};
+
+impl Registry {
+    /// Returns the keys of the `[unique_tags]` entries whose parent is `key`, sorted.
+    ///
+    /// Only the direct children are returned, see [`Registry::is_descendant`] for the
+    /// whole subtree.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "payments" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "payments.captured" = { uuid = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24", parent = "payments" }
+    ///     "payments.refunded" = { uuid = "ce4baeef-bd9c-4932-88be-24eb1033f695", parent = "payments" }
+    ///     "payments.refunded.partially" = { uuid = "a3e7c969-8413-4f6b-9928-28a140227b0b", parent = "payments.refunded" }
+    /// "#).unwrap();
+    ///
+    /// let children: Vec<_> = registry.children_of("payments").collect();
+    /// assert_eq!(children, ["payments.captured", "payments.refunded"]);
+    /// assert_eq!(registry.children_of("payments.captured").count(), 0);
+    /// ```
+    pub fn children_of<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries(Section::UniqueTags)
+            .filter(move |(_, entry)| entry.parent() == Some(key))
+            .map(|(key, _)| key)
+    }
+
+    /// Returns `true` if `tag` is a descendant of `ancestor`: a child of it, or of one of
+    /// its descendants, following the `parent` fields of the `[unique_tags]` entries.
+    ///
+    /// A tag is not a descendant of itself, and tags missing from the registry have no
+    /// ancestor. Cycles, which [`Registry::validate`] reports, do not loop forever.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "payments" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "payments.refunded" = { uuid = "ce4baeef-bd9c-4932-88be-24eb1033f695", parent = "payments" }
+    ///     "payments.refunded.partially" = { uuid = "a3e7c969-8413-4f6b-9928-28a140227b0b", parent = "payments.refunded" }
+    /// "#).unwrap();
+    /// let tag = |key| registry.get(key).unwrap().tag;
+    ///
+    /// assert!(registry.is_descendant(tag("payments.refunded.partially"), tag("payments")));
+    /// assert!(!registry.is_descendant(tag("payments"), tag("payments.refunded")));
+    /// assert!(!registry.is_descendant(tag("payments"), tag("payments")));
+    /// ```
+    pub fn is_descendant(&self, tag: UniqueTag, ancestor: UniqueTag) -> bool {
+        let Some(key) = self.key_of_tag(tag) else {
+            return false;
+        };
+        self.ancestors(key)
+            .any(|key| self.key_of_tag(ancestor) == Some(key))
+    }
+
+    /// Returns the key of the `[unique_tags]` entry of `tag`, if any.
+    fn key_of_tag(&self, tag: UniqueTag) -> Option<&str> {
+        self.entries(Section::UniqueTags)
+            .find(|(_, entry)| entry.tag() == tag)
+            .map(|(key, _)| key)
+    }
+
+    /// Returns the parent of `key`, then its parent and so on, each key once.
+    fn ancestors<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let mut seen = BTreeSet::from([key]);
+        let mut current = key;
+        std::iter::from_fn(move || {
+            let parent = self.entry(Section::UniqueTags, current)?.parent()?;
+            if !seen.insert(parent) {
+                return None;
+            }
+            current = parent;
+            Some(parent)
+        })
+    }
+
+    /// Returns the cycles of parents, each as its keys sorted, in key order.
+    pub(super) fn parent_cycles(&self) -> BTreeSet<Vec<String>> {
+        let mut cycles = BTreeSet::new();
+        for (key, _) in self.entries(Section::UniqueTags) {
+            let mut path = vec![key];
+            let mut current = key;
+            while let Some(parent) = self
+                .entry(Section::UniqueTags, current)
+                .and_then(|entry| entry.parent())
+            {
+                if let Some(start) = path.iter().position(|key| *key == parent) {
+                    let mut cycle: Vec<String> =
+                        path[start..].iter().map(|key| key.to_string()).collect();
+                    cycle.sort();
+                    cycles.insert(cycle);
+                    break;
+                }
+                path.push(parent);
+                current = parent;
+            }
+        }
+        cycles
+    }
+}
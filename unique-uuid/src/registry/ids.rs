@@ -0,0 +1,146 @@
+//! Renumbering of the small integer ids of the entries.
+use std::fmt;
+
+use super::{Registry, Section};
+
+/// How [`Registry::compact_ids`] treats the registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CompactIdsPolicy {
+    /// Only compute the new ids, leaving the registry untouched.
+    #[default]
+    DryRun,
+
+    /// Renumber the entries. The previous ids stop identifying them, which breaks every
+    /// consumer still using them: this policy must be chosen explicitly.
+    Renumber,
+}
+
+/// An entry renumbered by [`Registry::compact_ids`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemappedId {
+    /// The section holding the entry.
+    pub section: Section,
+
+    /// The key of the entry.
+    pub key: String,
+
+    /// The id of the entry before compaction.
+    pub old: u32,
+
+    /// The id of the entry after compaction.
+    pub new: u32,
+}
+
+impl fmt::Display for RemappedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` in [{}]: {} -> {}",
+            self.key, self.section, self.old, self.new
+        )
+    }
+}
+
+/// The mapping from the previous ids to the new ones, returned by
+/// [`Registry::compact_ids`] so that the data keyed by the previous ids can be migrated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdRemap {
+    /// Every entry with an id, in the order of their new ids, including those whose id
+    /// is unchanged.
+    pub entries: Vec<RemappedId>,
+
+    /// The last id allocated after compaction, the `last` field of the `[ids]` table.
+    pub last: u32,
+}
+
+impl IdRemap {
+    /// Returns the new id of the entry whose id was `old`, if any.
+    pub fn get(&self, old: u32) -> Option<u32> {
+        self.entries
+            .iter()
+            .find(|entry| entry.old == old)
+            .map(|entry| entry.new)
+    }
+
+    /// Returns `true` if every id is unchanged.
+    pub fn is_identity(&self) -> bool {
+        self.entries.iter().all(|entry| entry.old == entry.new)
+    }
+}
+
+impl Registry {
+    /// Renumbers the ids of the entries densely from 1, leaving every UUID untouched,
+    /// and returns the previous and new id of each entry.
+    ///
+    /// The entries with an `id` field are renumbered in a deterministic order: those of
+    /// `[unique_tags]` first, then those of `[unique_type_tags]`, each sorted by key.
+    /// The `last` field of the `[ids]` table becomes the greatest new id, so that the
+    /// macros allocate the next ids right after it. Entries without an id get none.
+    ///
+    /// The ids of pruned entries are otherwise never reused: compaction is the one
+    /// operation reassigning them, and breaks every consumer of the previous ids. It
+    /// therefore requires [`CompactIdsPolicy::Renumber`]; with
+    /// [`CompactIdsPolicy::DryRun`], the returned mapping is only computed. The
+    /// renumbered registry still has to be written back, e.g. with [`Registry::save`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{CompactIdsPolicy, Registry, Section};
+    ///
+    /// let mut registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", id = 7 }
+    ///     "orders.cancelled" = { uuid = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24", id = 3 }
+    ///
+    ///     [unique_type_tags]
+    ///     "::Order" = { uuid = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50", id = 1 }
+    ///
+    ///     [ids]
+    ///     last = 9
+    /// "#).unwrap();
+    ///
+    /// // Nothing changes without an explicit policy
+    /// let preview = registry.compact_ids(CompactIdsPolicy::DryRun);
+    /// assert_eq!(registry.entry(Section::UniqueTags, "orders.created").unwrap().id(), Some(7));
+    ///
+    /// let remap = registry.compact_ids(CompactIdsPolicy::Renumber);
+    /// assert_eq!(remap, preview);
+    /// assert_eq!((remap.get(3), remap.get(7), remap.get(1)), (Some(1), Some(2), Some(3)));
+    /// assert_eq!(remap.last, 3);
+    /// assert_eq!(registry.entry(Section::UniqueTags, "orders.created").unwrap().id(), Some(2));
+    /// assert!(registry.to_toml_string().contains("[ids]\nlast = 3\n"));
+    /// ```
+    pub fn compact_ids(&mut self, policy: CompactIdsPolicy) -> IdRemap {
+        let mut remap = IdRemap::default();
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                if let Some(old) = entry.id() {
+                    remap.last += 1;
+                    remap.entries.push(RemappedId {
+                        section,
+                        key: key.to_string(),
+                        old,
+                        new: remap.last,
+                    });
+                }
+            }
+        }
+        if policy == CompactIdsPolicy::DryRun || remap.entries.is_empty() {
+            return remap;
+        }
+
+        for remapped in &remap.entries {
+            if let Some(entry) = self.section_mut(remapped.section).get_mut(&remapped.key) {
+                entry
+                    .extra
+                    .insert("id".to_string(), toml::Value::Integer(remapped.new.into()));
+            }
+        }
+        let last =
+            toml::Table::from_iter([("last".to_string(), toml::Value::Integer(remap.last.into()))]);
+        self.other
+            .insert("ids".to_string(), toml::Value::Table(last));
+        remap
+    }
+}
@@ -0,0 +1,173 @@
+//! A Markdown inventory of the registry, for documentation sites.
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use super::{in_namespace, namespace_of, Entry, Registry, Section};
+
+/// How [`Registry::write_markdown`] groups the entries of each section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkdownGrouping {
+    /// A table per namespace (see [`namespace_of`]).
+    #[default]
+    Namespace,
+
+    /// A table per crate that minted the entries (see [`Entry::crate_name`]).
+    Crate,
+}
+
+/// Options of [`Registry::write_markdown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// Only list the entries of this namespace (see [`in_namespace`]).
+    pub namespace: Option<String>,
+
+    /// How the entries of each section are grouped.
+    pub grouping: MarkdownGrouping,
+
+    /// Whether to include the `Description` column, `true` by default.
+    pub description: bool,
+
+    /// Whether to include the `Crate` column, `true` by default.
+    pub crate_name: bool,
+
+    /// Whether to include the `Created` column, `true` by default.
+    pub created: bool,
+
+    /// Whether to include the `Groups` column, `true` by default.
+    pub groups: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            namespace: None,
+            grouping: MarkdownGrouping::default(),
+            description: true,
+            crate_name: true,
+            created: true,
+            groups: true,
+        }
+    }
+}
+
+impl Registry {
+    /// Writes an inventory of the registry to `writer` as a Markdown page.
+    ///
+    /// The page has a heading per section, tags first, and a table per group of
+    /// entries, each group under a heading of its own: the entries without namespace
+    /// (or crate) first, then the others sorted by name. Each table lists the key and UUID
+    /// of its entries sorted by key, then the columns selected by `options`, with `-`
+    /// for missing metadata. The output only depends on the entries, so that it can be
+    /// committed and reviewed.
+    ///
+    /// # Errors
+    /// Returns an error if writing to `writer` fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{MarkdownGrouping, MarkdownOptions, Registry};
+    ///
+    /// # let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/markdown");
+    /// # let golden = |name: &str| std::fs::read_to_string(format!("{}/{}", fixtures, name)).unwrap();
+    /// let registry = Registry::load(format!("{}/registry.toml", fixtures)).unwrap();
+    ///
+    /// let mut page = Vec::new();
+    /// registry.write_markdown(&mut page, &MarkdownOptions::default()).unwrap();
+    /// assert_eq!(String::from_utf8(page).unwrap(), golden("by_namespace.md"));
+    ///
+    /// let options = MarkdownOptions {
+    ///     namespace: Some("orders".to_string()),
+    ///     grouping: MarkdownGrouping::Crate,
+    ///     description: false,
+    ///     ..MarkdownOptions::default()
+    /// };
+    /// let mut page = Vec::new();
+    /// registry.write_markdown(&mut page, &options).unwrap();
+    /// assert_eq!(String::from_utf8(page).unwrap(), golden("orders_by_crate.md"));
+    /// ```
+    pub fn write_markdown(
+        &self,
+        mut writer: impl Write,
+        options: &MarkdownOptions,
+    ) -> io::Result<()> {
+        let mut columns = vec!["Key", "UUID"];
+        for (included, column) in [
+            (options.description, "Description"),
+            (options.crate_name, "Crate"),
+            (options.created, "Created"),
+            (options.groups, "Groups"),
+        ] {
+            if included {
+                columns.push(column);
+            }
+        }
+
+        writeln!(writer, "# Registry")?;
+        for section in Section::ALL {
+            let title = match section {
+                Section::UniqueTags => "Tags",
+                Section::UniqueTypeTags => "Type tags",
+            };
+            writeln!(writer, "\n## {}", title)?;
+
+            let mut groups: BTreeMap<Option<&str>, Vec<(&str, &Entry)>> = BTreeMap::new();
+            let entries = self.entries(section).filter(|(key, _)| {
+                options
+                    .namespace
+                    .as_ref()
+                    .is_none_or(|namespace| in_namespace(key, namespace))
+            });
+            for (key, entry) in entries {
+                let group = match options.grouping {
+                    MarkdownGrouping::Namespace => namespace_of(key),
+                    MarkdownGrouping::Crate => entry.crate_name(),
+                };
+                groups.entry(group).or_default().push((key, entry));
+            }
+            if groups.is_empty() {
+                writeln!(writer, "\nNo entries.")?;
+            }
+
+            for (group, entries) in groups {
+                let heading = match (group, options.grouping) {
+                    (Some(group), _) => format!("`{}`", cell(group)),
+                    (None, MarkdownGrouping::Namespace) => "Without namespace".to_string(),
+                    (None, MarkdownGrouping::Crate) => "Unknown crate".to_string(),
+                };
+                writeln!(writer, "\n### {}\n", heading)?;
+                writeln!(writer, "| {} |", columns.join(" | "))?;
+                writeln!(writer, "|{}", "---|".repeat(columns.len()))?;
+                for (key, entry) in entries {
+                    let mut row = vec![format!("`{}`", cell(key)), format!("`{}`", entry.uuid())];
+                    for (included, value) in [
+                        (options.description, entry.description()),
+                        (options.crate_name, entry.crate_name()),
+                        (options.created, entry.created()),
+                    ] {
+                        if included {
+                            row.push(value.map_or_else(|| "-".to_string(), cell));
+                        }
+                    }
+                    if options.groups {
+                        let groups: Vec<&str> = entry.groups().collect();
+                        row.push(if groups.is_empty() {
+                            "-".to_string()
+                        } else {
+                            cell(&groups.join(", "))
+                        });
+                    }
+                    writeln!(writer, "| {} |", row.join(" | "))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes `value` for a table cell: pipes would end the cell, and line breaks the row.
+fn cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
@@ -0,0 +1,260 @@
+//! Three-way merge of registries.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use uuid::Uuid;
+
+use super::{Entry, Registry, Section};
+
+/// How [`Registry::merge`] resolves a key modified differently on both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MergePolicy {
+    /// Fail with [`MergeError::Conflicts`].
+    #[default]
+    Error,
+
+    /// Keep the entry from `ours`.
+    PreferOurs,
+
+    /// Keep the entry from `theirs`.
+    PreferTheirs,
+}
+
+/// A key modified differently on both sides of a merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// The section holding the key.
+    pub section: Section,
+
+    /// The conflicting key.
+    pub key: String,
+
+    /// The UUID stored under the key in `ours`, or the nil UUID if `ours` removed it.
+    pub ours: Uuid,
+
+    /// The UUID stored under the key in `theirs`, or the nil UUID if `theirs` removed it.
+    pub theirs: Uuid,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` in [{}] is {} in ours but {} in theirs",
+            self.key, self.section, self.ours, self.theirs
+        )
+    }
+}
+
+/// An error that occurred while merging registries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// Some keys were modified differently on both sides, and the policy was
+    /// [`MergePolicy::Error`].
+    Conflicts(Vec<MergeConflict>),
+
+    /// The merged registry would use the same UUID under several keys.
+    DuplicateUuid {
+        /// The duplicated UUID.
+        uuid: Uuid,
+        /// The entries using the UUID.
+        entries: Vec<(Section, String)>,
+    },
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::Conflicts(conflicts) => {
+                write!(f, "{} conflicting entries", conflicts.len())?;
+                for conflict in conflicts {
+                    write!(f, "\n  {}", conflict)?;
+                }
+                Ok(())
+            }
+            MergeError::DuplicateUuid { uuid, entries } => {
+                write!(f, "merged registry uses UUID {} for several keys:", uuid)?;
+                for (section, key) in entries {
+                    write!(f, " `{}` in [{}]", key, section)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+impl Registry {
+    /// Merges the registries `ours` and `theirs`, which both derive from `base`.
+    ///
+    /// Keys are merged one by one, like `git` merges lines, comparing their UUIDs:
+    /// - a key added on one side only is kept
+    /// - a key removed on one side and with an unchanged UUID on the other is removed
+    /// - a key whose UUID changed on one side only takes the changed entry
+    /// - a key whose UUID changed differently on both sides (including removed on one
+    ///   side and changed on the other) is resolved according to `policy`
+    ///
+    /// The other fields of a key with the same UUID on both sides, e.g. `description`,
+    /// `owner` or `created`, are merged one by one in the same way, and never conflict:
+    /// a field changed differently on both sides takes the value of `theirs` with
+    /// [`MergePolicy::PreferTheirs`], and of `ours` otherwise.
+    ///
+    /// Malformed entries are not merged. Run [`Registry::validate`] on the inputs first
+    /// to find them.
+    ///
+    /// # Errors
+    /// Returns [`MergeError::Conflicts`] if `policy` is [`MergePolicy::Error`] and some
+    /// keys conflict, and [`MergeError::DuplicateUuid`] if the merged registry would
+    /// use the same UUID under several keys (typically a tag added on both branches
+    /// under different names).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{MergeError, MergePolicy, Registry};
+    ///
+    /// let base = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "shared" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    /// "#).unwrap();
+    /// let ours = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "shared" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "from_ours" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+    ///     "both" = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50"
+    /// "#).unwrap();
+    /// let theirs = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "shared" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "from_theirs" = "ce4baeef-bd9c-4932-88be-24eb1033f695"
+    ///     "both" = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb"
+    /// "#).unwrap();
+    ///
+    /// // "both" was added with different UUIDs on each side
+    /// let err = Registry::merge(&base, &ours, &theirs, MergePolicy::Error).unwrap_err();
+    /// assert!(matches!(err, MergeError::Conflicts(conflicts) if conflicts[0].key == "both"));
+    ///
+    /// let merged = Registry::merge(&base, &ours, &theirs, MergePolicy::PreferTheirs).unwrap();
+    /// assert_eq!(merged.len(), 4);
+    /// assert_eq!(merged.get("both"), theirs.get("both"));
+    /// ```
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{MergeError, MergePolicy, Registry};
+    ///
+    /// let base = Registry::new();
+    /// let ours = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    /// "#).unwrap();
+    /// let theirs = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "order_created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    /// "#).unwrap();
+    ///
+    /// let err = Registry::merge(&base, &ours, &theirs, MergePolicy::PreferOurs).unwrap_err();
+    /// assert!(matches!(err, MergeError::DuplicateUuid { .. }));
+    /// ```
+    pub fn merge(
+        base: &Registry,
+        ours: &Registry,
+        theirs: &Registry,
+        policy: MergePolicy,
+    ) -> Result<Registry, MergeError> {
+        let mut merged = Registry {
+            other: ours.other.clone(),
+            ..Registry::new()
+        };
+        let mut conflicts = Vec::new();
+
+        for section in Section::ALL {
+            let keys: BTreeSet<&str> = ours
+                .entries(section)
+                .chain(theirs.entries(section))
+                .map(|(key, _)| key)
+                .collect();
+
+            for key in keys {
+                let base_entry = base.entry(section, key);
+                let ours_entry = ours.entry(section, key);
+                let theirs_entry = theirs.entry(section, key);
+
+                let uuid = |entry: Option<&Entry>| entry.map(Entry::uuid);
+                let resolved = match (ours_entry, theirs_entry) {
+                    (Some(ours), Some(theirs)) if ours.uuid == theirs.uuid => {
+                        Some(merge_fields(base_entry, ours, theirs, policy))
+                    }
+                    _ if uuid(theirs_entry) == uuid(base_entry) => ours_entry.cloned(),
+                    _ if uuid(ours_entry) == uuid(base_entry) => theirs_entry.cloned(),
+                    _ => match policy {
+                        MergePolicy::PreferOurs => ours_entry.cloned(),
+                        MergePolicy::PreferTheirs => theirs_entry.cloned(),
+                        MergePolicy::Error => {
+                            conflicts.push(MergeConflict {
+                                section,
+                                key: key.to_string(),
+                                ours: uuid(ours_entry).unwrap_or_else(Uuid::nil),
+                                theirs: uuid(theirs_entry).unwrap_or_else(Uuid::nil),
+                            });
+                            continue;
+                        }
+                    },
+                };
+
+                if let Some(entry) = resolved {
+                    merged.insert(section, key, entry);
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(MergeError::Conflicts(conflicts));
+        }
+
+        let mut by_uuid: BTreeMap<Uuid, Vec<(Section, String)>> = BTreeMap::new();
+        for section in Section::ALL {
+            for (key, entry) in merged.entries(section) {
+                by_uuid
+                    .entry(entry.uuid())
+                    .or_default()
+                    .push((section, key.to_string()));
+            }
+        }
+        if let Some((uuid, entries)) = by_uuid.into_iter().find(|(_, e)| e.len() > 1) {
+            return Err(MergeError::DuplicateUuid { uuid, entries });
+        }
+
+        Ok(merged)
+    }
+}
+
+/// Merges the fields of `ours` and `theirs`, two entries with the same UUID, one by one:
+/// a field changed on one side only takes the changed value, and a field changed
+/// differently on both sides the value of `theirs` with [`MergePolicy::PreferTheirs`],
+/// and the value of `ours` otherwise.
+fn merge_fields(base: Option<&Entry>, ours: &Entry, theirs: &Entry, policy: MergePolicy) -> Entry {
+    let fields: BTreeSet<&String> = ours.extra.keys().chain(theirs.extra.keys()).collect();
+    let mut extra = toml::Table::new();
+    for field in fields {
+        let base = base.and_then(|base| base.extra.get(field));
+        let (ours, theirs) = (ours.extra.get(field), theirs.extra.get(field));
+        let value = if ours == theirs || theirs == base {
+            ours
+        } else if ours == base || policy == MergePolicy::PreferTheirs {
+            theirs
+        } else {
+            ours
+        };
+        if let Some(value) = value {
+            extra.insert(field.clone(), value.clone());
+        }
+    }
+    Entry {
+        uuid: ours.uuid,
+        structured: ours.structured || theirs.structured,
+        extra,
+    }
+}
@@ -0,0 +1,37 @@
+//! The named scalar values recorded in the `meta` table of structured entries:
+//!
+//! ```toml
+//! [unique_type_tags]
+//! "::OrderCreated" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", meta = { code = 7 } }
+//!
+//! [config]
+//! unique_meta = ["code"]
+//! ```
+//!
+//! The macros set them from `meta(...)` and reject a value of a `unique_meta` name that
+//! another entry already has; [`Registry::validate`](super::Registry::validate) reports
+//! the registries breaking that rule anyway.
+use super::{Entry, MetaValue};
+
+impl Entry {
+    /// Returns the value named `name` in the `meta` table of the entry, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{MetaValue, Registry, Section};
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = { uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa", meta = { code = 7, topic = "orders" } }
+    /// "#).unwrap();
+    ///
+    /// let entry = registry.entry(Section::UniqueTags, "orders.created").unwrap();
+    /// assert_eq!(entry.meta("code"), Some(MetaValue::Integer(7)));
+    /// assert_eq!(entry.meta("topic"), Some(MetaValue::String("orders".to_string())));
+    /// assert_eq!(entry.meta("priority"), None);
+    /// ```
+    pub fn meta(&self, name: &str) -> Option<MetaValue> {
+        MetaValue::from_toml(self.extra.get("meta")?.get(name)?)
+    }
+}
@@ -0,0 +1,920 @@
+//! Runtime access to the registry file written by the procedural macros.
+//!
+//! The [`unique_tag!`](crate::unique_tag) and [`UniqueTypeTag`](crate::UniqueTypeTag)
+//! macros persist every identifier they mint in a `types.toml` file. This module lets
+//! application code, tooling and test harnesses read that same file back.
+//!
+//! This module is only available with the `registry-file` feature.
+//!
+//! # Entry forms
+//!
+//! Entries are accepted in two forms:
+//! - the legacy plain form, `"key" = "uuid"`
+//! - the structured form, `"key" = { uuid = "uuid", ... }`, where any field other
+//!   than `uuid` is preserved as-is
+//!
+//! The following optional fields of structured entries are understood:
+//! - `aliases`, an array of alternative keys for the entry
+//! - `reserved`, a boolean marking entries pre-allocated for future use, which the
+//!   macros refuse to claim, see [`Registry::reserve`]
+//! - `owner`, the person or team to contact to lift the reservation of a reserved entry
+//! - `pinned`, a boolean marking entries whose UUID was chosen explicitly
+//! - `description`, a human-readable description of the entry
+//! - `created`, the RFC 3339 timestamp at which the macros minted the entry
+//! - `crate`, the name of the crate whose compilation minted the entry
+//! - `source`, the `file:line` of the macro invocation that minted the entry, relative
+//!   to its crate, when the compiler provides it
+//! - `parent`, the key of the `[unique_tags]` entry this entry is a child of, see
+//!   [`Registry::children_of`]
+//! - `id`, the small integer id the macros allocate alongside the UUID with `ids = true`
+//!   in the `[config]` table, unique across both sections and never reused
+//! - `meta`, a table of named integers, booleans and strings, see [`Entry::meta`]
+//! - `groups`, an array of labels independent of the key, see
+//!   [`Registry::tags_in_group`]
+//!
+//! # Namespaces
+//!
+//! Keys are grouped in namespaces by their `.` or `::` separators: `orders.created`
+//! belongs to the `orders` namespace, and `::shop::Order` to `::shop`. See
+//! [`namespace_of`] and [`in_namespace`].
+//!
+//! # Journal
+//!
+//! With `journal = true` in the `[config]` table of the registry, the macros append new
+//! entries to a journal next to the registry file (see [`journal_path`]), which
+//! [`Registry::load`] merges back and [`Registry::compact`] moves into the registry file.
+//!
+//! # Fragments
+//!
+//! With `fragments = true` instead, the macros write each new entry to a fragment file
+//! of its own (see [`fragments_path`]) without waiting for the registry lock.
+//! [`Registry::load`] merges the pending fragments too, and [`Registry::compact`], which
+//! the macros also run whenever the lock is free, moves them into the registry file. Of
+//! several fragments minting the same key, the one that claimed it first wins.
+//!
+//! # Test Registry
+//!
+//! The tags of `unique_tag!(test, ...)` are written to a registry of their own (see
+//! [`test_registry_path`]), which [`Registry::load`] does not read, so that validation
+//! and exports leave them out unless that file is loaded explicitly.
+//!
+//! # Example
+//!
+//! ```rust
+//! use unique_uuid::registry::Registry;
+//!
+//! let registry = Registry::parse(r#"
+//!     [unique_tags]
+//!     "my_tag" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+//!
+//!     [unique_type_tags]
+//!     "::MyType" = { uuid = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50" }
+//! "#).unwrap();
+//!
+//! let tag = registry.get("my_tag").unwrap().tag;
+//! assert_eq!(registry.reverse(tag), Some("my_tag"));
+//! assert!(registry.get("::MyType").is_some());
+//! ```
+use std::{collections::BTreeMap, path::Path};
+
+use unique_uuid_core::{Config, Layout, Registry as Document};
+use uuid::Uuid;
+
+use crate::{TagInfo, TagKind, UniqueTag};
+
+mod codegen;
+mod config;
+mod diff;
+mod export;
+#[cfg(feature = "linkme")]
+mod factory;
+#[cfg(feature = "fingerprint")]
+mod fingerprint;
+mod format;
+mod groups;
+mod hierarchy;
+mod ids;
+mod markdown;
+mod merge;
+mod meta;
+mod prune;
+mod rename;
+mod reserve;
+mod selftest;
+mod stats;
+mod summary;
+#[cfg(feature = "linkme")]
+mod type_tags;
+mod usage;
+mod validate;
+
+pub use codegen::{
+    constant_name, CHeaderOptions, CodegenError, RustModuleOptions, TypeScriptOptions,
+};
+pub use diff::{DiffEntry, RegistryDiff, RenamedEntry, UuidChange};
+#[cfg(feature = "linkme")]
+pub use factory::{construct, construct_as, FactoryEntry, FACTORIES};
+pub use ids::{CompactIdsPolicy, IdRemap, RemappedId};
+pub use markdown::{MarkdownGrouping, MarkdownOptions};
+pub use merge::{MergeConflict, MergeError, MergePolicy};
+pub use prune::PrunedEntry;
+pub use rename::RenameError;
+pub use reserve::{Reservation, ReservationError};
+#[doc(hidden)]
+pub use selftest::assert_type_tag;
+pub use stats::RegistryStats;
+pub use summary::RegistrySummary;
+#[cfg(feature = "linkme")]
+pub use type_tags::{find_by_tag, iter_type_tags, TypeTagEntry, TYPE_TAGS};
+pub use unique_uuid_core::{
+    fragments_path, in_namespace, journal_path, test_registry_path, CaseInsensitiveKeys, Error,
+    Format, MetaValue, Section,
+};
+pub use usage::UsageLog;
+pub use validate::{IssueKind, Severity, ValidationIssue};
+
+impl From<Section> for TagKind {
+    fn from(section: Section) -> Self {
+        match section {
+            Section::UniqueTags => TagKind::Tag,
+            Section::UniqueTypeTags => TagKind::TypeTag,
+        }
+    }
+}
+
+/// A single entry of the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    uuid: Uuid,
+    structured: bool,
+    extra: toml::Table,
+}
+
+impl Entry {
+    /// Creates a new entry in the legacy plain form.
+    pub fn new(uuid: Uuid) -> Self {
+        Self {
+            uuid,
+            structured: false,
+            extra: toml::Table::new(),
+        }
+    }
+
+    /// Creates a new entry in the structured form written by the macros, with the time it
+    /// was minted and the crate minting it.
+    pub(crate) fn minted(uuid: Uuid) -> Self {
+        let mut extra = match unique_uuid_core::new_entry(uuid) {
+            toml::Value::Table(table) => table,
+            _ => toml::Table::new(),
+        };
+        extra.remove("uuid");
+        Self {
+            uuid,
+            structured: true,
+            extra,
+        }
+    }
+
+    /// Returns the entry in structured form with the flag `name` set, e.g. `pinned` or
+    /// `reserved`.
+    pub(crate) fn flagged(self, name: &str) -> Self {
+        self.with_field(name, toml::Value::Boolean(true))
+    }
+
+    /// Returns the entry in structured form with the field `name` set to `value`.
+    pub(crate) fn with_field(mut self, name: &str, value: toml::Value) -> Self {
+        self.structured = true;
+        self.extra.insert(name.to_string(), value);
+        self
+    }
+
+    /// Returns the entry without the field `name`, keeping its form.
+    pub(crate) fn without_field(mut self, name: &str) -> Self {
+        self.extra.remove(name);
+        self
+    }
+
+    /// Returns the entry with the UUID `uuid`, keeping its other fields.
+    #[cfg(feature = "build")]
+    pub(crate) fn with_uuid(mut self, uuid: Uuid) -> Self {
+        self.uuid = uuid;
+        self
+    }
+
+    /// Returns the UUID stored in the entry.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Returns the UUID stored in the entry as a [`UniqueTag`].
+    pub fn tag(&self) -> UniqueTag {
+        UniqueTag(self.uuid)
+    }
+
+    /// Returns `true` if the entry is written in the structured form.
+    pub fn is_structured(&self) -> bool {
+        self.structured
+    }
+
+    /// Returns the [`TagInfo`] of the entry, stored under `key` in `section`.
+    pub fn info<'a>(&'a self, key: &'a str, section: Section) -> TagInfo<'a> {
+        TagInfo {
+            tag: self.tag(),
+            key,
+            kind: section.into(),
+            namespace: namespace_of(key),
+            description: self.description(),
+            source_crate: self.crate_name(),
+        }
+    }
+
+    /// Returns the alternative keys of the entry (the `aliases` field).
+    pub fn aliases(&self) -> impl Iterator<Item = &str> {
+        self.extra
+            .get("aliases")
+            .and_then(toml::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(toml::Value::as_str)
+    }
+
+    /// Returns the description of the entry (the `description` field).
+    pub fn description(&self) -> Option<&str> {
+        self.extra.get("description").and_then(toml::Value::as_str)
+    }
+
+    /// Returns the time at which the entry was minted (the `created` field), as an
+    /// RFC 3339 timestamp.
+    pub fn created(&self) -> Option<&str> {
+        self.extra.get("created").and_then(toml::Value::as_str)
+    }
+
+    /// Returns the name of the crate that minted the entry (the `crate` field).
+    pub fn crate_name(&self) -> Option<&str> {
+        self.extra.get("crate").and_then(toml::Value::as_str)
+    }
+
+    /// Returns the location of the macro invocation that minted the entry (the `source`
+    /// field), e.g. `src/events.rs:42`.
+    pub fn source(&self) -> Option<&str> {
+        self.extra.get("source").and_then(toml::Value::as_str)
+    }
+
+    /// Returns the key of the parent of the entry (the `parent` field), if any.
+    pub fn parent(&self) -> Option<&str> {
+        self.extra.get("parent").and_then(toml::Value::as_str)
+    }
+
+    /// Returns the small integer id of the entry (the `id` field), if any.
+    pub fn id(&self) -> Option<u32> {
+        self.extra
+            .get("id")
+            .and_then(toml::Value::as_integer)
+            .and_then(|id| u32::try_from(id).ok())
+    }
+
+    /// Returns `true` if the entry is reserved (`reserved = true`).
+    pub fn is_reserved(&self) -> bool {
+        self.flag("reserved")
+    }
+
+    /// Returns the owner of the reservation of the entry (the `owner` field), the person
+    /// or team to contact to lift it, see [`Registry::reserve`].
+    pub fn owner(&self) -> Option<&str> {
+        self.extra.get("owner").and_then(toml::Value::as_str)
+    }
+
+    /// Returns `true` if the UUID of the entry was pinned explicitly (`pinned = true`).
+    pub fn is_pinned(&self) -> bool {
+        self.flag("pinned")
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.extra
+            .get(name)
+            .and_then(toml::Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    fn to_value(&self) -> toml::Value {
+        if self.structured {
+            let mut table = self.extra.clone();
+            table.insert(
+                "uuid".to_string(),
+                toml::Value::String(self.uuid.to_string()),
+            );
+            toml::Value::Table(table)
+        } else {
+            toml::Value::String(self.uuid.to_string())
+        }
+    }
+
+    fn from_value(value: &toml::Value) -> Result<Self, String> {
+        match value {
+            toml::Value::String(uuid) => Ok(Self::new(parse_uuid(uuid)?)),
+            toml::Value::Table(table) => {
+                let mut extra = table.clone();
+                let uuid = match extra.remove("uuid") {
+                    Some(toml::Value::String(uuid)) => parse_uuid(&uuid)?,
+                    Some(_) => return Err("the `uuid` field must be a string".to_string()),
+                    None => return Err("missing `uuid` field".to_string()),
+                };
+                Ok(Self {
+                    uuid,
+                    structured: true,
+                    extra,
+                })
+            }
+            _ => Err("expected a UUID string or a table".to_string()),
+        }
+    }
+}
+
+/// Returns the namespace of `key`, that is everything before its last `.` or `::`
+/// separator, or `None` if the key has no namespace.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::registry::namespace_of;
+///
+/// assert_eq!(namespace_of("orders.created"), Some("orders"));
+/// assert_eq!(namespace_of("::shop::orders::Order"), Some("::shop::orders"));
+/// assert_eq!(namespace_of("::Order"), None);
+/// assert_eq!(namespace_of("standalone"), None);
+/// ```
+pub fn namespace_of(key: &str) -> Option<&str> {
+    let dot = key.rfind('.');
+    let path = key.rfind("::");
+    let end = match (dot, path) {
+        (Some(dot), Some(path)) => dot.max(path),
+        (end, None) | (None, end) => end?,
+    };
+    Some(&key[..end]).filter(|namespace| !namespace.is_empty())
+}
+
+fn parse_uuid(value: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(value).map_err(|err| format!("invalid UUID `{}`: {}", value, err))
+}
+
+/// An entry that could not be interpreted, kept aside so it can be reported by
+/// [`Registry::validate`].
+#[derive(Debug, Clone, PartialEq)]
+struct RejectedEntry {
+    section: Section,
+    key: String,
+    value: toml::Value,
+    message: String,
+}
+
+/// The content of a registry file.
+///
+/// Entries are kept sorted by key within each section. Entries that cannot be
+/// interpreted (e.g. malformed UUIDs) do not prevent the registry from loading; they
+/// are ignored by lookups and reported by [`Registry::validate`].
+///
+/// # Canonical form
+///
+/// [`Registry::to_toml_string`] and [`Registry::save`] write the registry exactly like
+/// the macros do: one table per section, in the order of [`Section::ALL`], with keys
+/// sorted and one entry per line. Rewriting an unmodified registry therefore never
+/// produces spurious changes.
+///
+/// A leading byte order mark and `\r\n` line endings, as written by some editors on
+/// Windows, are accepted; [`Registry::save`] preserves them. Registries holding the same
+/// entries are equal regardless of the layout of the files they were parsed from.
+#[derive(Debug, Clone, Default)]
+pub struct Registry {
+    unique_tags: BTreeMap<String, Entry>,
+    unique_type_tags: BTreeMap<String, Entry>,
+    rejected: Vec<RejectedEntry>,
+    other: toml::Table,
+    layout: Layout,
+}
+
+impl PartialEq for Registry {
+    fn eq(&self, other: &Self) -> bool {
+        self.unique_tags == other.unique_tags
+            && self.unique_type_tags == other.unique_type_tags
+            && self.rejected == other.rejected
+            && self.other == other.other
+    }
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the registry stored at `path`, in the [`Format`] of its extension, with the
+    /// entries of its journal and of its pending fragments (see [`Registry::compact`]).
+    ///
+    /// A section can be stored in a file of its own, holding only that section, with the
+    /// `tags_file` and `type_tags_file` keys of the `[config]` table: paths relative to the
+    /// directory of the registry file, in the format of their extension. The registry file
+    /// must then not hold entries of that section, a missing section file is an empty
+    /// section, and [`Registry::save`] writes each section back to its own file. The
+    /// loaded registry holds every section, so that [`Registry::validate`] checks them
+    /// together:
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use unique_uuid::registry::{IssueKind, Registry};
+    ///
+    /// let directory = std::env::temp_dir().join("unique-uuid-doc-split");
+    /// fs::create_dir_all(directory.join("protocol")).unwrap();
+    /// let path = directory.join("types.toml");
+    /// fs::write(
+    ///     &path,
+    ///     "[unique_type_tags]\n\"::Order\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n\
+    ///      [config]\ntags_file = \"protocol/tags.toml\"\n",
+    /// )
+    /// .unwrap();
+    /// fs::write(
+    ///     directory.join("protocol/tags.toml"),
+    ///     "[unique_tags]\n\"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let registry = Registry::load(&path).unwrap();
+    /// assert!(registry.get("orders.created").is_some());
+    /// let issues = registry.validate();
+    /// assert_eq!(issues[0].kind, IssueKind::DuplicateUuid);
+    ///
+    /// // Saved back, each section stays in its own file
+    /// registry.save(&path).unwrap();
+    /// assert!(!fs::read_to_string(&path).unwrap().contains("orders.created"));
+    /// assert_eq!(Registry::load(&path).unwrap(), registry);
+    /// ```
+    ///
+    /// A path with a `.d` extension, e.g. `types.d`, is a registry directory, in which the
+    /// macros keep one file per crate: its `.toml` files are loaded, with their journals,
+    /// and merged. A key stored in two of them is an error naming both files:
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let directory = std::env::temp_dir().join("unique-uuid-doc-directory/types.d");
+    /// # let _ = fs::remove_dir_all(&directory);
+    /// fs::create_dir_all(&directory).unwrap();
+    /// fs::write(
+    ///     directory.join("billing.toml"),
+    ///     "[unique_tags]\n\"orders.created\" = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n",
+    /// )
+    /// .unwrap();
+    /// fs::write(
+    ///     directory.join("shipping.toml"),
+    ///     "[unique_tags]\n\"orders.shipped\" = \"8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4\"\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// let registry = Registry::load(&directory).unwrap();
+    /// assert!(registry.get("orders.created").is_some());
+    /// assert!(registry.get("orders.shipped").is_some());
+    ///
+    /// fs::write(
+    ///     directory.join("support.toml"),
+    ///     "[unique_tags]\n\"orders.shipped\" = \"ce4baeef-bd9c-4932-88be-24eb1033f695\"\n",
+    /// )
+    /// .unwrap();
+    /// let message = Registry::load(&directory).unwrap_err().to_string();
+    /// assert!(message.contains("shipping.toml` and `"), "{}", message);
+    /// assert!(message.ends_with("support.toml`: remove one of them"), "{}", message);
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the file, a section file or its journal cannot be read, or if
+    /// one of the files is not a valid document.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let (document, layout) = unique_uuid_core::load(path)?;
+        Self::from_document(document, layout).map_err(|message| Error::Parse {
+            path: Some(path.to_path_buf()),
+            message,
+        })
+    }
+
+    /// Parses a registry from the content of a registry file.
+    ///
+    /// # Errors
+    /// Returns an error if `contents` is not valid TOML.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// // As saved by Notepad: a byte order mark and `\r\n` line endings
+    /// let contents = "\u{feff}[unique_tags]\r\n\
+    ///     a = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\r\n\
+    ///     \r\n\
+    ///     [unique_type_tags]\r\n";
+    ///
+    /// let registry = Registry::parse(contents).unwrap();
+    /// assert!(registry.get("a").is_some());
+    /// assert_eq!(
+    ///     registry.to_toml_string(),
+    ///     "[unique_tags]\na = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\n[unique_type_tags]\n"
+    /// );
+    /// ```
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        Self::parse_document(contents, Format::Toml)
+    }
+
+    /// Parses a registry from the content of a registry file in `format`.
+    fn parse_document(contents: &str, format: Format) -> Result<Self, Error> {
+        format
+            .parse(&Layout::strip(contents))
+            .and_then(|document| Self::from_document(document, Layout::detect(contents)))
+            .map_err(|message| Error::Parse {
+                path: None,
+                message,
+            })
+    }
+
+    /// Reads the entries of `document`, read from a file laid out as `layout`.
+    fn from_document(document: Document, layout: Layout) -> Result<Self, String> {
+        Config::from_items(&document.other)?.check_case_collisions(&document)?;
+        let mut registry = Self {
+            layout,
+            other: document.other,
+            ..Self::new()
+        };
+        for (section, entries) in [
+            (Section::UniqueTags, document.unique_tags),
+            (Section::UniqueTypeTags, document.unique_type_tags),
+        ] {
+            for (key, value) in entries {
+                match Entry::from_value(&value) {
+                    Ok(entry) => {
+                        registry.section_mut(section).insert(key, entry);
+                    }
+                    Err(message) => registry.rejected.push(RejectedEntry {
+                        section,
+                        key,
+                        value,
+                        message,
+                    }),
+                }
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Serializes the registry in its canonical form.
+    ///
+    /// Malformed entries are written back unchanged, as are any top-level items
+    /// unknown to this version of the crate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let contents = "[unique_tags]\n\
+    ///     a = \"2af1216e-7042-406a-a577-c39a7e7c3faa\"\n\
+    ///     b = { uuid = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\" }\n\
+    ///     \n\
+    ///     [unique_type_tags]\n\
+    ///     \"::Test\" = \"6aac8026-6ca8-4c82-ac39-d74bedf1ac50\"\n";
+    ///
+    /// let registry = Registry::parse(contents).unwrap();
+    /// assert_eq!(registry.to_toml_string(), contents);
+    /// ```
+    pub fn to_toml_string(&self) -> String {
+        self.document().to_canonical_string()
+    }
+
+    /// Returns the values of the entries of `section` as written back, malformed entries
+    /// included, sorted by key.
+    fn section_values(&self, section: Section) -> BTreeMap<String, toml::Value> {
+        let mut values: BTreeMap<String, toml::Value> = self
+            .entries(section)
+            .map(|(key, entry)| (key.to_string(), entry.to_value()))
+            .collect();
+        for rejected in self.rejected.iter().filter(|r| r.section == section) {
+            values.insert(rejected.key.clone(), rejected.value.clone());
+        }
+        values
+    }
+
+    /// Writes the registry to `path` in its canonical form, in the [`Format`] of its
+    /// extension.
+    ///
+    /// A registry parsed from a file starting with a byte order mark, or whose lines end
+    /// with `\r\n`, is written the same way, so that the line endings chosen by the
+    /// project do not churn.
+    ///
+    /// The registry is first written to a temporary file next to `path`, which is then
+    /// renamed over it, so readers never observe a partially written registry.
+    ///
+    /// The journal of the registry is left untouched. Since its entries do not override
+    /// those of the registry file, a loaded registry can be saved back safely, but an
+    /// entry removed or renamed while it is still in the journal comes back on the next
+    /// load: modify a registry with [`Registry::update`] instead, which moves the journal
+    /// into the registry file under the journal lock.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use unique_uuid::registry::{Entry, Registry, Section};
+    /// # let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/layout/windows.toml");
+    ///
+    /// let path = std::env::temp_dir().join("unique-uuid-doc-save-windows.toml");
+    /// fs::copy(&fixture, &path).unwrap();
+    ///
+    /// // Saved back unmodified, the file is unchanged
+    /// let mut registry = Registry::load(&path).unwrap();
+    /// registry.save(&path).unwrap();
+    /// assert_eq!(fs::read(&path).unwrap(), fs::read(&fixture).unwrap());
+    ///
+    /// // New entries get the same line endings
+    /// let uuid = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24".parse().unwrap();
+    /// registry.insert(Section::UniqueTags, "orders.paid", Entry::new(uuid));
+    /// registry.save(&path).unwrap();
+    /// let contents = fs::read_to_string(&path).unwrap();
+    /// assert!(contents.starts_with('\u{feff}'));
+    /// assert!(contents.contains("\"orders.paid\" = \"d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24\"\r\n"));
+    /// assert!(!contents.replace("\r\n", "").contains('\n'));
+    /// ```
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        unique_uuid_core::save(path, &self.document(), self.layout)
+    }
+
+    /// Moves the entries of the journal of the registry stored at `path`, and of its
+    /// pending fragments, into the registry file, returning the number of entries added
+    /// to it.
+    ///
+    /// The journal is locked for the duration of the compaction, which makes concurrent
+    /// writers wait until it is done. The merged registry is written to a temporary file
+    /// renamed over the registry file, and the journal is only emptied, and the
+    /// fragments deleted, afterwards: if the compaction is interrupted, the registry file,
+    /// the journal and the fragments still hold every entry, and the next compaction
+    /// completes it. A missing journal has no entries. The temporary files of fragments
+    /// whose write was interrupted, e.g. by a crash, are deleted once a minute old.
+    ///
+    /// # Errors
+    /// Returns an error if the registry, the journal or the fragments cannot be read or
+    /// written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use unique_uuid::registry::{journal_path, Registry};
+    /// # let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/journal");
+    ///
+    /// let directory = std::env::temp_dir().join("unique-uuid-doc-compact");
+    /// fs::create_dir_all(&directory).unwrap();
+    /// let path = directory.join("types.toml");
+    /// fs::copy(fixtures.join("types.toml"), &path).unwrap();
+    /// fs::copy(fixtures.join("types.toml.journal"), journal_path(&path)).unwrap();
+    ///
+    /// let loaded = Registry::load(&path).unwrap();
+    /// assert_eq!(Registry::compact(&path).unwrap(), 2);
+    ///
+    /// // The entries moved from the journal to the registry file
+    /// assert_eq!(fs::read_to_string(journal_path(&path)).unwrap(), "");
+    /// assert_eq!(Registry::load(&path).unwrap(), loaded);
+    /// assert_eq!(
+    ///     fs::read_to_string(&path).unwrap(),
+    ///     fs::read_to_string(fixtures.join("compacted.toml")).unwrap(),
+    /// );
+    /// ```
+    ///
+    /// A compaction interrupted after writing the registry file leaves entries in both
+    /// places, which loads the same and is completed by the next compaction:
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use unique_uuid::registry::{journal_path, Registry};
+    /// # let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/journal");
+    ///
+    /// let directory = std::env::temp_dir().join("unique-uuid-doc-compact-interrupted");
+    /// fs::create_dir_all(&directory).unwrap();
+    /// let path = directory.join("types.toml");
+    /// fs::copy(fixtures.join("compacted.toml"), &path).unwrap();
+    /// fs::copy(fixtures.join("types.toml.journal"), journal_path(&path)).unwrap();
+    /// // A temporary file left by an interrupted write is ignored and replaced
+    /// fs::write(directory.join("types.toml.tmp"), "[unique_tags]\n\"orders.").unwrap();
+    ///
+    /// let expected = Registry::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+    /// assert_eq!(Registry::load(&path).unwrap(), expected);
+    /// assert_eq!(Registry::compact(&path).unwrap(), 0);
+    /// assert_eq!(fs::read_to_string(journal_path(&path)).unwrap(), "");
+    /// assert_eq!(
+    ///     fs::read_to_string(&path).unwrap(),
+    ///     fs::read_to_string(fixtures.join("compacted.toml")).unwrap(),
+    /// );
+    /// ```
+    pub fn compact(path: impl AsRef<Path>) -> Result<usize, Error> {
+        unique_uuid_core::compact(path)
+    }
+
+    /// Applies `f` to the registry stored at `path` and saves it, unless `f` fails,
+    /// returning the result of `f`. A missing registry has no entries.
+    ///
+    /// The journal the macros append to is locked from the time the registry is loaded
+    /// until it is saved, which makes them wait, so that neither `f` nor the macros lose
+    /// the entries written meanwhile. Its entries are moved into the registry file, as by
+    /// [`Registry::compact`], so that an entry removed or renamed by `f` does not come
+    /// back from the journal.
+    ///
+    /// # Errors
+    /// Returns the error of `f`, or an error if the registry cannot be read or written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::fs;
+    /// use unique_uuid::registry::{journal_path, Error, Registry, Section};
+    /// # let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/journal");
+    ///
+    /// let directory = std::env::temp_dir().join("unique-uuid-doc-update");
+    /// fs::create_dir_all(&directory).unwrap();
+    /// let path = directory.join("types.toml");
+    /// fs::copy(fixtures.join("types.toml"), &path).unwrap();
+    /// fs::copy(fixtures.join("types.toml.journal"), journal_path(&path)).unwrap();
+    ///
+    /// let removed = Registry::update(&path, |registry| {
+    ///     Ok::<_, Error>(registry.remove(Section::UniqueTags, "orders.created").is_some())
+    /// })
+    /// .unwrap();
+    /// assert!(removed);
+    /// assert_eq!(fs::read_to_string(journal_path(&path)).unwrap(), "");
+    /// assert!(Registry::load(&path).unwrap().get("orders.created").is_none());
+    /// ```
+    pub fn update<T, E: From<Error>>(
+        path: impl AsRef<Path>,
+        f: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let path = path.as_ref();
+        unique_uuid_core::update(path, |document| {
+            let mut registry = Self::from_document(std::mem::take(document), Layout::default())
+                .map_err(|message| Error::Parse {
+                    path: Some(path.to_path_buf()),
+                    message,
+                })?;
+            let value = f(&mut registry)?;
+            *document = registry.document();
+            Ok(value)
+        })
+    }
+
+    /// Inserts an entry, returning the entry previously stored under `key`.
+    pub fn insert(
+        &mut self,
+        section: Section,
+        key: impl Into<String>,
+        entry: Entry,
+    ) -> Option<Entry> {
+        let key = key.into();
+        self.rejected
+            .retain(|rejected| rejected.section != section || rejected.key != key);
+        self.section_mut(section).insert(key, entry)
+    }
+
+    /// Removes the entry stored under `key`, returning it.
+    pub fn remove(&mut self, section: Section, key: &str) -> Option<Entry> {
+        self.section_mut(section).remove(key)
+    }
+
+    /// Returns the entries of the given section, sorted by key.
+    pub fn entries(&self, section: Section) -> impl Iterator<Item = (&str, &Entry)> {
+        self.section(section)
+            .iter()
+            .map(|(key, entry)| (key.as_str(), entry))
+    }
+
+    /// Returns the string tags of the registry, sorted by key.
+    pub fn tags(&self) -> impl Iterator<Item = (&str, UniqueTag)> {
+        self.entries(Section::UniqueTags)
+            .map(|(key, entry)| (key, entry.tag()))
+    }
+
+    /// Returns the type tags of the registry, sorted by key.
+    pub fn type_tags(&self) -> impl Iterator<Item = (&str, UniqueTag)> {
+        self.entries(Section::UniqueTypeTags)
+            .map(|(key, entry)| (key, entry.tag()))
+    }
+
+    /// Returns the entry stored under `key` in the given section.
+    pub fn entry(&self, section: Section, key: &str) -> Option<&Entry> {
+        self.section(section).get(key)
+    }
+
+    /// Returns the tag stored under `key` with its metadata, looking first in
+    /// `[unique_tags]` and then in `[unique_type_tags]`.
+    ///
+    /// A key stored nowhere resolves to the entry listing it among its `aliases`, whose
+    /// [`TagInfo::key`] is the key of the entry. Must stay in sync with the macros.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::{registry::Registry, TagKind};
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = { crate = "billing", description = "An order was placed", uuid = "2af1216e-7042-406a-a577-c39a7e7c3faa" }
+    ///
+    ///     [unique_type_tags]
+    ///     "::Order" = { uuid = "a8dfa7b5-37f3-43a1-b0c7-acf1a34f84db", aliases = ["::OrderRecord"] }
+    /// "#).unwrap();
+    ///
+    /// let info = registry.get("orders.created").unwrap();
+    /// assert_eq!(info.tag.0.to_string(), "2af1216e-7042-406a-a577-c39a7e7c3faa");
+    /// assert_eq!(info.kind, TagKind::Tag);
+    /// assert_eq!(info.namespace, Some("orders"));
+    /// assert_eq!(info.description, Some("An order was placed"));
+    /// assert_eq!(info.source_crate, Some("billing"));
+    ///
+    /// let alias = registry.get("::OrderRecord").unwrap();
+    /// assert_eq!(alias.key, "::Order");
+    /// assert_eq!(alias.tag, registry.get("::Order").unwrap().tag);
+    /// ```
+    pub fn get(&self, key: &str) -> Option<TagInfo<'_>> {
+        let stored = Section::ALL.into_iter().find_map(|section| {
+            let (key, entry) = self.section(section).get_key_value(key)?;
+            Some(entry.info(key, section))
+        });
+        stored.or_else(|| {
+            Section::ALL.into_iter().find_map(|section| {
+                self.entries(section)
+                    .find(|(_, entry)| entry.aliases().any(|alias| alias == key))
+                    .map(|(key, entry)| entry.info(key, section))
+            })
+        })
+    }
+
+    /// Returns the key under which `tag` is stored, if any.
+    pub fn reverse(&self, tag: UniqueTag) -> Option<&str> {
+        Section::ALL.into_iter().find_map(|section| {
+            self.entries(section)
+                .find(|(_, entry)| entry.tag() == tag)
+                .map(|(key, _)| key)
+        })
+    }
+
+    /// Registers the key of every entry as the name of its tag in the
+    /// [`names`](crate::names) table, so that [`Debug`] output of [`UniqueTag`] shows
+    /// them.
+    ///
+    /// Requires the `named-debug` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::Registry;
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "payments.refunded" = "ce4baeef-bd9c-4932-88be-24eb1033f695"
+    /// "#).unwrap();
+    /// registry.register_names();
+    ///
+    /// let tag = registry.get("payments.refunded").unwrap().tag;
+    /// assert_eq!(
+    ///     format!("{:?}", tag),
+    ///     "payments.refunded (ce4baeef-bd9c-4932-88be-24eb1033f695)"
+    /// );
+    /// ```
+    #[cfg(feature = "named-debug")]
+    pub fn register_names(&self) {
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                crate::names::register_name(entry.tag(), key);
+            }
+        }
+    }
+
+    /// Returns the number of valid entries across all sections.
+    pub fn len(&self) -> usize {
+        self.unique_tags.len() + self.unique_type_tags.len()
+    }
+
+    /// Returns `true` if the registry has no valid entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn section(&self, section: Section) -> &BTreeMap<String, Entry> {
+        match section {
+            Section::UniqueTags => &self.unique_tags,
+            Section::UniqueTypeTags => &self.unique_type_tags,
+        }
+    }
+
+    fn section_mut(&mut self, section: Section) -> &mut BTreeMap<String, Entry> {
+        match section {
+            Section::UniqueTags => &mut self.unique_tags,
+            Section::UniqueTypeTags => &mut self.unique_type_tags,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Removal of stale registry entries.
+use std::collections::HashSet;
+
+use super::{Entry, Registry, Section};
+
+/// An entry removed by [`Registry::prune`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunedEntry {
+    /// The section that held the entry.
+    pub section: Section,
+
+    /// The key of the entry.
+    pub key: String,
+
+    /// The removed entry.
+    pub entry: Entry,
+}
+
+impl Registry {
+    /// Removes every entry whose key (or one of whose aliases) is not in `keep`,
+    /// returning the removed entries so they can be archived.
+    ///
+    /// Reserved and pinned entries are never removed, and neither are malformed
+    /// entries, nor the ancestors of a kept entry through their `parent` fields, so that
+    /// pruning never leaves a dangling parent. The pruned registry still has to be
+    /// written back, e.g. with [`Registry::save`].
+    ///
+    /// `keep` is typically built from a [`UsageLog`](super::UsageLog) with
+    /// [`UsageLog::keys`](super::UsageLog::keys).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    /// use unique_uuid::registry::{Registry, Section};
+    ///
+    /// let mut registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "orders.cancelled" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+    ///     "orders.v2" = { uuid = "ce4baeef-bd9c-4932-88be-24eb1033f695", reserved = true }
+    ///     "orders.fixed" = { uuid = "a3e7c969-8413-4f6b-9928-28a140227b0b", pinned = true }
+    ///
+    ///     [unique_type_tags]
+    ///     "::Order" = { uuid = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50", aliases = ["::OrderRecord"] }
+    ///     "::Invoice" = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb"
+    /// "#).unwrap();
+    ///
+    /// let keep: HashSet<String> = ["orders.created", "::OrderRecord"]
+    ///     .into_iter()
+    ///     .map(String::from)
+    ///     .collect();
+    /// let pruned = registry.prune(&keep);
+    ///
+    /// let pruned: Vec<_> = pruned.iter().map(|p| (p.section, p.key.as_str())).collect();
+    /// assert_eq!(
+    ///     pruned,
+    ///     [(Section::UniqueTags, "orders.cancelled"), (Section::UniqueTypeTags, "::Invoice")]
+    /// );
+    /// assert!(registry.get("orders.v2").is_some());
+    /// assert!(registry.get("orders.fixed").is_some());
+    /// assert!(registry.get("::Order").is_some());
+    /// ```
+    pub fn prune(&mut self, keep: &HashSet<String>) -> Vec<PrunedEntry> {
+        let mut pruned = Vec::new();
+        for section in Section::ALL {
+            let live = self.live_keys(section, |key| keep.contains(key));
+            let stale: Vec<String> = self
+                .entries(section)
+                .filter(|(key, entry)| {
+                    !entry.is_reserved() && !entry.is_pinned() && !live.contains(key)
+                })
+                .map(|(key, _)| key.to_string())
+                .collect();
+
+            for key in stale {
+                if let Some(entry) = self.remove(section, &key) {
+                    pruned.push(PrunedEntry {
+                        section,
+                        key,
+                        entry,
+                    });
+                }
+            }
+        }
+        pruned
+    }
+
+    /// Returns the keys of `section` in use: those for which `used` holds for the key or
+    /// one of its aliases, and their ancestors through their `parent` fields.
+    pub(crate) fn live_keys(&self, section: Section, used: impl Fn(&str) -> bool) -> HashSet<&str> {
+        let mut live = HashSet::new();
+        for (key, entry) in self.entries(section) {
+            if !used(key) && !entry.aliases().any(&used) {
+                continue;
+            }
+            let mut key = Some(key);
+            // Stops at a key already visited, so that parent cycles terminate
+            while let Some(current) = key.filter(|current| live.insert(*current)) {
+                key = self.entry(section, current).and_then(Entry::parent);
+            }
+        }
+        live
+    }
+}
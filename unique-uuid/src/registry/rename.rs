@@ -0,0 +1,82 @@
+//! Renaming of registry entries.
+use std::fmt;
+
+use super::{Registry, Section};
+
+/// An error that occurred while renaming a registry entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenameError {
+    /// No entry is stored under the old key.
+    MissingKey(String),
+
+    /// The section already holds an entry under the new key.
+    KeyExists {
+        /// The section holding both keys.
+        section: Section,
+        /// The new key.
+        key: String,
+    },
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::MissingKey(key) => write!(f, "no entry is stored under `{}`", key),
+            RenameError::KeyExists { section, key } => {
+                write!(f, "[{}] already holds an entry under `{}`", section, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+impl Registry {
+    /// Moves the entry stored under `old` to `new`, keeping its UUID and metadata.
+    ///
+    /// The entry is looked up first in `[unique_tags]` and then in
+    /// `[unique_type_tags]`, and stays in its section. Returns the section of the
+    /// renamed entry.
+    ///
+    /// # Errors
+    /// Returns [`RenameError::MissingKey`] if no entry is stored under `old`, and
+    /// [`RenameError::KeyExists`] if its section already holds an entry under `new`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Registry, RenameError, Section};
+    ///
+    /// let mut registry = Registry::parse(r#"
+    ///     [unique_type_tags]
+    ///     "::OrderRecord" = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50"
+    ///     "::Invoice" = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb"
+    /// "#).unwrap();
+    /// let tag = registry.get("::OrderRecord").unwrap().tag;
+    ///
+    /// assert_eq!(registry.rename("::OrderRecord", "::Order"), Ok(Section::UniqueTypeTags));
+    /// assert_eq!(registry.get("::Order").map(|info| info.tag), Some(tag));
+    /// assert!(registry.get("::OrderRecord").is_none());
+    ///
+    /// assert!(matches!(
+    ///     registry.rename("::Order", "::Invoice"),
+    ///     Err(RenameError::KeyExists { .. })
+    /// ));
+    /// ```
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<Section, RenameError> {
+        let section = Section::ALL
+            .into_iter()
+            .find(|section| self.entry(*section, old).is_some())
+            .ok_or_else(|| RenameError::MissingKey(old.to_string()))?;
+        if self.entry(section, new).is_some() {
+            return Err(RenameError::KeyExists {
+                section,
+                key: new.to_string(),
+            });
+        }
+
+        let entry = self.remove(section, old).expect("entry was found above");
+        self.insert(section, new, entry);
+        Ok(section)
+    }
+}
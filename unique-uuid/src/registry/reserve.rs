@@ -0,0 +1,192 @@
+//! Entries reserved for future use, which the macros refuse to claim.
+use std::fmt;
+
+use unique_uuid_core::new_uuid;
+
+use super::{Entry, Registry, Section};
+use crate::UniqueTag;
+
+/// A reserved entry of a registry, see [`Registry::reservations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reservation {
+    /// The section of the entry.
+    pub section: Section,
+
+    /// The key of the entry.
+    pub key: String,
+
+    /// The tag pre-allocated for the key.
+    pub tag: UniqueTag,
+
+    /// The owner of the reservation, to contact to lift it (the `owner` field).
+    pub owner: Option<String>,
+}
+
+impl fmt::Display for Reservation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "reserved: `{}` in [{}] ({})",
+            self.key, self.section, self.tag.0
+        )?;
+        if let Some(owner) = &self.owner {
+            write!(f, ", owned by `{}`", owner)?;
+        }
+        Ok(())
+    }
+}
+
+/// An error that occurred while reserving an entry or lifting its reservation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReservationError {
+    /// The key to reserve has an entry that is not reserved, i.e. is already in use.
+    InUse {
+        /// The section of the entry.
+        section: Section,
+        /// The key of the entry.
+        key: String,
+    },
+
+    /// The key whose reservation to lift has no reserved entry.
+    NotReserved(String),
+}
+
+impl fmt::Display for ReservationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReservationError::InUse { section, key } => write!(
+                f,
+                "`{}` cannot be reserved: [{}] already has an entry for it",
+                key, section
+            ),
+            ReservationError::NotReserved(key) => {
+                write!(f, "no reserved entry is stored under `{}`", key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReservationError {}
+
+impl Registry {
+    /// Reserves `key` in `section`: a new entry is minted with `reserved = true`,
+    /// pre-allocating its UUID for future use, and `owner` as the person or team to
+    /// contact to lift the reservation. The macros fail to resolve a reserved key until
+    /// the reservation is lifted with [`Registry::lift_reservation`].
+    ///
+    /// Reserving a reserved key keeps its UUID, and updates its owner. Returns the tag of
+    /// the entry.
+    ///
+    /// # Errors
+    /// Returns [`ReservationError::InUse`] if `key` has an entry that is not reserved.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Registry, ReservationError, Section};
+    ///
+    /// let mut registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "handshake.v1" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    /// "#).unwrap();
+    ///
+    /// let tag = registry
+    ///     .reserve(Section::UniqueTags, "handshake.v2", Some("protocol-team"))
+    ///     .unwrap();
+    /// let entry = registry.entry(Section::UniqueTags, "handshake.v2").unwrap();
+    /// assert!(entry.is_reserved());
+    /// assert_eq!(entry.owner(), Some("protocol-team"));
+    /// assert_eq!(entry.tag(), tag);
+    ///
+    /// assert!(matches!(
+    ///     registry.reserve(Section::UniqueTags, "handshake.v1", None),
+    ///     Err(ReservationError::InUse { .. })
+    /// ));
+    /// ```
+    pub fn reserve(
+        &mut self,
+        section: Section,
+        key: &str,
+        owner: Option<&str>,
+    ) -> Result<UniqueTag, ReservationError> {
+        let entry = match self.entry(section, key) {
+            Some(entry) if entry.is_reserved() => entry.clone(),
+            Some(_) => {
+                return Err(ReservationError::InUse {
+                    section,
+                    key: key.to_string(),
+                })
+            }
+            None => Entry::minted(new_uuid(&self.config())).flagged("reserved"),
+        };
+        let entry = match owner {
+            Some(owner) => entry.with_field("owner", toml::Value::String(owner.to_string())),
+            None => entry,
+        };
+        let tag = entry.tag();
+        self.insert(section, key, entry);
+        Ok(tag)
+    }
+
+    /// Lifts the reservation of `key`, removing `reserved = true` and the owner from its
+    /// entry, so that the macros may claim it with its pre-allocated UUID.
+    ///
+    /// The entry is looked up first in `[unique_tags]` and then in
+    /// `[unique_type_tags]`. Returns the section of the entry.
+    ///
+    /// # Errors
+    /// Returns [`ReservationError::NotReserved`] if no reserved entry is stored under
+    /// `key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Registry, Section};
+    ///
+    /// let mut registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "handshake.v2" = { uuid = "ce4baeef-bd9c-4932-88be-24eb1033f695", reserved = true }
+    /// "#).unwrap();
+    ///
+    /// assert_eq!(registry.lift_reservation("handshake.v2"), Ok(Section::UniqueTags));
+    /// assert!(!registry.entry(Section::UniqueTags, "handshake.v2").unwrap().is_reserved());
+    /// assert!(registry.lift_reservation("handshake.v2").is_err());
+    /// ```
+    pub fn lift_reservation(&mut self, key: &str) -> Result<Section, ReservationError> {
+        let section = Section::ALL
+            .into_iter()
+            .find(|section| {
+                self.entry(*section, key)
+                    .is_some_and(|entry| entry.is_reserved())
+            })
+            .ok_or_else(|| ReservationError::NotReserved(key.to_string()))?;
+        let entry = self.remove(section, key).expect("entry was found above");
+        self.insert(
+            section,
+            key,
+            entry.without_field("reserved").without_field("owner"),
+        );
+        Ok(section)
+    }
+
+    /// Returns the reserved entries of the registry, `[unique_tags]` first and each
+    /// section in key order.
+    ///
+    /// Reservations are not validation issues, see [`Registry::validate`], but are
+    /// listed alongside them by `unique-uuid-admin verify`.
+    pub fn reservations(&self) -> Vec<Reservation> {
+        Section::ALL
+            .into_iter()
+            .flat_map(|section| {
+                self.entries(section)
+                    .filter(|(_, entry)| entry.is_reserved())
+                    .map(move |(key, entry)| Reservation {
+                        section,
+                        key: key.to_string(),
+                        tag: entry.tag(),
+                        owner: entry.owner().map(str::to_string),
+                    })
+            })
+            .collect()
+    }
+}
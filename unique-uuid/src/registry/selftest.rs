@@ -0,0 +1,34 @@
+//! The check run by the tests generated by `#[unique_type_tag(selftest)]`.
+use super::{Registry, Section};
+use crate::UniqueTag;
+
+/// Asserts that the registry at `path` stores the UUID of `tag` under `key` in
+/// `[unique_type_tags]`, `type_name` being the type `tag` is the `TYPE_TAG` of.
+///
+/// # Panics
+/// Panics if the registry cannot be loaded, if it does not store `key`, or if it stores
+/// another UUID under `key`, e.g. after a hand-edit of the registry.
+#[doc(hidden)]
+pub fn assert_type_tag(path: &str, key: &str, type_name: &str, tag: UniqueTag) {
+    let registry = match Registry::load(path) {
+        Ok(registry) => registry,
+        Err(err) => panic!("cannot load the registry `{}`: {}", path, err),
+    };
+    match registry.entry(Section::UniqueTypeTags, key) {
+        Some(entry) if entry.uuid() == tag.0 => {}
+        Some(entry) => panic!(
+            "the registry `{}` stores {} under `{}`, but `{}::TYPE_TAG` was compiled as {}: \
+             the UUID of a type must never change, restore it in the registry",
+            path,
+            entry.uuid(),
+            key,
+            type_name,
+            tag.0
+        ),
+        None => panic!(
+            "the registry `{}` does not store `{}`, but `{}::TYPE_TAG` was compiled as {}: \
+             restore the entry in the registry",
+            path, key, type_name, tag.0
+        ),
+    }
+}
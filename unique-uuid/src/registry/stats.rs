@@ -0,0 +1,133 @@
+//! Health indicators of a registry, to size cleanups.
+use std::{collections::BTreeMap, collections::BTreeSet, fmt};
+
+use super::{namespace_of, Registry, Section};
+
+/// Health indicators of a registry, returned by [`Registry::stats`].
+///
+/// Only valid entries are counted. The [`Display`](fmt::Display) implementation renders
+/// a short report, one indicator per line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistryStats {
+    /// The number of entries in each section.
+    pub per_section: BTreeMap<Section, usize>,
+
+    /// The number of `[unique_type_tags]` keys in the legacy `"::Type"` form, i.e.
+    /// without a module path.
+    pub legacy_type_keys: usize,
+
+    /// The number of entries recording neither the crate that minted them nor when, e.g.
+    /// entries in the legacy plain form.
+    pub missing_metadata: usize,
+
+    /// The number of entries whose UUID was pinned explicitly (`pinned = true`).
+    pub pinned: usize,
+
+    /// The number of entries per version of their UUID, e.g. `4` for the random UUIDs
+    /// minted by the macros and `5` for name-based UUIDs.
+    pub uuid_versions: BTreeMap<usize, usize>,
+
+    /// The number of distinct namespaces (see [`namespace_of`]), nested namespaces being
+    /// counted on their own.
+    pub namespaces: usize,
+}
+
+impl RegistryStats {
+    /// Returns the total number of entries.
+    pub fn entries(&self) -> usize {
+        self.per_section.values().sum()
+    }
+}
+
+impl fmt::Display for RegistryStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections = Section::ALL
+            .iter()
+            .map(|section| {
+                let count = self.per_section.get(section).copied().unwrap_or_default();
+                format!("{} in [{}]", count, section)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(f, "entries: {} ({})", self.entries(), sections)?;
+        writeln!(f, "legacy `::Type` keys: {}", self.legacy_type_keys)?;
+        writeln!(f, "missing metadata: {}", self.missing_metadata)?;
+        writeln!(f, "pinned: {}", self.pinned)?;
+        let versions = self
+            .uuid_versions
+            .iter()
+            .map(|(version, count)| format!("v{}: {}", version, count))
+            .collect::<Vec<_>>();
+        if versions.is_empty() {
+            writeln!(f, "UUID versions: -")?;
+        } else {
+            writeln!(f, "UUID versions: {}", versions.join(", "))?;
+        }
+        write!(f, "namespaces: {}", self.namespaces)
+    }
+}
+
+impl Registry {
+    /// Returns health indicators of the registry: entry counts, legacy keys, missing
+    /// metadata, UUID versions and namespaces.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Registry, Section};
+    ///
+    /// # let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/stats/registry.toml");
+    /// let stats = Registry::load(path).unwrap().stats();
+    ///
+    /// assert_eq!(stats.entries(), 8);
+    /// assert_eq!(stats.per_section[&Section::UniqueTags], 5);
+    /// assert_eq!(stats.per_section[&Section::UniqueTypeTags], 3);
+    /// assert_eq!(stats.legacy_type_keys, 2);
+    /// assert_eq!(stats.missing_metadata, 4);
+    /// assert_eq!(stats.pinned, 2);
+    /// assert_eq!(stats.uuid_versions[&4], 6);
+    /// assert_eq!(stats.uuid_versions[&5], 2);
+    /// assert_eq!(stats.namespaces, 4);
+    ///
+    /// assert_eq!(
+    ///     stats.to_string(),
+    ///     "entries: 8 (5 in [unique_tags], 3 in [unique_type_tags])\n\
+    ///      legacy `::Type` keys: 2\n\
+    ///      missing metadata: 4\n\
+    ///      pinned: 2\n\
+    ///      UUID versions: v4: 6, v5: 2\n\
+    ///      namespaces: 4"
+    /// );
+    /// ```
+    pub fn stats(&self) -> RegistryStats {
+        let mut stats = RegistryStats::default();
+        let mut namespaces = BTreeSet::new();
+
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                *stats.per_section.entry(section).or_default() += 1;
+                if section == Section::UniqueTypeTags
+                    && key
+                        .strip_prefix("::")
+                        .is_some_and(|name| !name.contains("::"))
+                {
+                    stats.legacy_type_keys += 1;
+                }
+                if entry.crate_name().is_none() && entry.created().is_none() {
+                    stats.missing_metadata += 1;
+                }
+                if entry.is_pinned() {
+                    stats.pinned += 1;
+                }
+                *stats
+                    .uuid_versions
+                    .entry(entry.uuid().get_version_num())
+                    .or_default() += 1;
+                namespaces.extend(namespace_of(key));
+            }
+        }
+
+        stats.namespaces = namespaces.len();
+        stats
+    }
+}
@@ -0,0 +1,114 @@
+//! Aggregated statistics of a registry.
+use std::collections::BTreeMap;
+
+use super::{namespace_of, Registry, Section};
+
+/// Entry counts of a registry, returned by [`Registry::summary`].
+///
+/// Only valid entries are counted; aliases are counted separately and never as extra
+/// entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistrySummary {
+    /// The number of valid entries.
+    pub entries: usize,
+
+    /// The number of entries in the legacy plain form.
+    pub legacy: usize,
+
+    /// The number of entries in the structured form.
+    pub structured: usize,
+
+    /// The number of aliases, across all entries.
+    pub aliases: usize,
+
+    /// The number of malformed entries, which are not counted anywhere else.
+    pub malformed: usize,
+
+    /// The number of entries in each section.
+    pub per_section: BTreeMap<Section, usize>,
+
+    /// The number of entries in each namespace (see [`namespace_of`]), with `None` for
+    /// the entries without a namespace. Nested namespaces are counted on their own.
+    pub per_namespace: BTreeMap<Option<String>, usize>,
+
+    /// The number of entries minted by each crate, with `None` for the entries that do
+    /// not record their crate.
+    pub per_crate: BTreeMap<Option<String>, usize>,
+
+    /// The earliest `created` timestamp, if any entry records one.
+    pub oldest: Option<String>,
+
+    /// The latest `created` timestamp, if any entry records one.
+    pub newest: Option<String>,
+}
+
+impl Registry {
+    /// Returns entry counts per section, namespace and minting crate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{Registry, Section};
+    ///
+    /// # let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/summary/registry.toml");
+    /// let summary = Registry::load(path).unwrap().summary();
+    ///
+    /// assert_eq!(summary.entries, 6);
+    /// assert_eq!((summary.legacy, summary.structured), (2, 4));
+    /// assert_eq!(summary.aliases, 3);
+    /// assert_eq!(summary.malformed, 1);
+    ///
+    /// assert_eq!(summary.per_section[&Section::UniqueTags], 4);
+    /// assert_eq!(summary.per_section[&Section::UniqueTypeTags], 2);
+    ///
+    /// assert_eq!(summary.per_namespace[&Some("billing".to_string())], 2);
+    /// assert_eq!(summary.per_namespace[&Some("::shop".to_string())], 1);
+    /// assert_eq!(summary.per_namespace[&None], 2);
+    ///
+    /// assert_eq!(summary.per_crate[&Some("billing-core".to_string())], 2);
+    /// assert_eq!(summary.per_crate[&None], 3);
+    ///
+    /// assert_eq!(summary.oldest.as_deref(), Some("2023-11-30T23:59:59Z"));
+    /// assert_eq!(summary.newest.as_deref(), Some("2024-09-12T08:30:00Z"));
+    /// ```
+    pub fn summary(&self) -> RegistrySummary {
+        let mut summary = RegistrySummary {
+            malformed: self.rejected.len(),
+            ..RegistrySummary::default()
+        };
+
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                summary.entries += 1;
+                if entry.is_structured() {
+                    summary.structured += 1;
+                } else {
+                    summary.legacy += 1;
+                }
+                summary.aliases += entry.aliases().count();
+
+                *summary.per_section.entry(section).or_default() += 1;
+                *summary
+                    .per_namespace
+                    .entry(namespace_of(key).map(str::to_string))
+                    .or_default() += 1;
+                *summary
+                    .per_crate
+                    .entry(entry.crate_name().map(str::to_string))
+                    .or_default() += 1;
+
+                // Timestamps are all RFC 3339 in UTC, which sort lexicographically
+                if let Some(created) = entry.created() {
+                    if summary.oldest.as_deref().is_none_or(|oldest| created < oldest) {
+                        summary.oldest = Some(created.to_string());
+                    }
+                    if summary.newest.as_deref().is_none_or(|newest| created > newest) {
+                        summary.newest = Some(created.to_string());
+                    }
+                }
+            }
+        }
+
+        summary
+    }
+}
@@ -0,0 +1,102 @@
+//! The type tags registered in the running binary by the derive.
+//!
+//! With the `linkme` feature, `#[derive(UniqueTypeTag)]` registers every non-generic type
+//! it is applied to in a [`linkme`] distributed slice, whatever the crate the type is
+//! defined in. The registrations are collected by the linker, so they are available
+//! without any initialization code, and without reading the registry file.
+use std::sync::OnceLock;
+
+use super::namespace_of;
+use crate::{TagInfo, TagKind, UniqueTag};
+
+/// A type registered by `#[derive(UniqueTypeTag)]`, see [`iter_type_tags`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeTagEntry {
+    /// The tag of the type.
+    pub tag: UniqueTag,
+
+    /// The key of the type in the registry, e.g. `::Order`.
+    pub key: &'static str,
+
+    /// The path of the type, e.g. `shop::orders::Order`.
+    pub type_name: &'static str,
+
+    /// The name of the crate defining the type.
+    pub crate_name: &'static str,
+}
+
+impl From<&TypeTagEntry> for TagInfo<'static> {
+    fn from(entry: &TypeTagEntry) -> Self {
+        TagInfo {
+            tag: entry.tag,
+            key: entry.key,
+            kind: TagKind::TypeTag,
+            namespace: namespace_of(entry.key),
+            description: None,
+            source_crate: Some(entry.crate_name),
+        }
+    }
+}
+
+/// The registrations of the derive, in link order.
+#[doc(hidden)]
+#[linkme::distributed_slice]
+pub static TYPE_TAGS: [TypeTagEntry];
+
+/// Returns every type registered by `#[derive(UniqueTypeTag)]` in the running binary, in
+/// no particular order.
+///
+/// This function is only available with the `linkme` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{registry::iter_type_tags, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Shipment;
+///
+/// let entry = iter_type_tags()
+///     .find(|entry| entry.tag == Shipment::TYPE_TAG)
+///     .unwrap();
+/// assert_eq!(entry.key, "::Shipment");
+/// assert!(entry.type_name.ends_with("::Shipment"));
+/// ```
+pub fn iter_type_tags() -> impl Iterator<Item = &'static TypeTagEntry> {
+    TYPE_TAGS.iter()
+}
+
+/// Returns the type registered with `tag` in the running binary, if any.
+///
+/// The registrations are sorted by tag on the first call, so that lookups take a
+/// logarithmic time. If several types share a tag, e.g. types of different crates
+/// resolved against different registries, any of them is returned.
+///
+/// This function is only available with the `linkme` feature.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{registry::find_by_tag, unique_tag, TagKind, UniqueTypeTag};
+///
+/// #[derive(UniqueTypeTag)]
+/// struct Parcel;
+///
+/// let info = find_by_tag(Parcel::TYPE_TAG).unwrap();
+/// assert_eq!(info.key, "::Parcel");
+/// assert_eq!(info.kind, TagKind::TypeTag);
+/// assert_eq!(info.source_crate, Some("unique-uuid"));
+/// assert!(find_by_tag(unique_tag!("parcels.sent")).is_none());
+/// ```
+pub fn find_by_tag(tag: UniqueTag) -> Option<TagInfo<'static>> {
+    static INDEX: OnceLock<Vec<&'static TypeTagEntry>> = OnceLock::new();
+    let index = INDEX.get_or_init(|| {
+        let mut index: Vec<_> = TYPE_TAGS.iter().collect();
+        index.sort_by_key(|entry| entry.tag);
+        index
+    });
+    index
+        .binary_search_by_key(&tag, |entry| entry.tag)
+        .ok()
+        .map(|position| index[position].into())
+}
@@ -0,0 +1,106 @@
+//! Reading the usage log written by the macros.
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs,
+    path::Path,
+};
+
+use super::{Error, Registry, Section};
+
+/// The set of registry keys referenced by a build, as recorded by the macros.
+///
+/// When the `UNIQUE_UUID_TRACK_USAGE` environment variable is set to a path during a
+/// build, every macro expansion records the key it resolved in that file, one
+/// `<section>\t<key>` line per key. After a clean workspace build, the log therefore
+/// lists every live entry, and [`Registry::unused_entries`] reports the others.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::registry::{Registry, Section, UsageLog};
+///
+/// let registry = Registry::parse(r#"
+///     [unique_tags]
+///     "orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+///     "orders.legacy" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+///
+///     [unique_type_tags]
+///     "::Order" = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50"
+/// "#).unwrap();
+/// let usage = UsageLog::parse("unique_tags\torders.created\nunique_type_tags\t::Order\n");
+///
+/// assert!(usage.contains(Section::UniqueTypeTags, "::Order"));
+/// assert_eq!(
+///     registry.unused_entries(&usage),
+///     [(Section::UniqueTags, "orders.legacy")]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageLog {
+    entries: BTreeSet<(Section, String)>,
+}
+
+impl UsageLog {
+    /// Loads the usage log stored at `path`.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Self::parse(&contents))
+    }
+
+    /// Parses the content of a usage log. Lines that do not name a known section are
+    /// ignored.
+    pub fn parse(contents: &str) -> Self {
+        let entries = contents
+            .lines()
+            .filter_map(|line| {
+                let (section, key) = line.split_once('\t')?;
+                let section = Section::ALL.into_iter().find(|s| s.name() == section)?;
+                Some((section, key.to_string()))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Returns `true` if the log records a use of `key` in the given section.
+    pub fn contains(&self, section: Section, key: &str) -> bool {
+        self.entries.contains(&(section, key.to_string()))
+    }
+
+    /// Returns the used entries, sorted by section and key.
+    pub fn entries(&self) -> impl Iterator<Item = (Section, &str)> {
+        self.entries
+            .iter()
+            .map(|(section, key)| (*section, key.as_str()))
+    }
+
+    /// Returns the used keys, regardless of their section.
+    pub fn keys(&self) -> HashSet<String> {
+        self.entries.iter().map(|(_, key)| key.clone()).collect()
+    }
+}
+
+impl Registry {
+    /// Returns the entries of the registry that are not recorded in `usage`, sorted by
+    /// section and key.
+    ///
+    /// As for [`Registry::prune`], an entry is used if `usage` records its key or one of
+    /// its aliases, or if it is the ancestor of a used entry through `parent` fields.
+    pub fn unused_entries(&self, usage: &UsageLog) -> Vec<(Section, &str)> {
+        Section::ALL
+            .into_iter()
+            .flat_map(|section| {
+                let live = self.live_keys(section, |key| usage.contains(section, key));
+                self.entries(section)
+                    .filter(move |(key, _)| !live.contains(key))
+                    .map(move |(key, _)| (section, key))
+            })
+            .collect()
+    }
+}
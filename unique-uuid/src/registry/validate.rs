@@ -0,0 +1,403 @@
+//! Sanity checks over the content of a registry.
+use std::{collections::BTreeMap, fmt};
+
+use unique_uuid_core::fold_key;
+use uuid::Uuid;
+
+use super::{MetaValue, Registry, Section};
+
+impl Registry {
+    /// Checks the registry for problems, returning every issue found.
+    ///
+    /// The following issues are detected:
+    /// - the same UUID used by several entries, in the same or in different sections
+    ///   ([`Severity::Error`])
+    /// - the same `id` used by several entries ([`Severity::Error`])
+    /// - entries whose value is not a valid UUID or structured entry
+    ///   ([`Severity::Error`])
+    /// - empty keys ([`Severity::Error`])
+    /// - keys of the same section that only differ by case or Unicode normalization
+    ///   ([`Severity::Warning`])
+    /// - keys present in both sections, which makes [`Registry::get`] ambiguous
+    ///   ([`Severity::Warning`])
+    /// - entries minted by a crate (their `crate` field) whose UUID does not start with
+    ///   the prefix reserved for that crate, see [`Registry::prefixes`] ([`Severity::Error`])
+    /// - `[unique_tags]` keys not matching the `tag_pattern` setting, see
+    ///   [`Registry::tag_pattern`] ([`Severity::Warning`])
+    /// - `[unique_tags]` entries whose `parent` is not a `[unique_tags]` key
+    ///   ([`Severity::Error`])
+    /// - cycles of `parent` fields, e.g. an entry being its own grandparent
+    ///   ([`Severity::Error`])
+    /// - entries sharing a `meta` value whose name is in the `unique_meta` setting, see
+    ///   [`Registry::unique_meta`] ([`Severity::Error`])
+    /// - entries with distinct UUIDs sharing a 64-bit projection, see
+    ///   [`UniqueTag::to_u64`](crate::UniqueTag::to_u64) ([`Severity::Error`])
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use unique_uuid::registry::{IssueKind, Registry, Severity};
+    ///
+    /// let registry = Registry::parse(r#"
+    ///     [unique_tags]
+    ///     "my_tag" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    ///     "My_Tag" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+    ///     "broken" = "not-a-uuid"
+    /// "#).unwrap();
+    ///
+    /// let issues = registry.validate();
+    /// assert_eq!(issues.len(), 2);
+    /// assert_eq!(issues[0].kind, IssueKind::MalformedEntry);
+    /// assert_eq!(issues[0].severity, Severity::Error);
+    /// assert_eq!(issues[1].kind, IssueKind::EquivalentKeys);
+    /// assert_eq!(issues[1].severity, Severity::Warning);
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        for rejected in &self.rejected {
+            issues.push(ValidationIssue {
+                severity: Severity::Error,
+                kind: IssueKind::MalformedEntry,
+                entries: vec![(rejected.section, rejected.key.clone())],
+                description: format!(
+                    "entry `{}` in [{}] is malformed ({}): {}",
+                    rejected.key, rejected.section, rejected.value, rejected.message
+                ),
+            });
+        }
+
+        for section in Section::ALL {
+            if self.entry(section, "").is_some() {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::EmptyKey,
+                    entries: vec![(section, String::new())],
+                    description: format!("[{}] contains an entry with an empty key", section),
+                });
+            }
+        }
+
+        let mut by_uuid: BTreeMap<Uuid, Vec<(Section, String)>> = BTreeMap::new();
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                by_uuid
+                    .entry(entry.uuid())
+                    .or_default()
+                    .push((section, key.to_string()));
+            }
+        }
+        for (uuid, entries) in by_uuid {
+            if entries.len() > 1 {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::DuplicateUuid,
+                    description: format!(
+                        "UUID {} is used by several entries: {}",
+                        uuid,
+                        describe_entries(&entries)
+                    ),
+                    entries,
+                });
+            }
+        }
+
+        let mut by_id: BTreeMap<u32, Vec<(Section, String)>> = BTreeMap::new();
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                if let Some(id) = entry.id() {
+                    by_id
+                        .entry(id)
+                        .or_default()
+                        .push((section, key.to_string()));
+                }
+            }
+        }
+        for (id, entries) in by_id {
+            if entries.len() > 1 {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::DuplicateId,
+                    description: format!(
+                        "id {} is used by several entries: {}",
+                        id,
+                        describe_entries(&entries)
+                    ),
+                    entries,
+                });
+            }
+        }
+
+        for section in Section::ALL {
+            let mut by_folded_key: BTreeMap<String, Vec<(Section, String)>> = BTreeMap::new();
+            for (key, _) in self.entries(section) {
+                by_folded_key
+                    .entry(fold_key(key))
+                    .or_default()
+                    .push((section, key.to_string()));
+            }
+            for entries in by_folded_key.into_values() {
+                if entries.len() > 1 {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Warning,
+                        kind: IssueKind::EquivalentKeys,
+                        description: format!(
+                            "keys only differ by case or Unicode normalization: {}",
+                            describe_entries(&entries)
+                        ),
+                        entries,
+                    });
+                }
+            }
+        }
+
+        for (key, _) in self.entries(Section::UniqueTags) {
+            if self.entry(Section::UniqueTypeTags, key).is_some() {
+                let entries = vec![
+                    (Section::UniqueTags, key.to_string()),
+                    (Section::UniqueTypeTags, key.to_string()),
+                ];
+                issues.push(ValidationIssue {
+                    severity: Severity::Warning,
+                    kind: IssueKind::KeyInBothSections,
+                    description: format!(
+                        "key `{}` is present in both sections: {}",
+                        key,
+                        describe_entries(&entries)
+                    ),
+                    entries,
+                });
+            }
+        }
+
+        let prefixes = self.prefixes();
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                let Some((crate_name, prefix)) = entry
+                    .crate_name()
+                    .and_then(|name| prefixes.get_key_value(name))
+                else {
+                    continue;
+                };
+                if !entry.uuid().as_bytes().starts_with(prefix) {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        kind: IssueKind::PrefixMismatch,
+                        entries: vec![(section, key.to_string())],
+                        description: format!(
+                            "entry `{}` in [{}] was minted by `{}`, but its UUID {} does \
+                             not start with the prefix reserved for that crate",
+                            key,
+                            section,
+                            crate_name,
+                            entry.uuid()
+                        ),
+                    });
+                }
+            }
+        }
+
+        if let Some(pattern) = self.config().tag_pattern() {
+            for (key, _) in self.entries(Section::UniqueTags) {
+                if !pattern.is_match(key) {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Warning,
+                        kind: IssueKind::PatternMismatch,
+                        entries: vec![(Section::UniqueTags, key.to_string())],
+                        description: format!(
+                            "key `{}` does not match the `tag_pattern` of the registry, `{}`",
+                            key, pattern
+                        ),
+                    });
+                }
+            }
+        }
+
+        for (key, entry) in self.entries(Section::UniqueTags) {
+            let Some(parent) = entry.parent() else {
+                continue;
+            };
+            if self.entry(Section::UniqueTags, parent).is_none() {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::DanglingParent,
+                    entries: vec![(Section::UniqueTags, key.to_string())],
+                    description: format!(
+                        "the parent of `{}` in [unique_tags], `{}`, is not in [unique_tags]",
+                        key, parent
+                    ),
+                });
+            }
+        }
+
+        for cycle in self.parent_cycles() {
+            let entries: Vec<_> = cycle
+                .into_iter()
+                .map(|key| (Section::UniqueTags, key))
+                .collect();
+            issues.push(ValidationIssue {
+                severity: Severity::Error,
+                kind: IssueKind::ParentCycle,
+                description: format!(
+                    "entries are their own ancestors through their `parent` fields: {}",
+                    describe_entries(&entries)
+                ),
+                entries,
+            });
+        }
+
+        for name in self.unique_meta() {
+            let mut by_value: BTreeMap<MetaValue, Vec<(Section, String)>> = BTreeMap::new();
+            for section in Section::ALL {
+                for (key, entry) in self.entries(section) {
+                    if let Some(value) = entry.meta(name) {
+                        by_value
+                            .entry(value)
+                            .or_default()
+                            .push((section, key.to_string()));
+                    }
+                }
+            }
+            for (value, entries) in by_value {
+                if entries.len() > 1 {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        kind: IssueKind::DuplicateMeta,
+                        description: format!(
+                            "`meta.{}` = {} is used by several entries, but `{}` is in the \
+                             `unique_meta` setting of the registry: {}",
+                            name,
+                            value,
+                            name,
+                            describe_entries(&entries)
+                        ),
+                        entries,
+                    });
+                }
+            }
+        }
+
+        let mut by_tag_64: BTreeMap<u64, BTreeMap<Uuid, Vec<(Section, String)>>> =
+            BTreeMap::new();
+        for section in Section::ALL {
+            for (key, entry) in self.entries(section) {
+                by_tag_64
+                    .entry(entry.tag().to_u64())
+                    .or_default()
+                    .entry(entry.uuid())
+                    .or_default()
+                    .push((section, key.to_string()));
+            }
+        }
+        for (tag_64, by_uuid) in by_tag_64 {
+            // Entries sharing their UUID are reported as such
+            if by_uuid.len() > 1 {
+                let entries: Vec<_> = by_uuid.into_values().flatten().collect();
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::Tag64Collision,
+                    description: format!(
+                        "the 64-bit projection {:#018x} is shared by entries with distinct \
+                         UUIDs: {}",
+                        tag_64,
+                        describe_entries(&entries)
+                    ),
+                    entries,
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+/// The severity of a [`ValidationIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    /// The registry is usable, but the issue likely reveals a mistake.
+    Warning,
+
+    /// The registry is inconsistent and should be fixed.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// The class of a [`ValidationIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IssueKind {
+    /// Several entries share the same UUID.
+    DuplicateUuid,
+
+    /// Several entries share the same `id`.
+    DuplicateId,
+
+    /// Several keys of the same section only differ by case or Unicode normalization.
+    EquivalentKeys,
+
+    /// An entry is neither a valid UUID string nor a valid structured entry.
+    MalformedEntry,
+
+    /// An entry has an empty key.
+    EmptyKey,
+
+    /// The same key is present in both sections.
+    KeyInBothSections,
+
+    /// The UUID of an entry does not start with the prefix reserved for its crate.
+    PrefixMismatch,
+
+    /// A `[unique_tags]` key does not match the `tag_pattern` setting.
+    PatternMismatch,
+
+    /// The `parent` of an entry is not a `[unique_tags]` key.
+    DanglingParent,
+
+    /// Entries are their own ancestors through their `parent` fields.
+    ParentCycle,
+
+    /// Several entries share the same value of a `meta` name listed in the `unique_meta`
+    /// setting.
+    DuplicateMeta,
+
+    /// Entries with distinct UUIDs share the same 64-bit projection, see
+    /// [`UniqueTag::to_u64`](crate::UniqueTag::to_u64).
+    Tag64Collision,
+}
+
+/// A problem found by [`Registry::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// How serious the issue is.
+    pub severity: Severity,
+
+    /// The class of the issue.
+    pub kind: IssueKind,
+
+    /// The entries involved in the issue.
+    pub entries: Vec<(Section, String)>,
+
+    /// A human-readable description of the issue.
+    pub description: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.description)
+    }
+}
+
+fn describe_entries(entries: &[(Section, String)]) -> String {
+    entries
+        .iter()
+        .map(|(section, key)| format!("`{}` in [{}]", key, section))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
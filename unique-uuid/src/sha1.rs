@@ -0,0 +1,106 @@
+//! A minimal SHA-1 implementation usable in `const` context.
+//!
+//! SHA-1 is only used to derive name-based (version 5) UUIDs, as specified by
+//! RFC 9562. It must not be relied upon for any security purpose.
+
+use crate::UniqueTag;
+
+/// Returns the SHA-1 digest of the concatenation of `parts`.
+pub(crate) const fn digest(parts: &[&[u8]]) -> [u8; 20] {
+    digest_with_tags(parts, &[])
+}
+
+/// Returns the SHA-1 digest of the concatenation of `parts`, followed by the bytes of
+/// every tag of `tags`.
+pub(crate) const fn digest_with_tags(parts: &[&[u8]], tags: &[UniqueTag]) -> [u8; 20] {
+    let mut length = tags.len() * 16;
+    let mut i = 0;
+    while i < parts.len() {
+        length += parts[i].len();
+        i += 1;
+    }
+
+    // The message is followed by a `0x80` byte, zero padding and the 64-bit length
+    let blocks = (length + 8) / 64 + 1;
+    let bit_length = (length as u64).wrapping_mul(8).to_be_bytes();
+
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut block = 0;
+    while block < blocks {
+        let mut w = [0u32; 80];
+        let mut j = 0;
+        while j < 64 {
+            let position = block * 64 + j;
+            let byte = if position < length {
+                byte_at(parts, tags, position)
+            } else if position == length {
+                0x80
+            } else if position >= blocks * 64 - 8 {
+                bit_length[position - (blocks * 64 - 8)]
+            } else {
+                0
+            };
+            w[j / 4] |= (byte as u32) << (24 - 8 * (j % 4));
+            j += 1;
+        }
+        let mut t = 16;
+        while t < 80 {
+            w[t] = (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1);
+            t += 1;
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        let mut t = 0;
+        while t < 80 {
+            let (f, k) = if t < 20 {
+                ((b & c) | (!b & d), 0x5A827999)
+            } else if t < 40 {
+                (b ^ c ^ d, 0x6ED9EBA1)
+            } else if t < 60 {
+                ((b & c) | (b & d) | (c & d), 0x8F1BBCDC)
+            } else {
+                (b ^ c ^ d, 0xCA62C1D6)
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(w[t]);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+            t += 1;
+        }
+
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+        state[4] = state[4].wrapping_add(e);
+        block += 1;
+    }
+
+    let mut output = [0u8; 20];
+    let mut i = 0;
+    while i < 20 {
+        output[i] = state[i / 4].to_be_bytes()[i % 4];
+        i += 1;
+    }
+    output
+}
+
+/// Returns the byte at `position` in the concatenation of `parts` and `tags`.
+const fn byte_at(parts: &[&[u8]], tags: &[UniqueTag], mut position: usize) -> u8 {
+    let mut i = 0;
+    while i < parts.len() {
+        if position < parts[i].len() {
+            return parts[i][position];
+        }
+        position -= parts[i].len();
+        i += 1;
+    }
+    tags[position / 16].0.as_bytes()[position % 16]
+}
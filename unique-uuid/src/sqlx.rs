@@ -0,0 +1,63 @@
+//! [`sqlx`] support for storing [`UniqueTag`] in Postgres `uuid` columns.
+//!
+//! This module is only available with the `sqlx` feature. Every implementation delegates
+//! to the one of [`Uuid`], so a tag is stored exactly like its UUID.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sqlx::PgPool;
+//! use unique_uuid::UniqueTag;
+//!
+//! async fn find_kind(pool: &PgPool, id: i64) -> sqlx::Result<UniqueTag> {
+//!     sqlx::query_scalar("SELECT kind FROM events WHERE id = $1")
+//!         .bind(id)
+//!         .fetch_one(pool)
+//!         .await
+//! }
+//!
+//! async fn insert_event(pool: &PgPool, kind: UniqueTag) -> sqlx::Result<()> {
+//!     sqlx::query("INSERT INTO events (kind) VALUES ($1)")
+//!         .bind(kind)
+//!         .execute(pool)
+//!         .await
+//!         .map(|_| ())
+//! }
+//! ```
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres, Type,
+};
+use uuid::Uuid;
+
+use crate::UniqueTag;
+
+impl Type<Postgres> for UniqueTag {
+    fn type_info() -> PgTypeInfo {
+        <Uuid as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <Uuid as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for UniqueTag {
+    fn array_type_info() -> PgTypeInfo {
+        <Uuid as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for UniqueTag {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <Uuid as Encode<Postgres>>::encode_by_ref(&self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for UniqueTag {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        <Uuid as Decode<Postgres>>::decode(value).map(UniqueTag)
+    }
+}
@@ -0,0 +1,226 @@
+//! The inventory of the type tags of a binary, embedded in a linker section.
+//!
+//! With the `tag-section` feature, `#[derive(UniqueTypeTag)]` places a record of every
+//! non-generic type in a section of its own, so that tooling can list the type tags a
+//! compiled binary contains without running it:
+//!
+//! | Format | Section |
+//! |--------|---------|
+//! | ELF    | `.unique_uuid_tags` |
+//! | Mach-O | `__uu_tags` of the `__DATA` segment |
+//! | PE     | `.uu_tags` |
+//!
+//! Each record is laid out as:
+//!
+//! | Bytes | Content |
+//! |-------|---------|
+//! | 4     | [`MAGIC`], `UUTG` |
+//! | 16    | the UUID of the tag, big-endian as in [`uuid::Uuid::as_bytes`] |
+//! | 2     | the length of the key, little-endian |
+//! | n     | the key of the type in the registry, e.g. `::Order`, in UTF-8 |
+//!
+//! The records are `#[used]`, so that the compiler keeps them although nothing refers to
+//! them. The linker only keeps the records of the library crates the binary links code
+//! from, and may pad the section with zeros, which [`parse_section`] skips.
+//!
+//! With the `inspect` feature, [`read_binary`] finds the section in an ELF, Mach-O or PE
+//! file and parses it; `unique-uuid-admin inspect <binary>` prints the result.
+use std::fmt;
+
+use crate::UniqueTag;
+
+/// The bytes starting every record.
+pub const MAGIC: [u8; 4] = *b"UUTG";
+
+/// The section of the records in an ELF file.
+pub const ELF_SECTION: &str = ".unique_uuid_tags";
+
+/// The section of the records in a Mach-O file, in the `__DATA` segment.
+pub const MACHO_SECTION: &str = "__uu_tags";
+
+/// The section of the records in a PE file, whose names are limited to 8 bytes.
+pub const PE_SECTION: &str = ".uu_tags";
+
+/// The length of the record of `key`.
+#[doc(hidden)]
+pub const fn record_len(key: &str) -> usize {
+    MAGIC.len() + 16 + 2 + key.len()
+}
+
+/// Returns the record of `tag` and `key`, `N` being [`record_len`] of `key`.
+///
+/// # Panics
+/// Panics, at compile time in a constant, if `N` is not the length of the record or if
+/// `key` is longer than `u16::MAX` bytes.
+#[doc(hidden)]
+pub const fn record<const N: usize>(tag: UniqueTag, key: &str) -> [u8; N] {
+    assert!(
+        N == record_len(key),
+        "the record length does not match the key"
+    );
+    assert!(key.len() <= u16::MAX as usize, "the key is too long");
+    let mut record = [0u8; N];
+    let mut offset = 0;
+    while offset < MAGIC.len() {
+        record[offset] = MAGIC[offset];
+        offset += 1;
+    }
+    let uuid = tag.0.as_bytes();
+    let mut index = 0;
+    while index < uuid.len() {
+        record[offset + index] = uuid[index];
+        index += 1;
+    }
+    offset += uuid.len();
+    let length = (key.len() as u16).to_le_bytes();
+    record[offset] = length[0];
+    record[offset + 1] = length[1];
+    offset += 2;
+    let key = key.as_bytes();
+    let mut index = 0;
+    while index < key.len() {
+        record[offset + index] = key[index];
+        index += 1;
+    }
+    record
+}
+
+/// A type tag found in the section of a binary.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SectionTag {
+    /// The key of the type in the registry, e.g. `::Order`.
+    pub key: String,
+
+    /// The tag of the type.
+    pub tag: UniqueTag,
+}
+
+/// An error that occurred while reading the section of a binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionError {
+    /// The section holds bytes that do not start a record at this offset.
+    Malformed(usize),
+
+    /// The record at this offset ends past the end of the section.
+    Truncated(usize),
+
+    /// The key of the record at this offset is not valid UTF-8.
+    InvalidKey(usize),
+
+    /// The file is not an ELF, Mach-O or PE file.
+    Object(String),
+
+    /// The binary has no section of records, e.g. as it was built without the
+    /// `tag-section` feature.
+    MissingSection,
+}
+
+impl fmt::Display for SectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionError::Malformed(offset) => {
+                write!(
+                    f,
+                    "no tag record starts at offset {} of the section",
+                    offset
+                )
+            }
+            SectionError::Truncated(offset) => write!(
+                f,
+                "the tag record at offset {} ends past the end of the section",
+                offset
+            ),
+            SectionError::InvalidKey(offset) => write!(
+                f,
+                "the key of the tag record at offset {} is not valid UTF-8",
+                offset
+            ),
+            SectionError::Object(message) => write!(f, "cannot parse the binary: {}", message),
+            SectionError::MissingSection => write!(
+                f,
+                "the binary has no section of tag records, was it built with the \
+                 `tag-section` feature of `unique-uuid`?"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SectionError {}
+
+/// Parses `section`, the content of the section of a binary, returning its records in
+/// their order. Zero bytes between the records, padding added by the linker, are skipped.
+///
+/// # Errors
+/// Fails if the section holds anything but records and padding.
+///
+/// # Example
+///
+/// ```rust
+/// use unique_uuid::{tag_section::parse_section, UniqueTag};
+///
+/// let mut section = vec![0; 3];
+/// section.extend(b"UUTG");
+/// section.extend(*UniqueTag::from_u128(1).0.as_bytes());
+/// section.extend([7, 0]);
+/// section.extend(b"::Order");
+///
+/// let tags = parse_section(&section).unwrap();
+/// assert_eq!(tags.len(), 1);
+/// assert_eq!(tags[0].key, "::Order");
+/// assert_eq!(tags[0].tag, UniqueTag::from_u128(1));
+///
+/// assert!(parse_section(b"UUTG\x01").is_err());
+/// ```
+pub fn parse_section(section: &[u8]) -> Result<Vec<SectionTag>, SectionError> {
+    let mut tags = Vec::new();
+    let mut offset = 0;
+    while offset < section.len() {
+        if section[offset] == 0 {
+            offset += 1;
+            continue;
+        }
+        let start = offset;
+        if !section[start..].starts_with(&MAGIC) {
+            return Err(SectionError::Malformed(start));
+        }
+        let header = MAGIC.len() + 16 + 2;
+        let Some(fixed) = section.get(start..start + header) else {
+            return Err(SectionError::Truncated(start));
+        };
+        let uuid: [u8; 16] = fixed[MAGIC.len()..MAGIC.len() + 16].try_into().unwrap();
+        let length = usize::from(u16::from_le_bytes([fixed[header - 2], fixed[header - 1]]));
+        let Some(key) = section.get(start + header..start + header + length) else {
+            return Err(SectionError::Truncated(start));
+        };
+        let key = std::str::from_utf8(key).map_err(|_| SectionError::InvalidKey(start))?;
+        tags.push(SectionTag {
+            key: key.to_string(),
+            tag: UniqueTag(uuid::Uuid::from_bytes(uuid)),
+        });
+        offset = start + header + length;
+    }
+    Ok(tags)
+}
+
+/// Finds the section of records in `binary`, the content of an ELF, Mach-O or PE file,
+/// and parses it with [`parse_section`].
+///
+/// This function is only available with the `inspect` feature.
+///
+/// # Errors
+/// Fails if `binary` cannot be parsed, has no section of records, or if the section is
+/// malformed.
+#[cfg(feature = "inspect")]
+pub fn read_binary(binary: &[u8]) -> Result<Vec<SectionTag>, SectionError> {
+    use object::{Object, ObjectSection};
+
+    let file = object::File::parse(binary).map_err(|err| SectionError::Object(err.to_string()))?;
+    let section = [ELF_SECTION, MACHO_SECTION, PE_SECTION]
+        .into_iter()
+        .find_map(|name| file.section_by_name(name))
+        .ok_or(SectionError::MissingSection)?;
+    let data = section
+        .data()
+        .map_err(|err| SectionError::Object(err.to_string()))?;
+    parse_section(data)
+}
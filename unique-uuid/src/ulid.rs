@@ -0,0 +1,59 @@
+//! Conversions between [`UniqueTag`]s and [`Ulid`]s.
+//!
+//! This module is only available with the `ulid` feature, for `ulid` 1. A ULID and a
+//! UUID are both 128-bit values, so the conversions are lossless: the bits are kept as
+//! they are, the first byte of the UUID becoming the most significant byte of the ULID.
+//!
+//! # Timestamps
+//!
+//! A ULID reads its first 48 bits as the millisecond timestamp of its creation, and sorts
+//! by it. The tags minted by the macros are random version 4 UUIDs, whose first 48 bits
+//! are random (or the prefix the registry reserves for the crate), and the tags derived
+//! by [`UniqueTag::new_v5`] are hashes: their [`Ulid::timestamp_ms`] is meaningless, and
+//! sorting them as ULIDs does not follow the order in which they were minted. Only a tag
+//! converted from a ULID carries the timestamp of that ULID.
+//!
+//! # Example
+//!
+//! ```rust
+//! use ulid::Ulid;
+//! use unique_uuid::{uuid::uuid, UniqueTag};
+//!
+//! let tag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+//! let ulid = tag.to_ulid();
+//! assert_eq!(ulid.0, tag.0.as_u128());
+//! assert_eq!(UniqueTag::from_ulid(ulid), tag);
+//!
+//! // The Crockford base32 form of the ULID parses back to the same tag
+//! let string = tag.to_ulid_string();
+//! assert_eq!(string.len(), 26);
+//! assert_eq!(UniqueTag::from_ulid(Ulid::from_string(&string).unwrap()), tag);
+//! ```
+use ulid::Ulid;
+use uuid::Uuid;
+
+use crate::UniqueTag;
+
+impl UniqueTag {
+    /// Returns the ULID with the same 128 bits as the tag.
+    ///
+    /// The timestamp of the ULID, its first 48 bits, is only meaningful if the tag was
+    /// converted from a ULID: the tags minted by the macros are random, and those
+    /// derived by [`UniqueTag::new_v5`] are hashes.
+    pub const fn to_ulid(self) -> Ulid {
+        Ulid(self.0.as_u128())
+    }
+
+    /// Returns the tag with the same 128 bits as `ulid`.
+    ///
+    /// The tag is not a valid version 4 UUID in general, and not in the registry.
+    pub const fn from_ulid(ulid: Ulid) -> UniqueTag {
+        UniqueTag(Uuid::from_u128(ulid.0))
+    }
+
+    /// Returns the ULID of the tag in its canonical form, 26 characters of Crockford
+    /// base32.
+    pub fn to_ulid_string(self) -> String {
+        self.to_ulid().to_string()
+    }
+}
@@ -0,0 +1,62 @@
+//! Conversions between [`UniqueTag`] and JavaScript values, for use with `wasm-bindgen`.
+//!
+//! This module is only available with the `wasm` feature.
+use js_sys::{JsString, Uint8Array};
+use wasm_bindgen::JsValue;
+
+use crate::UniqueTag;
+
+impl UniqueTag {
+    /// Returns the tag as a JavaScript string, in the 36-character hyphenated form.
+    ///
+    /// Requires the `wasm` feature.
+    pub fn to_js_string(&self) -> JsString {
+        JsString::from(self.0.hyphenated().to_string())
+    }
+
+    /// Parses a tag from a JavaScript string, in any form accepted by
+    /// [`uuid::Uuid::parse_str`].
+    ///
+    /// Requires the `wasm` feature.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a valid UUID.
+    pub fn from_js_string(value: &JsString) -> Result<Self, js_sys::Error> {
+        let value = String::from(value);
+        uuid::Uuid::parse_str(&value)
+            .map(UniqueTag)
+            .map_err(|err| js_sys::Error::new(&format!("invalid tag `{}`: {}", value, err)))
+    }
+
+    /// Returns the 16 bytes of the tag, in big-endian order, as a new `Uint8Array`.
+    ///
+    /// Requires the `wasm` feature.
+    pub fn to_uint8array(&self) -> Uint8Array {
+        Uint8Array::from(&self.0.as_bytes()[..])
+    }
+
+    /// Reads a tag from the 16 bytes of a `Uint8Array`, in big-endian order.
+    ///
+    /// Requires the `wasm` feature.
+    ///
+    /// # Errors
+    /// Returns an error if the array does not hold exactly 16 bytes.
+    pub fn from_uint8array(value: &Uint8Array) -> Result<Self, js_sys::Error> {
+        let mut bytes = [0u8; 16];
+        if value.length() as usize != bytes.len() {
+            return Err(js_sys::Error::new(&format!(
+                "a tag is 16 bytes long, got {}",
+                value.length()
+            )));
+        }
+        value.copy_to(&mut bytes);
+        Ok(UniqueTag(uuid::Uuid::from_bytes(bytes)))
+    }
+}
+
+impl From<UniqueTag> for JsValue {
+    /// Converts the tag to a JavaScript string, as [`UniqueTag::to_js_string`].
+    fn from(tag: UniqueTag) -> Self {
+        tag.to_js_string().into()
+    }
+}
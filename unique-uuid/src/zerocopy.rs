@@ -0,0 +1,65 @@
+//! [`zerocopy`] support for viewing bytes as [`UniqueTag`]s without copying them.
+//!
+//! This module is only available with the `zerocopy` feature, for `zerocopy` 0.7.
+//! `UniqueTag` implements `FromZeroes`, `FromBytes`, `AsBytes` and `Unaligned`, so a
+//! 16-byte region of a frame can be read as a `&UniqueTag` in place, whatever its
+//! alignment. The bytes of a tag are the big-endian bytes of its UUID, as returned by
+//! [`Uuid::as_bytes`](uuid::Uuid::as_bytes).
+//!
+//! # Layout
+//!
+//! `UniqueTag` is a `#[repr(transparent)]` wrapper around a [`Uuid`](uuid::Uuid), which
+//! `uuid` guarantees to have the layout of a `[u8; 16]`: a tag is therefore exactly 16
+//! bytes, aligned to 1, and every bit pattern is a valid tag. This is checked at compile
+//! time below.
+//!
+//! # Example
+//!
+//! ```rust
+//! use unique_uuid::{uuid::Uuid, UniqueTag};
+//! use zerocopy::{AsBytes, FromBytes, Ref};
+//!
+//! // A frame carrying the tag of its payload after a 1-byte header, so unaligned
+//! let mut frame = vec![0x01];
+//! frame.extend_from_slice(&[
+//!     0x2a, 0xf1, 0x21, 0x6e, 0x70, 0x42, 0x40, 0x6a, 0xa5, 0x77, 0xc3, 0x9a, 0x7e, 0x7c,
+//!     0x3f, 0xaa,
+//! ]);
+//!
+//! let tag: &UniqueTag = UniqueTag::ref_from(&frame[1..17]).unwrap();
+//! assert_eq!(*tag, UniqueTag(Uuid::from_bytes(frame[1..17].try_into().unwrap())));
+//! assert_eq!(tag.0.to_string(), "2af1216e-7042-406a-a577-c39a7e7c3faa");
+//! assert_eq!(tag.as_bytes(), &frame[1..17]);
+//!
+//! let (tag, rest) = Ref::<_, UniqueTag>::new_unaligned_from_prefix(&frame[1..]).unwrap();
+//! assert_eq!(tag.0.as_bytes(), &frame[1..17]);
+//! assert!(rest.is_empty());
+//! ```
+use zerocopy::{AsBytes, FromBytes, FromZeroes, Unaligned};
+
+use crate::UniqueTag;
+
+// The implementations below rely on this layout
+const _: () = assert!(std::mem::size_of::<UniqueTag>() == 16);
+const _: () = assert!(std::mem::align_of::<UniqueTag>() == 1);
+
+// SAFETY: `UniqueTag` is a `repr(transparent)` wrapper around a `Uuid`, which has the
+// layout of a `[u8; 16]`: the all-zeroes bit pattern, the nil UUID, is valid.
+unsafe impl FromZeroes for UniqueTag {
+    fn only_derive_is_allowed_to_implement_this_trait() {}
+}
+
+// SAFETY: every bit pattern of a `[u8; 16]` is a valid UUID, and thus a valid tag.
+unsafe impl FromBytes for UniqueTag {
+    fn only_derive_is_allowed_to_implement_this_trait() {}
+}
+
+// SAFETY: a `[u8; 16]` has no padding, nor interior mutability.
+unsafe impl AsBytes for UniqueTag {
+    fn only_derive_is_allowed_to_implement_this_trait() {}
+}
+
+// SAFETY: a `[u8; 16]` is aligned to 1, as checked above.
+unsafe impl Unaligned for UniqueTag {
+    fn only_derive_is_allowed_to_implement_this_trait() {}
+}
@@ -0,0 +1,249 @@
+//! Registries written by a `RegistryBuilder`, as build scripts do.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use unique_uuid::{
+    build::{BuilderError, RegistryBuilder},
+    registry::{Registry, Section},
+};
+use unique_uuid_core::{RegistryHandle, Section as CoreSection};
+use uuid::Uuid;
+
+const CREATED: &str = "2af1216e-7042-406a-a577-c39a7e7c3faa";
+const PINNED: &str = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50";
+
+/// Copies the fixture registry, with `config` appended, to a file of its own.
+fn registry(name: &str, config: &str) -> PathBuf {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/builder/orders.toml");
+    let path = std::env::temp_dir().join(format!("unique-uuid-builder-{}.toml", name));
+    let _ = fs::remove_file(unique_uuid::registry::journal_path(&path));
+    fs::write(
+        &path,
+        format!("{}{}", fs::read_to_string(fixture).unwrap(), config),
+    )
+    .unwrap();
+    path
+}
+
+fn uuid(uuid: &str) -> Uuid {
+    uuid.parse().unwrap()
+}
+
+#[test]
+fn nothing_is_written_before_commit() {
+    let path = registry("commit", "");
+    let before = fs::read_to_string(&path).unwrap();
+    let mut builder = RegistryBuilder::open(&path).unwrap();
+    let paid = builder.get_or_insert_tag("orders.paid").unwrap();
+    let created = builder.get_or_insert_tag("orders.created").unwrap();
+    assert_eq!(created.0, uuid(CREATED));
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+    assert_eq!(builder.commit().unwrap(), 1);
+    let registry = Registry::load(&path).unwrap();
+    assert_eq!(registry.get("orders.paid").unwrap().tag, paid);
+    let entry = registry.entry(Section::UniqueTags, "orders.paid").unwrap();
+    assert!(entry.created().is_some());
+
+    // Committing without changes leaves the file alone
+    let after = fs::read_to_string(&path).unwrap();
+    let mut builder = RegistryBuilder::open(&path).unwrap();
+    builder.get_or_insert_tag("orders.paid").unwrap();
+    assert_eq!(builder.commit().unwrap(), 0);
+    assert_eq!(fs::read_to_string(&path).unwrap(), after);
+}
+
+#[test]
+fn pin_conflicts_follow_the_registry_setting() {
+    let conflict = |config: &str| {
+        let path = registry(&format!("pin{}", config.len()), config);
+        let mut builder = RegistryBuilder::open(&path).unwrap();
+        let result = builder.pin_tag("orders.created", uuid(PINNED));
+        builder.commit().unwrap();
+        (result, Registry::load(&path).unwrap())
+    };
+
+    let (result, _) = conflict("");
+    match result {
+        Err(BuilderError::PinConflict {
+            key,
+            pinned,
+            existing,
+        }) => {
+            assert_eq!(key, "orders.created");
+            assert_eq!((pinned, existing), (uuid(PINNED), uuid(CREATED)));
+        }
+        other => panic!("expected a pin conflict, got {:?}", other),
+    }
+
+    let (result, registry) = conflict("\n[config]\npin_conflict = \"prefer-pinned\"\n");
+    assert_eq!(result.unwrap().0, uuid(PINNED));
+    let entry = registry
+        .entry(Section::UniqueTags, "orders.created")
+        .unwrap();
+    assert_eq!(entry.uuid(), uuid(PINNED));
+    assert!(entry.is_pinned());
+
+    let (result, registry) = conflict("\n[config]\npin_conflict = \"prefer-registry\"\n");
+    assert_eq!(result.unwrap().0, uuid(CREATED));
+    let entry = registry
+        .entry(Section::UniqueTags, "orders.created")
+        .unwrap();
+    assert_eq!(entry.uuid(), uuid(CREATED));
+}
+
+#[test]
+fn pinned_uuids_belong_to_a_single_key() {
+    let path = registry("pin-owner", "");
+    let mut builder = RegistryBuilder::open(&path).unwrap();
+    let tag = builder.pin_tag("orders.placed", uuid(PINNED)).unwrap();
+    assert_eq!(tag.0, uuid(PINNED));
+    assert_eq!(builder.pin_tag("orders.placed", uuid(PINNED)).unwrap(), tag);
+    match builder.pin_tag("orders.legacy", uuid(CREATED)) {
+        Err(BuilderError::UuidInUse { section, owner, .. }) => {
+            assert_eq!(
+                (section, owner.as_str()),
+                (Section::UniqueTags, "orders.created")
+            );
+        }
+        other => panic!("expected the UUID to be in use, got {:?}", other),
+    }
+    builder.commit().unwrap();
+    let registry = Registry::load(&path).unwrap();
+    assert!(registry
+        .entry(Section::UniqueTags, "orders.placed")
+        .unwrap()
+        .is_pinned());
+    assert!(registry
+        .entry(Section::UniqueTags, "orders.legacy")
+        .is_none());
+}
+
+#[test]
+fn reservations_are_kept_apart() {
+    let path = registry("reserve", "");
+    let mut builder = RegistryBuilder::open(&path).unwrap();
+    let v3 = builder.reserve("orders.v3").unwrap();
+    assert_eq!(builder.reserve("orders.v3").unwrap(), v3);
+    assert_eq!(
+        builder.reserve("orders.v2").unwrap().0,
+        uuid("ce4baeef-bd9c-4932-88be-24eb1033f695")
+    );
+
+    // Reserved entries cannot be claimed, and entries in use cannot be reserved
+    for key in ["orders.v2", "orders.v3"] {
+        assert!(matches!(
+            builder.get_or_insert_tag(key),
+            Err(BuilderError::Reserved { .. })
+        ));
+    }
+    assert!(matches!(
+        builder.reserve("orders.created"),
+        Err(BuilderError::InUse { .. })
+    ));
+    builder.commit().unwrap();
+
+    let registry = Registry::load(&path).unwrap();
+    let entry = registry.entry(Section::UniqueTags, "orders.v3").unwrap();
+    assert!(entry.is_reserved());
+    assert_eq!(entry.tag(), v3);
+}
+
+#[test]
+fn concurrent_entries_are_kept() {
+    let path = registry("concurrent", "");
+    let mut builder = RegistryBuilder::open(&path).unwrap();
+    builder.get_or_insert_type("Invoice").unwrap();
+    builder.get_or_insert_tag("orders.paid").unwrap();
+
+    // A macro expansion writes other entries meanwhile, one of them for a key of the builder
+    let mut registry = Registry::load(&path).unwrap();
+    let shipped = "8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4".parse().unwrap();
+    registry.insert(
+        Section::UniqueTags,
+        "orders.shipped",
+        unique_uuid::registry::Entry::new(shipped),
+    );
+    registry.save(&path).unwrap();
+    let mut other = RegistryBuilder::open(&path).unwrap();
+    other.get_or_insert_tag("orders.paid").unwrap();
+    other.commit().unwrap();
+
+    match builder.commit() {
+        Err(BuilderError::Concurrent { section, key }) => {
+            assert_eq!(
+                (section, key.as_str()),
+                (Section::UniqueTags, "orders.paid")
+            );
+        }
+        other => panic!("expected a concurrent entry, got {:?}", other),
+    }
+    let registry = Registry::load(&path).unwrap();
+    assert_eq!(registry.get("orders.shipped").unwrap().tag.0, shipped);
+    assert!(registry.get("::Invoice").is_none());
+}
+
+#[test]
+fn commits_keep_the_entries_minted_meanwhile() {
+    let path = registry("macros", "");
+    std::thread::scope(|scope| {
+        // Macro expansions of other crates, each with a handle of its own
+        let macros: Vec<_> = (0..4)
+            .map(|index| {
+                let handle = RegistryHandle::open(&path);
+                scope.spawn(move || {
+                    for key in 0..8 {
+                        let key = format!("thread{}.key{}", index, key);
+                        handle
+                            .lookup_or_insert(&key, CoreSection::UniqueTags)
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for key in 0..8 {
+            let mut builder = RegistryBuilder::open(&path).unwrap();
+            builder
+                .get_or_insert_tag(&format!("builder.key{}", key))
+                .unwrap();
+            builder.commit().unwrap();
+        }
+        for thread in macros {
+            thread.join().unwrap();
+        }
+    });
+
+    let registry = Registry::load(&path).unwrap();
+    for key in 0..8 {
+        assert!(registry.get(&format!("builder.key{}", key)).is_some());
+        for index in 0..4 {
+            assert!(registry
+                .get(&format!("thread{}.key{}", index, key))
+                .is_some());
+        }
+    }
+    assert_eq!(registry.get("orders.created").unwrap().tag.0, uuid(CREATED));
+}
+
+#[test]
+fn journals_are_compacted_by_the_commit() {
+    let path = registry("journal", "\n[config]\njournal = true\n");
+    let journal = unique_uuid::registry::journal_path(&path);
+    fs::write(
+        &journal,
+        "unique_tags.\"orders.shipped\" = \"8c4c2ab6-b0e6-4e5c-9e58-34e1e3d1c8a4\"\n",
+    )
+    .unwrap();
+
+    let mut builder = RegistryBuilder::open(&path).unwrap();
+    assert!(builder.get_or_insert_tag("orders.shipped").is_ok());
+    builder.get_or_insert_tag("orders.paid").unwrap();
+    builder.commit().unwrap();
+
+    assert_eq!(fs::read_to_string(&journal).unwrap(), "");
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(contents.contains("\"orders.shipped\""), "{}", contents);
+    assert!(contents.contains("\"orders.paid\""), "{}", contents);
+}
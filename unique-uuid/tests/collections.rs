@@ -0,0 +1,106 @@
+//! Serialization of `TagSet` and `TagMap` in human-readable and binary formats.
+use serde::{de::DeserializeOwned, Serialize};
+use unique_uuid::{TagMap, TagSet, UniqueTag};
+use uuid::Uuid;
+
+const AUDIO: &str = "2af1216e-7042-406a-a577-c39a7e7c3faa";
+const VIDEO: &str = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24";
+
+fn tag(uuid: &str) -> UniqueTag {
+    UniqueTag(uuid.parse().unwrap())
+}
+
+/// `count` distinct tags, not in order.
+fn tags(count: u128) -> impl Iterator<Item = UniqueTag> {
+    (0..count).map(|i| UniqueTag(Uuid::from_u128(i.wrapping_mul(0x9e37_79b9_7f4a_7c15))))
+}
+
+fn set(count: u128) -> TagSet {
+    let mut set = TagSet::new();
+    for tag in tags(count) {
+        set.insert(tag);
+    }
+    set
+}
+
+fn map(count: u128) -> TagMap<String> {
+    tags(count)
+        .enumerate()
+        .map(|(i, tag)| (tag, format!("value {}", i)))
+        .collect()
+}
+
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(value: &T) {
+    let json = serde_json::to_string(value).unwrap();
+    assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value, "{}", json);
+    let bytes = bincode::serialize(value).unwrap();
+    assert_eq!(&bincode::deserialize::<T>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn sets_round_trip() {
+    for count in [0, 1, 1000] {
+        round_trip(&set(count));
+    }
+}
+
+#[test]
+fn maps_round_trip() {
+    for count in [0, 1, 1000] {
+        round_trip(&map(count));
+    }
+}
+
+#[test]
+fn sets_are_sequences_of_tags() {
+    let set = set(0);
+    assert_eq!(serde_json::to_string(&set).unwrap(), "[]");
+
+    let mut set = TagSet::new();
+    set.insert(tag(VIDEO));
+    set.insert(tag(AUDIO));
+    assert_eq!(
+        serde_json::to_string(&set).unwrap(),
+        format!(r#"["{}","{}"]"#, AUDIO, VIDEO)
+    );
+    let unsorted: TagSet = serde_json::from_str(&format!(r#"["{}","{}"]"#, VIDEO, AUDIO)).unwrap();
+    assert_eq!(unsorted, set);
+}
+
+#[test]
+fn maps_are_keyed_by_uuid_strings_in_human_readable_formats() {
+    let map: TagMap<u16> = [(tag(VIDEO), 20), (tag(AUDIO), 10)].into_iter().collect();
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        format!(r#"{{"{}":10,"{}":20}}"#, AUDIO, VIDEO)
+    );
+    assert_eq!(serde_json::to_string(&TagMap::<u16>::new()).unwrap(), "{}");
+}
+
+#[test]
+fn maps_are_compact_entry_lists_in_binary_formats() {
+    let map: TagMap<u16> = [(tag(AUDIO), 10)].into_iter().collect();
+    let bytes = bincode::serialize(&map).unwrap();
+    // The length of the list, then the length-prefixed 16 bytes of the tag and the value
+    assert_eq!(bytes.len(), 8 + 8 + 16 + 2);
+    assert_eq!(bytes, bincode::serialize(&vec![(tag(AUDIO), 10u16)]).unwrap());
+}
+
+#[test]
+fn duplicate_tags_are_rejected() {
+    let err = serde_json::from_str::<TagSet>(&format!(r#"["{}","{}","{}"]"#, AUDIO, VIDEO, AUDIO))
+        .unwrap_err();
+    assert!(err.to_string().starts_with(&format!("duplicate tag {}", AUDIO)), "{}", err);
+
+    let err = serde_json::from_str::<TagMap<u16>>(&format!(r#"{{"{}":1,"{}":2}}"#, VIDEO, VIDEO))
+        .unwrap_err();
+    assert!(err.to_string().starts_with(&format!("duplicate tag {}", VIDEO)), "{}", err);
+
+    let bytes = bincode::serialize(&vec![tag(VIDEO), tag(VIDEO)]).unwrap();
+    let err = bincode::deserialize::<TagSet>(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), format!("duplicate tag {}", VIDEO));
+
+    let bytes = bincode::serialize(&vec![(tag(AUDIO), 1u16), (tag(AUDIO), 2)]).unwrap();
+    let err = bincode::deserialize::<TagMap<u16>>(&bytes).unwrap_err();
+    assert_eq!(err.to_string(), format!("duplicate tag {}", AUDIO));
+}
@@ -0,0 +1,101 @@
+use std::sync::{Arc, Mutex};
+
+use unique_uuid::{unique_tag, DispatchTable, DuplicateHandler, UniqueTag, UniqueTypeTag};
+
+#[derive(UniqueTypeTag)]
+struct OrderCreated;
+
+#[derive(UniqueTypeTag)]
+struct OrderPaid;
+
+#[derive(UniqueTypeTag)]
+struct Shipment;
+
+/// A table routing the three message types, recording the handled order ids.
+fn table(log: &Arc<Mutex<Vec<String>>>) -> DispatchTable<u32, &'static str> {
+    let mut table = DispatchTable::new();
+    let created = Arc::clone(log);
+    let paid = Arc::clone(log);
+    let shipped = Arc::clone(log);
+    table
+        .on::<OrderCreated>(move |id| {
+            created.lock().unwrap().push(format!("created #{}", id));
+            "created"
+        })
+        .unwrap()
+        .on::<OrderPaid>(move |id| {
+            paid.lock().unwrap().push(format!("paid #{}", id));
+            "paid"
+        })
+        .unwrap()
+        .on_tag(Shipment::TYPE_TAG, move |id| {
+            shipped.lock().unwrap().push(format!("shipped #{}", id));
+            "shipped"
+        })
+        .unwrap();
+    table
+}
+
+#[test]
+fn routes_each_type_to_its_handler() {
+    let log = Arc::default();
+    let table = table(&log);
+    let messages: [(UniqueTag, u32); 4] = [
+        (OrderCreated::TYPE_TAG, 1),
+        (OrderPaid::TYPE_TAG, 1),
+        (OrderCreated::TYPE_TAG, 2),
+        (Shipment::TYPE_TAG, 1),
+    ];
+    let outputs: Vec<_> = messages
+        .iter()
+        .map(|(tag, id)| table.dispatch(*tag, *id))
+        .collect();
+
+    assert_eq!(
+        outputs,
+        [
+            Some("created"),
+            Some("paid"),
+            Some("created"),
+            Some("shipped")
+        ]
+    );
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["created #1", "paid #1", "created #2", "shipped #1"]
+    );
+    assert_eq!(table.len(), 3);
+}
+
+#[test]
+fn unknown_tags_go_to_the_fallback() {
+    let log = Arc::default();
+    let mut table = table(&log);
+    let unknown = unique_tag!("my_custom_tag");
+    assert!(!table.handles(unknown));
+    assert_eq!(table.dispatch(unknown, 1), None);
+
+    table.fallback(|tag, _| {
+        assert_eq!(tag, unique_tag!("my_custom_tag"));
+        "unknown"
+    });
+    assert_eq!(table.dispatch(unknown, 1), Some("unknown"));
+    assert_eq!(table.dispatch(Shipment::TYPE_TAG, 1), Some("shipped"));
+    assert_eq!(*log.lock().unwrap(), ["shipped #1"]);
+}
+
+#[test]
+fn rejects_a_second_handler_for_a_tag() {
+    let log = Arc::default();
+    let mut table = table(&log);
+
+    let err = table
+        .on_tag(OrderPaid::TYPE_TAG, |_| "replaced")
+        .expect_err("the tag already has a handler");
+    assert_eq!(err, DuplicateHandler(OrderPaid::TYPE_TAG));
+    assert!(err.to_string().contains(&OrderPaid::TYPE_TAG.0.to_string()));
+
+    // The first handler is kept
+    assert_eq!(table.dispatch(OrderPaid::TYPE_TAG, 3), Some("paid"));
+    assert_eq!(table.len(), 3);
+}
@@ -0,0 +1,56 @@
+//! The stability of the export formats: the fields of their first version keep their
+//! names and order, and later fields are appended after them.
+use std::{fs, path::PathBuf};
+
+use unique_uuid::registry::Registry;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/export")
+        .join(name)
+}
+
+fn registry() -> Registry {
+    Registry::load(fixture("registry.toml")).unwrap()
+}
+
+#[test]
+fn csv_rows_start_with_the_first_fields() {
+    let mut csv = Vec::new();
+    registry().export_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let v1 = fs::read_to_string(fixture("registry.v1.csv")).unwrap();
+
+    assert_eq!(csv.lines().count(), v1.lines().count());
+    for (line, first) in csv.lines().zip(v1.lines()) {
+        assert!(
+            line.starts_with(first) && line[first.len()..].starts_with(','),
+            "`{}` does not extend `{}`",
+            line,
+            first
+        );
+    }
+}
+
+#[test]
+fn json_objects_start_with_the_first_fields() {
+    let mut json = Vec::new();
+    registry().export_json(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    let v1 = fs::read_to_string(fixture("registry.v1.json")).unwrap();
+
+    assert_eq!(json.lines().count(), v1.lines().count());
+    for (line, first) in json.lines().zip(v1.lines()) {
+        let first = first.trim_end_matches(',');
+        let Some(fields) = first.strip_suffix('}') else {
+            assert_eq!(line, first);
+            continue;
+        };
+        assert!(
+            line.starts_with(fields) && line[fields.len()..].starts_with(", \""),
+            "`{}` does not extend `{}`",
+            line,
+            first
+        );
+    }
+}
@@ -0,0 +1,27 @@
+/* @generated by unique-uuid from the registry file, do not edit.
+ * Regenerate it with `unique-uuid-admin c-header`.
+ *
+ * Every tag is the 16 bytes of its UUID in big-endian (network) order.
+ */
+#ifndef UU_TAGS_H
+#define UU_TAGS_H
+
+#include <stdint.h>
+
+/* [unique_tags] */
+
+/* orders.created (2af1216e-7042-406a-a577-c39a7e7c3faa) */
+static const uint8_t UU_TAG_ORDERS_CREATED[16] = {0x2a, 0xf1, 0x21, 0x6e, 0x70, 0x42, 0x40, 0x6a, 0xa5, 0x77, 0xc3, 0x9a, 0x7e, 0x7c, 0x3f, 0xaa};
+
+/* payments.refunded (ce4baeef-bd9c-4932-88be-24eb1033f695) */
+static const uint8_t UU_TAG_PAYMENTS_REFUNDED[16] = {0xce, 0x4b, 0xae, 0xef, 0xbd, 0x9c, 0x49, 0x32, 0x88, 0xbe, 0x24, 0xeb, 0x10, 0x33, 0xf6, 0x95};
+
+/* [unique_type_tags] */
+
+/* ::OrderRecord (6aac8026-6ca8-4c82-ac39-d74bedf1ac50) */
+static const uint8_t UU_TYPE_ORDER_RECORD[16] = {0x6a, 0xac, 0x80, 0x26, 0x6c, 0xa8, 0x4c, 0x82, 0xac, 0x39, 0xd7, 0x4b, 0xed, 0xf1, 0xac, 0x50};
+
+/* ::shop::Invoice (8701a796-9ffe-4723-89cf-ebcaaafe9ebb) */
+static const uint8_t UU_TYPE_SHOP_INVOICE[16] = {0x87, 0x01, 0xa7, 0x96, 0x9f, 0xfe, 0x47, 0x23, 0x89, 0xcf, 0xeb, 0xca, 0xaa, 0xfe, 0x9e, 0xbb};
+
+#endif /* UU_TAGS_H */
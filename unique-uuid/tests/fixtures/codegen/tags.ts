@@ -0,0 +1,42 @@
+// @generated by unique-uuid from the registry file, do not edit.
+// Regenerate it with `unique-uuid-admin typescript`.
+
+// [unique_tags]
+
+/**
+ * `orders.created` (`2af1216e-7042-406a-a577-c39a7e7c3faa`)
+ *
+ * An order was placed.
+ *
+ * Emitted once per order.
+ */
+export const ORDERS_CREATED = "2af1216e-7042-406a-a577-c39a7e7c3faa" as const;
+
+/**
+ * `payments.refunded` (`ce4baeef-bd9c-4932-88be-24eb1033f695`)
+ */
+export const PAYMENTS_REFUNDED = "ce4baeef-bd9c-4932-88be-24eb1033f695" as const;
+
+// [unique_type_tags]
+
+/**
+ * `::OrderRecord` (`6aac8026-6ca8-4c82-ac39-d74bedf1ac50`)
+ */
+export const TYPE_ORDER_RECORD = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50" as const;
+
+/**
+ * `::shop::Invoice` (`8701a796-9ffe-4723-89cf-ebcaaafe9ebb`)
+ */
+export const TYPE_SHOP_INVOICE = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb" as const;
+
+/** The entries of the `[unique_tags]` section, by key. */
+export const uniqueTags = {
+  "orders.created": ORDERS_CREATED,
+  "payments.refunded": PAYMENTS_REFUNDED,
+} as const;
+
+/** The entries of the `[unique_type_tags]` section, by key. */
+export const uniqueTypeTags = {
+  "::OrderRecord": TYPE_ORDER_RECORD,
+  "::shop::Invoice": TYPE_SHOP_INVOICE,
+} as const;
@@ -0,0 +1,28 @@
+// @generated by unique-uuid from the registry file, do not edit.
+// Regenerate it with `unique-uuid-admin codegen`.
+
+/// The entries of the `[unique_tags]` section.
+pub mod unique_tags {
+    /// `orders.created` (`2af1216e-7042-406a-a577-c39a7e7c3faa`)
+    ///
+    /// An order was placed.
+    ///
+    /// Emitted once per order.
+    pub const ORDERS_CREATED: unique_uuid::UniqueTag =
+        unique_uuid::UniqueTag(unique_uuid::uuid::uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+
+    /// `payments.refunded` (`ce4baeef-bd9c-4932-88be-24eb1033f695`)
+    pub const PAYMENTS_REFUNDED: unique_uuid::UniqueTag =
+        unique_uuid::UniqueTag(unique_uuid::uuid::uuid!("ce4baeef-bd9c-4932-88be-24eb1033f695"));
+}
+
+/// The entries of the `[unique_type_tags]` section.
+pub mod unique_type_tags {
+    /// `::OrderRecord` (`6aac8026-6ca8-4c82-ac39-d74bedf1ac50`)
+    pub const ORDER_RECORD: unique_uuid::UniqueTag =
+        unique_uuid::UniqueTag(unique_uuid::uuid::uuid!("6aac8026-6ca8-4c82-ac39-d74bedf1ac50"));
+
+    /// `::shop::Invoice` (`8701a796-9ffe-4723-89cf-ebcaaafe9ebb`)
+    pub const SHOP_INVOICE: unique_uuid::UniqueTag =
+        unique_uuid::UniqueTag(unique_uuid::uuid::uuid!("8701a796-9ffe-4723-89cf-ebcaaafe9ebb"));
+}
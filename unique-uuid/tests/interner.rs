@@ -0,0 +1,106 @@
+//! The lifecycle of a `TagInterner`: interning, freezing and looking up.
+use std::sync::Arc;
+
+use unique_uuid::{
+    registry::Registry, unique_tag, FrozenTagInterner, InternedTag, TagInterner, UniqueTag,
+};
+
+const TAGS: [&str; 3] = [
+    "2af1216e-7042-406a-a577-c39a7e7c3faa",
+    "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24",
+    "6aac8026-6ca8-4c82-ac39-d74bedf1ac50",
+];
+
+fn tags() -> Vec<UniqueTag> {
+    TAGS.iter()
+        .map(|uuid| UniqueTag(uuid.parse().unwrap()))
+        .collect()
+}
+
+#[test]
+fn interning_allocates_dense_handles_once() {
+    let mut interner = TagInterner::new();
+    assert!(interner.is_empty());
+    let handles: Vec<InternedTag> = tags().into_iter().map(|tag| interner.intern(tag)).collect();
+    assert_eq!(
+        handles.iter().map(|handle| handle.index()).collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+
+    // Interning again returns the same handles, without allocating new ones
+    for (tag, handle) in tags().into_iter().zip(&handles) {
+        assert_eq!(interner.intern(tag), *handle);
+        assert_eq!(interner.get(tag), Some(*handle));
+        assert_eq!(interner.resolve(*handle), tag);
+    }
+    assert_eq!(interner.len(), 3);
+    assert_eq!(interner.get(unique_tag!(test, "plugins")), None);
+}
+
+#[test]
+fn freezing_keeps_every_handle() {
+    let interner: TagInterner = tags().into_iter().collect();
+    let before: Vec<_> = interner.iter().collect();
+    let frozen = interner.freeze();
+
+    assert_eq!(frozen.iter().collect::<Vec<_>>(), before);
+    assert_eq!(frozen.len(), 3);
+    for (handle, tag) in before {
+        assert_eq!(frozen.get(tag), Some(handle));
+        assert_eq!(frozen.resolve(handle), tag);
+    }
+    assert_eq!(frozen.get(unique_tag!(test, "plugins")), None);
+}
+
+#[test]
+fn frozen_interners_are_shared_between_threads() {
+    fn assert_sync<T: Send + Sync>() {}
+    assert_sync::<FrozenTagInterner>();
+
+    let frozen = Arc::new(tags().into_iter().collect::<TagInterner>().freeze());
+    let threads: Vec<_> = tags()
+        .into_iter()
+        .map(|tag| {
+            let frozen = Arc::clone(&frozen);
+            std::thread::spawn(move || frozen.get(tag).map(|handle| frozen.resolve(handle)))
+        })
+        .collect();
+    for (thread, tag) in threads.into_iter().zip(tags()) {
+        assert_eq!(thread.join().unwrap(), Some(tag));
+    }
+}
+
+#[test]
+#[should_panic(expected = "#1 was not allocated by this interner, which holds 1 tags")]
+fn resolving_a_foreign_handle_panics() {
+    let mut other = TagInterner::new();
+    other.intern(unique_tag!(test, "plugins.audio"));
+    let foreign = other.intern(unique_tag!(test, "plugins.video"));
+
+    let mut interner = TagInterner::new();
+    interner.intern(unique_tag!(test, "plugins"));
+    interner.freeze().resolve(foreign);
+}
+
+#[test]
+fn registries_are_interned_in_key_order() {
+    let registry = Registry::parse(&format!(
+        r#"
+        [unique_tags]
+        "orders.created" = "{}"
+        "orders.alias" = {{ uuid = "{}", aliases = ["orders.legacy"] }}
+
+        [unique_type_tags]
+        "::Order" = "{}"
+        "#,
+        TAGS[0], TAGS[1], TAGS[2]
+    ))
+    .unwrap();
+
+    let interner = TagInterner::from_registry(&registry);
+    let order: Vec<_> = interner.iter().map(|(_, tag)| tag).collect();
+    let [created, alias, order_type] = tags()[..] else {
+        unreachable!()
+    };
+    assert_eq!(order, [alias, created, order_type]);
+}
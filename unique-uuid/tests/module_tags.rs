@@ -0,0 +1,69 @@
+use unique_uuid::{unique_type_tags, uuid::uuid, UniqueTag, UniqueTypeTag};
+
+#[unique_type_tags]
+mod events {
+    use unique_uuid::{uuid::uuid, UniqueTag, UniqueTypeTag};
+
+    pub struct OrderCreated;
+
+    #[derive(UniqueTypeTag)]
+    pub struct OrderPaid;
+
+    pub enum Checkout {
+        Started,
+        Completed,
+    }
+
+    pub struct Generic<T>(pub T);
+
+    pub struct Cart;
+
+    impl UniqueTypeTag for Cart {
+        const TYPE_TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+    }
+
+    #[unique_type_tag(skip)]
+    pub struct Draft;
+
+    pub mod shipping {
+        pub struct Shipment;
+    }
+}
+
+mod derived {
+    use unique_uuid::UniqueTypeTag;
+
+    #[derive(UniqueTypeTag)]
+    pub struct OrderCreated;
+
+    #[derive(UniqueTypeTag)]
+    pub struct Shipment;
+}
+
+#[test]
+fn every_type_of_the_module_is_tagged() {
+    assert_eq!(
+        events::OrderCreated::TYPE_TAG,
+        derived::OrderCreated::TYPE_TAG
+    );
+    assert_eq!(
+        events::shipping::Shipment::TYPE_TAG,
+        derived::Shipment::TYPE_TAG
+    );
+    assert_ne!(events::Checkout::TYPE_TAG, events::OrderPaid::TYPE_TAG);
+    assert_eq!(
+        events::Generic::<u8>::TYPE_TAG,
+        events::Generic::<String>::TYPE_TAG
+    );
+    let _ = (events::Checkout::Started, events::Checkout::Completed);
+}
+
+#[test]
+fn manual_impls_and_skipped_types_are_left_alone() {
+    assert_eq!(
+        events::Cart::TYPE_TAG,
+        UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"))
+    );
+    // `skip` is removed along with the attribute, which the compiler would reject
+    let _ = events::Draft;
+}
@@ -0,0 +1,53 @@
+use unique_uuid::{module_unique_tag, unique_tag, UniqueTag};
+
+mod orders {
+    use unique_uuid::{module_unique_tag, UniqueTag};
+
+    pub const INIT: UniqueTag = module_unique_tag!("tenants");
+
+    pub fn init() -> UniqueTag {
+        module_unique_tag!("tenants")
+    }
+
+    pub mod billing {
+        use unique_uuid::{module_unique_tag, UniqueTag};
+
+        pub const INIT: UniqueTag = module_unique_tag!("tenants");
+    }
+}
+
+mod shipping {
+    use unique_uuid::{module_unique_tag, UniqueTag};
+
+    pub const INIT: UniqueTag = module_unique_tag!("tenants");
+}
+
+#[test]
+fn each_module_gets_its_own_tag() {
+    let tags = [
+        module_unique_tag!("tenants"),
+        orders::INIT,
+        orders::billing::INIT,
+        shipping::INIT,
+        unique_tag!("tenants"),
+    ];
+    for (i, tag) in tags.iter().enumerate() {
+        assert!(tags[i + 1..].iter().all(|other| other != tag), "{:?}", tags);
+    }
+}
+
+#[test]
+fn module_tags_are_stable() {
+    assert_eq!(orders::INIT, orders::init());
+    assert_eq!(
+        orders::INIT,
+        UniqueTag::new_v5(unique_tag!("tenants"), b"module_unique_tag::orders")
+    );
+    assert_eq!(
+        orders::billing::INIT,
+        UniqueTag::new_v5(
+            unique_tag!("tenants"),
+            b"module_unique_tag::orders::billing"
+        )
+    );
+}
@@ -0,0 +1,52 @@
+use unique_uuid::{phf_map::TagPhfMap, tag_phf_map, unique_tag, uuid::Uuid, UniqueTag};
+
+const ENTRIES: TagPhfMap<usize> = tag_phf_map! {
+    "build.greeting" => 0,
+    "build.messages.goodbye" => 1,
+    "build.messages.hello" => 2,
+    "legacy.order_placed" => 3,
+    "my_custom_tag" => 4,
+    "parcels.sent" => 5,
+    "tenants" => 6,
+};
+
+const SENT: UniqueTag = unique_tag!("parcels.sent");
+const TENANTS: UniqueTag = unique_tag!("tenants");
+
+// Lookups are evaluated while compiling
+const _: () = assert!(ENTRIES.get(&SENT).is_some());
+const _: () = assert!(matches!(ENTRIES.get(&TENANTS), Some(6)));
+const _: () = assert!(ENTRIES.get(&UniqueTag(Uuid::nil())).is_none());
+
+#[test]
+fn every_entry_is_found() {
+    assert_eq!(ENTRIES.len(), 7);
+    let mut values: Vec<_> = ENTRIES
+        .iter()
+        .map(|(tag, value)| {
+            assert_eq!(ENTRIES.get(&tag), Some(value));
+            assert!(ENTRIES.contains(&tag));
+            *value
+        })
+        .collect();
+    values.sort();
+    assert_eq!(values, (0..7).collect::<Vec<_>>());
+    assert_eq!(ENTRIES.get(&unique_tag!("build.messages.hello")), Some(&2));
+}
+
+#[test]
+fn tags_out_of_the_map_are_missed() {
+    for tag in [
+        UniqueTag(Uuid::nil()),
+        UniqueTag(Uuid::max()),
+        unique_tag!("test1"),
+        unique_tag!("test2"),
+        UniqueTag::new_v5(SENT, b"acme"),
+    ] {
+        assert_eq!(ENTRIES.get(&tag), None);
+        assert!(!ENTRIES.contains(&tag));
+    }
+
+    let empty: TagPhfMap<u8> = tag_phf_map! {};
+    assert_eq!(empty.get(&SENT), None);
+}
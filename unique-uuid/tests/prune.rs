@@ -0,0 +1,138 @@
+//! Pruning the registry down to the keys in use.
+use std::collections::HashSet;
+
+use unique_uuid::registry::{Registry, Section, UsageLog};
+
+const REGISTRY: &str = r#"
+[unique_tags]
+"orders.created" = "2af1216e-7042-406a-a577-c39a7e7c3faa"
+"orders.cancelled" = "d7edd7b5-1715-4bb1-9b6c-2cbf4ec11b24"
+"orders.v2" = { uuid = "ce4baeef-bd9c-4932-88be-24eb1033f695", reserved = true, owner = "billing" }
+"orders.fixed" = { uuid = "a3e7c969-8413-4f6b-9928-28a140227b0b", pinned = true }
+plugins = "c0c07ffb-fb2a-4abd-8b92-172505df923f"
+"plugins.audio" = { uuid = "21c6dfa7-d261-44a5-a92a-ee11f7871d78", parent = "plugins" }
+"plugins.audio.codecs" = { uuid = "4b657326-1cb4-4050-b624-06cc831b734d", parent = "plugins.audio" }
+"plugins.video" = { uuid = "1765a55a-9a70-402a-99c8-3d0b35e3aba4", parent = "plugins" }
+
+[unique_type_tags]
+"::Order" = { uuid = "6aac8026-6ca8-4c82-ac39-d74bedf1ac50", aliases = ["::OrderRecord", "::LegacyOrder"] }
+"::Invoice" = "8701a796-9ffe-4723-89cf-ebcaaafe9ebb"
+"#;
+
+fn keep(keys: &[&str]) -> HashSet<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+}
+
+/// Prunes the registry down to `keys`, returning the pruned keys by section.
+fn prune(keys: &[&str]) -> (Registry, Vec<(Section, String)>) {
+    let mut registry = Registry::parse(REGISTRY).unwrap();
+    let pruned = registry
+        .prune(&keep(keys))
+        .into_iter()
+        .map(|pruned| (pruned.section, pruned.key))
+        .collect();
+    (registry, pruned)
+}
+
+/// Returns the usage log recording `keys`, each in the section of its registry entry.
+fn usage(keys: &[(Section, &str)]) -> UsageLog {
+    let log: String = keys
+        .iter()
+        .map(|(section, key)| format!("{}\t{}\n", section.name(), key))
+        .collect();
+    UsageLog::parse(&log)
+}
+
+#[test]
+fn entries_used_through_an_alias_are_kept() {
+    let (registry, pruned) = prune(&["::LegacyOrder"]);
+    assert!(registry.get("::Order").is_some());
+    assert!(registry.get("::OrderRecord").is_some());
+    assert!(pruned.contains(&(Section::UniqueTypeTags, "::Invoice".to_string())));
+    assert!(!pruned.iter().any(|(_, key)| key == "::Order"));
+
+    let (registry, pruned) = prune(&[]);
+    assert!(registry.get("::Order").is_none());
+    assert!(pruned.contains(&(Section::UniqueTypeTags, "::Order".to_string())));
+}
+
+#[test]
+fn namespaced_entries_are_pruned_by_key() {
+    // A key keeps neither its siblings nor its children
+    let (registry, _) = prune(&["orders.created", "plugins"]);
+    assert!(registry.get("orders.created").is_some());
+    assert!(registry.get("orders.cancelled").is_none());
+    assert!(registry.get("plugins").is_some());
+    assert!(registry.get("plugins.audio").is_none());
+    assert!(registry.get("plugins.video").is_none());
+
+    // But a kept entry keeps its ancestors
+    let (registry, pruned) = prune(&["plugins.audio.codecs"]);
+    assert!(registry.get("plugins.audio.codecs").is_some());
+    assert!(registry.get("plugins.audio").is_some());
+    assert!(registry.get("plugins").is_some());
+    assert!(registry.get("plugins.video").is_none());
+    assert!(registry.validate().is_empty(), "{:?}", registry.validate());
+    assert_eq!(
+        pruned,
+        [
+            (Section::UniqueTags, "orders.cancelled".to_string()),
+            (Section::UniqueTags, "orders.created".to_string()),
+            (Section::UniqueTags, "plugins.video".to_string()),
+            (Section::UniqueTypeTags, "::Invoice".to_string()),
+            (Section::UniqueTypeTags, "::Order".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn reserved_and_pinned_entries_are_never_pruned() {
+    let (registry, pruned) = prune(&[]);
+    let v2 = registry.entry(Section::UniqueTags, "orders.v2").unwrap();
+    assert!(v2.is_reserved());
+    assert_eq!(v2.owner(), Some("billing"));
+    assert!(registry
+        .entry(Section::UniqueTags, "orders.fixed")
+        .unwrap()
+        .is_pinned());
+    assert!(!pruned
+        .iter()
+        .any(|(_, key)| key == "orders.v2" || key == "orders.fixed"));
+    assert_eq!(registry.len(), 2);
+}
+
+#[test]
+fn unused_entries_agree_with_prune() {
+    let registry = Registry::parse(REGISTRY).unwrap();
+    let usage = usage(&[
+        (Section::UniqueTags, "plugins.audio"),
+        (Section::UniqueTypeTags, "::OrderRecord"),
+    ]);
+    assert_eq!(
+        registry.unused_entries(&usage),
+        [
+            (Section::UniqueTags, "orders.cancelled"),
+            (Section::UniqueTags, "orders.created"),
+            (Section::UniqueTags, "orders.fixed"),
+            (Section::UniqueTags, "orders.v2"),
+            (Section::UniqueTags, "plugins.audio.codecs"),
+            (Section::UniqueTags, "plugins.video"),
+            (Section::UniqueTypeTags, "::Invoice"),
+        ]
+    );
+
+    // Pruning removes the same entries, except the reserved and pinned ones
+    let mut pruned = registry.clone();
+    let pruned: Vec<_> = pruned
+        .prune(&usage.keys())
+        .into_iter()
+        .map(|pruned| (pruned.section, pruned.key))
+        .collect();
+    let unused: Vec<_> = registry
+        .unused_entries(&usage)
+        .into_iter()
+        .filter(|(_, key)| !matches!(*key, "orders.v2" | "orders.fixed"))
+        .map(|(section, key)| (section, key.to_string()))
+        .collect();
+    assert_eq!(pruned, unused);
+}
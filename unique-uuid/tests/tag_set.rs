@@ -0,0 +1,76 @@
+//! Set operations of `TagSet`, checked against `BTreeSet`.
+use std::collections::BTreeSet;
+
+use proptest::prelude::*;
+use unique_uuid::{TagSet, UniqueTag};
+use uuid::Uuid;
+
+/// Tags drawn from a small range, so that generated sets overlap.
+fn tags() -> impl Strategy<Value = Vec<UniqueTag>> {
+    prop::collection::vec(
+        (0u128..48).prop_map(|i| UniqueTag(Uuid::from_u128(i.wrapping_mul(0x9e37_79b9_7f4a_7c15)))),
+        0..32,
+    )
+}
+
+fn sorted<'a>(tags: impl Iterator<Item = &'a UniqueTag>) -> Vec<UniqueTag> {
+    tags.copied().collect()
+}
+
+proptest! {
+    #[test]
+    fn collecting_matches_btree_set(tags in tags()) {
+        let set: TagSet = tags.iter().copied().collect();
+        let reference: BTreeSet<_> = tags.iter().copied().collect();
+        prop_assert_eq!(set.as_slice().to_vec(), sorted(reference.iter()));
+        prop_assert_eq!(tags.iter().collect::<TagSet>(), set);
+    }
+
+    #[test]
+    fn extending_matches_btree_set(first in tags(), second in tags()) {
+        let mut set: TagSet = first.iter().collect();
+        set.extend(second.iter().copied());
+        let mut reference: BTreeSet<_> = first.iter().copied().collect();
+        reference.extend(second.iter().copied());
+        prop_assert_eq!(set.as_slice().to_vec(), sorted(reference.iter()));
+    }
+
+    #[test]
+    fn operations_match_btree_set(first in tags(), second in tags()) {
+        let (a, b): (TagSet, TagSet) = (first.iter().collect(), second.iter().collect());
+        let (x, y): (BTreeSet<_>, BTreeSet<_>) =
+            (first.iter().copied().collect(), second.iter().copied().collect());
+
+        prop_assert_eq!(sorted(a.union(&b)), sorted(x.union(&y)));
+        prop_assert_eq!(sorted(a.intersection(&b)), sorted(x.intersection(&y)));
+        prop_assert_eq!(sorted(a.difference(&b)), sorted(x.difference(&y)));
+        prop_assert_eq!(
+            sorted(a.symmetric_difference(&b)),
+            sorted(x.symmetric_difference(&y))
+        );
+        prop_assert_eq!((&a | &b).as_slice().to_vec(), sorted((&x | &y).iter()));
+        prop_assert_eq!((&a & &b).as_slice().to_vec(), sorted((&x & &y).iter()));
+        prop_assert_eq!((&a - &b).as_slice().to_vec(), sorted((&x - &y).iter()));
+        prop_assert_eq!((&a ^ &b).as_slice().to_vec(), sorted((&x ^ &y).iter()));
+
+        prop_assert_eq!(a.is_disjoint(&b), x.is_disjoint(&y));
+        prop_assert_eq!(a.is_subset(&b), x.is_subset(&y));
+        prop_assert_eq!(a.is_superset(&b), x.is_superset(&y));
+        prop_assert!(a.is_superset(&(&a & &b)));
+    }
+
+    #[test]
+    fn size_hints_bound_the_operations(first in tags(), second in tags()) {
+        let (a, b): (TagSet, TagSet) = (first.iter().collect(), second.iter().collect());
+        let check = |hint: (usize, Option<usize>), len: usize| {
+            hint.0 <= len && hint.1.is_none_or(|upper| len <= upper)
+        };
+        prop_assert!(check(a.union(&b).size_hint(), a.union(&b).count()));
+        prop_assert!(check(a.intersection(&b).size_hint(), a.intersection(&b).count()));
+        prop_assert!(check(a.difference(&b).size_hint(), a.difference(&b).count()));
+        prop_assert!(check(
+            a.symmetric_difference(&b).size_hint(),
+            a.symmetric_difference(&b).count()
+        ));
+    }
+}
@@ -0,0 +1,29 @@
+use ulid::Ulid;
+use unique_uuid::{unique_tag, uuid::Uuid, UniqueTag};
+
+#[test]
+fn tags_round_trip_through_ulids() {
+    let tags = [
+        unique_tag!("tenants"),
+        UniqueTag::new_v5(unique_tag!("tenants"), b"acme"),
+        UniqueTag(Uuid::nil()),
+        UniqueTag(Uuid::max()),
+    ];
+    for tag in tags {
+        assert_eq!(UniqueTag::from_ulid(tag.to_ulid()), tag);
+        let parsed = Ulid::from_string(&tag.to_ulid_string()).unwrap();
+        assert_eq!(UniqueTag::from_ulid(parsed), tag);
+    }
+}
+
+#[test]
+fn ulids_round_trip_through_tags() {
+    let ulid = Ulid::from_string("01ARZ3NDEKTSV4RRFFQ69G5FAV").unwrap();
+    let tag = UniqueTag::from_ulid(ulid);
+    assert_eq!(tag.to_ulid(), ulid);
+    assert_eq!(tag.to_ulid_string(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+
+    // The timestamp of the ULID is kept in the first 48 bits of the tag
+    assert_eq!(tag.to_ulid().timestamp_ms(), ulid.timestamp_ms());
+    assert_eq!(tag.0.as_u128() >> 80, u128::from(ulid.timestamp_ms()));
+}
@@ -0,0 +1,91 @@
+//! The issues `Registry::validate` reports on the registries of `tests/fixtures/validation`
+//! and `tests/fixtures/case`.
+use std::path::Path;
+
+use unique_uuid::registry::{IssueKind, Registry, Severity, ValidationIssue};
+
+/// Returns the issues found in `tests/fixtures/validation/<name>.toml`.
+fn validate(name: &str) -> Vec<ValidationIssue> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/validation")
+        .join(format!("{}.toml", name));
+    Registry::load(path).unwrap().validate()
+}
+
+/// Returns the kinds of the issues found in `tests/fixtures/validation/<name>.toml`.
+fn kinds(name: &str) -> Vec<IssueKind> {
+    validate(name).into_iter().map(|issue| issue.kind).collect()
+}
+
+#[test]
+fn valid_registries_have_no_issue() {
+    assert_eq!(kinds("valid"), []);
+}
+
+#[test]
+fn duplicates_are_reported() {
+    assert_eq!(kinds("duplicate_uuid"), [IssueKind::DuplicateUuid; 2]);
+    assert_eq!(kinds("duplicate_id"), [IssueKind::DuplicateId]);
+    assert_eq!(kinds("duplicate_meta"), [IssueKind::DuplicateMeta]);
+    assert_eq!(kinds("tag64_collision"), [IssueKind::Tag64Collision]);
+}
+
+#[test]
+fn malformed_entries_are_reported() {
+    assert_eq!(kinds("malformed_uuid"), [IssueKind::MalformedEntry; 3]);
+    assert_eq!(kinds("empty_key"), [IssueKind::EmptyKey]);
+}
+
+#[test]
+fn ambiguous_keys_are_reported() {
+    assert_eq!(kinds("case_insensitive"), [IssueKind::EquivalentKeys]);
+    assert_eq!(kinds("unicode_normalization"), [IssueKind::EquivalentKeys]);
+    assert_eq!(
+        kinds("conflicting_sections"),
+        [IssueKind::KeyInBothSections]
+    );
+}
+
+#[test]
+fn keys_are_folded_as_the_macros_fold_them() {
+    // Loading rejects `::STRASSE` next to `::Straße` under `case_insensitive_keys = "error"`,
+    // validating reports it whatever the setting
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/case/unicode.toml");
+    assert!(Registry::load(&path).is_err());
+    let text = std::fs::read_to_string(&path).unwrap();
+    let registry = Registry::parse(&text.replace("\"error\"", "\"off\"")).unwrap();
+    let kinds: Vec<_> = registry
+        .validate()
+        .into_iter()
+        .map(|issue| issue.kind)
+        .collect();
+    assert_eq!(kinds, [IssueKind::EquivalentKeys]);
+}
+
+#[test]
+fn configuration_violations_are_reported() {
+    assert_eq!(kinds("prefix_mismatch"), [IssueKind::PrefixMismatch]);
+    assert_eq!(kinds("pattern_mismatch"), [IssueKind::PatternMismatch; 2]);
+}
+
+#[test]
+fn broken_hierarchies_are_reported() {
+    assert_eq!(kinds("dangling_parent"), [IssueKind::DanglingParent]);
+    assert_eq!(kinds("parent_cycle"), [IssueKind::ParentCycle]);
+}
+
+#[test]
+fn issues_have_the_documented_severity() {
+    let severity = |name: &str| {
+        validate(name)
+            .into_iter()
+            .map(|issue| issue.severity)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(severity("duplicate_id"), [Severity::Error]);
+    assert_eq!(severity("case_insensitive"), [Severity::Warning]);
+    assert_eq!(severity("conflicting_sections"), [Severity::Warning]);
+    assert_eq!(severity("prefix_mismatch"), [Severity::Error]);
+    assert_eq!(severity("pattern_mismatch"), [Severity::Warning; 2]);
+    assert_eq!(severity("dangling_parent"), [Severity::Error]);
+}
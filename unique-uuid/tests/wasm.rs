@@ -0,0 +1,40 @@
+//! Round trips of `UniqueTag` through JavaScript values.
+//!
+//! Run with `wasm-bindgen-test-runner` as the runner of the `wasm32-unknown-unknown`
+//! target:
+//!
+//! ```sh
+//! CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+//!     cargo test -p unique-uuid --target wasm32-unknown-unknown --features wasm --test wasm
+//! ```
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use js_sys::{JsString, Uint8Array};
+use unique_uuid::{uuid::uuid, UniqueTag};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const TAG: UniqueTag = UniqueTag(uuid!("2af1216e-7042-406a-a577-c39a7e7c3faa"));
+
+#[wasm_bindgen_test]
+fn string_round_trip() {
+    let value: JsValue = TAG.into();
+    let string = value.dyn_into::<JsString>().unwrap();
+    assert_eq!(string.length(), 36);
+    assert_eq!(
+        String::from(&string),
+        "2af1216e-7042-406a-a577-c39a7e7c3faa"
+    );
+    assert_eq!(UniqueTag::from_js_string(&string).unwrap(), TAG);
+
+    assert!(UniqueTag::from_js_string(&JsString::from("not a tag")).is_err());
+}
+
+#[wasm_bindgen_test]
+fn uint8array_round_trip() {
+    let array = TAG.to_uint8array();
+    assert_eq!(array.to_vec(), TAG.0.as_bytes());
+    assert_eq!(UniqueTag::from_uint8array(&array).unwrap(), TAG);
+
+    assert!(UniqueTag::from_uint8array(&Uint8Array::new_with_length(15)).is_err());
+}